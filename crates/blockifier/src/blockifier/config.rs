@@ -26,14 +26,16 @@ pub struct ConcurrencyConfig {
     pub enabled: bool,
     pub n_workers: usize,
     pub chunk_size: usize,
+    // If set, n_workers and chunk_size are upper bounds, lowered when transactions conflict.
+    pub adaptive: bool,
 }
 
 impl ConcurrencyConfig {
     pub fn create_for_testing(concurrency_enabled: bool) -> Self {
         if concurrency_enabled {
-            return Self { enabled: true, n_workers: 4, chunk_size: 64 };
+            return Self { enabled: true, n_workers: 4, chunk_size: 64, adaptive: false };
         }
-        Self { enabled: false, n_workers: 0, chunk_size: 0 }
+        Self { enabled: false, n_workers: 0, chunk_size: 0, adaptive: false }
     }
 }
 
@@ -58,6 +60,13 @@ impl SerializeConfig for ConcurrencyConfig {
                 "The size of the transaction chunk executed in parallel.",
                 ParamPrivacyInput::Public,
             ),
+            ser_param(
+                "adaptive",
+                &self.adaptive,
+                "Adapts the number of workers and the chunk size between chunks to the rate of \
+                 conflicts between transactions, up to the configured values.",
+                ParamPrivacyInput::Public,
+            ),
         ])
     }
 }
@@ -1,19 +1,26 @@
+use std::cmp::min;
 use std::collections::{HashMap, HashSet};
 use std::panic::{self, catch_unwind, AssertUnwindSafe};
 use std::sync::{Arc, Mutex};
+use std::time::Instant;
 
-use itertools::FoldWhile::{Continue, Done};
-use itertools::Itertools;
 use starknet_api::block::BlockHashAndNumber;
 use starknet_api::core::ClassHash;
+use starknet_api::transaction::TransactionHash;
 use thiserror::Error;
 
 use crate::blockifier::block::pre_process_block;
 use crate::blockifier::config::TransactionExecutorConfig;
 use crate::bouncer::{Bouncer, BouncerWeights};
+use crate::concurrency::tuner::{ChunkExecutionStats, ConcurrencyTuner};
 use crate::concurrency::worker_logic::WorkerExecutor;
 use crate::context::BlockContext;
-use crate::state::cached_state::{CachedState, CommitmentStateDiff, TransactionalState};
+use crate::state::cached_state::{
+    CachedState,
+    CommitmentStateDiff,
+    StateChangesKeys,
+    TransactionalState,
+};
 use crate::state::errors::StateError;
 use crate::state::state_api::{StateReader, StateResult};
 use crate::transaction::errors::TransactionExecutionError;
@@ -33,6 +40,8 @@ pub enum TransactionExecutorError {
     BlockFull,
     #[error(transparent)]
     StateError(#[from] StateError),
+    #[error("Transaction was not executed before the executor's deadline.")]
+    Timeout,
     #[error(transparent)]
     TransactionExecutionError(#[from] TransactionExecutionError),
 }
@@ -40,6 +49,22 @@ pub enum TransactionExecutorError {
 pub type TransactionExecutorResult<T> = Result<T, TransactionExecutorError>;
 pub type VisitedSegmentsMapping = Vec<(ClassHash, Vec<usize>)>;
 
+/// A transaction that was added to the block, as reported to a [TransactionCommitObserver].
+pub struct CommittedTransaction<'a> {
+    pub tx_hash: TransactionHash,
+    pub execution_info: &'a TransactionExecutionInfo,
+    pub state_changes_keys: &'a StateChangesKeys,
+    // The weights the transaction added to the block.
+    pub bouncer_weights: BouncerWeights,
+}
+
+/// Observes the transactions added to the block as they are committed, in their order in the
+/// block; e.g., to stream their traces while the block is being built.
+/// Transactions that fail execution are not added to the block, and are not reported.
+pub trait TransactionCommitObserver: Send + Sync {
+    fn on_tx_committed(&self, committed_tx: CommittedTransaction<'_>);
+}
+
 /// A transaction executor, used for building a single block.
 pub struct TransactionExecutor<S: StateReader> {
     pub block_context: BlockContext,
@@ -53,6 +78,12 @@ pub struct TransactionExecutor<S: StateReader> {
     // committing the chunk. The block state is wrapped with an Option<_> to allow setting it to
     // `None` while it is moved to the worker executor.
     pub block_state: Option<CachedState<S>>,
+
+    commit_observer: Option<Arc<dyn TransactionCommitObserver>>,
+
+    // Concurrency-related fields.
+    concurrency_tuner: ConcurrencyTuner,
+    chunk_stats: Vec<ChunkExecutionStats>,
 }
 
 impl<S: StateReader> TransactionExecutor<S> {
@@ -79,6 +110,7 @@ impl<S: StateReader> TransactionExecutor<S> {
         config: TransactionExecutorConfig,
     ) -> Self {
         let bouncer_config = block_context.bouncer_config.clone();
+        let concurrency_tuner = ConcurrencyTuner::new(&config.concurrency_config);
         // Note: the state might not be empty even at this point; it is the creator's
         // responsibility to tune the bouncer according to pre and post block process.
         Self {
@@ -86,16 +118,45 @@ impl<S: StateReader> TransactionExecutor<S> {
             bouncer: Bouncer::new(bouncer_config),
             config,
             block_state: Some(block_state),
+            commit_observer: None,
+            concurrency_tuner,
+            chunk_stats: Vec::new(),
         }
     }
 
+    /// Sets a wall-clock deadline for adding transactions to the block: a transaction still
+    /// running at the deadline is cut off by the VM and fails with a `Timeout` error, leaving the
+    /// block state untouched, and no further transactions are executed.
+    pub fn set_deadline(&mut self, deadline: Option<Instant>) {
+        self.block_context.execution_deadline = deadline;
+    }
+
+    /// Sets an observer that is notified of each transaction added to the block, as it is
+    /// committed.
+    pub fn set_commit_observer(
+        &mut self,
+        commit_observer: Option<Arc<dyn TransactionCommitObserver>>,
+    ) {
+        self.commit_observer = commit_observer;
+    }
+
+    /// Returns the execution statistics of the chunks executed so far in concurrency mode, in
+    /// execution order.
+    pub fn chunk_stats(&self) -> &[ChunkExecutionStats] {
+        &self.chunk_stats
+    }
+
     /// Executes the given transaction on the state maintained by the executor.
     /// Returns the execution result (info or error) if there is room for the transaction;
-    /// Otherwise, returns BlockFull error.
+    /// Otherwise, returns BlockFull error. Returns Timeout error if the executor's deadline passed
+    /// before the transaction finished executing.
     pub fn execute(
         &mut self,
         tx: &Transaction,
     ) -> TransactionExecutorResult<TransactionExecutionInfo> {
+        if self.block_context.execution_deadline_passed() {
+            return Err(TransactionExecutorError::Timeout);
+        }
         let mut transactional_state = TransactionalState::create_transactional(
             self.block_state.as_mut().expect(BLOCK_STATE_ACCESS_ERR),
         );
@@ -107,16 +168,29 @@ impl<S: StateReader> TransactionExecutor<S> {
         let tx_execution_result =
             tx.execute_raw(&mut transactional_state, &self.block_context, execution_flags);
         match tx_execution_result {
+            // A transaction cut off at the deadline fails or is reverted, so its result is ignored.
+            _ if self.block_context.execution_deadline_passed() => {
+                transactional_state.abort();
+                Err(TransactionExecutorError::Timeout)
+            }
             Ok(tx_execution_info) => {
                 let tx_state_changes_keys =
                     transactional_state.get_actual_state_changes()?.state_maps.into_keys();
-                self.bouncer.try_update(
+                let tx_bouncer_weights = self.bouncer.try_update(
                     &transactional_state,
                     &tx_state_changes_keys,
                     &tx_execution_info.summarize(&self.block_context.versioned_constants),
                     &tx_execution_info.receipt.resources,
                 )?;
                 transactional_state.commit();
+                if let Some(commit_observer) = &self.commit_observer {
+                    commit_observer.on_tx_committed(CommittedTransaction {
+                        tx_hash: Transaction::tx_hash(tx),
+                        execution_info: &tx_execution_info,
+                        state_changes_keys: &tx_state_changes_keys,
+                        bouncer_weights: tx_bouncer_weights,
+                    });
+                }
                 Ok(tx_execution_info)
             }
             Err(error) => {
@@ -135,6 +209,10 @@ impl<S: StateReader> TransactionExecutor<S> {
             match self.execute(tx) {
                 Ok(tx_execution_info) => results.push(Ok(tx_execution_info)),
                 Err(TransactionExecutorError::BlockFull) => break,
+                Err(TransactionExecutorError::Timeout) => {
+                    results.push(Err(TransactionExecutorError::Timeout));
+                    break;
+                }
                 Err(error) => results.push(Err(error)),
             }
         }
@@ -182,8 +260,9 @@ impl<S: StateReader> TransactionExecutor<S> {
 
 impl<S: StateReader + Send + Sync> TransactionExecutor<S> {
     /// Executes the given transactions on the state maintained by the executor.
-    /// Stops if and when there is no more room in the block, and returns the executed transactions'
-    /// results.
+    /// Stops if and when there is no more room in the block, or the executor's deadline passes, and
+    /// returns the executed transactions' results; in the latter case, the last result is a Timeout
+    /// error.
     pub fn execute_txs(
         &mut self,
         txs: &[Transaction],
@@ -207,22 +286,59 @@ impl<S: StateReader + Send + Sync> TransactionExecutor<S> {
                  than 0. It equals {:?} ",
                 n_workers
             );
-            txs.chunks(chunk_size)
-                .fold_while(Vec::new(), |mut results, chunk| {
-                    let chunk_results = self.execute_chunk(chunk);
-                    if chunk_results.len() < chunk.len() {
-                        // Block is full.
-                        results.extend(chunk_results);
-                        Done(results)
-                    } else {
-                        results.extend(chunk_results);
-                        Continue(results)
-                    }
-                })
-                .into_inner()
+            let mut results = Vec::new();
+            let mut remaining_txs = txs;
+            while !remaining_txs.is_empty() {
+                let chunk_size = min(self.concurrency_tuner.chunk_size(), remaining_txs.len());
+                let (chunk, next_txs) = remaining_txs.split_at(chunk_size);
+                remaining_txs = next_txs;
+
+                let chunk_results = self.execute_tuned_chunk(chunk);
+                let timed_out =
+                    matches!(chunk_results.last(), Some(Err(TransactionExecutorError::Timeout)));
+                let block_full = chunk_results.len() < chunk.len();
+                results.extend(chunk_results);
+                if block_full || timed_out {
+                    // Block is full, or the deadline passed.
+                    break;
+                }
+            }
+            results
         }
     }
 
+    /// Executes the given chunk with the number of workers set by the concurrency tuner, and
+    /// updates the tuner with the chunk's execution statistics. A chunk with a single worker is
+    /// executed sequentially.
+    fn execute_tuned_chunk(
+        &mut self,
+        chunk: &[Transaction],
+    ) -> Vec<TransactionExecutorResult<TransactionExecutionInfo>> {
+        let n_workers = self.concurrency_tuner.n_workers();
+        let chunk_results = if n_workers == 1 {
+            let chunk_results = self.execute_txs_sequentially(chunk);
+            let n_committed_txs = chunk_results
+                .iter()
+                .filter(|result| !matches!(result, Err(TransactionExecutorError::Timeout)))
+                .count();
+            self.chunk_stats.push(ChunkExecutionStats {
+                n_txs: chunk.len(),
+                n_committed_txs,
+                n_workers,
+                n_executions: n_committed_txs,
+                ..Default::default()
+            });
+            chunk_results
+        } else {
+            self.execute_chunk(chunk)
+        };
+
+        let chunk_stats = self.chunk_stats.last().expect("Chunk statistics should be recorded.");
+        log::debug!("Executed a chunk: {chunk_stats:?}.");
+        self.concurrency_tuner.update(chunk_stats);
+        chunk_results
+    }
+
     pub fn execute_chunk(
         &mut self,
         chunk: &[Transaction],
@@ -231,12 +347,14 @@ impl<S: StateReader + Send + Sync> TransactionExecutor<S> {
 
         let block_state = self.block_state.take().expect("The block state should be `Some`.");
 
-        let worker_executor = Arc::new(WorkerExecutor::initialize(
+        let mut worker_executor = WorkerExecutor::initialize(
             block_state,
             chunk,
             &self.block_context,
             Mutex::new(&mut self.bouncer),
-        ));
+        );
+        worker_executor.commit_observer = self.commit_observer.clone();
+        let worker_executor = Arc::new(worker_executor);
 
         // No thread pool implementation is needed here since we already have our scheduler. The
         // initialized threads below will "busy wait" for new tasks using the `run` method until the
@@ -244,7 +362,7 @@ impl<S: StateReader + Send + Sync> TransactionExecutor<S> {
         // TODO(barak, 01/07/2024): Consider using tokio and spawn tasks that will be served by some
         // upper level tokio thread pool (Runtime in tokio terminology).
         std::thread::scope(|s| {
-            for _ in 0..self.config.concurrency_config.n_workers {
+            for _ in 0..self.concurrency_tuner.n_workers() {
                 let worker_executor = Arc::clone(&worker_executor);
                 s.spawn(move || {
                     // Making sure that the program will abort if a panic accured while halting the
@@ -288,6 +406,20 @@ impl<S: StateReader + Send + Sync> TransactionExecutor<S> {
             }
         }
 
+        if tx_execution_results.len() < chunk.len() && worker_executor.timed_out() {
+            tx_execution_results.push(Err(TransactionExecutorError::Timeout));
+        }
+
+        self.chunk_stats.push(ChunkExecutionStats {
+            n_txs: chunk.len(),
+            n_committed_txs,
+            n_workers: self.concurrency_tuner.n_workers(),
+            n_executions: worker_executor.scheduler.get_n_executions(),
+            n_validation_aborts: worker_executor.scheduler.get_n_validation_aborts(),
+            n_commit_reexecutions: worker_executor.scheduler.get_n_commit_reexecutions(),
+            n_read_conflicts: worker_executor.state.n_read_conflicts(),
+        });
+
         let block_state_after_commit = Arc::try_unwrap(worker_executor)
             .unwrap_or_else(|_| {
                 panic!(
@@ -1,3 +1,6 @@
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
 use assert_matches::assert_matches;
 use pretty_assertions::assert_eq;
 use rstest::rstest;
@@ -7,8 +10,10 @@ use starknet_api::transaction::TransactionVersion;
 use starknet_api::{declare_tx_args, deploy_account_tx_args, felt, invoke_tx_args, nonce};
 use starknet_types_core::felt::Felt;
 
-use crate::blockifier::config::TransactionExecutorConfig;
+use crate::blockifier::config::{ConcurrencyConfig, TransactionExecutorConfig};
 use crate::blockifier::transaction_executor::{
+    CommittedTransaction,
+    TransactionCommitObserver,
     TransactionExecutor,
     TransactionExecutorError,
     BLOCK_STATE_ACCESS_ERR,
@@ -364,3 +369,176 @@ fn test_execute_txs_bouncing(#[values(true, false)] concurrency_enabled: bool) {
         nonce!(4_u32)
     );
 }
+
+#[rstest]
+fn test_execute_txs_after_deadline(#[values(true, false)] concurrency_enabled: bool) {
+    let config = TransactionExecutorConfig::create_for_testing(concurrency_enabled);
+    let block_context = BlockContext::create_for_account_testing();
+
+    let TestInitData { state, account_address, contract_address, .. } =
+        create_test_init_data(&block_context.chain_info, CairoVersion::Cairo1);
+
+    let mut tx_executor = TransactionExecutor::new(state, block_context, config);
+    tx_executor.set_deadline(Some(Instant::now()));
+
+    let txs: Vec<Transaction> = [
+        emit_n_events_tx(1, account_address, contract_address, nonce!(0_u32)),
+        emit_n_events_tx(1, account_address, contract_address, nonce!(1_u32)),
+    ]
+    .into_iter()
+    .map(Transaction::Account)
+    .collect();
+
+    // Run.
+    let results = tx_executor.execute_txs(&txs);
+
+    // Check execution results.
+    assert_eq!(results.len(), 1);
+    assert_matches!(results[0].as_ref().unwrap_err(), TransactionExecutorError::Timeout);
+
+    // Check state: no transaction was added to the block.
+    assert_eq!(
+        tx_executor
+            .block_state
+            .as_ref()
+            .expect(BLOCK_STATE_ACCESS_ERR)
+            .get_nonce_at(account_address)
+            .unwrap(),
+        nonce!(0_u32)
+    );
+}
+
+#[rstest]
+fn test_execute_long_tx_after_deadline(#[values(true, false)] concurrency_enabled: bool) {
+    let config = TransactionExecutorConfig::create_for_testing(concurrency_enabled);
+    let block_context = BlockContext::create_for_account_testing();
+
+    let TestInitData { state, account_address, contract_address, .. } =
+        create_test_init_data(&block_context.chain_info, CairoVersion::Cairo0);
+
+    let mut tx_executor = TransactionExecutor::new(state, block_context, config);
+    // The transaction runs for much longer than the time left until the deadline.
+    tx_executor.set_deadline(Some(Instant::now() + Duration::from_millis(10)));
+
+    let tx = Transaction::Account(account_invoke_tx(invoke_tx_args! {
+        sender_address: account_address,
+        calldata: create_calldata(contract_address, "recurse", &[felt!(100000_u32)]),
+        nonce: nonce!(0_u32),
+    }));
+
+    // Run.
+    let results = tx_executor.execute_txs(&[tx]);
+
+    // Check execution results.
+    assert_eq!(results.len(), 1);
+    assert_matches!(results[0].as_ref().unwrap_err(), TransactionExecutorError::Timeout);
+
+    // Check state: the transaction was not added to the block.
+    assert_eq!(
+        tx_executor
+            .block_state
+            .as_ref()
+            .expect(BLOCK_STATE_ACCESS_ERR)
+            .get_nonce_at(account_address)
+            .unwrap(),
+        nonce!(0_u32)
+    );
+}
+
+#[derive(Default)]
+struct CommittedTxsRecorder {
+    bouncer_weights: Mutex<Vec<BouncerWeights>>,
+}
+
+impl TransactionCommitObserver for CommittedTxsRecorder {
+    fn on_tx_committed(&self, committed_tx: CommittedTransaction<'_>) {
+        self.bouncer_weights.lock().unwrap().push(committed_tx.bouncer_weights);
+    }
+}
+
+#[rstest]
+fn test_commit_observer(#[values(true, false)] concurrency_enabled: bool) {
+    let config = TransactionExecutorConfig::create_for_testing(concurrency_enabled);
+    let block_context = BlockContext::create_for_account_testing();
+
+    let TestInitData { state, account_address, contract_address, .. } =
+        create_test_init_data(&block_context.chain_info, CairoVersion::Cairo1);
+
+    let mut tx_executor = TransactionExecutor::new(state, block_context, config);
+    let recorder = Arc::new(CommittedTxsRecorder::default());
+    tx_executor.set_commit_observer(Some(recorder.clone()));
+
+    let txs: Vec<Transaction> = [
+        emit_n_events_tx(1, account_address, contract_address, nonce!(0_u32)),
+        // Fails execution, so it is not added to the block.
+        emit_n_events_tx(1, account_address, contract_address, nonce!(0_u32)),
+        emit_n_events_tx(2, account_address, contract_address, nonce!(1_u32)),
+        emit_n_events_tx(3, account_address, contract_address, nonce!(2_u32)),
+    ]
+    .into_iter()
+    .map(Transaction::Account)
+    .collect();
+
+    let results = tx_executor.execute_txs(&txs);
+    assert!(results[1].is_err());
+
+    // The committed transactions are reported in their order in the block, with their weights.
+    let bouncer_weights = recorder.bouncer_weights.lock().unwrap();
+    assert_eq!(
+        bouncer_weights.iter().map(|weights| weights.n_events).collect::<Vec<_>>(),
+        [1, 2, 3]
+    );
+    assert_eq!(
+        bouncer_weights.iter().fold(BouncerWeights::empty(), |total, weights| total + *weights),
+        *tx_executor.bouncer.get_accumulated_weights()
+    );
+}
+
+#[rstest]
+fn test_adaptive_concurrency_stats() {
+    let config = TransactionExecutorConfig {
+        concurrency_config: ConcurrencyConfig {
+            enabled: true,
+            n_workers: 4,
+            chunk_size: 2,
+            adaptive: true,
+        },
+    };
+    let block_context = BlockContext::create_for_account_testing();
+
+    let TestInitData { state, account_address, contract_address, .. } =
+        create_test_init_data(&block_context.chain_info, CairoVersion::Cairo1);
+
+    let mut tx_executor = TransactionExecutor::new(state, block_context, config);
+
+    // Transactions of a single account conflict on its nonce and balance.
+    let n_txs = 8;
+    let txs: Vec<Transaction> = (0..n_txs)
+        .map(|i| {
+            Transaction::Account(emit_n_events_tx(1, account_address, contract_address, nonce!(i)))
+        })
+        .collect();
+
+    let results = tx_executor.execute_txs(&txs);
+    assert_eq!(results.len(), n_txs);
+    assert!(results.iter().all(Result::is_ok));
+
+    // Each chunk's statistics are recorded, with the concurrency level it was executed with.
+    let chunk_stats = tx_executor.chunk_stats();
+    assert_eq!(chunk_stats[0].n_workers, 4);
+    assert_eq!(chunk_stats.iter().map(|stats| stats.n_txs).sum::<usize>(), n_txs);
+    assert_eq!(chunk_stats.iter().map(|stats| stats.n_committed_txs).sum::<usize>(), n_txs);
+    for stats in chunk_stats {
+        assert!(stats.n_txs <= 2);
+        assert!(stats.n_executions >= stats.n_committed_txs);
+    }
+    assert_eq!(
+        tx_executor
+            .block_state
+            .as_ref()
+            .expect(BLOCK_STATE_ACCESS_ERR)
+            .get_nonce_at(account_address)
+            .unwrap(),
+        nonce!(n_txs)
+    );
+}
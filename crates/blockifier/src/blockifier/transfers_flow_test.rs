@@ -8,8 +8,12 @@ use crate::test_utils::transfers_generator::{
 };
 
 #[rstest]
-#[case::concurrency_enabled(ConcurrencyConfig{enabled: true, n_workers: 4, chunk_size: 100})]
-#[case::concurrency_disabled(ConcurrencyConfig{enabled: false, n_workers: 0, chunk_size: 0})]
+#[case::concurrency_enabled(
+    ConcurrencyConfig{enabled: true, n_workers: 4, chunk_size: 100, adaptive: false}
+)]
+#[case::concurrency_disabled(
+    ConcurrencyConfig{enabled: false, n_workers: 0, chunk_size: 0, adaptive: false}
+)]
 pub fn transfers_flow_test(#[case] concurrency_config: ConcurrencyConfig) {
     let transfers_generator_config = TransfersGeneratorConfig {
         recipient_generator_type: RecipientGeneratorType::DisjointFromSenders,
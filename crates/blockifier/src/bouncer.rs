@@ -452,14 +452,14 @@ impl Bouncer {
         &self.accumulated_weights
     }
 
-    /// Updates the bouncer with a new transaction.
+    /// Updates the bouncer with a new transaction, and returns the weights it added to the block.
     pub fn try_update<S: StateReader>(
         &mut self,
         state_reader: &S,
         tx_state_changes_keys: &StateChangesKeys,
         tx_execution_summary: &ExecutionSummary,
         tx_resources: &TransactionResources,
-    ) -> TransactionExecutorResult<()> {
+    ) -> TransactionExecutorResult<BouncerWeights> {
         // The countings here should be linear in the transactional state changes and execution info
         // rather than the cumulative state attributes.
         let marginal_state_changes_keys =
@@ -493,7 +493,7 @@ impl Bouncer {
 
         self.update(tx_weights, tx_execution_summary, &marginal_state_changes_keys);
 
-        Ok(())
+        Ok(tx_weights)
     }
 
     fn update(
@@ -267,12 +267,14 @@ fn test_bouncer_try_update(#[case] added_ecdsa: usize, #[case] scenario: &'stati
 
     if result.is_ok() {
         // Try to update the bouncer.
-        result = bouncer.try_update(
-            &transactional_state,
-            &tx_state_changes_keys,
-            &execution_summary,
-            &tx_resources,
-        );
+        result = bouncer
+            .try_update(
+                &transactional_state,
+                &tx_state_changes_keys,
+                &execution_summary,
+                &tx_resources,
+            )
+            .map(|_tx_weights| ());
     }
 
     match scenario {
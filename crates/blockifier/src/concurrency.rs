@@ -2,6 +2,7 @@ pub mod fee_utils;
 pub mod scheduler;
 #[cfg(any(feature = "testing", test))]
 pub mod test_utils;
+pub mod tuner;
 pub mod utils;
 pub mod versioned_state;
 pub mod versioned_storage;
@@ -68,6 +68,10 @@ pub struct Scheduler {
     // Set to true when all transactions have been committed, or when calling the halt_scheduler
     // procedure, providing a cheap way for all threads to exit their main loops.
     done_marker: AtomicBool,
+    // Execution statistics of the chunk.
+    n_executions: AtomicUsize,
+    n_validation_aborts: AtomicUsize,
+    n_commit_reexecutions: AtomicUsize,
 }
 
 impl Scheduler {
@@ -81,6 +85,9 @@ impl Scheduler {
                 .take(chunk_size)
                 .collect(),
             done_marker: AtomicBool::new(false),
+            n_executions: AtomicUsize::new(0),
+            n_validation_aborts: AtomicUsize::new(0),
+            n_commit_reexecutions: AtomicUsize::new(0),
         }
     }
 
@@ -121,6 +128,7 @@ impl Scheduler {
         let mut status = self.lock_tx_status(tx_index);
         if *status == TransactionStatus::Executed {
             *status = TransactionStatus::Aborting;
+            self.n_validation_aborts.fetch_add(1, Ordering::Relaxed);
             return true;
         }
         false
@@ -142,6 +150,7 @@ impl Scheduler {
     /// validation index to ensure that higher transactions are validated. There is no need to set
     /// the transaction status to Executed, as it is already set to Committed.
     pub fn finish_execution_during_commit(&self, tx_index: TxIndex) {
+        self.n_commit_reexecutions.fetch_add(1, Ordering::Relaxed);
         self.decrease_validation_index(tx_index + 1);
     }
 
@@ -161,6 +170,21 @@ impl Scheduler {
         *self.commit_index.lock().unwrap()
     }
 
+    /// Returns the number of transaction executions in the chunk, including re-executions.
+    pub fn get_n_executions(&self) -> usize {
+        self.n_executions.load(Ordering::Acquire) + self.get_n_commit_reexecutions()
+    }
+
+    /// Returns the number of executed transactions that were aborted after failing validation.
+    pub fn get_n_validation_aborts(&self) -> usize {
+        self.n_validation_aborts.load(Ordering::Acquire)
+    }
+
+    /// Returns the number of transactions re-executed during their commit.
+    pub fn get_n_commit_reexecutions(&self) -> usize {
+        self.n_commit_reexecutions.load(Ordering::Acquire)
+    }
+
     pub fn halt(&self) {
         self.done_marker.store(true, Ordering::Release);
     }
@@ -201,6 +225,7 @@ impl Scheduler {
             let mut status = self.lock_tx_status(tx_index);
             if *status == TransactionStatus::ReadyToExecute {
                 *status = TransactionStatus::Executing;
+                self.n_executions.fetch_add(1, Ordering::Relaxed);
                 return true;
             }
         }
@@ -0,0 +1,91 @@
+use std::cmp::{max, min};
+
+use crate::blockifier::config::ConcurrencyConfig;
+
+#[cfg(test)]
+#[path = "tuner_test.rs"]
+pub mod test;
+
+// Above this conflict rate, the concurrency level is halved for the next chunk.
+pub const HIGH_CONFLICT_RATE: f64 = 0.5;
+// Below this conflict rate, the concurrency level is doubled for the next chunk, up to the
+// configured one.
+pub const LOW_CONFLICT_RATE: f64 = 0.1;
+
+/// Execution statistics of a single chunk.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct ChunkExecutionStats {
+    pub n_txs: usize,
+    pub n_committed_txs: usize,
+    // The chunk is executed sequentially when there is a single worker.
+    pub n_workers: usize,
+    // Transaction executions, including re-executions.
+    pub n_executions: usize,
+    pub n_validation_aborts: usize,
+    pub n_commit_reexecutions: usize,
+    // Read sets found to conflict with the writes of preceding transactions, in validation or in
+    // commit.
+    pub n_read_conflicts: usize,
+}
+
+impl ChunkExecutionStats {
+    /// Returns the number of re-executions caused by conflicts, per committed transaction.
+    // Precision loss is irrelevant for these counts.
+    #[allow(clippy::as_conversions)]
+    pub fn conflict_rate(&self) -> f64 {
+        if self.n_committed_txs == 0 {
+            return 0.0;
+        }
+        let n_conflicts = self.n_validation_aborts + self.n_commit_reexecutions;
+        n_conflicts as f64 / self.n_committed_txs as f64
+    }
+}
+
+/// Determines the number of workers and the chunk size of the next chunk.
+/// If adaptive, halves both after a chunk with a high conflict rate, down to sequential execution,
+/// and doubles them after a chunk with a low conflict rate, up to the configured values.
+#[derive(Debug)]
+pub struct ConcurrencyTuner {
+    adaptive: bool,
+    max_n_workers: usize,
+    max_chunk_size: usize,
+    n_workers: usize,
+    chunk_size: usize,
+}
+
+impl ConcurrencyTuner {
+    pub fn new(concurrency_config: &ConcurrencyConfig) -> Self {
+        Self {
+            adaptive: concurrency_config.adaptive,
+            max_n_workers: concurrency_config.n_workers,
+            max_chunk_size: concurrency_config.chunk_size,
+            n_workers: concurrency_config.n_workers,
+            chunk_size: concurrency_config.chunk_size,
+        }
+    }
+
+    pub fn n_workers(&self) -> usize {
+        self.n_workers
+    }
+
+    pub fn chunk_size(&self) -> usize {
+        self.chunk_size
+    }
+
+    /// Updates the concurrency level according to the statistics of the last executed chunk.
+    pub fn update(&mut self, chunk_stats: &ChunkExecutionStats) {
+        if !self.adaptive {
+            return;
+        }
+
+        // A sequentially executed chunk has no conflicts; concurrency is retried on the next chunk.
+        let conflict_rate = chunk_stats.conflict_rate();
+        if conflict_rate > HIGH_CONFLICT_RATE {
+            self.n_workers = max(self.n_workers / 2, 1);
+            self.chunk_size = max(self.chunk_size / 2, 1);
+        } else if conflict_rate < LOW_CONFLICT_RATE {
+            self.n_workers = min(self.n_workers * 2, self.max_n_workers);
+            self.chunk_size = min(self.chunk_size * 2, self.max_chunk_size);
+        }
+    }
+}
@@ -0,0 +1,65 @@
+use pretty_assertions::assert_eq;
+use rstest::rstest;
+
+use crate::blockifier::config::ConcurrencyConfig;
+use crate::concurrency::tuner::{ChunkExecutionStats, ConcurrencyTuner};
+
+fn chunk_stats(n_committed_txs: usize, n_conflicts: usize) -> ChunkExecutionStats {
+    ChunkExecutionStats {
+        n_txs: n_committed_txs,
+        n_committed_txs,
+        n_validation_aborts: n_conflicts,
+        ..Default::default()
+    }
+}
+
+fn concurrency_config(adaptive: bool) -> ConcurrencyConfig {
+    ConcurrencyConfig { enabled: true, n_workers: 4, chunk_size: 64, adaptive }
+}
+
+#[rstest]
+#[case::no_committed_txs(chunk_stats(0, 0), 0.0)]
+#[case::no_conflicts(chunk_stats(10, 0), 0.0)]
+#[case::conflicts(chunk_stats(10, 5), 0.5)]
+#[case::commit_reexecutions(
+    ChunkExecutionStats { n_commit_reexecutions: 2, ..chunk_stats(10, 5) },
+    0.7
+)]
+fn test_conflict_rate(#[case] chunk_stats: ChunkExecutionStats, #[case] expected_rate: f64) {
+    assert_eq!(chunk_stats.conflict_rate(), expected_rate);
+}
+
+#[rstest]
+fn test_adaptive_tuning() {
+    let mut tuner = ConcurrencyTuner::new(&concurrency_config(true));
+    let high_conflicts = chunk_stats(10, 8);
+    let low_conflicts = chunk_stats(10, 0);
+
+    // Lower down to sequential execution.
+    tuner.update(&high_conflicts);
+    assert_eq!((tuner.n_workers(), tuner.chunk_size()), (2, 32));
+    tuner.update(&high_conflicts);
+    assert_eq!((tuner.n_workers(), tuner.chunk_size()), (1, 16));
+    tuner.update(&high_conflicts);
+    assert_eq!((tuner.n_workers(), tuner.chunk_size()), (1, 8));
+
+    // A moderate conflict rate keeps the concurrency level.
+    tuner.update(&chunk_stats(10, 3));
+    assert_eq!((tuner.n_workers(), tuner.chunk_size()), (1, 8));
+
+    // Raise up to the configured values.
+    tuner.update(&low_conflicts);
+    assert_eq!((tuner.n_workers(), tuner.chunk_size()), (2, 16));
+    for _ in 0..3 {
+        tuner.update(&low_conflicts);
+    }
+    assert_eq!((tuner.n_workers(), tuner.chunk_size()), (4, 64));
+}
+
+#[rstest]
+fn test_non_adaptive_tuning() {
+    let mut tuner = ConcurrencyTuner::new(&concurrency_config(false));
+
+    tuner.update(&chunk_stats(10, 8));
+    assert_eq!((tuner.n_workers(), tuner.chunk_size()), (4, 64));
+}
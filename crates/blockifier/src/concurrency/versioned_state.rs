@@ -35,6 +35,8 @@ pub struct VersionedState<S: StateReader> {
     // in the compiled contract classes mapping.
     declared_contracts: VersionedStorage<ClassHash, bool>,
     compiled_contract_classes: VersionedStorage<ClassHash, RunnableContractClass>,
+    // The number of read sets found to conflict with the writes of preceding transactions.
+    n_read_conflicts: usize,
 }
 
 impl<S: StateReader> VersionedState<S> {
@@ -47,9 +49,14 @@ impl<S: StateReader> VersionedState<S> {
             compiled_class_hashes: VersionedStorage::default(),
             compiled_contract_classes: VersionedStorage::default(),
             declared_contracts: VersionedStorage::default(),
+            n_read_conflicts: 0,
         }
     }
 
+    pub fn n_read_conflicts(&self) -> usize {
+        self.n_read_conflicts
+    }
+
     fn get_writes_up_to_index(&mut self, tx_index: TxIndex) -> StateMaps {
         StateMaps {
             storage: self.storage.get_writes_up_to_index(tx_index),
@@ -77,6 +84,14 @@ impl<S: StateReader> VersionedState<S> {
     // TODO: Consider coupling the tx index with the read set to ensure any mismatch between them
     // will cause the validation to fail.
     fn validate_reads(&mut self, tx_index: TxIndex, reads: &StateMaps) -> bool {
+        let reads_valid = self.reads_match_versioned_state(tx_index, reads);
+        if !reads_valid {
+            self.n_read_conflicts += 1;
+        }
+        reads_valid
+    }
+
+    fn reads_match_versioned_state(&mut self, tx_index: TxIndex, reads: &StateMaps) -> bool {
         // If is the first transaction in the chunk, then the read set is valid. Since it has no
         // predecessors, there's nothing to compare it to.
         if tx_index == 0 {
@@ -233,6 +248,10 @@ impl<S: StateReader> ThreadSafeVersionedState<S> {
         VersionedStateProxy { tx_index, state: self.0.clone() }
     }
 
+    pub fn n_read_conflicts(&self) -> usize {
+        self.0.lock().expect("Failed to acquire state lock.").n_read_conflicts()
+    }
+
     pub fn into_inner_state(self) -> VersionedState<S> {
         Arc::try_unwrap(self.0)
             .unwrap_or_else(|_| {
@@ -1,13 +1,18 @@
 use std::collections::{HashMap, HashSet};
 use std::fmt::Debug;
-use std::sync::Mutex;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
 use std::thread;
 use std::time::Duration;
 
 use starknet_api::core::ClassHash;
 
 use super::versioned_state::VersionedState;
-use crate::blockifier::transaction_executor::TransactionExecutorError;
+use crate::blockifier::transaction_executor::{
+    CommittedTransaction,
+    TransactionCommitObserver,
+    TransactionExecutorError,
+};
 use crate::bouncer::Bouncer;
 use crate::concurrency::fee_utils::complete_fee_transfer_flow;
 use crate::concurrency::scheduler::{Scheduler, Task};
@@ -48,6 +53,8 @@ pub struct WorkerExecutor<'a, S: StateReader> {
     pub execution_outputs: Box<[Mutex<Option<ExecutionTaskOutput>>]>,
     pub block_context: &'a BlockContext,
     pub bouncer: Mutex<&'a mut Bouncer>,
+    pub commit_observer: Option<Arc<dyn TransactionCommitObserver>>,
+    timed_out: AtomicBool,
 }
 impl<'a, S: StateReader> WorkerExecutor<'a, S> {
     pub fn new(
@@ -60,7 +67,16 @@ impl<'a, S: StateReader> WorkerExecutor<'a, S> {
         let execution_outputs =
             std::iter::repeat_with(|| Mutex::new(None)).take(chunk.len()).collect();
 
-        WorkerExecutor { scheduler, state, chunk, execution_outputs, block_context, bouncer }
+        WorkerExecutor {
+            scheduler,
+            state,
+            chunk,
+            execution_outputs,
+            block_context,
+            bouncer,
+            commit_observer: None,
+            timed_out: AtomicBool::new(false),
+        }
     }
 
     // TODO(barak, 01/08/2024): Remove the `new` method or move it to test utils.
@@ -83,9 +99,24 @@ impl<'a, S: StateReader> WorkerExecutor<'a, S> {
             execution_outputs,
             block_context,
             bouncer,
+            commit_observer: None,
+            timed_out: AtomicBool::new(false),
         }
     }
 
+    /// Returns whether the chunk was halted since its deadline passed.
+    pub fn timed_out(&self) -> bool {
+        self.timed_out.load(Ordering::Acquire)
+    }
+
+    fn halt_if_deadline_passed(&self) -> bool {
+        let deadline_passed = self.block_context.execution_deadline_passed();
+        if deadline_passed {
+            self.timed_out.store(true, Ordering::Release);
+        }
+        deadline_passed
+    }
+
     pub fn run(&self) {
         let mut task = Task::AskForTask;
         loop {
@@ -194,7 +225,13 @@ impl<'a, S: StateReader> WorkerExecutor<'a, S> {
     ///         - Else (no room), do not commit. The block should be closed without the transaction.
     ///     * Else (execution failed), commit the transaction without fixing the call info or
     ///       updating the sequencer balance.
+    /// Transactions are not committed once the execution deadline of the block passes, since
+    /// their execution may have been cut off; the chunk is halted instead.
     fn commit_tx(&self, tx_index: TxIndex) -> bool {
+        if self.halt_if_deadline_passed() {
+            return false;
+        }
+
         let execution_output = lock_mutex_in_array(&self.execution_outputs, tx_index);
         let execution_output_ref = execution_output.as_ref().expect(EXECUTION_OUTPUTS_UNWRAP_ERROR);
         let reads = &execution_output_ref.reads;
@@ -219,6 +256,10 @@ impl<'a, S: StateReader> WorkerExecutor<'a, S> {
             let read_set = &execution_output.as_ref().expect(EXECUTION_OUTPUTS_UNWRAP_ERROR).reads;
             // Another validation after the re-execution for sanity check.
             assert!(tx_versioned_state.validate_reads(read_set));
+            drop(execution_output);
+            if self.halt_if_deadline_passed() {
+                return false;
+            }
         } else {
             // Release the execution output lock, since it is has been released in the other flow.
             drop(execution_output);
@@ -248,18 +289,27 @@ impl<'a, S: StateReader> WorkerExecutor<'a, S> {
                 &tx_execution_info.summarize(&self.block_context.versioned_constants),
                 &tx_execution_info.receipt.resources,
             );
-            if let Err(error) = bouncer_result {
-                match error {
-                    TransactionExecutorError::BlockFull => return false,
-                    _ => {
-                        // TODO(Avi, 01/07/2024): Consider propagating the error.
-                        panic!("Bouncer update failed. {error:?}: {error}");
-                    }
+            let tx_bouncer_weights = match bouncer_result {
+                Ok(tx_bouncer_weights) => tx_bouncer_weights,
+                Err(TransactionExecutorError::BlockFull) => return false,
+                Err(error) => {
+                    // TODO(Avi, 01/07/2024): Consider propagating the error.
+                    panic!("Bouncer update failed. {error:?}: {error}");
                 }
-            }
+            };
             complete_fee_transfer_flow(&tx_context, tx_execution_info, &mut tx_versioned_state);
             // Optimization: changing the sequencer balance storage cell does not trigger
             // (re-)validation of the next transactions.
+
+            // Commits are sequential, so the observer is notified in the order of the block.
+            if let Some(commit_observer) = &self.commit_observer {
+                commit_observer.on_tx_committed(CommittedTransaction {
+                    tx_hash: Transaction::tx_hash(&self.chunk[tx_index]),
+                    execution_info: tx_execution_info,
+                    state_changes_keys: &tx_state_changes_keys,
+                    bouncer_weights: tx_bouncer_weights,
+                });
+            }
         }
 
         true
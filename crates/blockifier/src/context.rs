@@ -1,4 +1,5 @@
 use std::collections::BTreeMap;
+use std::time::Instant;
 
 use papyrus_config::dumping::{append_sub_config_name, ser_param, SerializeConfig};
 use papyrus_config::{ParamPath, ParamPrivacyInput, SerializedParam};
@@ -70,6 +71,9 @@ pub struct BlockContext {
     pub(crate) chain_info: ChainInfo,
     pub(crate) versioned_constants: VersionedConstants,
     pub(crate) bouncer_config: BouncerConfig,
+    // Transactions still running at the deadline are cut off. Does not affect the results of the
+    // transactions that finish before it.
+    pub(crate) execution_deadline: Option<Instant>,
 }
 
 impl BlockContext {
@@ -79,7 +83,13 @@ impl BlockContext {
         versioned_constants: VersionedConstants,
         bouncer_config: BouncerConfig,
     ) -> Self {
-        BlockContext { block_info, chain_info, versioned_constants, bouncer_config }
+        BlockContext {
+            block_info,
+            chain_info,
+            versioned_constants,
+            bouncer_config,
+            execution_deadline: None,
+        }
     }
 
     pub fn block_info(&self) -> &BlockInfo {
@@ -94,6 +104,10 @@ impl BlockContext {
         &self.versioned_constants
     }
 
+    pub(crate) fn execution_deadline_passed(&self) -> bool {
+        self.execution_deadline.is_some_and(|deadline| Instant::now() >= deadline)
+    }
+
     pub fn to_tx_context(
         &self,
         tx_info_creator: &impl TransactionInfoCreator,
@@ -426,7 +426,7 @@ impl<'a> DeprecatedSyscallHintProcessor<'a> {
 
 impl ResourceTracker for DeprecatedSyscallHintProcessor<'_> {
     fn consumed(&self) -> bool {
-        self.context.vm_run_resources.consumed()
+        self.context.vm_run_consumed()
     }
 
     fn consume_step(&mut self) {
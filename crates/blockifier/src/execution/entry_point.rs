@@ -44,6 +44,10 @@ pub mod test;
 pub const FAULTY_CLASS_HASH: &str =
     "0x1A7820094FEAF82D53F53F214B81292D717E7BB9A92BB2488092CD306F3993F";
 
+// The number of VM steps between checks of the execution deadline, since reading the clock on every
+// step is costly.
+const DEADLINE_CHECK_INTERVAL_STEPS: usize = 10_000;
+
 pub type EntryPointExecutionResult<T> = Result<T, EntryPointExecutionError>;
 pub type ConstructorEntryPointExecutionResult<T> = Result<T, ConstructorEntryPointExecutionError>;
 
@@ -329,6 +333,15 @@ impl EntryPointExecutionContext {
         min(tx_upper_bound, block_upper_bound)
     }
 
+    /// Returns whether the VM run should stop, since either the available steps were consumed or
+    /// the execution deadline of the block passed.
+    pub fn vm_run_consumed(&self) -> bool {
+        self.vm_run_resources.consumed()
+            || (self.vm_run_resources.get_n_steps().is_some_and(|n_remaining_steps| {
+                n_remaining_steps % DEADLINE_CHECK_INTERVAL_STEPS == 0
+            }) && self.tx_context.block_context.execution_deadline_passed())
+    }
+
     /// Returns the available steps in run resources.
     pub fn n_remaining_steps(&self) -> usize {
         self.vm_run_resources.get_n_steps().expect("The number of steps must be initialized.")
@@ -636,7 +636,7 @@ impl<'a> SyscallHintProcessor<'a> {
 
 impl ResourceTracker for SyscallHintProcessor<'_> {
     fn consumed(&self) -> bool {
-        self.base.context.vm_run_resources.consumed()
+        self.base.context.vm_run_consumed()
     }
 
     fn consume_step(&mut self) {
@@ -1,5 +1,6 @@
 use std::collections::{HashMap, HashSet};
 use std::sync::Arc;
+use std::time::Instant;
 
 use cairo_vm::types::builtin_name::BuiltinName;
 use cairo_vm::vm::runners::cairo_runner::ResourceTracker;
@@ -429,6 +430,39 @@ fn test_infinite_recursion(
     }
 }
 
+#[rstest]
+// A run that passes the execution deadline is cut off, as if its steps ran out.
+fn test_recursion_cut_off_at_deadline(
+    #[values(true, false)] deadline_passed: bool,
+    mut block_context: BlockContext,
+) {
+    if deadline_passed {
+        block_context.execution_deadline = Some(Instant::now());
+    }
+    let TestInitData { mut state, account_address, contract_address, mut nonce_manager } =
+        create_test_init_data(&block_context.chain_info, CairoVersion::Cairo0);
+
+    let tx = account_invoke_tx(invoke_tx_args! {
+        sender_address: account_address,
+        calldata: create_calldata(contract_address, "recurse", &[felt!(10000_u32)]),
+        nonce: nonce_manager.next(account_address),
+    });
+    let charge_fee = tx.enforce_fee();
+    // Without validation, the execution is the first run of the transaction.
+    let tx_execution_info = tx.execute(&mut state, &block_context, charge_fee, false).unwrap();
+    if deadline_passed {
+        assert!(
+            tx_execution_info
+                .revert_error
+                .unwrap()
+                .to_string()
+                .contains("RunResources has no remaining steps.")
+        );
+    } else {
+        assert!(tx_execution_info.revert_error.is_none());
+    }
+}
+
 /// Tests that validation fails on insufficient steps if max fee is too low.
 #[rstest]
 #[case::v1(TransactionVersion::ONE, default_l1_resource_bounds())]
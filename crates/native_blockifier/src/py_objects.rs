@@ -145,6 +145,7 @@ impl From<PyConcurrencyConfig> for ConcurrencyConfig {
             enabled: py_concurrency_config.enabled,
             n_workers: py_concurrency_config.n_workers,
             chunk_size: py_concurrency_config.chunk_size,
+            adaptive: false,
         }
     }
 }
@@ -1,13 +1,18 @@
+use std::cmp::min;
 use std::collections::BTreeMap;
 use std::future::IntoFuture;
+use std::ops::RangeInclusive;
+use std::sync::Arc;
 
 use alloy_contract::{ContractInstance, Interface};
 use alloy_dyn_abi::SolType;
 use alloy_json_rpc::RpcError;
 pub(crate) use alloy_primitives::Address as EthereumContractAddress;
+use alloy_primitives::{Log, U256};
 use alloy_provider::network::Ethereum;
 use alloy_provider::{Provider, ProviderBuilder, RootProvider};
-use alloy_sol_types::sol_data;
+use alloy_rpc_types_eth::Filter;
+use alloy_sol_types::{sol, sol_data, SolEvent};
 use alloy_transport::TransportErrorKind;
 use alloy_transport_http::{Client, Http};
 use async_trait::async_trait;
@@ -15,11 +20,55 @@ use papyrus_config::dumping::{ser_param, ser_required_param, SerializeConfig};
 use papyrus_config::{ParamPath, ParamPrivacyInput, SerializationType, SerializedParam};
 use serde::{Deserialize, Serialize};
 use starknet_api::block::{BlockHash, BlockNumber};
+use starknet_api::core::{ContractAddress, EntryPointSelector, EthAddress, Nonce};
 use starknet_api::hash::StarkHash;
-use starknet_types_core::felt;
+use starknet_api::transaction::fields::{Calldata, Fee};
+use starknet_api::StarknetApiError;
+use starknet_types_core::felt::{self, Felt};
 use url::Url;
 
-use crate::BaseLayerContract;
+use crate::{BaseLayerContract, L1BlockHash, L1BlockNumber, L1Event, L1ToL2Message};
+
+// The maximal number of blocks whose events are fetched in a single logs query.
+const MAX_BLOCKS_PER_LOGS_QUERY: u64 = 1000;
+
+// The L1 to L2 messaging events of the Starknet contract.
+sol! {
+    event LogMessageToL2(
+        address indexed fromAddress,
+        uint256 indexed toAddress,
+        uint256 indexed selector,
+        uint256[] payload,
+        uint256 nonce,
+        uint256 fee
+    );
+
+    event ConsumedMessageToL2(
+        address indexed fromAddress,
+        uint256 indexed toAddress,
+        uint256 indexed selector,
+        uint256[] payload,
+        uint256 nonce
+    );
+
+    event MessageToL2CancellationStarted(
+        address indexed fromAddress,
+        uint256 indexed toAddress,
+        uint256 indexed selector,
+        uint256[] payload,
+        uint256 nonce
+    );
+
+    event MessageToL2Canceled(
+        address indexed fromAddress,
+        uint256 indexed toAddress,
+        uint256 indexed selector,
+        uint256[] payload,
+        uint256 nonce
+    );
+}
+
+pub type EthereumBaseLayerResult<T> = Result<T, EthereumBaseLayerError>;
 
 #[derive(thiserror::Error, Debug)]
 pub enum EthereumBaseLayerError {
@@ -27,12 +76,18 @@ pub enum EthereumBaseLayerError {
     Contract(#[from] alloy_contract::Error),
     #[error(transparent)]
     FeltParseError(#[from] felt::FromStrError),
+    #[error("L1 to L2 message fee {0} does not fit in 128 bits.")]
+    FeeOutOfRange(U256),
     #[error(transparent)]
     RpcError(#[from] RpcError<TransportErrorKind>),
     #[error(transparent)]
     Serde(#[from] serde_json::Error),
     #[error(transparent)]
+    StarknetApi(#[from] StarknetApiError),
+    #[error(transparent)]
     TypeError(#[from] alloy_sol_types::Error),
+    #[error("Unhandled event: {0:?}.")]
+    UnhandledEvent(Log),
 }
 
 #[derive(Debug, Deserialize, Serialize, Clone, PartialEq)]
@@ -124,4 +179,121 @@ impl BaseLayerContract for EthereumBaseLayerContract {
             BlockHash(StarkHash::from_hex(&state_block_hash.to_string())?),
         )))
     }
+
+    async fn latest_l1_block_number(
+        &self,
+        finality: u64,
+    ) -> Result<Option<L1BlockNumber>, Self::Error> {
+        Ok(self.contract.provider().get_block_number().await?.checked_sub(finality))
+    }
+
+    async fn l1_block_hash(
+        &self,
+        block_number: L1BlockNumber,
+    ) -> Result<Option<L1BlockHash>, Self::Error> {
+        let block =
+            self.contract.provider().get_block_by_number(block_number.into(), false).await?;
+        Ok(block.map(|block| block.header.hash.0))
+    }
+
+    async fn events(
+        &self,
+        block_range: RangeInclusive<L1BlockNumber>,
+    ) -> Result<Vec<L1Event>, Self::Error> {
+        let filter = Filter::new().address(*self.contract.address()).event_signature(vec![
+            LogMessageToL2::SIGNATURE_HASH,
+            ConsumedMessageToL2::SIGNATURE_HASH,
+            MessageToL2CancellationStarted::SIGNATURE_HASH,
+            MessageToL2Canceled::SIGNATURE_HASH,
+        ]);
+
+        // The range is queried in bounded chunks, since node providers limit the range of a query.
+        let mut events = Vec::new();
+        let mut from_block = *block_range.start();
+        while from_block <= *block_range.end() {
+            let to_block =
+                min(from_block.saturating_add(MAX_BLOCKS_PER_LOGS_QUERY - 1), *block_range.end());
+            let chunk_filter = filter.clone().from_block(from_block).to_block(to_block);
+            let logs = self.contract.provider().get_logs(&chunk_filter).await?;
+            for log in logs {
+                events.push(parse_event(&log.inner)?);
+            }
+            let Some(next_block) = to_block.checked_add(1) else {
+                break;
+            };
+            from_block = next_block;
+        }
+        Ok(events)
+    }
+}
+
+fn parse_event(log: &Log) -> EthereumBaseLayerResult<L1Event> {
+    let Some(&signature) = log.topics().first() else {
+        return Err(EthereumBaseLayerError::UnhandledEvent(log.clone()));
+    };
+
+    if signature == LogMessageToL2::SIGNATURE_HASH {
+        let event = LogMessageToL2::decode_log(log, true)?.data;
+        let fee = Fee(event
+            .fee
+            .try_into()
+            .map_err(|_| EthereumBaseLayerError::FeeOutOfRange(event.fee))?);
+        let message = l1_to_l2_message(
+            event.fromAddress,
+            event.toAddress,
+            event.selector,
+            &event.payload,
+            event.nonce,
+        )?;
+        Ok(L1Event::LogMessageToL2 { message, fee })
+    } else if signature == ConsumedMessageToL2::SIGNATURE_HASH {
+        let event = ConsumedMessageToL2::decode_log(log, true)?.data;
+        Ok(L1Event::ConsumedMessageToL2(l1_to_l2_message(
+            event.fromAddress,
+            event.toAddress,
+            event.selector,
+            &event.payload,
+            event.nonce,
+        )?))
+    } else if signature == MessageToL2CancellationStarted::SIGNATURE_HASH {
+        let event = MessageToL2CancellationStarted::decode_log(log, true)?.data;
+        Ok(L1Event::MessageToL2CancellationStarted(l1_to_l2_message(
+            event.fromAddress,
+            event.toAddress,
+            event.selector,
+            &event.payload,
+            event.nonce,
+        )?))
+    } else if signature == MessageToL2Canceled::SIGNATURE_HASH {
+        let event = MessageToL2Canceled::decode_log(log, true)?.data;
+        Ok(L1Event::MessageToL2Canceled(l1_to_l2_message(
+            event.fromAddress,
+            event.toAddress,
+            event.selector,
+            &event.payload,
+            event.nonce,
+        )?))
+    } else {
+        Err(EthereumBaseLayerError::UnhandledEvent(log.clone()))
+    }
+}
+
+fn l1_to_l2_message(
+    from_address: EthereumContractAddress,
+    to_address: U256,
+    selector: U256,
+    payload: &[U256],
+    nonce: U256,
+) -> EthereumBaseLayerResult<L1ToL2Message> {
+    Ok(L1ToL2Message {
+        from_address: EthAddress::try_from(Felt::from_bytes_be_slice(from_address.as_slice()))?,
+        to_address: ContractAddress::try_from(u256_to_felt(to_address))?,
+        entry_point_selector: EntryPointSelector(u256_to_felt(selector)),
+        payload: Calldata(Arc::new(payload.iter().copied().map(u256_to_felt).collect())),
+        nonce: Nonce(u256_to_felt(nonce)),
+    })
+}
+
+fn u256_to_felt(value: U256) -> Felt {
+    Felt::from_bytes_be(&value.to_be_bytes::<32>())
 }
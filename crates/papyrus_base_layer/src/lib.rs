@@ -1,5 +1,9 @@
+use std::ops::RangeInclusive;
+
 use async_trait::async_trait;
 use starknet_api::block::{BlockHash, BlockNumber};
+use starknet_api::core::{ContractAddress, EntryPointSelector, EthAddress, Nonce};
+use starknet_api::transaction::fields::{Calldata, Fee};
 
 pub mod ethereum_base_layer_contract;
 
@@ -20,4 +24,49 @@ pub trait BaseLayerContract {
         &self,
         finality: u64,
     ) -> Result<Option<(BlockNumber, BlockHash)>, Self::Error>;
+
+    /// Get the latest block number on the base layer.
+    /// Optionally, require minimum confirmations.
+    async fn latest_l1_block_number(
+        &self,
+        finality: u64,
+    ) -> Result<Option<L1BlockNumber>, Self::Error>;
+
+    /// Get the hash of the base layer block with the given number, if it exists.
+    async fn l1_block_hash(
+        &self,
+        block_number: L1BlockNumber,
+    ) -> Result<Option<L1BlockHash>, Self::Error>;
+
+    /// Get the L1 to L2 messaging events emitted by the Starknet contract in the given range of
+    /// base layer blocks, ordered by their emission.
+    async fn events(
+        &self,
+        block_range: RangeInclusive<L1BlockNumber>,
+    ) -> Result<Vec<L1Event>, Self::Error>;
+}
+
+pub type L1BlockNumber = u64;
+pub type L1BlockHash = [u8; 32];
+
+/// An L1 to L2 messaging event emitted by the Starknet contract.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum L1Event {
+    /// A message was sent to L2, to be handled by an L1 handler transaction.
+    LogMessageToL2 { message: L1ToL2Message, fee: Fee },
+    /// A message was consumed on L1, following the proof of the L2 block that handled it.
+    ConsumedMessageToL2(L1ToL2Message),
+    /// The sender started the cancellation of an unconsumed message.
+    MessageToL2CancellationStarted(L1ToL2Message),
+    /// The cancellation of a message was completed; it can no longer be consumed.
+    MessageToL2Canceled(L1ToL2Message),
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct L1ToL2Message {
+    pub from_address: EthAddress,
+    pub to_address: ContractAddress,
+    pub entry_point_selector: EntryPointSelector,
+    pub payload: Calldata,
+    pub nonce: Nonce,
 }
@@ -124,34 +124,14 @@ impl BlockifierStateReader for ExecutionStateReader {
                 }
             }
         }
-        let block_number = self
-            .storage_reader
+        self.storage_reader
             .begin_ro_txn()
             .map_err(storage_err_to_state_err)?
             .get_state_reader()
             .map_err(storage_err_to_state_err)?
-            .get_class_definition_block_number(&class_hash)
+            .get_compiled_class_hash(&class_hash)
             .map_err(storage_err_to_state_err)?
-            .ok_or(StateError::UndeclaredClassHash(class_hash))?;
-
-        let state_diff = self
-            .storage_reader
-            .begin_ro_txn()
-            .map_err(storage_err_to_state_err)?
-            .get_state_diff(block_number)
-            .map_err(storage_err_to_state_err)?
-            .ok_or(StateError::StateReadError(format!(
-                "Inner storage error. Missing state diff at block {block_number}."
-            )))?;
-
-        let compiled_class_hash = state_diff.declared_classes.get(&class_hash).ok_or(
-            StateError::StateReadError(format!(
-                "Inner storage error. Missing class declaration at block {block_number}, class \
-                 {class_hash}."
-            )),
-        )?;
-
-        Ok(*compiled_class_hash)
+            .ok_or(StateError::UndeclaredClassHash(class_hash))
     }
 }
 
@@ -21,8 +21,10 @@ use papyrus_storage::body::BodyStorageWriter;
 use papyrus_storage::class::ClassStorageWriter;
 use papyrus_storage::compiled_class::CasmStorageWriter;
 use papyrus_storage::header::HeaderStorageWriter;
+use papyrus_storage::snapshot::{export_state_snapshot, import_state_snapshot};
 use papyrus_storage::state::StateStorageWriter;
-use papyrus_storage::test_utils::get_test_storage;
+use papyrus_storage::test_utils::{get_test_storage, TestStorageBuilder};
+use papyrus_storage::{open_storage, StorageScope};
 use starknet_api::block::{BlockBody, BlockHash, BlockHeader, BlockHeaderWithoutHash, BlockNumber};
 use starknet_api::core::{ClassHash, CompiledClassHash, Nonce};
 use starknet_api::hash::StarkHash;
@@ -264,3 +266,54 @@ fn serialization_precision() {
     let deserialized = serde_json::to_string(&serialized).unwrap();
     assert_eq!(input, deserialized);
 }
+
+#[test]
+fn compiled_class_hash_after_snapshot_import() {
+    let class_hash = ClassHash(2u128.into());
+    let compiled_class_hash = CompiledClassHash(StarkHash::TWO);
+    let ((_, mut source_writer), source_config, source_temp_dir) =
+        TestStorageBuilder::default().build();
+    source_writer
+        .begin_rw_txn()
+        .unwrap()
+        .append_header(BlockNumber(0), &BlockHeader::default())
+        .unwrap()
+        .append_state_diff(
+            BlockNumber(0),
+            ThinStateDiff {
+                declared_classes: indexmap!(class_hash => compiled_class_hash),
+                ..Default::default()
+            },
+        )
+        .unwrap()
+        .append_classes(BlockNumber(0), &[(class_hash, &SierraContractClass::default())], &[])
+        .unwrap()
+        .append_casm(&class_hash, &get_test_casm())
+        .unwrap()
+        .commit()
+        .unwrap();
+    drop(source_writer);
+
+    let snapshot_path = source_temp_dir.path().join("snapshot");
+    export_state_snapshot(
+        source_config.db_config,
+        source_config.mmap_file_config,
+        BlockNumber(0),
+        &snapshot_path,
+    )
+    .unwrap();
+
+    // The storage is opened again after the import, which opens it on its own.
+    let (_, config, _temp_dir) =
+        TestStorageBuilder::default().scope(StorageScope::StateOnly).build();
+    import_state_snapshot(config.clone(), &snapshot_path).unwrap();
+    let (storage_reader, _) = open_storage(config).unwrap();
+
+    let state_reader = ExecutionStateReader {
+        storage_reader,
+        state_number: StateNumber::unchecked_right_after_block(BlockNumber(0)),
+        maybe_pending_data: None,
+        missing_compiled_class: Cell::new(None),
+    };
+    assert_eq!(state_reader.get_compiled_class_hash(class_hash).unwrap(), compiled_class_hash);
+}
@@ -172,7 +172,8 @@ fn verify_storage_scope(storage_reader: &StorageReader) -> RpcResult<()> {
         StorageScope::StateOnly => {
             Err(internal_server_error_with_msg("Unsupported method in state-only scope."))
         }
-        StorageScope::FullArchive => Ok(()),
+        // Requests for pruned blocks fail as for missing blocks, see get_accepted_block_number.
+        StorageScope::FullArchive | StorageScope::Pruned { .. } => Ok(()),
     }
 }
 
@@ -8,7 +8,7 @@ use jsonschema::JSONSchema;
 use papyrus_common::pending_classes::PendingClasses;
 use papyrus_execution::ExecutionConfig;
 use papyrus_storage::test_utils::get_test_storage_by_scope;
-use papyrus_storage::{StorageScope, StorageWriter};
+use papyrus_storage::{StorageReader, StorageScope, StorageWriter};
 use pretty_assertions::assert_eq;
 use regex::Regex;
 use serde::{Deserialize, Serialize};
@@ -66,33 +66,52 @@ pub(crate) fn get_test_rpc_server_and_storage_writer_from_params<T: JsonRpcServe
     pending_classes: Option<Arc<RwLock<PendingClasses>>>,
     storage_scope: Option<StorageScope>,
 ) -> (RpcModule<T>, StorageWriter) {
-    let mock_client = mock_client.unwrap_or_default();
-    let shared_highest_block = shared_highest_block.unwrap_or(get_test_highest_block());
-    let pending_data = pending_data.unwrap_or(get_test_pending_data());
-    let pending_classes = pending_classes.unwrap_or(get_test_pending_classes());
     let storage_scope = storage_scope.unwrap_or_default();
 
     let ((storage_reader, storage_writer), _temp_dir) = get_test_storage_by_scope(storage_scope);
-    let config = get_test_rpc_config();
-    let mock_client_arc = Arc::new(mock_client);
     (
-        T::new(
-            config.chain_id,
-            config.execution_config,
-            storage_reader,
-            config.max_events_chunk_size,
-            config.max_events_keys,
-            BlockHashAndNumber::default(),
+        get_test_rpc_server_from_storage_reader(
+            mock_client,
             shared_highest_block,
             pending_data,
             pending_classes,
-            mock_client_arc,
-        )
-        .into_rpc_module(),
+            storage_reader,
+        ),
         storage_writer,
     )
 }
 
+// Returns an `RPC module` over the given storage, for tests that need a specially configured
+// storage.
+pub(crate) fn get_test_rpc_server_from_storage_reader<T: JsonRpcServerTrait>(
+    mock_client: Option<MockStarknetWriter>,
+    shared_highest_block: Option<Arc<RwLock<Option<BlockHashAndNumber>>>>,
+    pending_data: Option<Arc<RwLock<PendingData>>>,
+    pending_classes: Option<Arc<RwLock<PendingClasses>>>,
+    storage_reader: StorageReader,
+) -> RpcModule<T> {
+    let mock_client = mock_client.unwrap_or_default();
+    let shared_highest_block = shared_highest_block.unwrap_or(get_test_highest_block());
+    let pending_data = pending_data.unwrap_or(get_test_pending_data());
+    let pending_classes = pending_classes.unwrap_or(get_test_pending_classes());
+
+    let config = get_test_rpc_config();
+    let mock_client_arc = Arc::new(mock_client);
+    T::new(
+        config.chain_id,
+        config.execution_config,
+        storage_reader,
+        config.max_events_chunk_size,
+        config.max_events_keys,
+        BlockHashAndNumber::default(),
+        shared_highest_block,
+        pending_data,
+        pending_classes,
+        mock_client_arc,
+    )
+    .into_rpc_module()
+}
+
 // Call a method on the `RPC module` without having to spin up a server.
 // Returns the raw `result field` in JSON-RPC response and the deserialized result if successful.
 // `params_obj` should be serialized to the format that JSON-RPC expects, which is either an array
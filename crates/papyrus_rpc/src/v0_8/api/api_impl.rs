@@ -747,10 +747,21 @@ impl JsonRpcServer for JsonRpcServerImpl {
         // pointing to the next relevant event. Otherwise, we return a continuation token None.
         let mut filtered_events = vec![];
         if start_event_index.0.0 <= latest_block_number {
-            for ((from_address, event_index), content) in txn
-                .iter_events(filter.address, start_event_index, to_block_number)
-                .map_err(internal_server_error)?
-            {
+            // Events filtered by their first key across all contracts are read from the index of
+            // events by first key, if the storage maintains it for the requested blocks.
+            let events_by_first_key = match (filter.address, filter.keys.first()) {
+                (None, Some(first_keys)) if !first_keys.is_empty() => txn
+                    .iter_events_by_first_key(first_keys, start_event_index, to_block_number)
+                    .map_err(internal_server_error)?,
+                _ => None,
+            };
+            let events = match events_by_first_key {
+                Some(events) => events,
+                None => txn
+                    .iter_events(filter.address, start_event_index, to_block_number)
+                    .map_err(internal_server_error)?,
+            };
+            for ((from_address, event_index), content) in events {
                 let block_number = (event_index.0).0;
                 if block_number > to_block_number {
                     break;
@@ -21,8 +21,9 @@ use papyrus_storage::body::{BodyStorageWriter, TransactionIndex};
 use papyrus_storage::class::ClassStorageWriter;
 use papyrus_storage::compiled_class::CasmStorageWriter;
 use papyrus_storage::header::HeaderStorageWriter;
+use papyrus_storage::pruning::PruningStorageWriter;
 use papyrus_storage::state::StateStorageWriter;
-use papyrus_storage::test_utils::get_test_storage;
+use papyrus_storage::test_utils::{get_test_storage, TestStorageBuilder};
 use papyrus_storage::StorageScope;
 use papyrus_test_utils::{
     auto_impl_get_test_instance,
@@ -41,6 +42,7 @@ use reqwest::StatusCode;
 use serde::{Deserialize, Serialize};
 use starknet_api::block::{
     Block as StarknetApiBlock,
+    BlockBody,
     BlockHash,
     BlockHashAndNumber,
     BlockHeader,
@@ -188,6 +190,7 @@ use crate::test_utils::{
     get_test_rpc_config,
     get_test_rpc_server_and_storage_writer,
     get_test_rpc_server_and_storage_writer_from_params,
+    get_test_rpc_server_from_storage_reader,
     method_name_to_spec_method_name,
     raw_call,
     validate_schema,
@@ -2199,6 +2202,82 @@ async fn get_storage_at() {
     assert_matches!(err, Error::Call(err) if err == BLOCK_NOT_FOUND.into());
 }
 
+#[tokio::test]
+async fn get_state_of_pruned_block() {
+    let (module, mut storage_writer) =
+        get_test_rpc_server_and_storage_writer::<JsonRpcServerImpl>();
+    let address = contract_address!("0x1");
+    let key = storage_key!("0x2");
+    for block_number in 0..3 {
+        let header = BlockHeader {
+            block_hash: BlockHash(Felt::from(block_number + 1)),
+            block_header_without_hash: BlockHeaderWithoutHash {
+                block_number: BlockNumber(block_number),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let mut diff = starknet_api::state::ThinStateDiff {
+            storage_diffs: indexmap! { address => indexmap! { key => Felt::from(block_number) } },
+            ..Default::default()
+        };
+        if block_number == 0 {
+            diff.deployed_contracts = indexmap! { address => class_hash!("0x3") };
+        } else {
+            diff.nonces = indexmap! { address => Nonce(Felt::from(block_number)) };
+        }
+        storage_writer
+            .begin_rw_txn()
+            .unwrap()
+            .append_header(BlockNumber(block_number), &header)
+            .unwrap()
+            .append_body(BlockNumber(block_number), BlockBody::default())
+            .unwrap()
+            .append_state_diff(BlockNumber(block_number), diff)
+            .unwrap()
+            .commit()
+            .unwrap();
+    }
+    // Prune the history of blocks 0 and 1.
+    let (txn, _) = storage_writer.begin_rw_txn().unwrap().prune_history(1, 100).unwrap();
+    txn.commit().unwrap();
+
+    let pruned_block_id = BlockId::HashOrNumber(BlockHashOrNumber::Number(BlockNumber(1)));
+    let err = module
+        .call::<_, Felt>("starknet_V0_8_getStorageAt", (address, key, pruned_block_id))
+        .await
+        .unwrap_err();
+    assert_matches!(err, Error::Call(err) if err == BLOCK_NOT_FOUND.into());
+    let err = module
+        .call::<_, Nonce>("starknet_V0_8_getNonce", (pruned_block_id, address))
+        .await
+        .unwrap_err();
+    assert_matches!(err, Error::Call(err) if err == BLOCK_NOT_FOUND.into());
+    let err = module
+        .call::<_, ClassHash>("starknet_V0_8_getClassHashAt", (pruned_block_id, address))
+        .await
+        .unwrap_err();
+    assert_matches!(err, Error::Call(err) if err == BLOCK_NOT_FOUND.into());
+
+    // The state of the retained block is readable.
+    let retained_block_id = BlockId::HashOrNumber(BlockHashOrNumber::Number(BlockNumber(2)));
+    let res = module
+        .call::<_, Felt>("starknet_V0_8_getStorageAt", (address, key, retained_block_id))
+        .await
+        .unwrap();
+    assert_eq!(res, Felt::from(2_u8));
+    let res = module
+        .call::<_, Nonce>("starknet_V0_8_getNonce", (retained_block_id, address))
+        .await
+        .unwrap();
+    assert_eq!(res, Nonce(Felt::from(2_u8)));
+    let res = module
+        .call::<_, ClassHash>("starknet_V0_8_getClassHashAt", (retained_block_id, address))
+        .await
+        .unwrap();
+    assert_eq!(res, class_hash!("0x3"));
+}
+
 fn generate_client_transaction_client_receipt_rpc_transaction_and_rpc_receipt(
     rng: &mut ChaCha8Rng,
 ) -> (ClientTransaction, ClientTransactionReceipt, Transaction, PendingTransactionReceipt) {
@@ -2794,17 +2873,45 @@ impl BlockMetadata {
 }
 
 async fn test_get_events(
+    block_metadatas: Vec<BlockMetadata>,
+    pending_block_metadata: Option<BlockMetadata>,
+    is_pending_up_to_date: bool,
+    filter: EventFilter,
+    expected_result_by_index: Vec<(Vec<EventIndex>, Option<ContinuationTokenAsStruct>)>,
+) {
+    let index_event_keys = false;
+    test_get_events_by_storage(
+        block_metadatas,
+        pending_block_metadata,
+        is_pending_up_to_date,
+        filter,
+        expected_result_by_index,
+        index_event_keys,
+    )
+    .await;
+}
+
+// Like `test_get_events`, over a storage that indexes the events by their first key if
+// `index_event_keys` is set.
+async fn test_get_events_by_storage(
     block_metadatas: Vec<BlockMetadata>,
     pending_block_metadata: Option<BlockMetadata>,
     is_pending_up_to_date: bool,
     mut filter: EventFilter,
     expected_result_by_index: Vec<(Vec<EventIndex>, Option<ContinuationTokenAsStruct>)>,
+    index_event_keys: bool,
 ) {
     let method_name = "starknet_V0_8_getEvents";
     let pending_data = get_test_pending_data();
-    let (module, mut storage_writer) = get_test_rpc_server_and_storage_writer_from_params::<
-        JsonRpcServerImpl,
-    >(None, None, Some(pending_data.clone()), None, None);
+    let ((storage_reader, mut storage_writer), _storage_config, _temp_dir) =
+        TestStorageBuilder::default().index_event_keys(index_event_keys).build();
+    let module = get_test_rpc_server_from_storage_reader::<JsonRpcServerImpl>(
+        None,
+        None,
+        Some(pending_data.clone()),
+        None,
+        storage_reader,
+    );
     let mut rng = get_rng();
 
     let mut event_index_to_event = HashMap::<EventIndex, Event>::new();
@@ -3148,6 +3255,112 @@ async fn get_events_pending_keys_filter() {
     .await;
 }
 
+lazy_static! {
+    static ref BLOCKS_METADATA_FOR_FIRST_KEY_FILTER_TEST: Vec<BlockMetadata> = vec![
+        BlockMetadata(vec![
+            vec![
+                EventMetadata { address: None, keys: Some(vec![KEY0_0.clone()]) },
+                DEFAULT_EVENT_METADATA,
+                EventMetadata { address: None, keys: Some(vec![KEY0_1.clone(), KEY2_0.clone()]) },
+            ],
+            // Only the first key of an event is matched by the first keys of the filter.
+            vec![EventMetadata {
+                address: None,
+                keys: Some(vec![UNRELATED_KEY.clone(), KEY0_0.clone()]),
+            }],
+        ]),
+        BlockMetadata(vec![
+            vec![DEFAULT_EVENT_METADATA],
+            vec![
+                EventMetadata { address: None, keys: Some(vec![KEY0_1.clone()]) },
+                EventMetadata { address: None, keys: Some(vec![KEY0_0.clone()]) },
+            ],
+        ]),
+        BlockMetadata(vec![vec![EventMetadata { address: None, keys: Some(vec![KEY0_0.clone()]) }]]),
+    ];
+    // Events of all the contracts, filtered by their first key only.
+    static ref EVENT_FILTER_FOR_FIRST_KEY_FILTER_TEST: EventFilter = EventFilter {
+        chunk_size: 2,
+        keys: vec![HashSet::from([KEY0_0.clone(), KEY0_1.clone()])],
+        ..Default::default()
+    };
+    static ref EXPECTED_RESULT_BY_INDEX_FOR_FIRST_KEY_FILTER_TEST: Vec<(Vec<EventIndex>, Option<ContinuationTokenAsStruct>,)> = vec![
+        (
+            vec![
+                EventIndex(
+                    TransactionIndex(BlockNumber(0), TransactionOffsetInBlock(0)),
+                    EventIndexInTransactionOutput(0),
+                ),
+                EventIndex(
+                    TransactionIndex(BlockNumber(0), TransactionOffsetInBlock(0)),
+                    EventIndexInTransactionOutput(2),
+                ),
+            ],
+            Some(ContinuationTokenAsStruct(EventIndex(
+                TransactionIndex(BlockNumber(1), TransactionOffsetInBlock(1)),
+                EventIndexInTransactionOutput(0),
+            ))),
+        ),
+        (
+            vec![
+                EventIndex(
+                    TransactionIndex(BlockNumber(1), TransactionOffsetInBlock(1)),
+                    EventIndexInTransactionOutput(0),
+                ),
+                EventIndex(
+                    TransactionIndex(BlockNumber(1), TransactionOffsetInBlock(1)),
+                    EventIndexInTransactionOutput(1),
+                ),
+            ],
+            Some(ContinuationTokenAsStruct(EventIndex(
+                TransactionIndex(BlockNumber(2), TransactionOffsetInBlock(0)),
+                EventIndexInTransactionOutput(0),
+            ))),
+        ),
+        (
+            vec![EventIndex(
+                TransactionIndex(BlockNumber(2), TransactionOffsetInBlock(0)),
+                EventIndexInTransactionOutput(0),
+            )],
+            None,
+        ),
+    ];
+}
+
+#[tokio::test]
+async fn get_events_first_key_filter() {
+    let pending_block_metadata = None;
+    let is_pending_up_to_date = true;
+    let index_event_keys = false;
+    test_get_events_by_storage(
+        BLOCKS_METADATA_FOR_FIRST_KEY_FILTER_TEST.clone(),
+        pending_block_metadata,
+        is_pending_up_to_date,
+        EVENT_FILTER_FOR_FIRST_KEY_FILTER_TEST.clone(),
+        EXPECTED_RESULT_BY_INDEX_FOR_FIRST_KEY_FILTER_TEST.clone(),
+        index_event_keys,
+    )
+    .await;
+}
+
+// The events are read from the index of events by first key, and the continuation tokens point to
+// the same events as without it.
+#[tokio::test]
+async fn get_events_first_key_filter_with_index() {
+    let pending_block_metadata = None;
+    let is_pending_up_to_date = true;
+    let index_event_keys = true;
+    test_get_events_by_storage(
+        BLOCKS_METADATA_FOR_FIRST_KEY_FILTER_TEST.clone(),
+        pending_block_metadata,
+        is_pending_up_to_date,
+        EVENT_FILTER_FOR_FIRST_KEY_FILTER_TEST.clone(),
+        EXPECTED_RESULT_BY_INDEX_FOR_FIRST_KEY_FILTER_TEST.clone(),
+        index_event_keys,
+    )
+    .await;
+}
+
 #[tokio::test]
 async fn get_events_from_block() {
     let blocks_metadata = vec![
@@ -1,6 +1,7 @@
 use jsonrpsee::types::ErrorObjectOwned;
 use papyrus_storage::db::TransactionKind;
 use papyrus_storage::header::HeaderStorageReader;
+use papyrus_storage::pruning::PruningStorageReader;
 use papyrus_storage::{StorageError, StorageReader, StorageTxn};
 use serde::{Deserialize, Serialize};
 use starknet_api::block::{BlockHash, BlockNumber, BlockStatus, BlockTimestamp, GasPrice};
@@ -103,12 +104,12 @@ pub fn get_block_header_by_number<Mode: TransactionKind>(
 
 /// Return the closest block number that corresponds to the given block id and is accepted (i.e not
 /// pending). Latest block means the most advanced block that we've downloaded and that we've
-/// downloaded its state diff.
+/// downloaded its state diff. Blocks whose history was pruned are not found.
 pub(crate) fn get_accepted_block_number<Mode: TransactionKind>(
     txn: &StorageTxn<'_, Mode>,
     block_id: BlockId,
 ) -> Result<BlockNumber, ErrorObjectOwned> {
-    Ok(match block_id {
+    let block_number = match block_id {
         BlockId::HashOrNumber(BlockHashOrNumber::Hash(block_hash)) => {
             let block_number = txn
                 .get_block_number_by_hash(&block_hash)
@@ -135,7 +136,11 @@ pub(crate) fn get_accepted_block_number<Mode: TransactionKind>(
         BlockId::Tag(Tag::Latest | Tag::Pending) => {
             get_latest_block_number(txn)?.ok_or_else(|| ErrorObjectOwned::from(BLOCK_NOT_FOUND))?
         }
-    })
+    };
+    if block_number < txn.get_pruning_marker().map_err(internal_server_error)? {
+        return Err(ErrorObjectOwned::from(BLOCK_NOT_FOUND));
+    }
+    Ok(block_number)
 }
 
 /// Validates that a given block wasn't reverted. Given an instance of this class, we can call its
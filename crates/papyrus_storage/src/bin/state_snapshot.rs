@@ -0,0 +1,126 @@
+use std::path::PathBuf;
+
+use clap::{Arg, ArgMatches, Command};
+use papyrus_storage::db::DbConfig;
+use papyrus_storage::mmap_file::MmapFileConfig;
+use papyrus_storage::snapshot::{export_state_snapshot, import_state_snapshot};
+use papyrus_storage::{StorageConfig, StorageScope};
+use starknet_api::block::BlockNumber;
+use starknet_api::core::ChainId;
+
+/// This executable exports the state of a storage at a given block to a snapshot file, or
+/// initializes a new storage from such a snapshot. The exported storage is opened for reading only,
+/// under the scope it was created with.
+fn main() {
+    let matches = get_cli_matches();
+    match matches.subcommand() {
+        Some(("export", matches)) => {
+            let db_config = get_db_config(matches);
+            let block_number = BlockNumber(
+                matches
+                    .get_one::<String>("block_number")
+                    .expect("Failed parsing block_number")
+                    .parse::<u64>()
+                    .expect("Failed parsing block_number"),
+            );
+            let file_path = get_file_path(matches);
+            match export_state_snapshot(
+                db_config,
+                MmapFileConfig::default(),
+                block_number,
+                &file_path,
+            ) {
+                Ok(()) => println!(
+                    "Exported the state of block {block_number} to file: {} .",
+                    file_path.display()
+                ),
+                Err(e) => println!("Failed exporting the state with error: {}", e),
+            }
+        }
+        Some(("import", matches)) => {
+            let scope = matches
+                .get_one::<String>("scope")
+                .expect("Failed parsing scope")
+                .to_string()
+                .try_into()
+                .expect("Failed parsing scope");
+            let storage_config = get_storage_config(matches, scope);
+            let file_path = get_file_path(matches);
+            match import_state_snapshot(storage_config, &file_path) {
+                Ok(block_number) => println!(
+                    "Imported the state of block {block_number} from file: {} .",
+                    file_path.display()
+                ),
+                Err(e) => println!("Failed importing the state with error: {}", e),
+            }
+        }
+        _ => unreachable!("A subcommand is required."),
+    }
+}
+
+fn get_db_config(matches: &ArgMatches) -> DbConfig {
+    let db_path = matches.get_one::<String>("db_path").expect("Failed parsing db_path");
+    let chain_id = matches.get_one::<String>("chain_id").expect("Failed parsing chain_id");
+    DbConfig {
+        path_prefix: db_path.into(),
+        chain_id: ChainId::Other(chain_id.to_string()),
+        ..Default::default()
+    }
+}
+
+fn get_storage_config(matches: &ArgMatches, scope: StorageScope) -> StorageConfig {
+    StorageConfig { db_config: get_db_config(matches), scope, ..Default::default() }
+}
+
+fn get_file_path(matches: &ArgMatches) -> PathBuf {
+    matches.get_one::<String>("file_path").expect("Failed parsing file_path").into()
+}
+
+/// The db_path and chain_id arguments define the storage, whose data is at <db_path>/<chain_id>.
+/// The export subcommand requires the block_number whose state to export, and the import
+/// subcommand requires the scope of the new storage, StateOnly or Pruned:<retained_blocks>. The
+/// file_path is an optional parameter, otherwise the snapshot is at "state_snapshot.bin".
+fn get_cli_matches() -> ArgMatches {
+    let storage_args = [
+        Arg::new("db_path")
+            .short('d')
+            .long("db_path")
+            .required(true)
+            .help("The path prefix of the storage."),
+        Arg::new("chain_id")
+            .short('c')
+            .long("chain_id")
+            .required(true)
+            .help("The chain id SN_MAIN/SN_SEPOLIA of the storage."),
+        Arg::new("file_path")
+            .short('f')
+            .long("file_path")
+            .default_value("state_snapshot.bin")
+            .help("The path of the snapshot file."),
+    ];
+    Command::new("State snapshot")
+        .subcommand_required(true)
+        .subcommand(
+            Command::new("export")
+                .about("Exports the state right after the given block to a snapshot file.")
+                .args(storage_args.clone())
+                .arg(
+                    Arg::new("block_number")
+                        .short('b')
+                        .long("block_number")
+                        .required(true)
+                        .help("The block number whose state to export."),
+                ),
+        )
+        .subcommand(
+            Command::new("import")
+                .about("Initializes an empty storage from a snapshot file.")
+                .args(storage_args)
+                .arg(
+                    Arg::new("scope").short('s').long("scope").default_value("StateOnly").help(
+                        "The scope of the new storage, StateOnly or Pruned:<retained_blocks>.",
+                    ),
+                ),
+        )
+        .get_matches()
+}
@@ -0,0 +1,94 @@
+use std::process::exit;
+
+use clap::{Arg, ArgAction, ArgMatches, Command};
+use papyrus_storage::db::DbConfig;
+use papyrus_storage::integrity::verify_storage_integrity;
+use papyrus_storage::mmap_file::MmapFileConfig;
+use papyrus_storage::open_storage_read_only;
+use starknet_api::block::BlockNumber;
+use starknet_api::core::ChainId;
+
+/// This executable verifies the integrity of a storage, and prints a report with the first block
+/// that failed each check. Exits with a non-zero status if any check failed. The storage is opened
+/// for reading only, so that it is not modified by the verification.
+fn main() {
+    let matches = get_cli_matches();
+    let db_path = matches.get_one::<String>("db_path").expect("Failed parsing db_path");
+    let chain_id = matches.get_one::<String>("chain_id").expect("Failed parsing chain_id");
+    let start_block = BlockNumber(
+        matches
+            .get_one::<String>("start_block")
+            .expect("Failed parsing start_block")
+            .parse::<u64>()
+            .expect("Failed parsing start_block"),
+    );
+    let end_block = matches
+        .get_one::<String>("end_block")
+        .map(|end_block| BlockNumber(end_block.parse::<u64>().expect("Failed parsing end_block")));
+    let recompute_block_hashes = matches.get_flag("recompute_block_hashes");
+
+    let db_config = DbConfig {
+        path_prefix: db_path.into(),
+        chain_id: ChainId::Other(chain_id.to_string()),
+        ..Default::default()
+    };
+    let reader = open_storage_read_only(db_config, MmapFileConfig::default())
+        .expect("Failed opening storage");
+
+    match verify_storage_integrity(&reader, start_block, end_block, recompute_block_hashes) {
+        Ok(report) => {
+            println!(
+                "{}",
+                serde_json::to_string_pretty(&report).expect("Failed serializing the report")
+            );
+            if !report.is_valid() {
+                exit(1);
+            }
+        }
+        Err(e) => {
+            println!("Failed verifying the storage with error: {}", e);
+            exit(1);
+        }
+    }
+}
+
+/// The db_path and chain_id arguments define the storage, whose data is at <db_path>/<chain_id>.
+/// The blocks from start_block up to end_block (exclusive), or up to the last stored block if it
+/// isn't given, are verified.
+fn get_cli_matches() -> ArgMatches {
+    Command::new("Verify storage integrity")
+        .arg(
+            Arg::new("db_path")
+                .short('d')
+                .long("db_path")
+                .required(true)
+                .help("The path prefix of the storage."),
+        )
+        .arg(
+            Arg::new("chain_id")
+                .short('c')
+                .long("chain_id")
+                .required(true)
+                .help("The chain id SN_MAIN/SN_SEPOLIA of the storage."),
+        )
+        .arg(
+            Arg::new("start_block")
+                .short('b')
+                .long("start_block")
+                .default_value("0")
+                .help("The first block to verify."),
+        )
+        .arg(
+            Arg::new("end_block")
+                .short('e')
+                .long("end_block")
+                .help("The block to stop at (exclusive). Defaults to the last stored block."),
+        )
+        .arg(
+            Arg::new("recompute_block_hashes")
+                .long("recompute_block_hashes")
+                .action(ArgAction::SetTrue)
+                .help("Recompute the hash of each block from its header, which is slow."),
+        )
+        .get_matches()
+}
@@ -2,14 +2,30 @@ use assert_matches::assert_matches;
 use papyrus_test_utils::{get_test_block, get_test_body};
 use pretty_assertions::assert_eq;
 use starknet_api::block::{BlockBody, BlockNumber};
-use starknet_api::transaction::TransactionOffsetInBlock;
+use starknet_api::core::{ContractAddress, Nonce};
+use starknet_api::transaction::{
+    DeployAccountTransaction,
+    DeployAccountTransactionOutput,
+    DeployAccountTransactionV1,
+    InvokeTransaction,
+    InvokeTransactionOutput,
+    InvokeTransactionV0,
+    InvokeTransactionV1,
+    L1HandlerTransaction,
+    L1HandlerTransactionOutput,
+    Transaction,
+    TransactionHash,
+    TransactionOffsetInBlock,
+    TransactionOutput,
+};
+use starknet_api::{contract_address, felt};
 use test_case::test_case;
 
 use crate::body::{BodyStorageReader, BodyStorageWriter, TransactionIndex};
 use crate::db::table_types::Table;
 use crate::db::{DbError, KeyAlreadyExistsError};
 use crate::test_utils::{get_test_storage, get_test_storage_by_scope};
-use crate::{OffsetKind, StorageError, StorageScope, StorageWriter};
+use crate::{MarkerKind, OffsetKind, StorageError, StorageScope, StorageWriter};
 
 #[tokio::test]
 async fn append_body() {
@@ -376,6 +392,133 @@ async fn revert_transactions() {
     );
 }
 
+// Returns a body with an invoke V1 transaction of account 0x1 with nonce 5, an invoke V0
+// transaction of account 0x1, a deploy account transaction of account 0x2 and an L1 handler
+// transaction. Only the invoke V1 and the deploy account transactions have an account nonce.
+fn get_body_with_senders() -> BlockBody {
+    let invoke_v1 = InvokeTransactionV1 {
+        sender_address: account(1),
+        nonce: Nonce(felt!(5_u8)),
+        ..Default::default()
+    };
+    let invoke_v0 = InvokeTransactionV0 { contract_address: account(1), ..Default::default() };
+    BlockBody {
+        transactions: vec![
+            Transaction::Invoke(InvokeTransaction::V1(invoke_v1)),
+            Transaction::Invoke(InvokeTransaction::V0(invoke_v0)),
+            Transaction::DeployAccount(DeployAccountTransaction::V1(
+                DeployAccountTransactionV1::default(),
+            )),
+            Transaction::L1Handler(L1HandlerTransaction::default()),
+        ],
+        transaction_outputs: vec![
+            TransactionOutput::Invoke(InvokeTransactionOutput::default()),
+            TransactionOutput::Invoke(InvokeTransactionOutput::default()),
+            TransactionOutput::DeployAccount(DeployAccountTransactionOutput {
+                contract_address: account(2),
+                ..Default::default()
+            }),
+            TransactionOutput::L1Handler(L1HandlerTransactionOutput::default()),
+        ],
+        transaction_hashes: (0_u8..4).map(|i| TransactionHash(felt!(i))).collect(),
+    }
+}
+
+fn account(address: u8) -> ContractAddress {
+    contract_address!(address)
+}
+
+#[test]
+fn transactions_by_sender_and_nonce() {
+    let ((reader, mut writer), _temp_dir) = get_test_storage();
+    writer
+        .begin_rw_txn()
+        .unwrap()
+        .append_body(BlockNumber(0), get_test_body(2, None, None, None))
+        .unwrap()
+        .append_body(BlockNumber(1), get_body_with_senders())
+        .unwrap()
+        .commit()
+        .unwrap();
+
+    let txn = reader.begin_ro_txn().unwrap();
+    assert_eq!(
+        txn.get_transaction_idx_by_sender_and_nonce(&account(1), &Nonce(felt!(5_u8))).unwrap(),
+        Some(TransactionIndex(BlockNumber(1), TransactionOffsetInBlock(0)))
+    );
+    assert_eq!(
+        txn.get_transaction_idx_by_sender_and_nonce(&account(2), &Nonce::default()).unwrap(),
+        Some(TransactionIndex(BlockNumber(1), TransactionOffsetInBlock(2)))
+    );
+    // The invoke V0 transaction is not indexed.
+    assert!(txn
+        .get_transaction_idx_by_sender_and_nonce(&account(1), &Nonce::default())
+        .unwrap()
+        .is_none());
+    drop(txn);
+
+    writer.begin_rw_txn().unwrap().revert_body(BlockNumber(1)).unwrap().0.commit().unwrap();
+
+    let txn = reader.begin_ro_txn().unwrap();
+    assert!(txn
+        .get_transaction_idx_by_sender_and_nonce(&account(1), &Nonce(felt!(5_u8)))
+        .unwrap()
+        .is_none());
+    assert!(txn
+        .get_transaction_idx_by_sender_and_nonce(&account(2), &Nonce::default())
+        .unwrap()
+        .is_none());
+}
+
+#[test]
+fn index_transactions_by_sender_and_nonce_in_batches() {
+    let ((reader, mut writer), _temp_dir) = get_test_storage();
+    writer
+        .begin_rw_txn()
+        .unwrap()
+        .append_body(BlockNumber(0), get_body_with_senders())
+        .unwrap()
+        .append_body(BlockNumber(1), get_test_body(2, None, None, None))
+        .unwrap()
+        .commit()
+        .unwrap();
+    // Simulate a storage that was written before the transactions were indexed by their sender
+    // and nonce.
+    let sender_and_nonce = (account(1), Nonce(felt!(5_u8)));
+    let wtxn = writer.begin_rw_txn().unwrap();
+    let transaction_sender_nonce_to_idx_table =
+        wtxn.open_table(&wtxn.tables.transaction_sender_nonce_to_idx).unwrap();
+    transaction_sender_nonce_to_idx_table.delete(&wtxn.txn, &sender_and_nonce).unwrap();
+    wtxn.commit().unwrap();
+
+    let get_migration_marker = || {
+        let txn = reader.begin_ro_txn().unwrap();
+        let markers_table = txn.open_table(&txn.tables.markers).unwrap();
+        markers_table.get(&txn.txn, &MarkerKind::SenderNonceIndexMigration).unwrap()
+    };
+
+    // Each call indexes a single block, and records the progress.
+    let (wtxn, done) =
+        writer.begin_rw_txn().unwrap().index_transactions_by_sender_and_nonce(1).unwrap();
+    wtxn.commit().unwrap();
+    assert!(!done);
+    assert_eq!(get_migration_marker(), Some(BlockNumber(1)));
+    assert_eq!(
+        reader
+            .begin_ro_txn()
+            .unwrap()
+            .get_transaction_idx_by_sender_and_nonce(&sender_and_nonce.0, &sender_and_nonce.1)
+            .unwrap(),
+        Some(TransactionIndex(BlockNumber(0), TransactionOffsetInBlock(0)))
+    );
+
+    let (wtxn, done) =
+        writer.begin_rw_txn().unwrap().index_transactions_by_sender_and_nonce(1).unwrap();
+    wtxn.commit().unwrap();
+    assert!(done);
+    assert_eq!(get_migration_marker(), None);
+}
+
 fn append_2_bodies(writer: &mut StorageWriter) {
     writer
         .begin_rw_txn()
@@ -5,6 +5,8 @@
 //!
 //! # Example
 //! ```
+//! use std::collections::HashSet;
+//!
 //! use papyrus_storage::open_storage;
 //! use papyrus_storage::body::TransactionIndex;
 //! use papyrus_storage::body::events::{EventIndex, EventsReader};
@@ -14,6 +16,7 @@
 //! use starknet_api::core::ContractAddress;
 //! use starknet_api::transaction::TransactionOffsetInBlock;
 //! use starknet_api::transaction::EventIndexInTransactionOutput;
+//! use starknet_api::transaction::EventKey;
 //!
 //! # let dir_handle = tempfile::tempdir().unwrap();
 //! # let dir = dir_handle.path().to_path_buf();
@@ -43,12 +46,22 @@
 //! for ((contract_address, event_index), event_content) in contract_events_iterator {
 //!    // Do something with the event.
 //! }
+//! // iterate events from all contracts whose first key is one of the given keys. Returns None if
+//! // the storage doesn't index the events by their first key from the given event index.
+//! let keys = HashSet::from([EventKey::default()]);
+//! if let Some(keyed_events_iterator) =
+//!     txn.iter_events_by_first_key(&keys, event_index, BlockNumber(0))?
+//! {
+//!     for ((contract_address, event_index), event_content) in keyed_events_iterator {
+//!        // Do something with the event.
+//!     }
+//! }
 //! # Ok::<(), papyrus_storage::StorageError>(())
 #[cfg(test)]
 #[path = "events_test.rs"]
 mod events_test;
 
-use std::collections::VecDeque;
+use std::collections::{HashSet, VecDeque};
 
 use serde::{Deserialize, Serialize};
 use starknet_api::block::BlockNumber;
@@ -57,15 +70,16 @@ use starknet_api::transaction::{
     Event,
     EventContent,
     EventIndexInTransactionOutput,
+    EventKey,
     TransactionOutput,
 };
 
 use super::TransactionMetadataTable;
-use crate::body::{EventsTableKey, TransactionIndex};
+use crate::body::{BodyStorageReader, EventsByFirstKeyTableKey, EventsTableKey, TransactionIndex};
 use crate::db::serialization::{NoVersionValueWrapper, VersionZeroWrapper};
 use crate::db::table_types::{CommonPrefix, DbCursor, DbCursorTrait, NoValue, SimpleTable, Table};
-use crate::db::{DbTransaction, RO};
-use crate::{FileHandlers, StorageResult, StorageTxn, TransactionMetadata};
+use crate::db::{DbTransaction, TransactionKind, RO, RW};
+use crate::{FileHandlers, MarkerKind, StorageResult, StorageTxn, TransactionMetadata};
 
 /// An identifier of an event.
 #[derive(Debug, Copy, Clone, Eq, PartialEq, Deserialize, Serialize, PartialOrd, Ord)]
@@ -91,6 +105,25 @@ pub trait EventsReader<'txn, 'env> {
         event_index: EventIndex,
         to_block_number: BlockNumber,
     ) -> StorageResult<EventIter<'txn, 'env>>;
+
+    /// Returns an iterator over the events whose first key is one of the given keys, from all the
+    /// contracts, by the order of the event index. Returns None if the events from the given event
+    /// index onwards aren't indexed by their first key, see
+    /// [`StorageConfig::index_event_keys`](crate::StorageConfig::index_event_keys).
+    ///
+    /// # Arguments
+    /// * keys - the first keys of the events to iterate over.
+    /// * event_index - event index to start iterate from it.
+    /// * to_block_number - block number to stop iterate at it.
+    ///
+    /// # Errors
+    /// Returns [`StorageError`](crate::StorageError) if there was an error.
+    fn iter_events_by_first_key(
+        &'env self,
+        keys: &HashSet<EventKey>,
+        event_index: EventIndex,
+        to_block_number: BlockNumber,
+    ) -> StorageResult<Option<EventIter<'txn, 'env>>>;
 }
 
 // TODO: support all read transactions (including RW).
@@ -109,20 +142,36 @@ impl<'txn, 'env> EventsReader<'txn, 'env> for StorageTxn<'env, RO> {
 
         Ok(EventIter::ByEventIndex(self.iter_events_by_event_index(event_index, to_block_number)?))
     }
+
+    fn iter_events_by_first_key(
+        &'env self,
+        keys: &HashSet<EventKey>,
+        event_index: EventIndex,
+        to_block_number: BlockNumber,
+    ) -> StorageResult<Option<EventIter<'txn, 'env>>> {
+        match self.get_events_by_first_key_marker()? {
+            Some(marker) if marker <= event_index.0.0 => Ok(Some(EventIter::ByFirstKey(
+                self.iter_events_by_first_key_index(keys, event_index, to_block_number)?,
+            ))),
+            _ => Ok(None),
+        }
+    }
 }
 
 // TODO(dvir): add transaction hash to the return value. In the RPC when returning events this is
 // with the transaction hash. We can do it efficiently here because we anyway read the relevant
 // entry in the transaction_metadata table..
 #[allow(missing_docs)]
-/// A wrapper of two iterators [`EventIterByContractAddress`] and [`EventIterByEventIndex`].
+/// A wrapper of the iterators [`EventIterByContractAddress`], [`EventIterByEventIndex`] and
+/// [`EventIterByFirstKey`].
 pub enum EventIter<'txn, 'env> {
     ByContractAddress(EventIterByContractAddress<'env, 'txn>),
     ByEventIndex(EventIterByEventIndex<'txn>),
+    ByFirstKey(EventIterByFirstKey<'env, 'txn>),
 }
 
-/// This iterator is a wrapper of two iterators [`EventIterByContractAddress`]
-/// and [`EventIterByEventIndex`].
+/// This iterator is a wrapper of the iterators [`EventIterByContractAddress`],
+/// [`EventIterByEventIndex`] and [`EventIterByFirstKey`].
 /// With this wrapper we can execute the same code, regardless the
 /// type of iteration used.
 impl Iterator for EventIter<'_, '_> {
@@ -132,6 +181,7 @@ impl Iterator for EventIter<'_, '_> {
         match self {
             EventIter::ByContractAddress(it) => it.next(),
             EventIter::ByEventIndex(it) => it.next(),
+            EventIter::ByFirstKey(it) => it.next(),
         }
         .unwrap_or(None)
     }
@@ -249,6 +299,101 @@ impl EventIterByEventIndex<'_> {
     }
 }
 
+/// This iterator goes over the events whose first key is one of the given keys, in the order of
+/// the event index, using the index of the events by their first key.
+pub struct EventIterByFirstKey<'env, 'txn> {
+    txn: &'txn DbTransaction<'env, RO>,
+    file_handlers: &'txn FileHandlers<RO>,
+    keys: HashSet<EventKey>,
+    start_event_index: EventIndex,
+    to_block_number: BlockNumber,
+    // A cursor for each key, with the next transaction that emitted an event with this key. The
+    // next transaction to return events from is the minimal among them.
+    cursors: Vec<(EventKey, EventsByFirstKeyTableCursor<'txn>, Option<TransactionIndex>)>,
+    // Queue of events to return from the iterator. When this queue is empty, we need to fetch the
+    // events of the next transaction.
+    events_queue: VecDeque<((ContractAddress, EventIndex), EventContent)>,
+    transaction_metadata_table: TransactionMetadataTable<'env>,
+}
+
+impl EventIterByFirstKey<'_, '_> {
+    /// Returns the next event. If there are no more events, returns None.
+    ///
+    /// # Errors
+    /// Returns [`StorageError`](crate::StorageError) if there was an error.
+    fn next(&mut self) -> StorageResult<Option<((ContractAddress, EventIndex), EventContent)>> {
+        while self.events_queue.is_empty() {
+            let Some(tx_index) = self.cursors.iter().filter_map(|(_, _, next)| *next).min() else {
+                return Ok(None);
+            };
+            // The transaction may have emitted events with several of the keys.
+            for (key, cursor, next) in self.cursors.iter_mut() {
+                if *next == Some(tx_index) {
+                    *next = next_transaction_with_key(key, cursor.next()?, self.to_block_number);
+                }
+            }
+            let tx_metadata =
+                self.transaction_metadata_table.get(self.txn, &tx_index)?.unwrap_or_else(|| {
+                    panic!("Transaction metadata not found for transaction index: {tx_index:?}")
+                });
+            let tx_output = self
+                .file_handlers
+                .get_transaction_output_unchecked(tx_metadata.tx_output_location)?;
+            for (i, event) in tx_output.events().iter().enumerate() {
+                let event_index = EventIndex(tx_index, EventIndexInTransactionOutput(i));
+                if event_index < self.start_event_index {
+                    continue;
+                }
+                if event.content.keys.first().is_some_and(|key| self.keys.contains(key)) {
+                    // TODO(dvir): don't clone the events here.
+                    self.events_queue
+                        .push_back(((event.from_address, event_index), event.content.clone()));
+                }
+            }
+        }
+
+        Ok(self.events_queue.pop_front())
+    }
+}
+
+// Returns the transaction index of the entry if it belongs to the key and is not beyond the last
+// block.
+fn next_transaction_with_key(
+    key: &EventKey,
+    entry: Option<(EventsByFirstKeyTableKey, NoValue)>,
+    to_block_number: BlockNumber,
+) -> Option<TransactionIndex> {
+    entry
+        .map(|((entry_key, tx_index), _)| (entry_key, tx_index))
+        .filter(|(entry_key, tx_index)| entry_key == key && tx_index.0 <= to_block_number)
+        .map(|(_, tx_index)| tx_index)
+}
+
+impl<'env, Mode: TransactionKind> StorageTxn<'env, Mode> {
+    // Returns the first block whose events are indexed by their first key, or None if the events
+    // aren't indexed.
+    pub(crate) fn get_events_by_first_key_marker(&self) -> StorageResult<Option<BlockNumber>> {
+        let markers_table = self.open_table(&self.tables.markers)?;
+        Ok(markers_table.get(&self.txn, &MarkerKind::EventsByFirstKey)?)
+    }
+}
+
+impl StorageTxn<'_, RW> {
+    // Starts indexing the events by their first key from the next block, unless they are already
+    // indexed.
+    pub(crate) fn start_indexing_events_by_first_key(self) -> StorageResult<Self> {
+        if self.get_events_by_first_key_marker()?.is_none() {
+            let markers_table = self.open_table(&self.tables.markers)?;
+            markers_table.upsert(
+                &self.txn,
+                &MarkerKind::EventsByFirstKey,
+                &self.get_body_marker()?,
+            )?;
+        }
+        Ok(self)
+    }
+}
+
 impl<'txn, 'env> StorageTxn<'env, RO>
 where
     'env: 'txn,
@@ -339,6 +484,48 @@ where
         it.find_next_event_by_event_index()?;
         Ok(it)
     }
+
+    /// Returns an events iterator that iterates the events whose first key is one of the given
+    /// keys by event index, from the given event index.
+    ///
+    /// # Arguments
+    /// * keys - the first keys of the events to iterate over.
+    /// * event_index - event index to start from the first event with an index greater or equals
+    ///   to.
+    /// * to_block_number - block number to stop iterate at it.
+    ///
+    /// # Errors
+    /// Returns [`StorageError`](crate::StorageError) if there was an error.
+    fn iter_events_by_first_key_index(
+        &'env self,
+        keys: &HashSet<EventKey>,
+        event_index: EventIndex,
+        to_block_number: BlockNumber,
+    ) -> StorageResult<EventIterByFirstKey<'env, 'txn>> {
+        let transaction_metadata_table = self.open_table(&self.tables.transaction_metadata)?;
+        let events_by_first_key_table = self.open_table(&self.tables.events_by_first_key)?;
+        let mut cursors = Vec::with_capacity(keys.len());
+        for key in keys {
+            let mut cursor = events_by_first_key_table.cursor(&self.txn)?;
+            let next = next_transaction_with_key(
+                key,
+                cursor.lower_bound(&(key.clone(), event_index.0))?,
+                to_block_number,
+            );
+            cursors.push((key.clone(), cursor, next));
+        }
+
+        Ok(EventIterByFirstKey {
+            txn: &self.txn,
+            file_handlers: &self.file_handlers,
+            keys: keys.clone(),
+            start_event_index: event_index,
+            to_block_number,
+            cursors,
+            events_queue: VecDeque::new(),
+            transaction_metadata_table,
+        })
+    }
 }
 
 fn get_events_from_tx(
@@ -360,6 +547,9 @@ fn get_events_from_tx(
 /// A cursor of the events table.
 type EventsTableCursor<'txn> =
     DbCursor<'txn, RO, EventsTableKey, NoVersionValueWrapper<NoValue>, CommonPrefix>;
+/// A cursor of the events by first key table.
+type EventsByFirstKeyTableCursor<'txn> =
+    DbCursor<'txn, RO, EventsByFirstKeyTableKey, NoVersionValueWrapper<NoValue>, CommonPrefix>;
 /// A cursor of the transaction outputs table.
 type TransactionMetadataTableCursor<'txn> =
     DbCursor<'txn, RO, TransactionIndex, VersionZeroWrapper<TransactionMetadata>, SimpleTable>;
@@ -1,22 +1,30 @@
+use std::collections::HashSet;
 use std::vec;
 
 use assert_matches::assert_matches;
 use papyrus_test_utils::get_test_block;
 use pretty_assertions::assert_eq;
-use starknet_api::block::BlockNumber;
+use starknet_api::block::{BlockHash, BlockHeader, BlockHeaderWithoutHash, BlockNumber};
+use starknet_api::core::ContractAddress;
 use starknet_api::transaction::{
     Event,
     EventContent,
     EventData,
     EventIndexInTransactionOutput,
+    EventKey,
+    InvokeTransactionOutput,
+    TransactionHash,
     TransactionOffsetInBlock,
+    TransactionOutput,
 };
+use starknet_types_core::felt::Felt;
 
 use crate::body::events::{get_events_from_tx, EventIndex, EventsReader};
 use crate::body::{BodyStorageWriter, TransactionIndex};
 use crate::db::table_types::Table;
 use crate::header::HeaderStorageWriter;
-use crate::test_utils::get_test_storage;
+use crate::test_utils::{get_test_storage, TestStorageBuilder};
+use crate::{open_storage, StorageConfig, StorageReader, StorageWriter};
 
 #[test]
 fn iter_events_by_key() {
@@ -253,3 +261,189 @@ fn get_events_from_tx_test() {
     assert_eq!(get_events_from_tx(events.clone(), tx_index, ca1, 3), vec![]);
     assert_eq!(get_events_from_tx(events.clone(), tx_index, ca2, 3), vec![]);
 }
+
+fn key(key: u8) -> EventKey {
+    EventKey(Felt::from(key))
+}
+
+fn event(from_address: u8, first_key: Option<u8>) -> Event {
+    Event {
+        from_address: ContractAddress::from(from_address),
+        content: EventContent {
+            keys: first_key.map(key).into_iter().collect(),
+            ..Default::default()
+        },
+    }
+}
+
+fn event_index(block_number: u64, tx_offset: usize, event_offset: usize) -> EventIndex {
+    EventIndex(
+        TransactionIndex(BlockNumber(block_number), TransactionOffsetInBlock(tx_offset)),
+        EventIndexInTransactionOutput(event_offset),
+    )
+}
+
+// Appends a block whose transactions emit the given events.
+fn append_block_with_events(
+    writer: &mut StorageWriter,
+    block_number: BlockNumber,
+    events_per_tx: Vec<Vec<Event>>,
+) {
+    let header = BlockHeader {
+        block_hash: BlockHash(Felt::from(block_number.0 + 1)),
+        block_header_without_hash: BlockHeaderWithoutHash { block_number, ..Default::default() },
+        ..Default::default()
+    };
+    let mut body = get_test_block(events_per_tx.len(), None, None, None).body;
+    body.transaction_hashes = (0..events_per_tx.len())
+        .map(|tx_offset| TransactionHash(Felt::from(block_number.0 * 100 + tx_offset as u64)))
+        .collect();
+    body.transaction_outputs = events_per_tx
+        .into_iter()
+        .map(|events| {
+            TransactionOutput::Invoke(InvokeTransactionOutput { events, ..Default::default() })
+        })
+        .collect();
+    writer
+        .begin_rw_txn()
+        .unwrap()
+        .append_header(block_number, &header)
+        .unwrap()
+        .append_body(block_number, body)
+        .unwrap()
+        .commit()
+        .unwrap();
+}
+
+// Appends 2 blocks, where the events are emitted by contracts 1 and 2 with first keys 1, 2 and 3.
+fn append_blocks_with_keyed_events(writer: &mut StorageWriter) {
+    append_block_with_events(
+        writer,
+        BlockNumber(0),
+        vec![
+            vec![event(1, Some(1)), event(2, Some(2)), event(1, Some(3))],
+            vec![event(2, None), event(2, Some(2))],
+        ],
+    );
+    append_block_with_events(
+        writer,
+        BlockNumber(1),
+        vec![vec![event(1, Some(1))], vec![event(1, Some(3))]],
+    );
+}
+
+// Returns the indices of the events whose first key is one of the given keys, or None if they
+// aren't indexed.
+fn get_events_by_first_key(
+    reader: &StorageReader,
+    keys: &[u8],
+    start_event_index: EventIndex,
+    to_block_number: BlockNumber,
+) -> Option<Vec<EventIndex>> {
+    let keys = keys.iter().copied().map(key).collect::<HashSet<_>>();
+    let txn = reader.begin_ro_txn().unwrap();
+    let events =
+        txn.iter_events_by_first_key(&keys, start_event_index, to_block_number).unwrap()?;
+    Some(events.map(|((_, event_index), _)| event_index).collect())
+}
+
+#[test]
+fn iter_events_by_first_key() {
+    let ((reader, mut writer), _config, _temp_dir) =
+        TestStorageBuilder::default().index_event_keys(true).build();
+    append_blocks_with_keyed_events(&mut writer);
+
+    assert_eq!(
+        get_events_by_first_key(&reader, &[1, 2], event_index(0, 0, 0), BlockNumber(1)),
+        Some(vec![
+            event_index(0, 0, 0),
+            event_index(0, 0, 1),
+            event_index(0, 1, 1),
+            event_index(1, 0, 0)
+        ])
+    );
+    assert_eq!(
+        get_events_by_first_key(&reader, &[3], event_index(0, 0, 0), BlockNumber(1)),
+        Some(vec![event_index(0, 0, 2), event_index(1, 1, 0)])
+    );
+    // Start from the middle of a transaction.
+    assert_eq!(
+        get_events_by_first_key(&reader, &[1, 2], event_index(0, 0, 1), BlockNumber(1)),
+        Some(vec![event_index(0, 0, 1), event_index(0, 1, 1), event_index(1, 0, 0)])
+    );
+    // Stop at the first block.
+    assert_eq!(
+        get_events_by_first_key(&reader, &[1, 2], event_index(0, 0, 0), BlockNumber(0)),
+        Some(vec![event_index(0, 0, 0), event_index(0, 0, 1), event_index(0, 1, 1)])
+    );
+    assert_eq!(
+        get_events_by_first_key(&reader, &[4], event_index(0, 0, 0), BlockNumber(1)),
+        Some(vec![])
+    );
+}
+
+#[test]
+fn events_by_first_key_are_indexed_from_enabling() {
+    let ((reader, mut writer), config, _temp_dir) = TestStorageBuilder::default().build();
+    append_block_with_events(&mut writer, BlockNumber(0), vec![vec![event(1, Some(1))]]);
+    assert_eq!(get_events_by_first_key(&reader, &[1], event_index(0, 0, 0), BlockNumber(0)), None);
+    drop(reader);
+    drop(writer);
+
+    let (reader, mut writer) =
+        open_storage(StorageConfig { index_event_keys: true, ..config.clone() }).unwrap();
+    append_block_with_events(&mut writer, BlockNumber(1), vec![vec![event(1, Some(1))]]);
+    assert_eq!(get_events_by_first_key(&reader, &[1], event_index(0, 0, 0), BlockNumber(1)), None);
+    assert_eq!(
+        get_events_by_first_key(&reader, &[1], event_index(1, 0, 0), BlockNumber(1)),
+        Some(vec![event_index(1, 0, 0)])
+    );
+    drop(reader);
+    drop(writer);
+
+    // The index is maintained once enabled.
+    let (reader, mut writer) = open_storage(config).unwrap();
+    append_block_with_events(&mut writer, BlockNumber(2), vec![vec![event(1, Some(1))]]);
+    assert_eq!(
+        get_events_by_first_key(&reader, &[1], event_index(1, 0, 0), BlockNumber(2)),
+        Some(vec![event_index(1, 0, 0), event_index(2, 0, 0)])
+    );
+}
+
+#[test]
+fn revert_events_by_first_key() {
+    let ((reader, mut writer), _config, _temp_dir) =
+        TestStorageBuilder::default().index_event_keys(true).build();
+    append_blocks_with_keyed_events(&mut writer);
+
+    writer
+        .begin_rw_txn()
+        .unwrap()
+        .revert_header(BlockNumber(1))
+        .unwrap()
+        .0
+        .revert_body(BlockNumber(1))
+        .unwrap()
+        .0
+        .commit()
+        .unwrap();
+    assert_eq!(
+        get_events_by_first_key(&reader, &[1, 3], event_index(0, 0, 0), BlockNumber(1)),
+        Some(vec![event_index(0, 0, 0), event_index(0, 0, 2)])
+    );
+    let txn = reader.begin_ro_txn().unwrap();
+    let events_by_first_key_table = txn.txn.open_table(&txn.tables.events_by_first_key).unwrap();
+    let reverted_tx_index = TransactionIndex(BlockNumber(1), TransactionOffsetInBlock(0));
+    assert_matches!(
+        events_by_first_key_table.get(&txn.txn, &(key(1), reverted_tx_index)),
+        Ok(None)
+    );
+    drop(txn);
+
+    // A different block can be appended instead of the reverted one.
+    append_block_with_events(&mut writer, BlockNumber(1), vec![vec![event(2, Some(3))]]);
+    assert_eq!(
+        get_events_by_first_key(&reader, &[1, 3], event_index(1, 0, 0), BlockNumber(1)),
+        Some(vec![event_index(1, 0, 0)])
+    );
+}
@@ -42,14 +42,18 @@
 mod body_test;
 pub mod events;
 
+use std::cmp::min;
 use std::collections::HashSet;
 use std::fmt::Debug;
 
 use papyrus_proc_macros::latency_histogram;
 use serde::{Deserialize, Serialize};
 use starknet_api::block::{BlockBody, BlockNumber};
-use starknet_api::core::ContractAddress;
+use starknet_api::core::{ContractAddress, Nonce};
 use starknet_api::transaction::{
+    DeclareTransaction,
+    EventKey,
+    InvokeTransaction,
     Transaction,
     TransactionHash,
     TransactionOffsetInBlock,
@@ -60,6 +64,7 @@ use tracing::debug;
 use crate::db::serialization::{NoVersionValueWrapper, VersionZeroWrapper};
 use crate::db::table_types::{CommonPrefix, DbCursorTrait, NoValue, SimpleTable, Table};
 use crate::db::{DbTransaction, TableHandle, TransactionKind, RW};
+use crate::pruning::PruningStorageReader;
 use crate::{
     FileHandlers,
     MarkerKind,
@@ -78,9 +83,18 @@ type TransactionMetadataTable<'env> =
     TableHandle<'env, TransactionIndex, VersionZeroWrapper<TransactionMetadata>, SimpleTable>;
 type TransactionHashToIdxTable<'env> =
     TableHandle<'env, TransactionHash, NoVersionValueWrapper<TransactionIndex>, SimpleTable>;
+type TransactionSenderNonceToIdxTable<'env> = TableHandle<
+    'env,
+    (ContractAddress, Nonce),
+    NoVersionValueWrapper<TransactionIndex>,
+    SimpleTable,
+>;
 type EventsTableKey = (ContractAddress, TransactionIndex);
 type EventsTable<'env> =
     TableHandle<'env, EventsTableKey, NoVersionValueWrapper<NoValue>, CommonPrefix>;
+type EventsByFirstKeyTableKey = (EventKey, TransactionIndex);
+type EventsByFirstKeyTable<'env> =
+    TableHandle<'env, EventsByFirstKeyTableKey, NoVersionValueWrapper<NoValue>, CommonPrefix>;
 
 /// The index of a transaction in a block.
 #[derive(Copy, Clone, Debug, Eq, PartialEq, Deserialize, Serialize, PartialOrd, Ord)]
@@ -116,6 +130,15 @@ pub trait BodyStorageReader {
         tx_index: &TransactionIndex,
     ) -> StorageResult<Option<TransactionHash>>;
 
+    /// Returns the index of the transaction that the given account sent with the given nonce.
+    /// Transactions without an account nonce (L1 handler, deploy and version 0 invoke and declare
+    /// transactions) are not indexed.
+    fn get_transaction_idx_by_sender_and_nonce(
+        &self,
+        sender_address: &ContractAddress,
+        nonce: &Nonce,
+    ) -> StorageResult<Option<TransactionIndex>>;
+
     /// Returns the transactions and their execution status of the block with the given number.
     fn get_block_transactions(
         &self,
@@ -217,6 +240,18 @@ impl<'env, Mode: TransactionKind> BodyStorageReader for StorageTxn<'env, Mode> {
         Ok(Some(tx_metadata.tx_hash))
     }
 
+    fn get_transaction_idx_by_sender_and_nonce(
+        &self,
+        sender_address: &ContractAddress,
+        nonce: &Nonce,
+    ) -> StorageResult<Option<TransactionIndex>> {
+        let transaction_sender_nonce_to_idx_table =
+            self.open_table(&self.tables.transaction_sender_nonce_to_idx)?;
+        let idx =
+            transaction_sender_nonce_to_idx_table.get(&self.txn, &(*sender_address, *nonce))?;
+        Ok(idx)
+    }
+
     fn get_block_transactions(
         &self,
         block_number: BlockNumber,
@@ -279,7 +314,7 @@ impl<'env, Mode: TransactionKind> StorageTxn<'env, Mode> {
         transaction_metadata_table: TransactionMetadataTable<'env>,
         tx_metadata_to_tx_object: fn(TransactionMetadata, &FileHandlers<Mode>) -> StorageResult<T>,
     ) -> StorageResult<Option<Vec<T>>> {
-        if self.get_body_marker()? <= block_number {
+        if self.get_body_marker()? <= block_number || block_number < self.get_pruning_marker()? {
             return Ok(None);
         }
         let mut cursor = transaction_metadata_table.cursor(&self.txn)?;
@@ -349,8 +384,14 @@ impl<'env> BodyStorageWriter for StorageTxn<'env, RW> {
 
         if self.scope != StorageScope::StateOnly {
             let events_table = self.open_table(&self.tables.events)?;
+            let events_by_first_key_table = match self.get_events_by_first_key_marker()? {
+                Some(_) => Some(self.open_table(&self.tables.events_by_first_key)?),
+                None => None,
+            };
             let transaction_hash_to_idx_table =
                 self.open_table(&self.tables.transaction_hash_to_idx)?;
+            let transaction_sender_nonce_to_idx_table =
+                self.open_table(&self.tables.transaction_sender_nonce_to_idx)?;
             let transaction_metadata_table = self.open_table(&self.tables.transaction_metadata)?;
             let file_offset_table = self.txn.open_table(&self.tables.file_offsets)?;
 
@@ -360,8 +401,10 @@ impl<'env> BodyStorageWriter for StorageTxn<'env, RW> {
                 &self.file_handlers,
                 &file_offset_table,
                 &transaction_hash_to_idx_table,
+                &transaction_sender_nonce_to_idx_table,
                 &transaction_metadata_table,
                 &events_table,
+                events_by_first_key_table.as_ref(),
                 block_number,
             )?;
         }
@@ -388,6 +431,7 @@ impl<'env> BodyStorageWriter for StorageTxn<'env, RW> {
             );
             return Ok((self, None));
         }
+        self.verify_block_not_pruned(block_number)?;
 
         let reverted_block_body = 'reverted_block_body: {
             if self.scope == StorageScope::StateOnly {
@@ -397,7 +441,13 @@ impl<'env> BodyStorageWriter for StorageTxn<'env, RW> {
             let transaction_metadata_table = self.open_table(&self.tables.transaction_metadata)?;
             let transaction_hash_to_idx_table =
                 self.open_table(&self.tables.transaction_hash_to_idx)?;
+            let transaction_sender_nonce_to_idx_table =
+                self.open_table(&self.tables.transaction_sender_nonce_to_idx)?;
             let events_table = self.open_table(&self.tables.events)?;
+            let events_by_first_key_table = match self.get_events_by_first_key_marker()? {
+                Some(_) => Some(self.open_table(&self.tables.events_by_first_key)?),
+                None => None,
+            };
 
             let transactions = self
                 .get_block_transactions(block_number)?
@@ -410,15 +460,27 @@ impl<'env> BodyStorageWriter for StorageTxn<'env, RW> {
                 .unwrap_or_else(|| panic!("Missing transaction hashes for block {block_number}."));
 
             // Delete the transactions data.
-            for (offset, (tx_hash, tx_output)) in
-                transaction_hashes.iter().zip(transaction_outputs.iter()).enumerate()
+            for (offset, ((tx, tx_hash), tx_output)) in transactions
+                .iter()
+                .zip(transaction_hashes.iter())
+                .zip(transaction_outputs.iter())
+                .enumerate()
             {
                 let tx_index = TransactionIndex(block_number, TransactionOffsetInBlock(offset));
 
                 for event in tx_output.events().iter() {
                     events_table.delete(&self.txn, &(event.from_address, tx_index))?;
+                    if let (Some(events_by_first_key_table), Some(first_key)) =
+                        (&events_by_first_key_table, event.content.keys.first())
+                    {
+                        events_by_first_key_table
+                            .delete(&self.txn, &(first_key.clone(), tx_index))?;
+                    }
                 }
                 transaction_hash_to_idx_table.delete(&self.txn, tx_hash)?;
+                if let Some(sender_and_nonce) = get_sender_and_nonce(tx, tx_output) {
+                    transaction_sender_nonce_to_idx_table.delete(&self.txn, &sender_and_nonce)?;
+                }
                 transaction_metadata_table.delete(&self.txn, &tx_index)?;
             }
             Some((transactions, transaction_outputs, transaction_hashes))
@@ -439,8 +501,10 @@ fn write_transactions<'env>(
     file_handlers: &FileHandlers<RW>,
     file_offset_table: &'env FileOffsetsTable<'env>,
     transaction_hash_to_idx_table: &'env TransactionHashToIdxTable<'env>,
+    transaction_sender_nonce_to_idx_table: &'env TransactionSenderNonceToIdxTable<'env>,
     transaction_metadata_table: &'env TransactionMetadataTable<'env>,
     events_table: &'env EventsTable<'env>,
+    events_by_first_key_table: Option<&'env EventsByFirstKeyTable<'env>>,
     block_number: BlockNumber,
 ) -> StorageResult<()> {
     for (index, ((tx, tx_output), tx_hash)) in block_body
@@ -454,8 +518,15 @@ fn write_transactions<'env>(
         let transaction_index = TransactionIndex(block_number, tx_offset_in_block);
         let tx_location = file_handlers.append_transaction(tx);
         let tx_output_location = file_handlers.append_transaction_output(tx_output);
-        write_events(tx_output, txn, events_table, transaction_index)?;
+        write_events(tx_output, txn, events_table, events_by_first_key_table, transaction_index)?;
         transaction_hash_to_idx_table.insert(txn, tx_hash, &transaction_index)?;
+        if let Some(sender_and_nonce) = get_sender_and_nonce(tx, tx_output) {
+            transaction_sender_nonce_to_idx_table.upsert(
+                txn,
+                &sender_and_nonce,
+                &transaction_index,
+            )?;
+        }
         transaction_metadata_table.append(
             txn,
             &transaction_index,
@@ -476,17 +547,41 @@ fn write_transactions<'env>(
     Ok(())
 }
 
+// Returns the account that sent the transaction and the nonce it used, or None if the transaction
+// doesn't have an account nonce. Version 0 invoke and declare transactions are skipped since
+// their nonce isn't enforced, so an account can have many of them with the same nonce.
+pub(crate) fn get_sender_and_nonce(
+    tx: &Transaction,
+    tx_output: &TransactionOutput,
+) -> Option<(ContractAddress, Nonce)> {
+    match (tx, tx_output) {
+        (Transaction::Invoke(InvokeTransaction::V0(_)), _)
+        | (Transaction::Declare(DeclareTransaction::V0(_)), _) => None,
+        (Transaction::Invoke(tx), _) => Some((tx.sender_address(), tx.nonce())),
+        (Transaction::Declare(tx), _) => Some((tx.sender_address(), tx.nonce())),
+        (Transaction::DeployAccount(tx), TransactionOutput::DeployAccount(tx_output)) => {
+            Some((tx_output.contract_address, tx.nonce()))
+        }
+        _ => None,
+    }
+}
+
 // This function assumes that the `transaction_index` is the last index used to call it.
 fn write_events<'env>(
     tx_output: &TransactionOutput,
     txn: &DbTransaction<'env, RW>,
     events_table: &'env EventsTable<'env>,
+    events_by_first_key_table: Option<&'env EventsByFirstKeyTable<'env>>,
     transaction_index: TransactionIndex,
 ) -> StorageResult<()> {
     let mut contract_addresses_set = HashSet::new();
+    let mut first_keys_set = HashSet::new();
 
     for event in tx_output.events().iter() {
         contract_addresses_set.insert(event.from_address);
+        if let Some(first_key) = event.content.keys.first() {
+            first_keys_set.insert(first_key.clone());
+        }
     }
 
     for contract_address in contract_addresses_set {
@@ -495,9 +590,82 @@ fn write_events<'env>(
         // is a table.
         events_table.append_greater_sub_key(txn, &key, &NoValue)?;
     }
+    if let Some(events_by_first_key_table) = events_by_first_key_table {
+        for first_key in first_keys_set {
+            events_by_first_key_table.append_greater_sub_key(
+                txn,
+                &(first_key, transaction_index),
+                &NoValue,
+            )?;
+        }
+    }
     Ok(())
 }
 
+impl<'env> StorageTxn<'env, RW> {
+    // Indexes the transactions of up to `max_n_blocks` blocks that are already in the storage by
+    // their sender and nonce, from the block where the previous call stopped. Used to migrate
+    // storages that were written before this index existed. Returns whether the transactions of
+    // all the stored blocks are indexed.
+    pub(crate) fn index_transactions_by_sender_and_nonce(
+        self,
+        max_n_blocks: u64,
+    ) -> StorageResult<(Self, bool)> {
+        let done = {
+            let markers_table = self.open_table(&self.tables.markers)?;
+            let transaction_metadata_table = self.open_table(&self.tables.transaction_metadata)?;
+            let transaction_sender_nonce_to_idx_table =
+                self.open_table(&self.tables.transaction_sender_nonce_to_idx)?;
+            let from_block = markers_table
+                .get(&self.txn, &MarkerKind::SenderNonceIndexMigration)?
+                .unwrap_or_default();
+            let body_marker = self.get_body_marker()?;
+            let to_block = min(body_marker, BlockNumber(from_block.0.saturating_add(max_n_blocks)));
+
+            let mut cursor = transaction_metadata_table.cursor(&self.txn)?;
+            let mut current =
+                cursor.lower_bound(&TransactionIndex(from_block, TransactionOffsetInBlock(0)))?;
+            let mut n_indexed_transactions = 0;
+            while let Some((tx_index, tx_metadata)) = current {
+                if tx_index.0 >= to_block {
+                    break;
+                }
+                let tx = self.file_handlers.get_transaction_unchecked(tx_metadata.tx_location)?;
+                let tx_output = self
+                    .file_handlers
+                    .get_transaction_output_unchecked(tx_metadata.tx_output_location)?;
+                if let Some(sender_and_nonce) = get_sender_and_nonce(&tx, &tx_output) {
+                    transaction_sender_nonce_to_idx_table.upsert(
+                        &self.txn,
+                        &sender_and_nonce,
+                        &tx_index,
+                    )?;
+                    n_indexed_transactions += 1;
+                }
+                current = cursor.next()?;
+            }
+            debug!(
+                "Indexed {n_indexed_transactions} transactions of blocks {from_block} to \
+                 {to_block} by their sender and nonce."
+            );
+
+            // The progress marker is kept only while there are blocks left to index.
+            let done = to_block >= body_marker;
+            if done {
+                markers_table.delete(&self.txn, &MarkerKind::SenderNonceIndexMigration)?;
+            } else {
+                markers_table.upsert(
+                    &self.txn,
+                    &MarkerKind::SenderNonceIndexMigration,
+                    &to_block,
+                )?;
+            }
+            done
+        };
+        Ok((self, done))
+    }
+}
+
 fn update_marker<'env>(
     txn: &DbTransaction<'env, RW>,
     markers_table: &'env MarkersTable<'env>,
@@ -73,7 +73,7 @@ use starknet_api::core::ClassHash;
 use starknet_api::deprecated_contract_class::ContractClass as DeprecatedContractClass;
 use starknet_api::state::SierraContractClass;
 
-use crate::db::table_types::Table;
+use crate::db::table_types::{DbCursorTrait, Table};
 use crate::db::{TransactionKind, RW};
 use crate::state::{DeclaredClassesTable, DeprecatedDeclaredClassesTable, FileOffsetTable};
 use crate::{
@@ -100,6 +100,10 @@ pub trait ClassStorageReader {
 
     /// The block marker is the first block number that we don't have all of its classes.
     fn get_class_marker(&self) -> StorageResult<BlockNumber>;
+
+    /// Returns the Cairo 1 classes whose Sierra was not written with the classes of their block,
+    /// with the blocks that declared them, ordered by class hash.
+    fn get_classes_missing_sierra(&self) -> StorageResult<Vec<(ClassHash, BlockNumber)>>;
 }
 
 /// Interface for writing data related to classes or deprecated classes.
@@ -122,6 +126,22 @@ where
         classes: &[(ClassHash, &SierraContractClass)],
         deprecated_classes: &[(ClassHash, &DeprecatedContractClass)],
     ) -> StorageResult<Self>;
+
+    /// Marks Cairo 1 classes declared in the given block as missing their Sierra, when the classes
+    /// of the block were appended without them, so they can be filled later.
+    fn set_classes_missing_sierra(
+        self,
+        block_number: BlockNumber,
+        class_hashes: &[ClassHash],
+    ) -> StorageResult<Self>;
+
+    /// Writes the Sierra of a class that is marked as missing it, and unmarks the class. Classes
+    /// that are not marked as missing their Sierra are ignored.
+    fn fill_class_missing_sierra(
+        self,
+        class_hash: ClassHash,
+        class: &SierraContractClass,
+    ) -> StorageResult<Self>;
 }
 
 impl<'env, Mode: TransactionKind> ClassStorageReader for StorageTxn<'env, Mode> {
@@ -152,6 +172,16 @@ impl<'env, Mode: TransactionKind> ClassStorageReader for StorageTxn<'env, Mode>
         let markers_table = self.open_table(&self.tables.markers)?;
         Ok(markers_table.get(&self.txn, &MarkerKind::Class)?.unwrap_or_default())
     }
+
+    fn get_classes_missing_sierra(&self) -> StorageResult<Vec<(ClassHash, BlockNumber)>> {
+        let classes_missing_sierra_table = self.open_table(&self.tables.classes_missing_sierra)?;
+        let mut cursor = classes_missing_sierra_table.cursor(&self.txn)?;
+        let mut classes_missing_sierra = Vec::new();
+        while let Some(entry) = cursor.next()? {
+            classes_missing_sierra.push(entry);
+        }
+        Ok(classes_missing_sierra)
+    }
 }
 
 impl<'env> ClassStorageWriter for StorageTxn<'env, RW> {
@@ -198,6 +228,40 @@ impl<'env> ClassStorageWriter for StorageTxn<'env, RW> {
 
         Ok(self)
     }
+
+    fn set_classes_missing_sierra(
+        self,
+        block_number: BlockNumber,
+        class_hashes: &[ClassHash],
+    ) -> StorageResult<Self> {
+        let classes_missing_sierra_table = self.open_table(&self.tables.classes_missing_sierra)?;
+        for class_hash in class_hashes {
+            classes_missing_sierra_table.upsert(&self.txn, class_hash, &block_number)?;
+        }
+        Ok(self)
+    }
+
+    fn fill_class_missing_sierra(
+        self,
+        class_hash: ClassHash,
+        class: &SierraContractClass,
+    ) -> StorageResult<Self> {
+        let classes_missing_sierra_table = self.open_table(&self.tables.classes_missing_sierra)?;
+        if classes_missing_sierra_table.get(&self.txn, &class_hash)?.is_none() {
+            return Ok(self);
+        }
+        let declared_classes_table = self.open_table(&self.tables.declared_classes)?;
+        let file_offset_table = self.txn.open_table(&self.tables.file_offsets)?;
+        write_classes(
+            &[(class_hash, class)],
+            &self.txn,
+            &declared_classes_table,
+            &self.file_handlers,
+            &file_offset_table,
+        )?;
+        classes_missing_sierra_table.delete(&self.txn, &class_hash)?;
+        Ok(self)
+    }
 }
 
 fn write_classes<'env>(
@@ -121,3 +121,53 @@ fn append_deprecated_class_not_in_state_diff() {
         expected_deprecated_class
     );
 }
+
+#[test]
+fn fill_class_missing_sierra() {
+    let class_json = read_json_file("class.json");
+    let expected_class: SierraContractClass = serde_json::from_value(class_json).unwrap();
+    let class_hash = ClassHash::default();
+    let other_class_hash = ClassHash(StarkHash::ONE);
+
+    let ((reader, mut writer), _temp_dir) = get_test_storage();
+
+    writer
+        .begin_rw_txn()
+        .unwrap()
+        .append_state_diff(
+            BlockNumber(0),
+            ThinStateDiff {
+                declared_classes: indexmap! { class_hash => CompiledClassHash::default() },
+                ..Default::default()
+            },
+        )
+        .unwrap()
+        .append_classes(BlockNumber(0), &[], &[])
+        .unwrap()
+        .set_classes_missing_sierra(BlockNumber(0), &[class_hash])
+        .unwrap()
+        .commit()
+        .unwrap();
+
+    let txn = reader.begin_ro_txn().unwrap();
+    assert_eq!(txn.get_class_marker().unwrap(), BlockNumber(1));
+    assert_eq!(txn.get_class(&class_hash).unwrap(), None);
+    assert_eq!(txn.get_classes_missing_sierra().unwrap(), vec![(class_hash, BlockNumber(0))]);
+    drop(txn);
+
+    // A class that is not marked as missing its Sierra is ignored.
+    writer
+        .begin_rw_txn()
+        .unwrap()
+        .fill_class_missing_sierra(other_class_hash, &expected_class)
+        .unwrap()
+        .fill_class_missing_sierra(class_hash, &expected_class)
+        .unwrap()
+        .commit()
+        .unwrap();
+
+    let txn = reader.begin_ro_txn().unwrap();
+    assert_eq!(txn.get_class(&class_hash).unwrap(), Some(expected_class));
+    assert_eq!(txn.get_class(&other_class_hash).unwrap(), None);
+    assert!(txn.get_classes_missing_sierra().unwrap().is_empty());
+}
@@ -43,7 +43,7 @@ use self::table_types::{DbCursor, DbCursorTrait};
 use crate::db::table_types::TableType;
 
 // Maximum number of Sub-Databases.
-const MAX_DBS: usize = 18;
+const MAX_DBS: usize = 24;
 
 // Note that NO_TLS mode is used by default.
 type EnvironmentKind = WriteMap;
@@ -160,6 +160,10 @@ pub enum DbError {
     /// An error that occurred when trying to open a db file that does not exist.
     #[error("The file '{0}' does not exist.")]
     FileDoesNotExist(PathBuf),
+    /// An error that occurred when trying to open a table that does not exist in a database opened
+    /// for reading only.
+    #[error("The table '{0}' does not exist. Open the storage for writing once to create it.")]
+    TableDoesNotExist(&'static str),
     // TODO(dvir): consider adding more details about the error, table name, key, value and last
     // key in the tree.
     /// An error that occurred when trying to append a key when it is not the last.
@@ -187,6 +191,8 @@ impl KeyAlreadyExistsError {
     }
 }
 
+const MAX_READERS: u32 = 1 << 13; // 8K readers
+
 /// Tries to open an MDBX environment and returns a reader and a writer to it.
 /// There is a single non clonable writer instance, to make sure there is only one write transaction
 ///  at any given moment.
@@ -196,7 +202,6 @@ pub(crate) fn open_env(config: &DbConfig) -> DbResult<(DbReader, DbWriter)> {
     if config.enforce_file_exists && !db_file_path.exists() {
         return Err(DbError::FileDoesNotExist(db_file_path));
     }
-    const MAX_READERS: u32 = 1 << 13; // 8K readers
     let env = Arc::new(
         Environment::new()
             .set_geometry(Geometry {
@@ -220,6 +225,30 @@ pub(crate) fn open_env(config: &DbConfig) -> DbResult<(DbReader, DbWriter)> {
     Ok((DbReader { env: env.clone() }, DbWriter { env }))
 }
 
+/// Opens an existing MDBX environment for reading only and returns a reader to it.
+/// The database file is neither created nor resized, and its geometry is the one it was written
+/// with.
+pub(crate) fn open_env_read_only(config: &DbConfig) -> DbResult<DbReader> {
+    let db_file_path = config.path().join("mdbx.dat");
+    if !db_file_path.exists() {
+        return Err(DbError::FileDoesNotExist(db_file_path));
+    }
+    let env = Arc::new(
+        Environment::new()
+            .set_max_tables(MAX_DBS)
+            .set_max_readers(MAX_READERS)
+            .set_flags(DatabaseFlags {
+                mode: libmdbx::Mode::ReadOnly,
+                // There is no locality of pages in the database almost at all, so readahead will
+                // fill the RAM with garbage.
+                no_rdahead: true,
+                ..Default::default()
+            })
+            .open(&config.path())?,
+    );
+    Ok(DbReader { env })
+}
+
 // Size in bytes.
 const MDBX_MIN_PAGESIZE: usize = 256;
 const MDBX_MAX_PAGESIZE: usize = 65536; // 64KB
@@ -249,6 +278,25 @@ impl DbReader {
     pub(crate) fn begin_ro_txn(&self) -> DbResult<DbReadTransaction<'_>> {
         Ok(DbReadTransaction { txn: self.env.begin_ro_txn()? })
     }
+
+    // Returns the identifier of an existing table without creating it, for a database opened for
+    // reading only.
+    pub(crate) fn get_table_identifier<K: Key + Debug, V: ValueSerde + Debug, T: TableType>(
+        &self,
+        name: &'static str,
+    ) -> DbResult<TableIdentifier<K, V, T>> {
+        let txn = self.env.begin_ro_txn()?;
+        txn.open_table(Some(name)).map_err(|err| match err {
+            libmdbx::Error::NotFound => DbError::TableDoesNotExist(name),
+            err => DbError::Inner(err),
+        })?;
+        Ok(TableIdentifier {
+            name,
+            _key_type: PhantomData {},
+            _value_type: PhantomData {},
+            _table_type: PhantomData {},
+        })
+    }
 }
 
 type DbReadTransaction<'env> = DbTransaction<'env, RO>;
@@ -257,6 +305,13 @@ impl DbWriter {
     pub(crate) fn begin_rw_txn(&mut self) -> DbResult<DbWriteTransaction<'_>> {
         Ok(DbWriteTransaction { txn: self.env.begin_rw_txn()? })
     }
+
+    // Returns another writer to the environment, for a task writing in the background. The
+    // database serializes the write transactions of the writers, so that there is still only one
+    // write transaction at any given moment.
+    pub(crate) fn background_writer(&self) -> DbWriter {
+        DbWriter { env: self.env.clone() }
+    }
 }
 
 type DbWriteTransaction<'env> = DbTransaction<'env, RW>;
@@ -326,6 +326,7 @@ impl<'env> HeaderStorageWriter for StorageTxn<'env, RW> {
             return Ok((self, None, None));
         };
 
+        self.verify_block_not_pruned(block_number)?;
         let reverted_header = headers_table
             .get(&self.txn, &block_number)?
             .expect("Missing header for block {block_number}.");
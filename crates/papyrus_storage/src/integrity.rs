@@ -0,0 +1,399 @@
+//! Verification of the integrity of an existing storage.
+//!
+//! [`verify_storage_integrity`] walks a range of blocks and checks that:
+//! - the markers are consistent with each other.
+//! - each header links to the header of its parent block, and is indexed by its hash.
+//! - the state diffs, classes and CASMs written in the mmap files are within the written part of
+//!   the files, and can be decoded.
+//! - the transaction metadata of each block and the index of transactions by hash agree, and the
+//!   transactions and their outputs can be decoded.
+//! - optionally, the hash of each block matches the hash computed from its header.
+//!
+//! The result of each check is the first block that failed it, if any, in an [`IntegrityReport`].
+//! Blocks whose history was [pruned](crate::pruning) are not checked.
+
+#[cfg(test)]
+#[path = "integrity_test.rs"]
+mod integrity_test;
+
+use std::cmp::{max, min};
+use std::collections::BTreeMap;
+
+use serde::Serialize;
+use starknet_api::block::BlockNumber;
+use starknet_api::block_hash::block_hash_calculator::{
+    calculate_block_hash,
+    concat_counts,
+    BlockHeaderCommitments,
+};
+use starknet_api::transaction::TransactionOffsetInBlock;
+
+use crate::base_layer::BaseLayerStorageReader;
+use crate::body::{BodyStorageReader, TransactionIndex};
+use crate::class::ClassStorageReader;
+use crate::compiled_class::CasmStorageReader;
+use crate::db::table_types::{DbCursorTrait, Table};
+use crate::db::RO;
+use crate::header::HeaderStorageReader;
+use crate::mmap_file::LocationInFile;
+use crate::pruning::PruningStorageReader;
+use crate::state::StateStorageReader;
+use crate::{OffsetKind, StorageError, StorageReader, StorageResult, StorageScope, StorageTxn};
+
+/// The checks done by [`verify_storage_integrity`].
+#[derive(Clone, Copy, Debug, Eq, PartialEq, PartialOrd, Ord, Serialize)]
+pub enum IntegrityCheck {
+    /// The markers are consistent with each other.
+    Markers,
+    /// Each header links to the header of its parent block, and is indexed by its hash.
+    HeaderLinkage,
+    /// The objects in the mmap files are within the written part of the files, and can be decoded.
+    FileLocations,
+    /// The transaction metadata and the index of transactions by hash agree.
+    TransactionIndex,
+    /// The hash of each block matches the hash computed from its header.
+    BlockHash,
+}
+
+/// The result of a single check.
+#[derive(Clone, Debug, Default, Eq, PartialEq, Serialize)]
+pub struct CheckResult {
+    /// The number of verified blocks. Zero for the markers check, which is not done per block.
+    pub n_checked_blocks: u64,
+    /// The first block that failed the check, if any.
+    pub first_bad_block: Option<BlockNumber>,
+    /// The reason for the failure.
+    pub error: Option<String>,
+}
+
+impl CheckResult {
+    fn failure(block_number: BlockNumber, error: String) -> Self {
+        Self { n_checked_blocks: 0, first_bad_block: Some(block_number), error: Some(error) }
+    }
+}
+
+/// The results of the checks done by [`verify_storage_integrity`].
+#[derive(Clone, Debug, Eq, PartialEq, Serialize)]
+pub struct IntegrityReport {
+    /// The first block of the verified range.
+    pub start_block: BlockNumber,
+    /// The end of the verified range (exclusive).
+    pub end_block: BlockNumber,
+    /// The result of each check. Checks that weren't done are missing.
+    pub checks: BTreeMap<IntegrityCheck, CheckResult>,
+}
+
+impl IntegrityReport {
+    /// Returns whether all the checks passed.
+    pub fn is_valid(&self) -> bool {
+        self.checks.values().all(|result| result.first_bad_block.is_none())
+    }
+}
+
+/// Verifies the integrity of the blocks in the given range, up to the markers of the storage if
+/// `end_block` is not given. The recomputation of the block hashes is optional, since it is slow.
+/// Blocks whose hash can't be computed from their header, as of Starknet versions preceding
+/// 0.13.2, are skipped.
+pub fn verify_storage_integrity(
+    reader: &StorageReader,
+    start_block: BlockNumber,
+    end_block: Option<BlockNumber>,
+    recompute_block_hashes: bool,
+) -> StorageResult<IntegrityReport> {
+    let txn = reader.begin_ro_txn()?;
+    let header_marker = txn.get_header_marker()?;
+    let body_marker = txn.get_body_marker()?;
+    let state_marker = txn.get_state_marker()?;
+    let end_block = end_block.unwrap_or(max(max(header_marker, body_marker), state_marker));
+    let start_block = max(start_block, txn.get_pruning_marker()?);
+    let blocks_up_to = |marker: BlockNumber| start_block.iter_up_to(min(end_block, marker));
+
+    let mut checks = BTreeMap::new();
+    checks.insert(IntegrityCheck::Markers, txn.check_markers()?);
+    checks.insert(
+        IntegrityCheck::HeaderLinkage,
+        check_blocks(blocks_up_to(header_marker), |block_number| {
+            txn.check_header_linkage(block_number, start_block)
+        }),
+    );
+    checks.insert(
+        IntegrityCheck::FileLocations,
+        check_blocks(blocks_up_to(state_marker), |block_number| {
+            txn.check_file_locations(block_number)
+        }),
+    );
+    // The transactions aren't stored in state-only mode.
+    if txn.scope != StorageScope::StateOnly {
+        checks.insert(
+            IntegrityCheck::TransactionIndex,
+            check_blocks(blocks_up_to(body_marker), |block_number| {
+                txn.check_transaction_index(block_number)
+            }),
+        );
+    }
+    if recompute_block_hashes {
+        checks.insert(
+            IntegrityCheck::BlockHash,
+            check_blocks(blocks_up_to(header_marker), |block_number| {
+                txn.check_block_hash(block_number)
+            }),
+        );
+    }
+    Ok(IntegrityReport { start_block, end_block, checks })
+}
+
+// Runs the check on the blocks until the first failure. The check returns whether the block was
+// verified, or an error if it is invalid.
+fn check_blocks(
+    blocks: impl Iterator<Item = BlockNumber>,
+    mut check_block: impl FnMut(BlockNumber) -> StorageResult<bool>,
+) -> CheckResult {
+    let mut result = CheckResult::default();
+    for block_number in blocks {
+        match check_block(block_number) {
+            Ok(true) => result.n_checked_blocks += 1,
+            Ok(false) => {}
+            Err(err) => {
+                result.first_bad_block = Some(block_number);
+                result.error = Some(err.to_string());
+                break;
+            }
+        }
+    }
+    result
+}
+
+fn inconsistency(msg: String) -> StorageError {
+    StorageError::DBInconsistency { msg }
+}
+
+impl<'env> StorageTxn<'env, RO> {
+    // Checks the invariants documented at MarkerKind.
+    fn check_markers(&self) -> StorageResult<CheckResult> {
+        let header_marker = self.get_header_marker()?;
+        let body_marker = self.get_body_marker()?;
+        let state_marker = self.get_state_marker()?;
+        let class_marker = self.get_class_marker()?;
+        let compiled_class_marker = self.get_compiled_class_marker()?;
+        let base_layer_block_marker = self.get_base_layer_block_marker()?;
+        let pruning_marker = self.get_pruning_marker()?;
+
+        // Pairs of markers, where the first must not exceed the second.
+        let invariants = [
+            (("compiled class", compiled_class_marker), ("class", class_marker)),
+            (("class", class_marker), ("state", state_marker)),
+            (("state", state_marker), ("header", header_marker)),
+            (("body", body_marker), ("header", header_marker)),
+            (("base layer block", base_layer_block_marker), ("header", header_marker)),
+            (("pruning", pruning_marker), ("header", header_marker)),
+            (("pruning", pruning_marker), ("body", body_marker)),
+            (("pruning", pruning_marker), ("state", state_marker)),
+        ];
+        for ((name, marker), (bound_name, bound)) in invariants {
+            if marker > bound {
+                // The data of the blocks from the bound onwards is partial.
+                return Ok(CheckResult::failure(
+                    bound,
+                    format!(
+                        "The {name} marker {marker} is greater than the {bound_name} marker \
+                         {bound}."
+                    ),
+                ));
+            }
+        }
+        Ok(CheckResult::default())
+    }
+
+    fn check_header_linkage(
+        &self,
+        block_number: BlockNumber,
+        start_block: BlockNumber,
+    ) -> StorageResult<bool> {
+        let header = self
+            .get_block_header(block_number)?
+            .ok_or_else(|| inconsistency(format!("Missing header of block {block_number}.")))?;
+        if header.block_header_without_hash.block_number != block_number {
+            return Err(inconsistency(format!(
+                "The header of block {block_number} is of block {}.",
+                header.block_header_without_hash.block_number
+            )));
+        }
+        let indexed_block_number = self.get_block_number_by_hash(&header.block_hash)?;
+        if indexed_block_number != Some(block_number) {
+            return Err(inconsistency(format!(
+                "The hash {} of block {block_number} is indexed to block {indexed_block_number:?}.",
+                header.block_hash
+            )));
+        }
+        // The parent of the first block in the range is checked only if its header is stored.
+        let Some(parent_block_number) = block_number.prev() else {
+            return Ok(true);
+        };
+        let parent_header = match self.get_block_header(parent_block_number)? {
+            Some(parent_header) => parent_header,
+            None if block_number == start_block => return Ok(true),
+            None => {
+                return Err(inconsistency(format!(
+                    "Missing header of block {parent_block_number}."
+                )));
+            }
+        };
+        if header.block_header_without_hash.parent_hash != parent_header.block_hash {
+            return Err(inconsistency(format!(
+                "The parent hash {} of block {block_number} differs from the hash {} of block \
+                 {parent_block_number}.",
+                header.block_header_without_hash.parent_hash, parent_header.block_hash
+            )));
+        }
+        Ok(true)
+    }
+
+    fn check_file_locations(&self, block_number: BlockNumber) -> StorageResult<bool> {
+        let state_diffs_table = self.open_table(&self.tables.state_diffs)?;
+        let declared_classes_table = self.open_table(&self.tables.declared_classes)?;
+        let deprecated_declared_classes_table =
+            self.open_table(&self.tables.deprecated_declared_classes)?;
+        let casms_table = self.open_table(&self.tables.casms)?;
+
+        let location = state_diffs_table
+            .get(&self.txn, &block_number)?
+            .ok_or_else(|| inconsistency(format!("Missing state diff of block {block_number}.")))?;
+        self.check_location(OffsetKind::ThinStateDiff, location)?;
+        let thin_state_diff = self.file_handlers.get_thin_state_diff_unchecked(location)?;
+
+        if block_number < self.get_class_marker()? {
+            for class_hash in thin_state_diff.declared_classes.keys() {
+                let location = declared_classes_table
+                    .get(&self.txn, class_hash)?
+                    .ok_or_else(|| inconsistency(format!("Missing class {class_hash}.")))?;
+                self.check_location(OffsetKind::ContractClass, location)?;
+                self.file_handlers.get_contract_class_unchecked(location)?;
+            }
+            for class_hash in &thin_state_diff.deprecated_declared_classes {
+                let indexed_class =
+                    deprecated_declared_classes_table
+                        .get(&self.txn, class_hash)?
+                        .ok_or_else(|| inconsistency(format!("Missing class {class_hash}.")))?;
+                self.check_location(
+                    OffsetKind::DeprecatedContractClass,
+                    indexed_class.location_in_file,
+                )?;
+                self.file_handlers
+                    .get_deprecated_contract_class_unchecked(indexed_class.location_in_file)?;
+            }
+        }
+        if block_number < self.get_compiled_class_marker()? {
+            for class_hash in thin_state_diff.declared_classes.keys() {
+                let location = casms_table
+                    .get(&self.txn, class_hash)?
+                    .ok_or_else(|| inconsistency(format!("Missing CASM of class {class_hash}.")))?;
+                self.check_location(OffsetKind::Casm, location)?;
+                self.file_handlers.get_casm_unchecked(location)?;
+            }
+        }
+        Ok(true)
+    }
+
+    fn check_transaction_index(&self, block_number: BlockNumber) -> StorageResult<bool> {
+        let transaction_metadata_table = self.open_table(&self.tables.transaction_metadata)?;
+        let transaction_hash_to_idx_table =
+            self.open_table(&self.tables.transaction_hash_to_idx)?;
+
+        let mut cursor = transaction_metadata_table.cursor(&self.txn)?;
+        let mut current =
+            cursor.lower_bound(&TransactionIndex(block_number, TransactionOffsetInBlock(0)))?;
+        let mut expected_offset = 0;
+        while let Some((tx_index, tx_metadata)) = current {
+            if tx_index.0 != block_number {
+                break;
+            }
+            if tx_index.1 != TransactionOffsetInBlock(expected_offset) {
+                return Err(inconsistency(format!(
+                    "Missing transaction {expected_offset} of block {block_number}."
+                )));
+            }
+            let indexed_tx_index =
+                transaction_hash_to_idx_table.get(&self.txn, &tx_metadata.tx_hash)?;
+            if indexed_tx_index != Some(tx_index) {
+                return Err(inconsistency(format!(
+                    "Transaction {} at {tx_index:?} is indexed to {indexed_tx_index:?}.",
+                    tx_metadata.tx_hash
+                )));
+            }
+            self.check_location(OffsetKind::Transaction, tx_metadata.tx_location)?;
+            self.file_handlers.get_transaction_unchecked(tx_metadata.tx_location)?;
+            self.check_location(OffsetKind::TransactionOutput, tx_metadata.tx_output_location)?;
+            self.file_handlers.get_transaction_output_unchecked(tx_metadata.tx_output_location)?;
+
+            expected_offset += 1;
+            current = cursor.next()?;
+        }
+        Ok(true)
+    }
+
+    // Returns false if the hash can't be computed from the header.
+    fn check_block_hash(&self, block_number: BlockNumber) -> StorageResult<bool> {
+        let header = self
+            .get_block_header(block_number)?
+            .ok_or_else(|| inconsistency(format!("Missing header of block {block_number}.")))?;
+        let (
+            Some(transaction_commitment),
+            Some(event_commitment),
+            Some(receipt_commitment),
+            Some(state_diff_commitment),
+            Some(state_diff_length),
+        ) = (
+            header.transaction_commitment,
+            header.event_commitment,
+            header.receipt_commitment,
+            header.state_diff_commitment,
+            header.state_diff_length,
+        )
+        else {
+            return Ok(false);
+        };
+        let block_commitments = BlockHeaderCommitments {
+            transaction_commitment,
+            event_commitment,
+            receipt_commitment,
+            state_diff_commitment,
+            concatenated_counts: concat_counts(
+                header.n_transactions,
+                header.n_events,
+                state_diff_length,
+                header.block_header_without_hash.l1_da_mode,
+            ),
+        };
+        // Fails for Starknet versions whose block hash is computed differently.
+        let Ok(block_hash) =
+            calculate_block_hash(header.block_header_without_hash, block_commitments)
+        else {
+            return Ok(false);
+        };
+        if block_hash != header.block_hash {
+            return Err(inconsistency(format!(
+                "The hash of block {block_number} is {}, while its computed hash is {block_hash}.",
+                header.block_hash
+            )));
+        }
+        Ok(true)
+    }
+
+    // Checks that the object at the location is within the written part of its file, as reading
+    // beyond it is undefined.
+    fn check_location(
+        &self,
+        offset_kind: OffsetKind,
+        location: LocationInFile,
+    ) -> StorageResult<()> {
+        let file_offsets_table = self.open_table(&self.tables.file_offsets)?;
+        let file_offset = file_offsets_table.get(&self.txn, &offset_kind)?.unwrap_or_default();
+        if location.next_offset() > file_offset {
+            return Err(inconsistency(format!(
+                "{offset_kind:?} at location {location:?} is beyond the written offset \
+                 {file_offset} of its file."
+            )));
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,217 @@
+use indexmap::indexmap;
+use papyrus_test_utils::get_test_block;
+use pretty_assertions::assert_eq;
+use starknet_api::block::{
+    BlockHash,
+    BlockHeader,
+    BlockHeaderWithoutHash,
+    BlockNumber,
+    StarknetVersion,
+};
+use starknet_api::block_hash::block_hash_calculator::{
+    calculate_block_hash,
+    concat_counts,
+    BlockHeaderCommitments,
+};
+use starknet_api::core::{ClassHash, ContractAddress};
+use starknet_api::state::{StorageKey, ThinStateDiff};
+use starknet_api::transaction::TransactionHash;
+use starknet_api::{class_hash, contract_address, felt, storage_key};
+use starknet_types_core::felt::Felt;
+use test_case::test_case;
+
+use crate::body::BodyStorageWriter;
+use crate::class::ClassStorageWriter;
+use crate::db::table_types::Table;
+use crate::header::HeaderStorageWriter;
+use crate::integrity::{verify_storage_integrity, CheckResult, IntegrityCheck};
+use crate::state::StateStorageWriter;
+use crate::test_utils::{get_test_storage, get_test_storage_by_scope};
+use crate::{MarkerKind, StorageScope, StorageWriter};
+
+fn block_hash(block_number: BlockNumber) -> BlockHash {
+    BlockHash(Felt::from(block_number.0 + 1))
+}
+
+fn tx_hash(block_number: BlockNumber) -> TransactionHash {
+    TransactionHash(Felt::from(block_number.0 + 1))
+}
+
+fn contract() -> ContractAddress {
+    contract_address!(0x1_u8)
+}
+
+fn key() -> StorageKey {
+    storage_key!(0x2_u8)
+}
+
+fn class() -> ClassHash {
+    class_hash!(0x3_u8)
+}
+
+// Appends a block with a single transaction that updates the storage of a contract, with the
+// given parent hash.
+fn append_block(writer: &mut StorageWriter, block_number: BlockNumber, parent_hash: BlockHash) {
+    let header = BlockHeader {
+        block_hash: block_hash(block_number),
+        block_header_without_hash: BlockHeaderWithoutHash {
+            block_number,
+            parent_hash,
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+    let mut body = get_test_block(1, None, None, None).body;
+    body.transaction_hashes = vec![tx_hash(block_number)];
+    let mut state_diff = ThinStateDiff {
+        storage_diffs: indexmap! { contract() => indexmap! { key() => felt!(block_number.0) } },
+        ..Default::default()
+    };
+    if block_number == BlockNumber(0) {
+        state_diff.deployed_contracts = indexmap! { contract() => class() };
+    }
+    writer
+        .begin_rw_txn()
+        .unwrap()
+        .append_header(block_number, &header)
+        .unwrap()
+        .append_body(block_number, body)
+        .unwrap()
+        .append_state_diff(block_number, state_diff)
+        .unwrap()
+        .append_classes(block_number, &[], &[])
+        .unwrap()
+        .commit()
+        .unwrap();
+}
+
+// Appends 3 blocks, where block 2 has the given parent hash.
+fn append_blocks(writer: &mut StorageWriter, parent_hash_of_block_2: BlockHash) {
+    append_block(writer, BlockNumber(0), BlockHash::default());
+    append_block(writer, BlockNumber(1), block_hash(BlockNumber(0)));
+    append_block(writer, BlockNumber(2), parent_hash_of_block_2);
+}
+
+fn passed(n_checked_blocks: u64) -> CheckResult {
+    CheckResult { n_checked_blocks, ..Default::default() }
+}
+
+#[test]
+fn valid_storage() {
+    let ((reader, mut writer), _temp_dir) = get_test_storage();
+    append_blocks(&mut writer, block_hash(BlockNumber(1)));
+
+    let report = verify_storage_integrity(&reader, BlockNumber(0), None, false).unwrap();
+    assert!(report.is_valid());
+    assert_eq!(report.end_block, BlockNumber(3));
+    assert_eq!(report.checks[&IntegrityCheck::Markers], passed(0));
+    assert_eq!(report.checks[&IntegrityCheck::HeaderLinkage], passed(3));
+    assert_eq!(report.checks[&IntegrityCheck::FileLocations], passed(3));
+    assert_eq!(report.checks[&IntegrityCheck::TransactionIndex], passed(3));
+    assert!(!report.checks.contains_key(&IntegrityCheck::BlockHash));
+
+    // The range is bounded by the given blocks.
+    let report =
+        verify_storage_integrity(&reader, BlockNumber(1), Some(BlockNumber(2)), false).unwrap();
+    assert!(report.is_valid());
+    assert_eq!(report.checks[&IntegrityCheck::HeaderLinkage], passed(1));
+}
+
+#[test]
+fn state_only_storage() {
+    let ((reader, mut writer), _temp_dir) = get_test_storage_by_scope(StorageScope::StateOnly);
+    append_blocks(&mut writer, block_hash(BlockNumber(1)));
+
+    let report = verify_storage_integrity(&reader, BlockNumber(0), None, false).unwrap();
+    assert!(report.is_valid());
+    assert!(!report.checks.contains_key(&IntegrityCheck::TransactionIndex));
+}
+
+#[test]
+fn broken_header_linkage() {
+    let ((reader, mut writer), _temp_dir) = get_test_storage();
+    append_blocks(&mut writer, BlockHash(felt!("0x100")));
+
+    let report = verify_storage_integrity(&reader, BlockNumber(0), None, false).unwrap();
+    assert!(!report.is_valid());
+    let result = &report.checks[&IntegrityCheck::HeaderLinkage];
+    assert_eq!(result.n_checked_blocks, 2);
+    assert_eq!(result.first_bad_block, Some(BlockNumber(2)));
+    assert!(result.error.is_some());
+    assert_eq!(report.checks[&IntegrityCheck::TransactionIndex], passed(3));
+}
+
+#[test]
+fn missing_transaction_hash_index() {
+    let ((reader, mut writer), _temp_dir) = get_test_storage();
+    append_blocks(&mut writer, block_hash(BlockNumber(1)));
+    let txn = writer.begin_rw_txn().unwrap();
+    let transaction_hash_to_idx_table =
+        txn.open_table(&txn.tables.transaction_hash_to_idx).unwrap();
+    transaction_hash_to_idx_table.delete(&txn.txn, &tx_hash(BlockNumber(1))).unwrap();
+    txn.commit().unwrap();
+
+    let report = verify_storage_integrity(&reader, BlockNumber(0), None, false).unwrap();
+    assert!(!report.is_valid());
+    assert_eq!(
+        report.checks[&IntegrityCheck::TransactionIndex].first_bad_block,
+        Some(BlockNumber(1))
+    );
+    assert_eq!(report.checks[&IntegrityCheck::HeaderLinkage], passed(3));
+}
+
+#[test]
+fn inconsistent_markers() {
+    let ((reader, mut writer), _temp_dir) = get_test_storage();
+    append_blocks(&mut writer, block_hash(BlockNumber(1)));
+    let txn = writer.begin_rw_txn().unwrap();
+    let markers_table = txn.open_table(&txn.tables.markers).unwrap();
+    markers_table.upsert(&txn.txn, &MarkerKind::Class, &BlockNumber(4)).unwrap();
+    txn.commit().unwrap();
+
+    let report = verify_storage_integrity(&reader, BlockNumber(0), None, false).unwrap();
+    assert!(!report.is_valid());
+    assert_eq!(report.checks[&IntegrityCheck::Markers].first_bad_block, Some(BlockNumber(3)));
+}
+
+#[test_case(true; "correct hash")]
+#[test_case(false; "wrong hash")]
+fn block_hash_recomputation(is_hash_correct: bool) {
+    let block_header_without_hash =
+        BlockHeaderWithoutHash { starknet_version: StarknetVersion::V0_13_2, ..Default::default() };
+    let block_commitments = BlockHeaderCommitments {
+        concatenated_counts: concat_counts(0, 0, 0, block_header_without_hash.l1_da_mode),
+        ..Default::default()
+    };
+    let mut block_hash =
+        calculate_block_hash(block_header_without_hash.clone(), block_commitments.clone()).unwrap();
+    if !is_hash_correct {
+        block_hash = BlockHash(block_hash.0 + Felt::ONE);
+    }
+    let header = BlockHeader {
+        block_hash,
+        block_header_without_hash,
+        transaction_commitment: Some(block_commitments.transaction_commitment),
+        event_commitment: Some(block_commitments.event_commitment),
+        receipt_commitment: Some(block_commitments.receipt_commitment),
+        state_diff_commitment: Some(block_commitments.state_diff_commitment),
+        state_diff_length: Some(0),
+        ..Default::default()
+    };
+    let ((reader, mut writer), _temp_dir) = get_test_storage();
+    writer
+        .begin_rw_txn()
+        .unwrap()
+        .append_header(BlockNumber(0), &header)
+        .unwrap()
+        .commit()
+        .unwrap();
+
+    let report = verify_storage_integrity(&reader, BlockNumber(0), None, true).unwrap();
+    let result = &report.checks[&IntegrityCheck::BlockHash];
+    if is_hash_correct {
+        assert_eq!(*result, passed(1));
+    } else {
+        assert_eq!(result.first_bad_block, Some(BlockNumber(0)));
+    }
+}
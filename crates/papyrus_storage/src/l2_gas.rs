@@ -0,0 +1,92 @@
+//! Interface for handling the L2 gas data of blocks, used by the fee market.
+//!
+//! Import [`L2GasStorageReader`] and [`L2GasStorageWriter`] to read and write the L2 gas data of a
+//! block using a [`StorageTxn`].
+//! # Example
+//! ```
+//! use papyrus_storage::l2_gas::{L2GasInfo, L2GasStorageReader, L2GasStorageWriter};
+//! use papyrus_storage::open_storage;
+//! # use papyrus_storage::{db::DbConfig, StorageConfig};
+//! # use starknet_api::core::ChainId;
+//! use starknet_api::block::{BlockNumber, GasPrice};
+//! use starknet_api::execution_resources::GasAmount;
+//!
+//! # let dir_handle = tempfile::tempdir().unwrap();
+//! # let dir = dir_handle.path().to_path_buf();
+//! # let db_config = DbConfig {
+//! #     path_prefix: dir,
+//! #     chain_id: ChainId::Mainnet,
+//! #     enforce_file_exists: false,
+//! #     min_size: 1 << 20,    // 1MB
+//! #     max_size: 1 << 35,    // 32GB
+//! #     growth_step: 1 << 26, // 64MB
+//! # };
+//! # let storage_config = StorageConfig{db_config, ..Default::default()};
+//! let l2_gas_info = L2GasInfo { l2_gas_used: GasAmount(10), l2_gas_price: GasPrice(100000) };
+//! let (reader, mut writer) = open_storage(storage_config)?;
+//! writer
+//!     .begin_rw_txn()?                                      // Start a RW transaction.
+//!     .set_l2_gas_info(BlockNumber(0), &l2_gas_info)?       // Set the L2 gas data of a block.
+//!     .commit()?; // Commit the transaction.
+//! let stored_l2_gas_info = reader.begin_ro_txn()?.get_l2_gas_info(BlockNumber(0))?;
+//! assert_eq!(stored_l2_gas_info, Some(l2_gas_info));
+//! # Ok::<(), papyrus_storage::StorageError>(())
+//! ```
+#[cfg(test)]
+#[path = "l2_gas_test.rs"]
+mod l2_gas_test;
+
+use starknet_api::block::{BlockNumber, GasPrice};
+use starknet_api::execution_resources::GasAmount;
+
+use crate::db::table_types::Table;
+use crate::db::{TransactionKind, RW};
+use crate::{StorageResult, StorageTxn};
+
+/// The L2 gas data of a block, from which the L2 gas price of the next block is derived.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct L2GasInfo {
+    /// The total L2 gas used by the transactions of the block.
+    pub l2_gas_used: GasAmount,
+    /// The base L2 gas price of the block, in fri.
+    pub l2_gas_price: GasPrice,
+}
+
+/// Interface for reading the L2 gas data of blocks.
+pub trait L2GasStorageReader {
+    /// Returns the L2 gas data of the given block, if it was set.
+    fn get_l2_gas_info(&self, block_number: BlockNumber) -> StorageResult<Option<L2GasInfo>>;
+}
+
+/// Interface for writing the L2 gas data of blocks.
+pub trait L2GasStorageWriter
+where
+    Self: Sized,
+{
+    /// Sets the L2 gas data of the given block, overriding existing data.
+    // To enforce that no commit happen after a failure, we consume and return Self on success.
+    fn set_l2_gas_info(
+        self,
+        block_number: BlockNumber,
+        l2_gas_info: &L2GasInfo,
+    ) -> StorageResult<Self>;
+}
+
+impl<'env, Mode: TransactionKind> L2GasStorageReader for StorageTxn<'env, Mode> {
+    fn get_l2_gas_info(&self, block_number: BlockNumber) -> StorageResult<Option<L2GasInfo>> {
+        let l2_gas_table = self.open_table(&self.tables.l2_gas)?;
+        Ok(l2_gas_table.get(&self.txn, &block_number)?)
+    }
+}
+
+impl<'env> L2GasStorageWriter for StorageTxn<'env, RW> {
+    fn set_l2_gas_info(
+        self,
+        block_number: BlockNumber,
+        l2_gas_info: &L2GasInfo,
+    ) -> StorageResult<Self> {
+        let l2_gas_table = self.open_table(&self.tables.l2_gas)?;
+        l2_gas_table.upsert(&self.txn, &block_number, l2_gas_info)?;
+        Ok(self)
+    }
+}
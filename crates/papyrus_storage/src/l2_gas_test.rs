@@ -0,0 +1,55 @@
+use starknet_api::block::{BlockNumber, GasPrice};
+use starknet_api::execution_resources::GasAmount;
+use starknet_api::state::ThinStateDiff;
+
+use crate::l2_gas::{L2GasInfo, L2GasStorageReader, L2GasStorageWriter};
+use crate::state::StateStorageWriter;
+use crate::test_utils::get_test_storage;
+
+#[test]
+fn set_and_get_l2_gas_info() {
+    let (reader, mut writer) = get_test_storage().0;
+
+    // No data before it is set.
+    assert_eq!(reader.begin_ro_txn().unwrap().get_l2_gas_info(BlockNumber(0)).unwrap(), None);
+
+    let l2_gas_info = L2GasInfo { l2_gas_used: GasAmount(5), l2_gas_price: GasPrice(100000) };
+    writer
+        .begin_rw_txn()
+        .unwrap()
+        .set_l2_gas_info(BlockNumber(0), &l2_gas_info)
+        .unwrap()
+        .commit()
+        .unwrap();
+    let txn = reader.begin_ro_txn().unwrap();
+    assert_eq!(txn.get_l2_gas_info(BlockNumber(0)).unwrap(), Some(l2_gas_info));
+    assert_eq!(txn.get_l2_gas_info(BlockNumber(1)).unwrap(), None);
+}
+
+#[test]
+fn revert_state_diff_reverts_l2_gas_info() {
+    let (reader, mut writer) = get_test_storage().0;
+
+    let l2_gas_info = L2GasInfo { l2_gas_used: GasAmount(5), l2_gas_price: GasPrice(100000) };
+    writer
+        .begin_rw_txn()
+        .unwrap()
+        .append_state_diff(BlockNumber(0), ThinStateDiff::default())
+        .unwrap()
+        .set_l2_gas_info(BlockNumber(0), &l2_gas_info)
+        .unwrap()
+        .append_state_diff(BlockNumber(1), ThinStateDiff::default())
+        .unwrap()
+        .set_l2_gas_info(BlockNumber(1), &l2_gas_info)
+        .unwrap()
+        .commit()
+        .unwrap();
+
+    let (txn, _) = writer.begin_rw_txn().unwrap().revert_state_diff(BlockNumber(1)).unwrap();
+    txn.commit().unwrap();
+
+    // Only the L2 gas data of the reverted block is deleted.
+    let txn = reader.begin_ro_txn().unwrap();
+    assert_eq!(txn.get_l2_gas_info(BlockNumber(0)).unwrap(), Some(l2_gas_info));
+    assert_eq!(txn.get_l2_gas_info(BlockNumber(1)).unwrap(), None);
+}
@@ -54,11 +54,14 @@
 //! The storage version is composed of two components: [`STORAGE_VERSION_STATE`] for the state and
 //! [`STORAGE_VERSION_BLOCKS`] for blocks. Each version consists of a major and a minor version. A
 //! higher major version indicates that a re-sync is necessary, while a higher minor version
-//! indicates a change that is migratable.
+//! indicates a change that is migratable. Opening a storage with a lower minor blocks version
+//! migrates its blocks data to the current version, e.g., by filling newly added indices. The
+//! migration is done in bounded write transactions, and if it is interrupted, it resumes when the
+//! storage is opened again.
 //!
 //! When a storage is opened with [`StorageScope::StateOnly`], only the state version must match.
-//! For storage opened with [`StorageScope::FullArchive`], both versions must match the crate's
-//! versions.
+//! For storage opened with [`StorageScope::FullArchive`] or [`StorageScope::Pruned`], both versions
+//! must match the crate's versions.
 //!
 //! Incompatibility occurs when the code and the database have differing major versions. However,
 //! if the code has the same major version but a higher minor version compared to the database, it
@@ -87,8 +90,13 @@ pub mod utils;
 pub mod compression_utils;
 pub mod db;
 pub mod header;
+pub mod integrity;
+pub mod l2_gas;
+pub mod mempool_commit;
 pub mod mmap_file;
+pub mod pruning;
 mod serialization;
+pub mod snapshot;
 pub mod state;
 mod version;
 
@@ -111,6 +119,7 @@ use db::serialization::{Key, NoVersionValueWrapper, ValueSerde, VersionZeroWrapp
 use db::table_types::{CommonPrefix, NoValue, Table, TableType};
 use mmap_file::{
     open_file,
+    open_file_read_only,
     FileHandler,
     LocationInFile,
     MMapFileError,
@@ -123,19 +132,20 @@ use papyrus_config::{ParamPath, ParamPrivacyInput, SerializedParam};
 use papyrus_proc_macros::latency_histogram;
 use serde::{Deserialize, Serialize};
 use starknet_api::block::{BlockHash, BlockNumber, BlockSignature, StarknetVersion};
-use starknet_api::core::{ClassHash, ContractAddress, Nonce};
+use starknet_api::core::{ClassHash, CompiledClassHash, ContractAddress, Nonce};
 use starknet_api::deprecated_contract_class::ContractClass as DeprecatedContractClass;
 use starknet_api::state::{SierraContractClass, StateNumber, StorageKey, ThinStateDiff};
-use starknet_api::transaction::{Transaction, TransactionHash, TransactionOutput};
+use starknet_api::transaction::{EventKey, Transaction, TransactionHash, TransactionOutput};
 use starknet_types_core::felt::Felt;
 use tracing::{debug, warn};
-use validator::Validate;
+use validator::{Validate, ValidationError};
 use version::{StorageVersionError, Version};
 
 use crate::body::TransactionIndex;
 use crate::db::table_types::SimpleTable;
 use crate::db::{
     open_env,
+    open_env_read_only,
     DbConfig,
     DbError,
     DbReader,
@@ -148,26 +158,110 @@ use crate::db::{
     RW,
 };
 use crate::header::StorageBlockHeader;
+use crate::l2_gas::L2GasInfo;
+use crate::mempool_commit::MempoolCommit;
 use crate::mmap_file::MMapFileStats;
+use crate::pruning::{BackgroundPruner, PruningStorageReader, MIN_RETAINED_BLOCKS};
+use crate::snapshot::SnapshotError;
 use crate::state::data::IndexedDeprecatedContractClass;
 pub use crate::utils::update_storage_metrics;
-use crate::version::{VersionStorageReader, VersionStorageWriter};
+use crate::version::{migrate_blocks, VersionStorageReader, VersionStorageWriter};
 
 // For more details on the storage version, see the module documentation.
 /// The current version of the storage state code.
 pub const STORAGE_VERSION_STATE: Version = Version { major: 3, minor: 0 };
 /// The current version of the storage blocks code.
-pub const STORAGE_VERSION_BLOCKS: Version = Version { major: 3, minor: 0 };
+pub const STORAGE_VERSION_BLOCKS: Version = Version { major: 3, minor: 2 };
 
 /// Opens a storage and returns a [`StorageReader`] and a [`StorageWriter`].
 pub fn open_storage(
     storage_config: StorageConfig,
 ) -> StorageResult<(StorageReader, StorageWriter)> {
     let (db_reader, mut db_writer) = open_env(&storage_config.db_config)?;
-    let tables = Arc::new(Tables {
+    let tables = Arc::new(create_tables(&mut db_writer)?);
+    let (file_writers, file_readers) = open_storage_files(
+        &storage_config.db_config,
+        storage_config.mmap_file_config,
+        db_reader.clone(),
+        &tables.file_offsets,
+    )?;
+
+    let reader = StorageReader {
+        db_reader,
+        tables: tables.clone(),
+        scope: storage_config.scope,
+        file_readers,
+    };
+    let writer = StorageWriter {
+        db_writer,
+        tables,
+        scope: storage_config.scope,
+        file_writers,
+        _pruner: None,
+    };
+
+    let mut writer = set_version_if_needed(reader.clone(), writer)?;
+    verify_storage_version(reader.clone())?;
+    if storage_config.index_event_keys && storage_config.scope != StorageScope::StateOnly {
+        writer.begin_rw_txn()?.start_indexing_events_by_first_key()?.commit()?;
+    }
+    if let StorageScope::Pruned { retained_blocks } = storage_config.scope {
+        let pruner_writer = StorageWriter {
+            db_writer: writer.db_writer.background_writer(),
+            file_writers: writer.file_writers.clone(),
+            tables: writer.tables.clone(),
+            scope: writer.scope,
+            _pruner: None,
+        };
+        writer._pruner = Some(BackgroundPruner::spawn(pruner_writer, retained_blocks));
+    }
+    Ok((reader, writer))
+}
+
+/// Opens an existing storage for reading only, and returns a [`StorageReader`].
+///
+/// Unlike [`open_storage`], nothing is written: the database and the files are opened for reading
+/// only, and the storage is neither initialized nor migrated, and it is not pruned. The storage
+/// versions must have the crate's major versions, and minor versions that are not higher than the
+/// crate's. A storage in an older minor version lacks the data its migrations add, e.g. the index
+/// of the transactions by their sender and nonce. The scope is read from the stored version:
+/// [`StorageScope::StateOnly`] if only the state version is stored, and
+/// [`StorageScope::FullArchive`] otherwise, whether or not the history of old blocks was pruned.
+pub fn open_storage_read_only(
+    db_config: DbConfig,
+    mmap_file_config: MmapFileConfig,
+) -> StorageResult<StorageReader> {
+    let db_reader = open_env_read_only(&db_config)?;
+    let tables = Arc::new(get_tables(&db_reader)?);
+    let file_readers = open_storage_files_read_only(
+        &db_config,
+        mmap_file_config,
+        db_reader.clone(),
+        &tables.file_offsets,
+    )?;
+
+    let mut reader =
+        StorageReader { db_reader, tables, scope: StorageScope::FullArchive, file_readers };
+    reader.scope = match get_storage_version(reader.clone())? {
+        None => {
+            return Err(StorageError::StorageVersionInconsistency(
+                StorageVersionError::MissingStorageVersion,
+            ));
+        }
+        Some(StorageVersion::StateOnly(_)) => StorageScope::StateOnly,
+        Some(StorageVersion::FullArchive(_)) => StorageScope::FullArchive,
+    };
+    verify_storage_version_readable(reader.clone())?;
+    Ok(reader)
+}
+
+fn create_tables(db_writer: &mut DbWriter) -> StorageResult<Tables> {
+    Ok(Tables {
         block_hash_to_number: db_writer.create_simple_table("block_hash_to_number")?,
         block_signatures: db_writer.create_simple_table("block_signatures")?,
         casms: db_writer.create_simple_table("casms")?,
+        classes_missing_sierra: db_writer.create_simple_table("classes_missing_sierra")?,
+        compiled_class_hashes: db_writer.create_simple_table("compiled_class_hashes")?,
         contract_storage: db_writer.create_common_prefix_table("contract_storage")?,
         declared_classes: db_writer.create_simple_table("declared_classes")?,
         declared_classes_block: db_writer.create_simple_table("declared_classes_block")?,
@@ -175,36 +269,58 @@ pub fn open_storage(
             .create_simple_table("deprecated_declared_classes")?,
         deployed_contracts: db_writer.create_simple_table("deployed_contracts")?,
         events: db_writer.create_common_prefix_table("events")?,
+        events_by_first_key: db_writer.create_common_prefix_table("events_by_first_key")?,
         headers: db_writer.create_simple_table("headers")?,
+        l2_gas: db_writer.create_simple_table("l2_gas")?,
         markers: db_writer.create_simple_table("markers")?,
         nonces: db_writer.create_common_prefix_table("nonces")?,
         file_offsets: db_writer.create_simple_table("file_offsets")?,
+        pending_mempool_commits: db_writer.create_simple_table("pending_mempool_commits")?,
         state_diffs: db_writer.create_simple_table("state_diffs")?,
         transaction_hash_to_idx: db_writer.create_simple_table("transaction_hash_to_idx")?,
         transaction_metadata: db_writer.create_simple_table("transaction_metadata")?,
+        transaction_sender_nonce_to_idx: db_writer
+            .create_simple_table("transaction_sender_nonce_to_idx")?,
 
         // Version tables
         starknet_version: db_writer.create_simple_table("starknet_version")?,
         storage_version: db_writer.create_simple_table("storage_version")?,
-    });
-    let (file_writers, file_readers) = open_storage_files(
-        &storage_config.db_config,
-        storage_config.mmap_file_config,
-        db_reader.clone(),
-        &tables.file_offsets,
-    )?;
+    })
+}
 
-    let reader = StorageReader {
-        db_reader,
-        tables: tables.clone(),
-        scope: storage_config.scope,
-        file_readers,
-    };
-    let writer = StorageWriter { db_writer, tables, scope: storage_config.scope, file_writers };
+// Returns the identifiers of the tables of a database opened for reading only. Fails if a table
+// does not exist, i.e. the storage was last opened for writing by an older crate.
+fn get_tables(db_reader: &DbReader) -> StorageResult<Tables> {
+    Ok(Tables {
+        block_hash_to_number: db_reader.get_table_identifier("block_hash_to_number")?,
+        block_signatures: db_reader.get_table_identifier("block_signatures")?,
+        casms: db_reader.get_table_identifier("casms")?,
+        classes_missing_sierra: db_reader.get_table_identifier("classes_missing_sierra")?,
+        compiled_class_hashes: db_reader.get_table_identifier("compiled_class_hashes")?,
+        contract_storage: db_reader.get_table_identifier("contract_storage")?,
+        declared_classes: db_reader.get_table_identifier("declared_classes")?,
+        declared_classes_block: db_reader.get_table_identifier("declared_classes_block")?,
+        deprecated_declared_classes: db_reader
+            .get_table_identifier("deprecated_declared_classes")?,
+        deployed_contracts: db_reader.get_table_identifier("deployed_contracts")?,
+        events: db_reader.get_table_identifier("events")?,
+        events_by_first_key: db_reader.get_table_identifier("events_by_first_key")?,
+        headers: db_reader.get_table_identifier("headers")?,
+        l2_gas: db_reader.get_table_identifier("l2_gas")?,
+        markers: db_reader.get_table_identifier("markers")?,
+        nonces: db_reader.get_table_identifier("nonces")?,
+        file_offsets: db_reader.get_table_identifier("file_offsets")?,
+        pending_mempool_commits: db_reader.get_table_identifier("pending_mempool_commits")?,
+        state_diffs: db_reader.get_table_identifier("state_diffs")?,
+        transaction_hash_to_idx: db_reader.get_table_identifier("transaction_hash_to_idx")?,
+        transaction_metadata: db_reader.get_table_identifier("transaction_metadata")?,
+        transaction_sender_nonce_to_idx: db_reader
+            .get_table_identifier("transaction_sender_nonce_to_idx")?,
 
-    let writer = set_version_if_needed(reader.clone(), writer)?;
-    verify_storage_version(reader.clone())?;
-    Ok((reader, writer))
+        // Version tables
+        starknet_version: db_reader.get_table_identifier("starknet_version")?,
+        storage_version: db_reader.get_table_identifier("storage_version")?,
+    })
 }
 
 // In case storage version does not exist, set it to the crate version.
@@ -214,11 +330,11 @@ fn set_version_if_needed(
     reader: StorageReader,
     mut writer: StorageWriter,
 ) -> StorageResult<StorageWriter> {
-    let Some(existing_storage_version) = get_storage_version(reader)? else {
+    let Some(existing_storage_version) = get_storage_version(reader.clone())? else {
         // Initialize the storage version.
         writer.begin_rw_txn()?.set_state_version(&STORAGE_VERSION_STATE)?.commit()?;
-        // If in full-archive mode, also set the block version.
-        if writer.scope == StorageScope::FullArchive {
+        // If blocks are stored, also set the block version.
+        if writer.scope != StorageScope::StateOnly {
             writer.begin_rw_txn()?.set_blocks_version(&STORAGE_VERSION_BLOCKS)?.commit()?;
         }
         debug!(
@@ -231,6 +347,14 @@ fn set_version_if_needed(
     // Handle the case where the storage scope has changed.
     match existing_storage_version {
         StorageVersion::FullArchive(FullArchiveVersion { state_version: _, blocks_version: _ }) => {
+            // A pruned storage cannot change back to full-archive mode.
+            if writer.scope == StorageScope::FullArchive
+                && reader.begin_ro_txn()?.get_pruning_marker()? > BlockNumber(0)
+            {
+                return Err(StorageError::StorageVersionInconsistency(
+                    StorageVersionError::InconsistentStorageScope,
+                ));
+            }
             // TODO(yael): consider optimizing by deleting the block's data if the scope has changed
             // to StateOnly
             if writer.scope == StorageScope::StateOnly {
@@ -242,14 +366,26 @@ fn set_version_if_needed(
             }
         }
         StorageVersion::StateOnly(StateOnlyVersion { state_version: _ }) => {
-            // The storage cannot change from state-only to full-archive mode.
-            if writer.scope == StorageScope::FullArchive {
+            // The storage cannot change from state-only to a mode that stores blocks.
+            if writer.scope != StorageScope::StateOnly {
                 return Err(StorageError::StorageVersionInconsistency(
                     StorageVersionError::InconsistentStorageScope,
                 ));
             }
         }
     }
+    // Migrate the blocks data in write transactions of its own, before updating the blocks version,
+    // so an interrupted migration is resumed when the storage is reopened.
+    if let StorageVersion::FullArchive(FullArchiveVersion { state_version: _, blocks_version }) =
+        &existing_storage_version
+    {
+        if writer.scope != StorageScope::StateOnly
+            && STORAGE_VERSION_BLOCKS.major == blocks_version.major
+            && STORAGE_VERSION_BLOCKS.minor > blocks_version.minor
+        {
+            migrate_blocks(&mut writer, blocks_version)?;
+        }
+    }
     // Update the version if it's lower than the crate version.
     let mut wtxn = writer.begin_rw_txn()?;
     match existing_storage_version {
@@ -342,6 +478,25 @@ fn get_storage_version(reader: StorageReader) -> StorageResult<Option<StorageVer
 
 // Assumes the storage has a version.
 fn verify_storage_version(reader: StorageReader) -> StorageResult<()> {
+    verify_storage_version_by(reader, |crate_version, storage_version| {
+        crate_version == storage_version
+    })
+}
+
+// Assumes the storage has a version. Minor versions only add data, so a storage in an older minor
+// version can still be read.
+fn verify_storage_version_readable(reader: StorageReader) -> StorageResult<()> {
+    verify_storage_version_by(reader, |crate_version, storage_version| {
+        crate_version.major == storage_version.major && storage_version.minor <= crate_version.minor
+    })
+}
+
+// Verifies the storage versions are compatible with the crate's versions, as decided by
+// `is_compatible(crate_version, storage_version)`.
+fn verify_storage_version_by(
+    reader: StorageReader,
+    is_compatible: fn(&Version, &Version) -> bool,
+) -> StorageResult<()> {
     let existing_storage_version = get_storage_version(reader)?;
     debug!(
         "Crate storage version: State = {STORAGE_VERSION_STATE:} Blocks = \
@@ -353,7 +508,7 @@ fn verify_storage_version(reader: StorageReader) -> StorageResult<()> {
         Some(StorageVersion::FullArchive(FullArchiveVersion {
             state_version: existing_state_version,
             blocks_version: _,
-        })) if STORAGE_VERSION_STATE != existing_state_version => {
+        })) if !is_compatible(&STORAGE_VERSION_STATE, &existing_state_version) => {
             Err(StorageError::StorageVersionInconsistency(
                 StorageVersionError::InconsistentStorageVersion {
                     crate_version: STORAGE_VERSION_STATE,
@@ -365,7 +520,7 @@ fn verify_storage_version(reader: StorageReader) -> StorageResult<()> {
         Some(StorageVersion::FullArchive(FullArchiveVersion {
             state_version: _,
             blocks_version: existing_blocks_version,
-        })) if STORAGE_VERSION_BLOCKS != existing_blocks_version => {
+        })) if !is_compatible(&STORAGE_VERSION_BLOCKS, &existing_blocks_version) => {
             Err(StorageError::StorageVersionInconsistency(
                 StorageVersionError::InconsistentStorageVersion {
                     crate_version: STORAGE_VERSION_BLOCKS,
@@ -376,7 +531,7 @@ fn verify_storage_version(reader: StorageReader) -> StorageResult<()> {
 
         Some(StorageVersion::StateOnly(StateOnlyVersion {
             state_version: existing_state_version,
-        })) if STORAGE_VERSION_STATE != existing_state_version => {
+        })) if !is_compatible(&STORAGE_VERSION_STATE, &existing_state_version) => {
             Err(StorageError::StorageVersionInconsistency(
                 StorageVersionError::InconsistentStorageVersion {
                     crate_version: STORAGE_VERSION_STATE,
@@ -390,6 +545,8 @@ fn verify_storage_version(reader: StorageReader) -> StorageResult<()> {
 
 /// The categories of data to save in the storage.
 #[derive(Clone, Copy, Debug, Default, Serialize, Deserialize, PartialEq)]
+// Serialized as a string, to be set like other config params.
+#[serde(try_from = "String", into = "String")]
 pub enum StorageScope {
     /// Stores all types of data.
     #[default]
@@ -397,6 +554,47 @@ pub enum StorageScope {
     /// Stores the data describing the current state. In this mode the transaction, events and
     /// state-diffs are not stored.
     StateOnly,
+    /// Stores all types of data, but deletes the history of the blocks preceding the last
+    /// `retained_blocks` blocks in the background. See [`pruning`] for more details.
+    Pruned {
+        /// The number of latest blocks whose history is kept.
+        retained_blocks: u64,
+    },
+}
+
+const PRUNED_SCOPE_PREFIX: &str = "Pruned:";
+
+impl From<StorageScope> for String {
+    fn from(scope: StorageScope) -> Self {
+        match scope {
+            StorageScope::FullArchive => "FullArchive".to_owned(),
+            StorageScope::StateOnly => "StateOnly".to_owned(),
+            StorageScope::Pruned { retained_blocks } => {
+                format!("{PRUNED_SCOPE_PREFIX}{retained_blocks}")
+            }
+        }
+    }
+}
+
+impl TryFrom<String> for StorageScope {
+    type Error = String;
+
+    fn try_from(scope: String) -> Result<Self, Self::Error> {
+        match scope.as_str() {
+            "FullArchive" => Ok(StorageScope::FullArchive),
+            "StateOnly" => Ok(StorageScope::StateOnly),
+            _ => scope
+                .strip_prefix(PRUNED_SCOPE_PREFIX)
+                .and_then(|retained_blocks| retained_blocks.parse().ok())
+                .map(|retained_blocks| StorageScope::Pruned { retained_blocks })
+                .ok_or_else(|| {
+                    format!(
+                        "Invalid storage scope {scope:?}. Expected FullArchive, StateOnly or \
+                         {PRUNED_SCOPE_PREFIX}<retained_blocks>."
+                    )
+                }),
+        }
+    }
 }
 
 /// A struct for starting RO transactions ([`StorageTxn`]) to the storage.
@@ -442,12 +640,15 @@ impl StorageReader {
 
 /// A struct for starting RW transactions ([`StorageTxn`]) to the storage.
 /// There is a single non clonable writer instance, to make sure there is only one write transaction
-/// at any given moment.
+/// at any given moment. Under the [`StorageScope::Pruned`] scope, the writer also owns the
+/// background pruner, which is stopped when the writer is dropped.
 pub struct StorageWriter {
     db_writer: DbWriter,
     file_writers: FileHandlers<RW>,
     tables: Arc<Tables>,
     scope: StorageScope,
+    // Only held to stop the pruner when the writer is dropped.
+    _pruner: Option<BackgroundPruner>,
 }
 
 impl StorageWriter {
@@ -489,8 +690,10 @@ impl<'env, Mode: TransactionKind> StorageTxn<'env, Mode> {
         if self.scope == StorageScope::StateOnly {
             let unused_tables = [
                 self.tables.events.name,
+                self.tables.events_by_first_key.name,
                 self.tables.transaction_hash_to_idx.name,
                 self.tables.transaction_metadata.name,
+                self.tables.transaction_sender_nonce_to_idx.name,
             ];
             if unused_tables.contains(&table_id.name) {
                 return Err(StorageError::ScopeError {
@@ -513,6 +716,10 @@ struct_field_names! {
         block_hash_to_number: TableIdentifier<BlockHash, NoVersionValueWrapper<BlockNumber>, SimpleTable>,
         block_signatures: TableIdentifier<BlockNumber, VersionZeroWrapper<BlockSignature>, SimpleTable>,
         casms: TableIdentifier<ClassHash, VersionZeroWrapper<LocationInFile>, SimpleTable>,
+        classes_missing_sierra: TableIdentifier<ClassHash, NoVersionValueWrapper<BlockNumber>, SimpleTable>,
+        // The compiled class hashes of the classes whose declaring state diff is not stored, since
+        // it was pruned or imported from a snapshot.
+        compiled_class_hashes: TableIdentifier<ClassHash, NoVersionValueWrapper<CompiledClassHash>, SimpleTable>,
         // Empirically, defining the common prefix as (ContractAddress, StorageKey) is better space-wise than defining the
         // common prefix only as ContractAddress.
         contract_storage: TableIdentifier<((ContractAddress, StorageKey), BlockNumber), NoVersionValueWrapper<Felt>, CommonPrefix>,
@@ -522,14 +729,18 @@ struct_field_names! {
         // TODO(dvir): consider use here also the CommonPrefix table type.
         deployed_contracts: TableIdentifier<(ContractAddress, BlockNumber), VersionZeroWrapper<ClassHash>, SimpleTable>,
         events: TableIdentifier<(ContractAddress, TransactionIndex), NoVersionValueWrapper<NoValue>, CommonPrefix>,
+        events_by_first_key: TableIdentifier<(EventKey, TransactionIndex), NoVersionValueWrapper<NoValue>, CommonPrefix>,
         headers: TableIdentifier<BlockNumber, VersionZeroWrapper<StorageBlockHeader>, SimpleTable>,
+        l2_gas: TableIdentifier<BlockNumber, VersionZeroWrapper<L2GasInfo>, SimpleTable>,
         markers: TableIdentifier<MarkerKind, VersionZeroWrapper<BlockNumber>, SimpleTable>,
         nonces: TableIdentifier<(ContractAddress, BlockNumber), VersionZeroWrapper<Nonce>, CommonPrefix>,
         file_offsets: TableIdentifier<OffsetKind, NoVersionValueWrapper<usize>, SimpleTable>,
+        pending_mempool_commits: TableIdentifier<BlockNumber, VersionZeroWrapper<MempoolCommit>, SimpleTable>,
         state_diffs: TableIdentifier<BlockNumber, VersionZeroWrapper<LocationInFile>, SimpleTable>,
         transaction_hash_to_idx: TableIdentifier<TransactionHash, NoVersionValueWrapper<TransactionIndex>, SimpleTable>,
         // TODO(dvir): consider not saving transaction hash and calculating it from the transaction on demand.
         transaction_metadata: TableIdentifier<TransactionIndex, VersionZeroWrapper<TransactionMetadata>, SimpleTable>,
+        transaction_sender_nonce_to_idx: TableIdentifier<(ContractAddress, Nonce), NoVersionValueWrapper<TransactionIndex>, SimpleTable>,
 
         // Version tables
         starknet_version: TableIdentifier<BlockNumber, VersionZeroWrapper<StarknetVersion>, SimpleTable>,
@@ -587,6 +798,8 @@ pub enum StorageError {
     MMapFileError(#[from] MMapFileError),
     #[error(transparent)]
     StorageVersionInconsistency(#[from] StorageVersionError),
+    #[error(transparent)]
+    SnapshotError(#[from] SnapshotError),
     #[error("The table {table_name} is unused under the {storage_scope:?} storage scope.")]
     ScopeError { table_name: String, storage_scope: StorageScope },
     #[error(transparent)]
@@ -603,6 +816,11 @@ pub enum StorageError {
          {block_number}."
     )]
     BlockSignatureForNonExistingBlock { block_number: BlockNumber, block_signature: BlockSignature },
+    #[error(
+        "The history of block {block_number} was pruned. The first retained block is \
+         {pruning_marker}."
+    )]
+    PrunedBlock { block_number: BlockNumber, pruning_marker: BlockNumber },
 }
 
 /// A type alias that maps to std::result::Result<T, StorageError>.
@@ -616,17 +834,31 @@ pub struct StorageConfig {
     pub db_config: DbConfig,
     #[validate]
     pub mmap_file_config: MmapFileConfig,
+    #[validate(custom = "validate_storage_scope")]
     pub scope: StorageScope,
+    pub index_event_keys: bool,
 }
 
 impl SerializeConfig for StorageConfig {
     fn dump(&self) -> BTreeMap<ParamPath, SerializedParam> {
-        let mut dumped_config = BTreeMap::from_iter([ser_param(
-            "scope",
-            &self.scope,
-            "The categories of data saved in storage.",
-            ParamPrivacyInput::Public,
-        )]);
+        let mut dumped_config = BTreeMap::from_iter([
+            ser_param(
+                "scope",
+                &self.scope,
+                "The categories of data saved in storage: FullArchive, StateOnly, or \
+                 Pruned:<retained_blocks> to keep the history of the last retained_blocks blocks \
+                 only.",
+                ParamPrivacyInput::Public,
+            ),
+            ser_param(
+                "index_event_keys",
+                &self.index_event_keys,
+                "If true, the events are indexed by their first key from the next stored block \
+                 on, to speed up queries of events by key across all contracts. Once enabled, the \
+                 index is maintained even if this flag is turned off.",
+                ParamPrivacyInput::Public,
+            ),
+        ]);
         dumped_config
             .extend(append_sub_config_name(self.mmap_file_config.dump(), "mmap_file_config"));
         dumped_config.extend(append_sub_config_name(self.db_config.dump(), "db_config"));
@@ -634,6 +866,21 @@ impl SerializeConfig for StorageConfig {
     }
 }
 
+// Blocks that may be reverted must not be pruned.
+fn validate_storage_scope(scope: &StorageScope) -> Result<(), ValidationError> {
+    if let StorageScope::Pruned { retained_blocks } = scope {
+        if *retained_blocks < MIN_RETAINED_BLOCKS {
+            let mut error = ValidationError::new("too few retained blocks");
+            error.message = Some(
+                format!("The pruned scope must retain at least {MIN_RETAINED_BLOCKS} blocks.")
+                    .into(),
+            );
+            return Err(error);
+        }
+    }
+    Ok(())
+}
+
 /// A struct for the statistics of the tables in the database.
 #[derive(Serialize, Deserialize, Debug)]
 pub struct DbStats {
@@ -649,6 +896,14 @@ pub struct DbStats {
 // - CompiledClass <= Class <= State <= Header
 // - Body <= Header
 // - BaseLayerBlock <= Header
+// - Pruning <= min(Header, Body, State)
+// The batcher writes the headers only once their state root is known, so in its storage the Body
+// and State markers may exceed the Header marker.
+// EventsByFirstKey is the first block whose events are indexed by their first key, and it exists
+// only if the index is enabled.
+// SenderNonceIndexMigration is the first block whose transactions weren't indexed yet by their
+// sender and nonce by the migration to blocks version 3.2, and it exists only while the migration
+// is in progress.
 // Event is currently unsupported.
 pub(crate) enum MarkerKind {
     Header,
@@ -658,6 +913,9 @@ pub(crate) enum MarkerKind {
     Class,
     CompiledClass,
     BaseLayerBlock,
+    Pruning,
+    EventsByFirstKey,
+    SenderNonceIndexMigration,
 }
 
 pub(crate) type MarkersTable<'env> =
@@ -862,6 +1120,53 @@ fn open_storage_files(
     ))
 }
 
+// Opens the existing storage files for reading only.
+fn open_storage_files_read_only(
+    db_config: &DbConfig,
+    mmap_file_config: MmapFileConfig,
+    db_reader: DbReader,
+    file_offsets_table: &TableIdentifier<OffsetKind, NoVersionValueWrapper<usize>, SimpleTable>,
+) -> StorageResult<FileHandlers<RO>> {
+    let db_transaction = db_reader.begin_ro_txn()?;
+    let table = db_transaction.open_table(file_offsets_table)?;
+    let offset = |offset_kind: OffsetKind| -> StorageResult<usize> {
+        Ok(table.get(&db_transaction, &offset_kind)?.unwrap_or_default())
+    };
+
+    Ok(FileHandlers {
+        thin_state_diff: open_file_read_only(
+            mmap_file_config.clone(),
+            db_config.path().join("thin_state_diff.dat"),
+            offset(OffsetKind::ThinStateDiff)?,
+        )?,
+        contract_class: open_file_read_only(
+            mmap_file_config.clone(),
+            db_config.path().join("contract_class.dat"),
+            offset(OffsetKind::ContractClass)?,
+        )?,
+        casm: open_file_read_only(
+            mmap_file_config.clone(),
+            db_config.path().join("casm.dat"),
+            offset(OffsetKind::Casm)?,
+        )?,
+        deprecated_contract_class: open_file_read_only(
+            mmap_file_config.clone(),
+            db_config.path().join("deprecated_contract_class.dat"),
+            offset(OffsetKind::DeprecatedContractClass)?,
+        )?,
+        transaction_output: open_file_read_only(
+            mmap_file_config.clone(),
+            db_config.path().join("transaction_output.dat"),
+            offset(OffsetKind::TransactionOutput)?,
+        )?,
+        transaction: open_file_read_only(
+            mmap_file_config,
+            db_config.path().join("transaction.dat"),
+            offset(OffsetKind::Transaction)?,
+        )?,
+    })
+}
+
 /// Represents a kind of mmap file.
 #[derive(Copy, Clone, Debug, Serialize, Deserialize, Eq, PartialEq, PartialOrd, Ord)]
 pub enum OffsetKind {
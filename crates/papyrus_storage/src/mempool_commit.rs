@@ -0,0 +1,121 @@
+//! Interface for handling the mempool commits of blocks that the mempool did not acknowledge yet.
+//!
+//! A block's mempool commit is written together with the block, and deleted once the mempool
+//! committed the block, so it is not lost if the mempool is unavailable or the node restarts in
+//! between.
+//!
+//! Import [`MempoolCommitStorageReader`] and [`MempoolCommitStorageWriter`] to read and write the
+//! pending mempool commits using a [`StorageTxn`].
+//! # Example
+//! ```
+//! use papyrus_storage::mempool_commit::{
+//!     MempoolCommit,
+//!     MempoolCommitStorageReader,
+//!     MempoolCommitStorageWriter,
+//! };
+//! use papyrus_storage::open_storage;
+//! # use papyrus_storage::{db::DbConfig, StorageConfig};
+//! # use starknet_api::core::ChainId;
+//! use starknet_api::block::BlockNumber;
+//!
+//! # let dir_handle = tempfile::tempdir().unwrap();
+//! # let dir = dir_handle.path().to_path_buf();
+//! # let db_config = DbConfig {
+//! #     path_prefix: dir,
+//! #     chain_id: ChainId::Mainnet,
+//! #     enforce_file_exists: false,
+//! #     min_size: 1 << 20,    // 1MB
+//! #     max_size: 1 << 35,    // 32GB
+//! #     growth_step: 1 << 26, // 64MB
+//! # };
+//! # let storage_config = StorageConfig{db_config, ..Default::default()};
+//! let mempool_commit = MempoolCommit::default();
+//! let (reader, mut writer) = open_storage(storage_config)?;
+//! writer
+//!     .begin_rw_txn()?                                                // Start a RW transaction.
+//!     .set_pending_mempool_commit(BlockNumber(0), &mempool_commit)?   // Set a pending commit.
+//!     .commit()?; // Commit the transaction.
+//! let pending_commits = reader.begin_ro_txn()?.get_pending_mempool_commits()?;
+//! assert_eq!(pending_commits, vec![(BlockNumber(0), mempool_commit)]);
+//! # Ok::<(), papyrus_storage::StorageError>(())
+//! ```
+#[cfg(test)]
+#[path = "mempool_commit_test.rs"]
+mod mempool_commit_test;
+
+use std::collections::HashMap;
+
+use starknet_api::block::BlockNumber;
+use starknet_api::core::{ContractAddress, Nonce};
+use starknet_api::transaction::TransactionHash;
+
+use crate::db::table_types::{DbCursorTrait, Table};
+use crate::db::{TransactionKind, RW};
+use crate::{StorageResult, StorageTxn};
+
+/// The changes a committed block makes to the mempool.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct MempoolCommit {
+    /// The next nonces of the accounts that sent transactions in the block.
+    pub address_to_nonce: HashMap<ContractAddress, Nonce>,
+    /// The hashes of the transactions of the block.
+    pub tx_hashes: Vec<TransactionHash>,
+    /// The hashes of the transactions that failed execution while building the block.
+    pub rejected_tx_hashes: Vec<TransactionHash>,
+}
+
+/// Interface for reading the pending mempool commits.
+pub trait MempoolCommitStorageReader {
+    /// Returns the mempool commits that were not acknowledged yet, ordered by block number.
+    fn get_pending_mempool_commits(&self) -> StorageResult<Vec<(BlockNumber, MempoolCommit)>>;
+}
+
+/// Interface for writing the pending mempool commits.
+pub trait MempoolCommitStorageWriter
+where
+    Self: Sized,
+{
+    /// Sets the pending mempool commit of the given block, overriding existing data.
+    // To enforce that no commit happen after a failure, we consume and return Self on success.
+    fn set_pending_mempool_commit(
+        self,
+        block_number: BlockNumber,
+        mempool_commit: &MempoolCommit,
+    ) -> StorageResult<Self>;
+
+    /// Deletes the pending mempool commit of the given block, once the mempool committed it.
+    fn delete_pending_mempool_commit(self, block_number: BlockNumber) -> StorageResult<Self>;
+}
+
+impl<'env, Mode: TransactionKind> MempoolCommitStorageReader for StorageTxn<'env, Mode> {
+    fn get_pending_mempool_commits(&self) -> StorageResult<Vec<(BlockNumber, MempoolCommit)>> {
+        let pending_mempool_commits_table =
+            self.open_table(&self.tables.pending_mempool_commits)?;
+        let mut cursor = pending_mempool_commits_table.cursor(&self.txn)?;
+        let mut pending_commits = Vec::new();
+        while let Some(entry) = cursor.next()? {
+            pending_commits.push(entry);
+        }
+        Ok(pending_commits)
+    }
+}
+
+impl<'env> MempoolCommitStorageWriter for StorageTxn<'env, RW> {
+    fn set_pending_mempool_commit(
+        self,
+        block_number: BlockNumber,
+        mempool_commit: &MempoolCommit,
+    ) -> StorageResult<Self> {
+        let pending_mempool_commits_table =
+            self.open_table(&self.tables.pending_mempool_commits)?;
+        pending_mempool_commits_table.upsert(&self.txn, &block_number, mempool_commit)?;
+        Ok(self)
+    }
+
+    fn delete_pending_mempool_commit(self, block_number: BlockNumber) -> StorageResult<Self> {
+        let pending_mempool_commits_table =
+            self.open_table(&self.tables.pending_mempool_commits)?;
+        pending_mempool_commits_table.delete(&self.txn, &block_number)?;
+        Ok(self)
+    }
+}
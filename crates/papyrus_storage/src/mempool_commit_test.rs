@@ -0,0 +1,53 @@
+use std::collections::HashMap;
+
+use starknet_api::block::BlockNumber;
+use starknet_api::core::Nonce;
+use starknet_api::transaction::TransactionHash;
+use starknet_api::{contract_address, felt};
+
+use crate::mempool_commit::{
+    MempoolCommit,
+    MempoolCommitStorageReader,
+    MempoolCommitStorageWriter,
+};
+use crate::test_utils::get_test_storage;
+
+#[test]
+fn set_and_delete_pending_mempool_commits() {
+    let (reader, mut writer) = get_test_storage().0;
+
+    // No pending commits before they are set.
+    assert_eq!(reader.begin_ro_txn().unwrap().get_pending_mempool_commits().unwrap(), vec![]);
+
+    let mempool_commit = MempoolCommit {
+        address_to_nonce: HashMap::from([(contract_address!(1_u8), Nonce(felt!(2_u8)))]),
+        tx_hashes: vec![TransactionHash(felt!(3_u8))],
+        rejected_tx_hashes: vec![TransactionHash(felt!(4_u8))],
+    };
+    writer
+        .begin_rw_txn()
+        .unwrap()
+        .set_pending_mempool_commit(BlockNumber(1), &mempool_commit)
+        .unwrap()
+        .set_pending_mempool_commit(BlockNumber(0), &MempoolCommit::default())
+        .unwrap()
+        .commit()
+        .unwrap();
+    // Pending commits are ordered by block number.
+    assert_eq!(
+        reader.begin_ro_txn().unwrap().get_pending_mempool_commits().unwrap(),
+        vec![(BlockNumber(0), MempoolCommit::default()), (BlockNumber(1), mempool_commit.clone())]
+    );
+
+    writer
+        .begin_rw_txn()
+        .unwrap()
+        .delete_pending_mempool_commit(BlockNumber(0))
+        .unwrap()
+        .commit()
+        .unwrap();
+    assert_eq!(
+        reader.begin_ro_txn().unwrap().get_pending_mempool_commits().unwrap(),
+        vec![(BlockNumber(1), mempool_commit)]
+    );
+}
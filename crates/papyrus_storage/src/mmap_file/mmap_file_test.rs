@@ -46,6 +46,40 @@ fn write_read() {
     dir.close().unwrap();
 }
 
+#[test]
+fn read_only() {
+    let dir = tempdir().unwrap();
+    let file_path = dir.path().to_path_buf().join("test_read_only");
+    let config = get_mmap_file_test_config();
+
+    // Opening a file that does not exist for reading only fails and does not create it.
+    assert!(open_file_read_only::<NoVersionValueWrapper<Vec<u8>>>(
+        config.clone(),
+        file_path.clone(),
+        0
+    )
+    .is_err());
+    assert!(!file_path.exists());
+
+    let (mut writer, _) =
+        open_file::<NoVersionValueWrapper<Vec<u8>>>(config.clone(), file_path.clone(), 0).unwrap();
+    let data = vec![1, 2, 3];
+    let location_in_file = writer.append(&data);
+    writer.flush();
+    let file_size = file_path.metadata().unwrap().len();
+
+    let reader = open_file_read_only::<NoVersionValueWrapper<Vec<u8>>>(
+        config,
+        file_path.clone(),
+        location_in_file.next_offset(),
+    )
+    .unwrap();
+    assert_eq!(reader.get(location_in_file).unwrap().unwrap(), data);
+    assert_eq!(file_path.metadata().unwrap().len(), file_size);
+
+    dir.close().unwrap();
+}
+
 #[test]
 fn concurrent_reads() {
     let dir = tempdir().unwrap();
@@ -15,7 +15,7 @@ use std::path::PathBuf;
 use std::result;
 use std::sync::{Arc, Mutex};
 
-use memmap2::{MmapMut, MmapOptions};
+use memmap2::{Mmap, MmapMut, MmapOptions};
 use papyrus_config::dumping::{ser_param, SerializeConfig};
 use papyrus_config::{ParamPath, ParamPrivacyInput, SerializedParam};
 #[cfg(test)]
@@ -139,13 +139,36 @@ struct MMapFile<V: ValueSerde> {
     config: MmapFileConfig,
     file: File,
     size: usize,
-    mmap: MmapMut,
+    mmap: FileMmap,
     offset: usize,
     should_flush: bool,
     _value_type: PhantomData<V>,
 }
 
+/// The memory map of a file, which can be written only if the file was opened for writing.
+#[derive(Debug)]
+enum FileMmap {
+    ReadWrite(MmapMut),
+    ReadOnly(Mmap),
+}
+
+impl FileMmap {
+    fn as_ptr(&self) -> *const u8 {
+        match self {
+            FileMmap::ReadWrite(mmap) => mmap.as_ptr(),
+            FileMmap::ReadOnly(mmap) => mmap.as_ptr(),
+        }
+    }
+}
+
 impl<V: ValueSerde> MMapFile<V> {
+    fn writable_mmap(&mut self) -> &mut MmapMut {
+        match &mut self.mmap {
+            FileMmap::ReadWrite(mmap) => mmap,
+            FileMmap::ReadOnly(_) => unreachable!("A file opened for reading only has no writer."),
+        }
+    }
+
     /// Grows the file by the growth step.
     fn grow(&mut self) {
         self.flush();
@@ -159,7 +182,7 @@ impl<V: ValueSerde> MMapFile<V> {
     /// Flushes the mmap to the file.
     fn flush(&mut self) {
         trace!("Flushing mmap to file");
-        self.mmap.flush().expect("Failed to flush the mmap");
+        self.writable_mmap().flush().expect("Failed to flush the mmap");
         self.should_flush = false;
     }
 }
@@ -173,7 +196,8 @@ pub(crate) fn open_file<V: ValueSerde>(
 ) -> MmapFileResult<(FileHandler<V, RW>, FileHandler<V, RO>)> {
     let file = OpenOptions::new().read(true).write(true).create(true).truncate(false).open(path)?;
     let size = file.metadata()?.len();
-    let mmap = unsafe { MmapOptions::new().len(config.max_size).map_mut(&file)? };
+    let mmap =
+        FileMmap::ReadWrite(unsafe { MmapOptions::new().len(config.max_size).map_mut(&file)? });
     let mmap_ptr = mmap.as_ptr();
     let mmap_file = MMapFile {
         config,
@@ -199,6 +223,34 @@ pub(crate) fn open_file<V: ValueSerde>(
     Ok((write_file_handler, read_file_handler))
 }
 
+/// Open an existing memory mapped file for reading only. The file is neither created nor grown.
+#[instrument(level = "debug", err)]
+pub(crate) fn open_file_read_only<V: ValueSerde>(
+    config: MmapFileConfig,
+    path: PathBuf,
+    offset: usize,
+) -> MmapFileResult<FileHandler<V, RO>> {
+    let file = OpenOptions::new().read(true).open(path)?;
+    let size = file.metadata()?.len();
+    let mmap = FileMmap::ReadOnly(unsafe { MmapOptions::new().len(config.max_size).map(&file)? });
+    let mmap_ptr = mmap.as_ptr();
+    let mmap_file = MMapFile {
+        config,
+        file,
+        mmap,
+        size: size.try_into().expect("size should fit in usize"),
+        offset,
+        should_flush: false,
+        _value_type: PhantomData {},
+    };
+
+    Ok(FileHandler {
+        memory_ptr: mmap_ptr,
+        mmap_file: Arc::new(Mutex::new(mmap_file)),
+        _mode: PhantomData,
+    })
+}
+
 /// A wrapper around `MMapFile` that provides both write and read interfaces.
 #[derive(Clone, Debug)]
 pub(crate) struct FileHandler<V: ValueSerde, Mode: TransactionKind> {
@@ -233,10 +285,10 @@ impl<V: ValueSerde + Debug> Writer<V> for FileHandler<V, RW> {
             let mut mmap_file = self.mmap_file.lock().expect("Lock should not be poisoned");
             offset = mmap_file.offset;
             trace!("Inserting object at offset: {}", offset);
-            let mmap_slice = &mut mmap_file.mmap[offset..];
+            let mmap_slice = &mut mmap_file.writable_mmap()[offset..];
             mmap_slice[..len].copy_from_slice(&serialized);
             mmap_file
-                .mmap
+                .writable_mmap()
                 .flush_async_range(offset, len)
                 .expect("Failed to asynchronously flush the mmap after inserting");
             mmap_file.offset += len;
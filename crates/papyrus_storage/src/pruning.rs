@@ -0,0 +1,313 @@
+//! Interface for pruning the history of old blocks from the storage.
+//!
+//! Under the [`StorageScope::Pruned`] scope, the storage keeps the data of the last
+//! `retained_blocks` blocks only. The headers, bodies, events and state diffs of older blocks are
+//! deleted, along with the historical values of the state that are no longer needed to read the
+//! state of a retained block; the latest value of every state key remains readable. Declared
+//! classes are kept, since they are part of the current state.
+//!
+//! The pruning is done in the background, by a thread started when the storage is opened, in
+//! bounded write transactions. Note that the data written to the storage files is not reclaimed.
+//!
+//! Reading the history of a pruned block, or the state at a state number before the first retained
+//! block, returns [`StorageError::PrunedBlock`], and so does reverting a pruned block. The
+//! `retained_blocks` of the scope should therefore cover the depth of the reverts the node may
+//! perform; it is validated to be at least [`MIN_RETAINED_BLOCKS`].
+//!
+//! Import [`PruningStorageReader`] to read the first block whose history is retained.
+//!
+//! # Example
+//! ```
+//! use papyrus_storage::open_storage;
+//! # use papyrus_storage::{db::DbConfig, StorageConfig, StorageScope};
+//! use papyrus_storage::pruning::PruningStorageReader;
+//! use starknet_api::block::BlockNumber;
+//! # use starknet_api::core::ChainId;
+//!
+//! # let dir_handle = tempfile::tempdir().unwrap();
+//! # let dir = dir_handle.path().to_path_buf();
+//! # let db_config = DbConfig {
+//! #     path_prefix: dir,
+//! #     chain_id: ChainId::Mainnet,
+//! #     enforce_file_exists: false,
+//! #     min_size: 1 << 20,    // 1MB
+//! #     max_size: 1 << 35,    // 32GB
+//! #     growth_step: 1 << 26, // 64MB
+//! # };
+//! let scope = StorageScope::Pruned { retained_blocks: 1000 };
+//! # let storage_config = StorageConfig{db_config, scope, ..Default::default()};
+//! let (reader, mut writer) = open_storage(storage_config)?;
+//! let first_retained_block = reader.begin_ro_txn()?.get_pruning_marker()?;
+//! assert_eq!(first_retained_block, BlockNumber(0));
+//! # Ok::<(), papyrus_storage::StorageError>(())
+//! ```
+
+#[cfg(test)]
+#[path = "pruning_test.rs"]
+mod pruning_test;
+
+use std::cmp::min;
+use std::fmt::Debug;
+use std::sync::mpsc::{channel, RecvTimeoutError, Sender};
+use std::thread::JoinHandle;
+use std::time::Duration;
+
+use starknet_api::block::BlockNumber;
+use starknet_api::transaction::TransactionOffsetInBlock;
+use tracing::{debug, error};
+
+use crate::body::{get_sender_and_nonce, BodyStorageReader, TransactionIndex};
+use crate::db::table_types::{DbCursor, DbCursorTrait, Table};
+use crate::db::{DbTransaction, TransactionKind, RW};
+use crate::header::HeaderStorageReader;
+use crate::state::StateStorageReader;
+use crate::{MarkerKind, StorageError, StorageResult, StorageTxn, StorageWriter};
+
+/// The minimal number of blocks retained under the [`Pruned`](crate::StorageScope::Pruned) scope,
+/// covering the depth of the reverts the node may perform.
+pub const MIN_RETAINED_BLOCKS: u64 = 100;
+
+// The interval between consecutive pruning rounds of the background pruner.
+const PRUNING_INTERVAL: Duration = Duration::from_secs(10);
+// Bounds the time the background pruner holds the write lock of the storage.
+const MAX_BLOCKS_PRUNED_PER_TXN: u64 = 100;
+
+/// Interface for reading the pruning progress.
+pub trait PruningStorageReader {
+    /// The pruning marker is the first block whose history is retained; the history of the
+    /// blocks before it was deleted.
+    fn get_pruning_marker(&self) -> StorageResult<BlockNumber>;
+}
+
+/// Interface for pruning the history of old blocks.
+pub trait PruningStorageWriter
+where
+    Self: Sized,
+{
+    /// Deletes the history of the blocks preceding the last `retained_blocks` blocks whose header,
+    /// body and state diff are stored, up to `max_n_blocks` blocks. Returns the number of pruned
+    /// blocks.
+    fn prune_history(self, retained_blocks: u64, max_n_blocks: u64) -> StorageResult<(Self, u64)>;
+}
+
+impl<'env, Mode: TransactionKind> PruningStorageReader for StorageTxn<'env, Mode> {
+    fn get_pruning_marker(&self) -> StorageResult<BlockNumber> {
+        let markers_table = self.open_table(&self.tables.markers)?;
+        Ok(markers_table.get(&self.txn, &MarkerKind::Pruning)?.unwrap_or_default())
+    }
+}
+
+impl<'env> PruningStorageWriter for StorageTxn<'env, RW> {
+    fn prune_history(self, retained_blocks: u64, max_n_blocks: u64) -> StorageResult<(Self, u64)> {
+        let complete_blocks_marker =
+            min(min(self.get_header_marker()?, self.get_body_marker()?), self.get_state_marker()?);
+        let first_retained_block =
+            BlockNumber(complete_blocks_marker.0.saturating_sub(retained_blocks));
+        let pruning_marker = self.get_pruning_marker()?;
+        let new_pruning_marker =
+            min(first_retained_block, BlockNumber(pruning_marker.0.saturating_add(max_n_blocks)));
+        if new_pruning_marker <= pruning_marker {
+            return Ok((self, 0));
+        }
+
+        for block_number in pruning_marker.iter_up_to(new_pruning_marker) {
+            self.prune_header(block_number)?;
+            self.prune_body(block_number)?;
+            self.prune_state_diff(block_number)?;
+        }
+        let markers_table = self.open_table(&self.tables.markers)?;
+        markers_table.upsert(&self.txn, &MarkerKind::Pruning, &new_pruning_marker)?;
+        debug!("Pruned the history of blocks {pruning_marker} to {new_pruning_marker}.");
+
+        Ok((self, new_pruning_marker.0 - pruning_marker.0))
+    }
+}
+
+impl<'env, Mode: TransactionKind> StorageTxn<'env, Mode> {
+    // Returns an error if the history of the block was pruned.
+    pub(crate) fn verify_block_not_pruned(&self, block_number: BlockNumber) -> StorageResult<()> {
+        let pruning_marker = self.get_pruning_marker()?;
+        if block_number < pruning_marker {
+            return Err(StorageError::PrunedBlock { block_number, pruning_marker });
+        }
+        Ok(())
+    }
+}
+
+impl<'env> StorageTxn<'env, RW> {
+    fn prune_header(&self, block_number: BlockNumber) -> StorageResult<()> {
+        let headers_table = self.open_table(&self.tables.headers)?;
+        let block_hash_to_number_table = self.open_table(&self.tables.block_hash_to_number)?;
+        let block_signatures_table = self.open_table(&self.tables.block_signatures)?;
+
+        if let Some(header) = headers_table.get(&self.txn, &block_number)? {
+            block_hash_to_number_table.delete(&self.txn, &header.block_hash)?;
+            headers_table.delete(&self.txn, &block_number)?;
+        }
+        block_signatures_table.delete(&self.txn, &block_number)?;
+        Ok(())
+    }
+
+    fn prune_body(&self, block_number: BlockNumber) -> StorageResult<()> {
+        let (Some(transactions), Some(transaction_hashes), Some(transaction_outputs)) = (
+            self.get_block_transactions(block_number)?,
+            self.get_block_transaction_hashes(block_number)?,
+            self.get_block_transaction_outputs(block_number)?,
+        ) else {
+            return Ok(());
+        };
+        let transaction_metadata_table = self.open_table(&self.tables.transaction_metadata)?;
+        let transaction_hash_to_idx_table =
+            self.open_table(&self.tables.transaction_hash_to_idx)?;
+        let transaction_sender_nonce_to_idx_table =
+            self.open_table(&self.tables.transaction_sender_nonce_to_idx)?;
+        let events_table = self.open_table(&self.tables.events)?;
+        let events_by_first_key_table = match self.get_events_by_first_key_marker()? {
+            Some(_) => Some(self.open_table(&self.tables.events_by_first_key)?),
+            None => None,
+        };
+
+        for (offset, ((tx, tx_hash), tx_output)) in transactions
+            .iter()
+            .zip(transaction_hashes.iter())
+            .zip(transaction_outputs.iter())
+            .enumerate()
+        {
+            let tx_index = TransactionIndex(block_number, TransactionOffsetInBlock(offset));
+            for event in tx_output.events().iter() {
+                events_table.delete(&self.txn, &(event.from_address, tx_index))?;
+                if let (Some(events_by_first_key_table), Some(first_key)) =
+                    (&events_by_first_key_table, event.content.keys.first())
+                {
+                    events_by_first_key_table.delete(&self.txn, &(first_key.clone(), tx_index))?;
+                }
+            }
+            transaction_hash_to_idx_table.delete(&self.txn, tx_hash)?;
+            if let Some(sender_and_nonce) = get_sender_and_nonce(tx, tx_output) {
+                transaction_sender_nonce_to_idx_table.delete(&self.txn, &sender_and_nonce)?;
+            }
+            transaction_metadata_table.delete(&self.txn, &tx_index)?;
+        }
+        Ok(())
+    }
+
+    // Keeps the values written in the block, and deletes the values they override, as no retained
+    // state refers to them.
+    fn prune_state_diff(&self, block_number: BlockNumber) -> StorageResult<()> {
+        let Some(thin_state_diff) = self.get_state_diff(block_number)? else {
+            return Ok(());
+        };
+        let storage_table = self.open_table(&self.tables.contract_storage)?;
+        let nonces_table = self.open_table(&self.tables.nonces)?;
+        let deployed_contracts_table = self.open_table(&self.tables.deployed_contracts)?;
+        let state_diffs_table = self.open_table(&self.tables.state_diffs)?;
+        let compiled_class_hashes_table = self.open_table(&self.tables.compiled_class_hashes)?;
+
+        for (address, storage_entries) in &thin_state_diff.storage_diffs {
+            for key in storage_entries.keys() {
+                delete_overridden_values(
+                    &self.txn,
+                    &storage_table,
+                    (*address, *key),
+                    block_number,
+                )?;
+            }
+        }
+        // A nonce is written also for each deployed contract.
+        for address in
+            thin_state_diff.nonces.keys().chain(thin_state_diff.deployed_contracts.keys())
+        {
+            delete_overridden_values(&self.txn, &nonces_table, *address, block_number)?;
+        }
+        for address in
+            thin_state_diff.deployed_contracts.keys().chain(thin_state_diff.replaced_classes.keys())
+        {
+            delete_overridden_values(&self.txn, &deployed_contracts_table, *address, block_number)?;
+        }
+        // The compiled class hashes are read from the state diff that declared them, so they are
+        // kept once it is deleted.
+        for (class_hash, compiled_class_hash) in &thin_state_diff.declared_classes {
+            compiled_class_hashes_table.upsert(&self.txn, class_hash, compiled_class_hash)?;
+        }
+        state_diffs_table.delete(&self.txn, &block_number)?;
+        Ok(())
+    }
+}
+
+// Deletes the values of the key written before the given block.
+fn delete_overridden_values<'env, K, T>(
+    txn: &'env DbTransaction<'env, RW>,
+    table: &'env T,
+    key: K,
+    block_number: BlockNumber,
+) -> StorageResult<()>
+where
+    K: Copy + Debug + Eq,
+    T: Table<'env, Key = (K, BlockNumber)>,
+    DbCursor<'env, RW, T::Key, T::Value, T::TableVariant>:
+        DbCursorTrait<Key = T::Key, Value = T::Value>,
+{
+    let mut overridden_entries = Vec::new();
+    let mut cursor = table.cursor(txn)?;
+    cursor.lower_bound(&(key, block_number))?;
+    while let Some(((entry_key, entry_block_number), _value)) = cursor.prev()? {
+        if entry_key != key {
+            break;
+        }
+        overridden_entries.push((entry_key, entry_block_number));
+    }
+    for entry in overridden_entries {
+        table.delete(txn, &entry)?;
+    }
+    Ok(())
+}
+
+// Prunes the storage in the background, every PRUNING_INTERVAL, until dropped.
+pub(crate) struct BackgroundPruner {
+    stop_sender: Option<Sender<()>>,
+    join_handle: Option<JoinHandle<()>>,
+}
+
+impl BackgroundPruner {
+    // The writer must be dedicated to the pruner; its write transactions are serialized with the
+    // ones of the other writer by the database.
+    pub(crate) fn spawn(mut writer: StorageWriter, retained_blocks: u64) -> Self {
+        let (stop_sender, stop_receiver) = channel();
+        let join_handle = std::thread::spawn(move || {
+            loop {
+                if let Err(err) = prune_pending_blocks(&mut writer, retained_blocks) {
+                    error!("Failed to prune the storage: {err}.");
+                }
+                match stop_receiver.recv_timeout(PRUNING_INTERVAL) {
+                    Err(RecvTimeoutError::Timeout) => continue,
+                    // Stopped, or the pruner was dropped.
+                    Ok(()) | Err(RecvTimeoutError::Disconnected) => break,
+                }
+            }
+        });
+        Self { stop_sender: Some(stop_sender), join_handle: Some(join_handle) }
+    }
+}
+
+impl Drop for BackgroundPruner {
+    fn drop(&mut self) {
+        // Disconnecting the channel stops the pruner.
+        self.stop_sender.take();
+        if let Some(join_handle) = self.join_handle.take() {
+            if join_handle.join().is_err() {
+                error!("The storage pruner panicked.");
+            }
+        }
+    }
+}
+
+fn prune_pending_blocks(writer: &mut StorageWriter, retained_blocks: u64) -> StorageResult<()> {
+    loop {
+        let (txn, n_pruned_blocks) =
+            writer.begin_rw_txn()?.prune_history(retained_blocks, MAX_BLOCKS_PRUNED_PER_TXN)?;
+        txn.commit()?;
+        if n_pruned_blocks < MAX_BLOCKS_PRUNED_PER_TXN {
+            return Ok(());
+        }
+    }
+}
@@ -0,0 +1,279 @@
+use assert_matches::assert_matches;
+use indexmap::indexmap;
+use papyrus_test_utils::get_test_block;
+use pretty_assertions::assert_eq;
+use starknet_api::block::{BlockHash, BlockHeader, BlockHeaderWithoutHash, BlockNumber};
+use starknet_api::core::{ClassHash, CompiledClassHash, ContractAddress, Nonce};
+use starknet_api::state::{StateNumber, StorageKey, ThinStateDiff};
+use starknet_api::transaction::TransactionHash;
+use starknet_api::{class_hash, contract_address, felt, storage_key};
+use starknet_types_core::felt::Felt;
+use test_case::test_case;
+
+use crate::body::{BodyStorageReader, BodyStorageWriter};
+use crate::db::table_types::Table;
+use crate::header::{HeaderStorageReader, HeaderStorageWriter};
+use crate::pruning::{PruningStorageReader, PruningStorageWriter, MIN_RETAINED_BLOCKS};
+use crate::state::{StateStorageReader, StateStorageWriter};
+use crate::test_utils::{get_test_storage, get_test_storage_with_config_by_scope};
+use crate::version::StorageVersionError;
+use crate::{open_storage, validate_storage_scope, StorageError, StorageScope, StorageWriter};
+
+fn block_hash(block_number: BlockNumber) -> BlockHash {
+    BlockHash(Felt::from(block_number.0 + 1))
+}
+
+fn tx_hash(block_number: BlockNumber) -> TransactionHash {
+    TransactionHash(Felt::from(block_number.0 + 1))
+}
+
+// Appends a block with a single transaction, emitting a single event, and the given state diff.
+fn append_block(writer: &mut StorageWriter, block_number: BlockNumber, state_diff: ThinStateDiff) {
+    let header = BlockHeader {
+        block_hash: block_hash(block_number),
+        block_header_without_hash: BlockHeaderWithoutHash { block_number, ..Default::default() },
+        ..Default::default()
+    };
+    let mut body = get_test_block(1, Some(1), None, None).body;
+    body.transaction_hashes = vec![tx_hash(block_number)];
+    writer
+        .begin_rw_txn()
+        .unwrap()
+        .append_header(block_number, &header)
+        .unwrap()
+        .append_body(block_number, body)
+        .unwrap()
+        .append_state_diff(block_number, state_diff)
+        .unwrap()
+        .commit()
+        .unwrap();
+}
+
+fn contract() -> ContractAddress {
+    contract_address!(0x1_u8)
+}
+
+fn key() -> StorageKey {
+    storage_key!(0x2_u8)
+}
+
+fn class() -> ClassHash {
+    class_hash!(0x3_u8)
+}
+
+fn declared_class() -> ClassHash {
+    class_hash!(0x4_u8)
+}
+
+fn compiled_class_hash() -> CompiledClassHash {
+    CompiledClassHash(felt!(0x5_u8))
+}
+
+fn storage_diff(block_number: u8) -> ThinStateDiff {
+    ThinStateDiff {
+        storage_diffs: indexmap! {
+            contract() => indexmap! { key() => felt!(block_number) },
+        },
+        ..Default::default()
+    }
+}
+
+// Writes 4 blocks: block 0 deploys a contract and declares a class, and each block updates the
+// storage of the contract.
+fn append_blocks(writer: &mut StorageWriter) {
+    for block_number in 0..4 {
+        let mut state_diff = storage_diff(block_number);
+        if block_number == 0 {
+            state_diff.deployed_contracts = indexmap! { contract() => class() };
+            state_diff.declared_classes = indexmap! { declared_class() => compiled_class_hash() };
+        }
+        if block_number == 2 {
+            state_diff.nonces = indexmap! { contract() => Nonce(felt!(1_u8)) };
+        }
+        append_block(writer, BlockNumber(block_number.into()), state_diff);
+    }
+}
+
+#[test]
+fn prune_history() {
+    let ((reader, mut writer), _temp_dir) = get_test_storage();
+    append_blocks(&mut writer);
+
+    let (txn, n_pruned_blocks) = writer.begin_rw_txn().unwrap().prune_history(2, 100).unwrap();
+    txn.commit().unwrap();
+    assert_eq!(n_pruned_blocks, 2);
+
+    let txn = reader.begin_ro_txn().unwrap();
+    assert_eq!(txn.get_pruning_marker().unwrap(), BlockNumber(2));
+
+    // The history of the pruned blocks is deleted.
+    for block_number in [BlockNumber(0), BlockNumber(1)] {
+        assert_eq!(txn.get_block_header(block_number).unwrap(), None);
+        assert_eq!(txn.get_block_number_by_hash(&block_hash(block_number)).unwrap(), None);
+        assert_eq!(txn.get_block_transaction_hashes(block_number).unwrap(), None);
+        assert_eq!(txn.get_transaction_idx_by_hash(&tx_hash(block_number)).unwrap(), None);
+        assert_eq!(txn.get_state_diff(block_number).unwrap(), None);
+    }
+    let storage_table = txn.open_table(&txn.tables.contract_storage).unwrap();
+    let overridden_value_key = ((contract(), key()), BlockNumber(0));
+    assert_eq!(storage_table.get(&txn.txn, &overridden_value_key).unwrap(), None);
+
+    // The retained blocks are kept.
+    for block_number in [BlockNumber(2), BlockNumber(3)] {
+        assert!(txn.get_block_header(block_number).unwrap().is_some());
+        assert_eq!(
+            txn.get_block_transaction_hashes(block_number).unwrap(),
+            Some(vec![tx_hash(block_number)])
+        );
+        assert!(txn.get_state_diff(block_number).unwrap().is_some());
+    }
+
+    // The state of the retained blocks is readable.
+    let state_reader = txn.get_state_reader().unwrap();
+    let first_retained_state = StateNumber(BlockNumber(2));
+    assert_eq!(
+        state_reader.get_storage_at(first_retained_state, &contract(), &key()).unwrap(),
+        felt!(1_u8)
+    );
+    assert_eq!(
+        state_reader.get_class_hash_at(first_retained_state, &contract()).unwrap(),
+        Some(class())
+    );
+    assert_eq!(
+        state_reader.get_nonce_at(first_retained_state, &contract()).unwrap(),
+        Some(Nonce::default())
+    );
+    let latest_state = StateNumber(BlockNumber(4));
+    assert_eq!(
+        state_reader.get_storage_at(latest_state, &contract(), &key()).unwrap(),
+        felt!(3_u8)
+    );
+    assert_eq!(
+        state_reader.get_nonce_at(latest_state, &contract()).unwrap(),
+        Some(Nonce(felt!(1_u8)))
+    );
+    // The compiled class hash is kept after its declaring state diff is pruned.
+    assert_eq!(
+        state_reader.get_compiled_class_hash(&declared_class()).unwrap(),
+        Some(compiled_class_hash())
+    );
+
+    // The state before the first retained block is not readable.
+    let pruned_state = StateNumber(BlockNumber(1));
+    assert_matches!(
+        state_reader.get_storage_at(pruned_state, &contract(), &key()),
+        Err(StorageError::PrunedBlock {
+            block_number: BlockNumber(1),
+            pruning_marker: BlockNumber(2)
+        })
+    );
+    assert_matches!(
+        state_reader.get_nonce_at(pruned_state, &contract()),
+        Err(StorageError::PrunedBlock {
+            block_number: BlockNumber(1),
+            pruning_marker: BlockNumber(2)
+        })
+    );
+    assert_matches!(
+        state_reader.get_class_hash_at(pruned_state, &contract()),
+        Err(StorageError::PrunedBlock {
+            block_number: BlockNumber(1),
+            pruning_marker: BlockNumber(2)
+        })
+    );
+}
+
+#[test]
+fn revert_pruned_block() {
+    let ((_reader, mut writer), _temp_dir) = get_test_storage();
+    append_blocks(&mut writer);
+    let (txn, _) = writer.begin_rw_txn().unwrap().prune_history(0, 100).unwrap();
+    txn.commit().unwrap();
+
+    let last_block = BlockNumber(3);
+    assert_matches!(
+        writer.begin_rw_txn().unwrap().revert_header(last_block),
+        Err(StorageError::PrunedBlock {
+            block_number: BlockNumber(3),
+            pruning_marker: BlockNumber(4)
+        })
+    );
+    assert_matches!(
+        writer.begin_rw_txn().unwrap().revert_body(last_block),
+        Err(StorageError::PrunedBlock {
+            block_number: BlockNumber(3),
+            pruning_marker: BlockNumber(4)
+        })
+    );
+    assert_matches!(
+        writer.begin_rw_txn().unwrap().revert_state_diff(last_block),
+        Err(StorageError::PrunedBlock {
+            block_number: BlockNumber(3),
+            pruning_marker: BlockNumber(4)
+        })
+    );
+}
+
+#[test_case(4, 1, 0; "all blocks retained")]
+#[test_case(1, 2, 2; "bounded by max_n_blocks")]
+#[test_case(0, 100, 4; "no block retained")]
+fn prune_history_bounds(retained_blocks: u64, max_n_blocks: u64, expected_n_pruned_blocks: u64) {
+    let ((reader, mut writer), _temp_dir) = get_test_storage();
+    append_blocks(&mut writer);
+
+    let (txn, n_pruned_blocks) =
+        writer.begin_rw_txn().unwrap().prune_history(retained_blocks, max_n_blocks).unwrap();
+    txn.commit().unwrap();
+    assert_eq!(n_pruned_blocks, expected_n_pruned_blocks);
+    assert_eq!(
+        reader.begin_ro_txn().unwrap().get_pruning_marker().unwrap(),
+        BlockNumber(expected_n_pruned_blocks)
+    );
+}
+
+#[test]
+fn pruned_storage_cannot_become_full_archive() {
+    let ((reader, mut writer), mut config, _temp_dir) =
+        get_test_storage_with_config_by_scope(StorageScope::Pruned { retained_blocks: 2 });
+    append_blocks(&mut writer);
+    let (txn, _) = writer.begin_rw_txn().unwrap().prune_history(2, 100).unwrap();
+    txn.commit().unwrap();
+    drop(reader);
+    drop(writer);
+
+    config.scope = StorageScope::FullArchive;
+    assert_matches!(
+        open_storage(config.clone()),
+        Err(StorageError::StorageVersionInconsistency(
+            StorageVersionError::InconsistentStorageScope
+        ))
+    );
+
+    config.scope = StorageScope::Pruned { retained_blocks: 3 };
+    let (reader, _writer) = open_storage(config).unwrap();
+    assert_eq!(reader.begin_ro_txn().unwrap().get_pruning_marker().unwrap(), BlockNumber(2));
+}
+
+#[test_case(StorageScope::FullArchive, "FullArchive"; "full archive")]
+#[test_case(StorageScope::StateOnly, "StateOnly"; "state only")]
+#[test_case(StorageScope::Pruned { retained_blocks: 1000 }, "Pruned:1000"; "pruned")]
+fn storage_scope_serialization(scope: StorageScope, serialized_scope: &str) {
+    assert_eq!(serde_json::to_value(scope).unwrap(), serialized_scope);
+    assert_eq!(serde_json::from_value::<StorageScope>(serialized_scope.into()).unwrap(), scope);
+}
+
+#[test]
+fn invalid_storage_scope() {
+    assert!(serde_json::from_value::<StorageScope>("Pruned:".into()).is_err());
+}
+
+#[test]
+fn validate_retained_blocks() {
+    assert!(validate_storage_scope(&StorageScope::Pruned { retained_blocks: MIN_RETAINED_BLOCKS })
+        .is_ok());
+    assert!(validate_storage_scope(&StorageScope::Pruned {
+        retained_blocks: MIN_RETAINED_BLOCKS - 1
+    })
+    .is_err());
+    assert!(validate_storage_scope(&StorageScope::Pruned { retained_blocks: 0 }).is_err());
+}
@@ -137,6 +137,8 @@ use crate::compression_utils::{
 use crate::db::serialization::{StorageSerde, StorageSerdeError};
 use crate::db::table_types::NoValue;
 use crate::header::StorageBlockHeader;
+use crate::l2_gas::L2GasInfo;
+use crate::mempool_commit::MempoolCommit;
 use crate::mmap_file::LocationInFile;
 #[cfg(test)]
 use crate::serialization::serializers_test::{create_storage_serde_test, StorageSerdeTest};
@@ -310,6 +312,10 @@ auto_storage_serde! {
         Blob = 1,
     }
     pub struct L1ToL2Payload(pub Vec<Felt>);
+    pub struct L2GasInfo {
+        pub l2_gas_used: GasAmount,
+        pub l2_gas_price: GasPrice,
+    }
     pub struct L2ToL1Payload(pub Vec<Felt>);
     enum MarkerKind {
         Header = 0,
@@ -319,6 +325,14 @@ auto_storage_serde! {
         Class = 4,
         CompiledClass = 5,
         BaseLayerBlock = 6,
+        Pruning = 7,
+        EventsByFirstKey = 8,
+        SenderNonceIndexMigration = 9,
+    }
+    pub struct MempoolCommit {
+        pub address_to_nonce: HashMap<ContractAddress, Nonce>,
+        pub tx_hashes: Vec<TransactionHash>,
+        pub rejected_tx_hashes: Vec<TransactionHash>,
     }
     pub struct MessageToL1 {
         pub to_address: EthAddress,
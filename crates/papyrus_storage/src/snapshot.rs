@@ -0,0 +1,696 @@
+//! Interface for exporting the state at a given block to a snapshot file, and for initializing a
+//! new storage from such a snapshot, instead of appending all the state diffs since genesis.
+//!
+//! A snapshot of block `N` holds the state right after block `N`: the latest storage values, class
+//! hashes and nonces of all the contracts, the Sierra, CASM and deprecated classes declared up to
+//! block `N` along with the compiled class hashes of the Cairo 1 classes, and the header of block
+//! `N`.
+//!
+//! The snapshot file starts with a header, followed by chunks of entries of a single kind. Each
+//! chunk is compressed separately, with a checksum of its content that is verified on import.
+//! ```text
+//! magic | format version | chain id | block header (JSON)
+//! (chunk kind | number of entries | compressed entries)*
+//! end of chunks | total number of entries | empty
+//! ```
+//!
+//! A snapshot can only be imported into an empty storage under the [`StorageScope::StateOnly`] or
+//! [`StorageScope::Pruned`] scope, since the history of the blocks preceding the snapshot is
+//! missing. After the import, all the markers point to the block following the snapshot, and the
+//! [pruning marker](crate::pruning::PruningStorageReader) marks the history before it as deleted.
+//! Since the state diffs declaring the classes are not imported, the compiled class hashes are kept
+//! aside, as for pruned blocks, and read through
+//! [`StateReader::get_compiled_class_hash`](crate::state::StateReader::get_compiled_class_hash).
+//!
+//! # Example
+//! ```
+//! # use papyrus_storage::{db::DbConfig, StorageConfig, StorageScope};
+//! use papyrus_storage::class::ClassStorageWriter;
+//! use papyrus_storage::header::HeaderStorageWriter;
+//! use papyrus_storage::open_storage;
+//! use papyrus_storage::snapshot::{export_state_snapshot, import_state_snapshot};
+//! use papyrus_storage::state::StateStorageWriter;
+//! use starknet_api::block::{BlockHeader, BlockNumber};
+//! use starknet_api::core::ChainId;
+//! use starknet_api::state::ThinStateDiff;
+//!
+//! # let dir_handle = tempfile::tempdir().unwrap();
+//! # let new_dir_handle = tempfile::tempdir().unwrap();
+//! # let snapshot_path = dir_handle.path().join("snapshot");
+//! # let db_config = DbConfig {
+//! #     path_prefix: dir_handle.path().to_path_buf(),
+//! #     chain_id: ChainId::Mainnet,
+//! #     enforce_file_exists: false,
+//! #     min_size: 1 << 20,    // 1MB
+//! #     max_size: 1 << 35,    // 32GB
+//! #     growth_step: 1 << 26, // 64MB
+//! # };
+//! # let storage_config = StorageConfig{db_config, ..Default::default()};
+//! let (_, mut writer) = open_storage(storage_config.clone())?;
+//! writer
+//!     .begin_rw_txn()?
+//!     .append_header(BlockNumber(0), &BlockHeader::default())?
+//!     .append_state_diff(BlockNumber(0), ThinStateDiff::default())?
+//!     .append_classes(BlockNumber(0), &[], &[])?
+//!     .commit()?;
+//! // The storage is opened again for the export.
+//! drop(writer);
+//! export_state_snapshot(
+//!     storage_config.db_config.clone(),
+//!     storage_config.mmap_file_config.clone(),
+//!     BlockNumber(0),
+//!     &snapshot_path,
+//! )?;
+//!
+//! let mut new_storage_config = storage_config;
+//! new_storage_config.db_config.path_prefix = new_dir_handle.path().to_path_buf();
+//! new_storage_config.scope = StorageScope::StateOnly;
+//! let snapshot_block_number = import_state_snapshot(new_storage_config, &snapshot_path)?;
+//! assert_eq!(snapshot_block_number, BlockNumber(0));
+//! # Ok::<(), papyrus_storage::StorageError>(())
+//! ```
+
+#[cfg(test)]
+#[path = "snapshot_test.rs"]
+mod snapshot_test;
+
+use std::cmp::min;
+use std::fmt::Debug;
+use std::fs::File;
+use std::io::{BufReader, BufWriter, Read, Write};
+use std::path::Path;
+
+use cairo_lang_starknet_classes::casm_contract_class::CasmContractClass;
+use starknet_api::block::{BlockHeader, BlockNumber};
+use starknet_api::core::{ChainId, ClassHash, CompiledClassHash, ContractAddress, Nonce};
+use starknet_api::deprecated_contract_class::ContractClass as DeprecatedContractClass;
+use starknet_api::state::{SierraContractClass, StorageKey};
+use starknet_types_core::felt::Felt;
+use tracing::{debug, info};
+
+use crate::class::ClassStorageReader;
+use crate::compiled_class::CasmStorageReader;
+use crate::compression_utils::MAX_DECOMPRESSED_SIZE;
+use crate::db::serialization::{StorageSerde, StorageSerdeError, ValueSerde};
+use crate::db::table_types::{DbCursor, DbCursorTrait, Table};
+use crate::db::{DbConfig, DbTransaction, RO, RW};
+use crate::header::{HeaderStorageReader, HeaderStorageWriter};
+use crate::mmap_file::MmapFileConfig;
+use crate::pruning::PruningStorageReader;
+use crate::state::data::IndexedDeprecatedContractClass;
+use crate::state::StateStorageReader;
+use crate::{
+    open_storage,
+    open_storage_read_only,
+    MarkerKind,
+    OffsetKind,
+    StorageConfig,
+    StorageError,
+    StorageResult,
+    StorageScope,
+    StorageTxn,
+    StorageWriter,
+};
+
+const SNAPSHOT_MAGIC: &[u8; 8] = b"PAPYSNAP";
+const SNAPSHOT_FORMAT_VERSION: u8 = 0;
+// The serialized size of the entries of a chunk (16 MB), above which the chunk is written. Must be
+// smaller than MAX_DECOMPRESSED_SIZE.
+const CHUNK_SIZE: usize = 1 << 24;
+// The compression level of the chunks. Higher levels are slower but compress better.
+const COMPRESSION_LEVEL: i32 = zstd::DEFAULT_COMPRESSION_LEVEL;
+// Marks the end of the chunks in the snapshot file.
+const END_OF_CHUNKS: u8 = 0;
+
+/// Errors related to state snapshots.
+#[allow(missing_docs)]
+#[derive(thiserror::Error, Debug)]
+pub enum SnapshotError {
+    #[error(
+        "The state of block {block_number} is not available in the storage; the available blocks \
+         are {first_available_block} to {marker} (exclusive)."
+    )]
+    BlockNotAvailable {
+        block_number: BlockNumber,
+        first_available_block: BlockNumber,
+        marker: BlockNumber,
+    },
+    #[error("Snapshots can only be imported into an empty storage.")]
+    NonEmptyStorage,
+    #[error("Snapshots cannot be imported into a storage under the {0:?} scope.")]
+    UnsupportedScope(StorageScope),
+    #[error("The snapshot is of chain {found}, while the storage is of chain {expected}.")]
+    ChainIdMismatch { expected: String, found: String },
+    #[error("Unsupported snapshot format version {0}.")]
+    UnsupportedFormatVersion(u8),
+    #[error("Corrupted snapshot: {msg}.")]
+    Corrupted { msg: String },
+    #[error(transparent)]
+    Serialization(#[from] StorageSerdeError),
+}
+
+/// Exports the state right after the given block to a snapshot file.
+/// The storage is opened for reading only, under the scope it was created with, so that it is
+/// neither modified nor migrated by the export.
+pub fn export_state_snapshot(
+    db_config: DbConfig,
+    mmap_file_config: MmapFileConfig,
+    block_number: BlockNumber,
+    file_path: &Path,
+) -> StorageResult<()> {
+    let chain_id = db_config.chain_id.clone();
+    let reader = open_storage_read_only(db_config, mmap_file_config)?;
+    let txn = reader.begin_ro_txn()?;
+    let mut snapshot = BufWriter::new(File::create(file_path)?);
+    write_snapshot(&txn, block_number, &chain_id, &mut snapshot)?;
+    snapshot.flush()?;
+    info!("Exported the state of block {block_number} to {}.", file_path.display());
+    Ok(())
+}
+
+/// Initializes an empty storage from a snapshot file. Returns the block number of the snapshot.
+pub fn import_state_snapshot(
+    storage_config: StorageConfig,
+    file_path: &Path,
+) -> StorageResult<BlockNumber> {
+    if storage_config.scope == StorageScope::FullArchive {
+        return Err(SnapshotError::UnsupportedScope(storage_config.scope).into());
+    }
+    let chain_id = storage_config.db_config.chain_id.clone();
+    let (_reader, mut writer) = open_storage(storage_config)?;
+    let mut snapshot = BufReader::new(File::open(file_path)?);
+    let block_number = read_snapshot(&mut writer, &chain_id, &mut snapshot)?;
+    info!("Imported the state of block {block_number} from {}.", file_path.display());
+    Ok(block_number)
+}
+
+// An entry of the state, as written in the snapshot. The values of the state are written at the
+// snapshot block.
+#[derive(Debug)]
+enum SnapshotEntry {
+    Storage(ContractAddress, StorageKey, Felt),
+    ClassHash(ContractAddress, ClassHash),
+    Nonce(ContractAddress, Nonce),
+    // Along with the block in which the class was declared.
+    Class(ClassHash, BlockNumber, SierraContractClass),
+    DeprecatedClass(ClassHash, BlockNumber, DeprecatedContractClass),
+    Casm(ClassHash, CasmContractClass),
+    CompiledClassHash(ClassHash, CompiledClassHash),
+}
+
+impl SnapshotEntry {
+    // The kind of the chunks holding this entry. Zero is reserved for END_OF_CHUNKS.
+    fn chunk_kind(&self) -> u8 {
+        match self {
+            SnapshotEntry::Storage(..) => 1,
+            SnapshotEntry::ClassHash(..) => 2,
+            SnapshotEntry::Nonce(..) => 3,
+            SnapshotEntry::Class(..) => 4,
+            SnapshotEntry::DeprecatedClass(..) => 5,
+            SnapshotEntry::Casm(..) => 6,
+            SnapshotEntry::CompiledClassHash(..) => 7,
+        }
+    }
+
+    fn serialize_into(&self, res: &mut impl Write) -> Result<(), StorageSerdeError> {
+        match self {
+            SnapshotEntry::Storage(address, key, value) => {
+                address.serialize_into(res)?;
+                key.serialize_into(res)?;
+                value.serialize_into(res)
+            }
+            SnapshotEntry::ClassHash(address, class_hash) => {
+                address.serialize_into(res)?;
+                class_hash.serialize_into(res)
+            }
+            SnapshotEntry::Nonce(address, nonce) => {
+                address.serialize_into(res)?;
+                nonce.serialize_into(res)
+            }
+            SnapshotEntry::Class(class_hash, block_number, class) => {
+                class_hash.serialize_into(res)?;
+                block_number.serialize_into(res)?;
+                class.serialize_into(res)
+            }
+            SnapshotEntry::DeprecatedClass(class_hash, block_number, class) => {
+                class_hash.serialize_into(res)?;
+                block_number.serialize_into(res)?;
+                class.serialize_into(res)
+            }
+            SnapshotEntry::Casm(class_hash, casm) => {
+                class_hash.serialize_into(res)?;
+                casm.serialize_into(res)
+            }
+            SnapshotEntry::CompiledClassHash(class_hash, compiled_class_hash) => {
+                class_hash.serialize_into(res)?;
+                compiled_class_hash.serialize_into(res)
+            }
+        }
+    }
+
+    fn deserialize_from(chunk_kind: u8, bytes: &mut impl Read) -> Option<Self> {
+        match chunk_kind {
+            1 => Some(SnapshotEntry::Storage(
+                ContractAddress::deserialize_from(bytes)?,
+                StorageKey::deserialize_from(bytes)?,
+                Felt::deserialize_from(bytes)?,
+            )),
+            2 => Some(SnapshotEntry::ClassHash(
+                ContractAddress::deserialize_from(bytes)?,
+                ClassHash::deserialize_from(bytes)?,
+            )),
+            3 => Some(SnapshotEntry::Nonce(
+                ContractAddress::deserialize_from(bytes)?,
+                Nonce::deserialize_from(bytes)?,
+            )),
+            4 => Some(SnapshotEntry::Class(
+                ClassHash::deserialize_from(bytes)?,
+                BlockNumber::deserialize_from(bytes)?,
+                SierraContractClass::deserialize_from(bytes)?,
+            )),
+            5 => Some(SnapshotEntry::DeprecatedClass(
+                ClassHash::deserialize_from(bytes)?,
+                BlockNumber::deserialize_from(bytes)?,
+                DeprecatedContractClass::deserialize_from(bytes)?,
+            )),
+            6 => Some(SnapshotEntry::Casm(
+                ClassHash::deserialize_from(bytes)?,
+                CasmContractClass::deserialize_from(bytes)?,
+            )),
+            7 => Some(SnapshotEntry::CompiledClassHash(
+                ClassHash::deserialize_from(bytes)?,
+                CompiledClassHash::deserialize_from(bytes)?,
+            )),
+            _ => None,
+        }
+    }
+}
+
+// Groups the entries into compressed chunks of a single kind.
+struct ChunkWriter<W: Write> {
+    writer: W,
+    compressor: zstd::bulk::Compressor<'static>,
+    chunk_kind: u8,
+    chunk: Vec<u8>,
+    n_chunk_entries: usize,
+    n_entries: usize,
+}
+
+impl<W: Write> ChunkWriter<W> {
+    fn new(writer: W) -> StorageResult<Self> {
+        let mut compressor = zstd::bulk::Compressor::new(COMPRESSION_LEVEL)?;
+        compressor.include_checksum(true)?;
+        Ok(Self {
+            writer,
+            compressor,
+            chunk_kind: END_OF_CHUNKS,
+            chunk: Vec::new(),
+            n_chunk_entries: 0,
+            n_entries: 0,
+        })
+    }
+
+    fn write_entry(&mut self, entry: SnapshotEntry) -> StorageResult<()> {
+        if entry.chunk_kind() != self.chunk_kind {
+            self.flush_chunk()?;
+            self.chunk_kind = entry.chunk_kind();
+        }
+        entry.serialize_into(&mut self.chunk).map_err(SnapshotError::from)?;
+        self.n_chunk_entries += 1;
+        if self.chunk.len() >= CHUNK_SIZE {
+            self.flush_chunk()?;
+        }
+        Ok(())
+    }
+
+    fn flush_chunk(&mut self) -> StorageResult<()> {
+        if self.n_chunk_entries == 0 {
+            return Ok(());
+        }
+        let compressed_chunk = self.compressor.compress(&self.chunk)?;
+        write_chunk(&mut self.writer, self.chunk_kind, self.n_chunk_entries, compressed_chunk)?;
+        self.n_entries += self.n_chunk_entries;
+        self.n_chunk_entries = 0;
+        self.chunk.clear();
+        Ok(())
+    }
+
+    fn finish(mut self) -> StorageResult<usize> {
+        self.flush_chunk()?;
+        // The last chunk holds the total number of entries.
+        write_chunk(&mut self.writer, END_OF_CHUNKS, self.n_entries, Vec::new())?;
+        Ok(self.n_entries)
+    }
+}
+
+fn write_chunk(
+    writer: &mut impl Write,
+    chunk_kind: u8,
+    n_entries: usize,
+    compressed_chunk: Vec<u8>,
+) -> Result<(), SnapshotError> {
+    chunk_kind.serialize_into(writer)?;
+    n_entries.serialize_into(writer)?;
+    compressed_chunk.serialize_into(writer)?;
+    Ok(())
+}
+
+fn write_snapshot_header(
+    writer: &mut impl Write,
+    chain_id: &ChainId,
+    block_header: &BlockHeader,
+) -> Result<(), SnapshotError> {
+    writer.write_all(SNAPSHOT_MAGIC).map_err(StorageSerdeError::from)?;
+    SNAPSHOT_FORMAT_VERSION.serialize_into(writer)?;
+    chain_id.to_string().serialize_into(writer)?;
+    serde_json::to_string(block_header).map_err(StorageSerdeError::from)?.serialize_into(writer)?;
+    Ok(())
+}
+
+fn corrupted(msg: impl Into<String>) -> StorageError {
+    SnapshotError::Corrupted { msg: msg.into() }.into()
+}
+
+fn write_snapshot(
+    txn: &StorageTxn<'_, RO>,
+    block_number: BlockNumber,
+    chain_id: &ChainId,
+    mut writer: impl Write,
+) -> StorageResult<()> {
+    // The state after the block, including its declared classes, must be fully stored.
+    let marker = min(
+        min(txn.get_header_marker()?, txn.get_state_marker()?),
+        min(txn.get_class_marker()?, txn.get_compiled_class_marker()?),
+    );
+    let first_available_block = txn.get_pruning_marker()?;
+    if block_number >= marker || block_number < first_available_block {
+        return Err(SnapshotError::BlockNotAvailable {
+            block_number,
+            first_available_block,
+            marker,
+        }
+        .into());
+    }
+    let block_header =
+        txn.get_block_header(block_number)?.ok_or(StorageError::DBInconsistency {
+            msg: format!("Missing header of block {block_number}."),
+        })?;
+
+    write_snapshot_header(&mut writer, chain_id, &block_header)?;
+
+    let mut chunk_writer = ChunkWriter::new(writer)?;
+    txn.export_state_entries(block_number, &mut chunk_writer)?;
+    let n_entries = chunk_writer.finish()?;
+    debug!("Wrote {n_entries} entries to the snapshot of block {block_number}.");
+    Ok(())
+}
+
+// Returns the block number of the snapshot.
+fn read_snapshot(
+    writer: &mut StorageWriter,
+    chain_id: &ChainId,
+    mut reader: impl Read,
+) -> StorageResult<BlockNumber> {
+    if writer.scope == StorageScope::FullArchive {
+        return Err(SnapshotError::UnsupportedScope(writer.scope).into());
+    }
+    let mut magic = [0u8; SNAPSHOT_MAGIC.len()];
+    reader.read_exact(&mut magic).map_err(|_| corrupted("missing header"))?;
+    if magic != *SNAPSHOT_MAGIC {
+        return Err(corrupted("not a snapshot file"));
+    }
+    let format_version = u8::deserialize_from(&mut reader).ok_or(corrupted("missing header"))?;
+    if format_version != SNAPSHOT_FORMAT_VERSION {
+        return Err(SnapshotError::UnsupportedFormatVersion(format_version).into());
+    }
+    let snapshot_chain_id =
+        String::deserialize_from(&mut reader).ok_or(corrupted("missing chain id"))?;
+    if snapshot_chain_id != chain_id.to_string() {
+        return Err(SnapshotError::ChainIdMismatch {
+            expected: chain_id.to_string(),
+            found: snapshot_chain_id,
+        }
+        .into());
+    }
+    let block_header: BlockHeader = serde_json::from_str(
+        &String::deserialize_from(&mut reader).ok_or(corrupted("missing block header"))?,
+    )
+    .map_err(|_| corrupted("invalid block header"))?;
+    let block_number = block_header.block_header_without_hash.block_number;
+
+    let txn = writer.begin_rw_txn()?;
+    if txn.get_header_marker()? != BlockNumber(0) || txn.get_state_marker()? != BlockNumber(0) {
+        return Err(SnapshotError::NonEmptyStorage.into());
+    }
+    drop(txn);
+
+    // Each chunk is written in its own transaction, and the markers are set only once all the
+    // entries are written. Since the entries are upserted, an interrupted import can be restarted.
+    let mut n_entries = 0;
+    loop {
+        let chunk_kind = u8::deserialize_from(&mut reader).ok_or(corrupted("missing chunk"))?;
+        let n_chunk_entries =
+            usize::deserialize_from(&mut reader).ok_or(corrupted("missing chunk"))?;
+        let compressed_chunk =
+            Vec::<u8>::deserialize_from(&mut reader).ok_or(corrupted("truncated chunk"))?;
+        if chunk_kind == END_OF_CHUNKS {
+            if n_chunk_entries != n_entries {
+                return Err(corrupted(format!(
+                    "expected {n_chunk_entries} entries, found {n_entries}"
+                )));
+            }
+            break;
+        }
+        let chunk = zstd::bulk::decompress(&compressed_chunk, MAX_DECOMPRESSED_SIZE)
+            .map_err(|err| corrupted(format!("invalid chunk: {err}")))?;
+        writer
+            .begin_rw_txn()?
+            .write_state_chunk(block_number, chunk_kind, n_chunk_entries, &chunk)?
+            .commit()?;
+        n_entries += n_chunk_entries;
+    }
+
+    writer.begin_rw_txn()?.set_snapshot_markers(&block_header)?.commit()?;
+    debug!("Read {n_entries} entries from the snapshot of block {block_number}.");
+    Ok(block_number)
+}
+
+impl<'env> StorageTxn<'env, RO> {
+    fn export_state_entries<W: Write>(
+        &self,
+        block_number: BlockNumber,
+        chunk_writer: &mut ChunkWriter<W>,
+    ) -> StorageResult<()> {
+        let storage_table = self.open_table(&self.tables.contract_storage)?;
+        let deployed_contracts_table = self.open_table(&self.tables.deployed_contracts)?;
+        let nonces_table = self.open_table(&self.tables.nonces)?;
+        let declared_classes_block_table = self.open_table(&self.tables.declared_classes_block)?;
+        let deprecated_declared_classes_table =
+            self.open_table(&self.tables.deprecated_declared_classes)?;
+
+        for_each_value_at(&self.txn, &storage_table, block_number, |(address, key), value| {
+            // Zero is the default value.
+            if value == Felt::ZERO {
+                return Ok(());
+            }
+            chunk_writer.write_entry(SnapshotEntry::Storage(address, key, value))
+        })?;
+        for_each_value_at(&self.txn, &deployed_contracts_table, block_number, |address, hash| {
+            chunk_writer.write_entry(SnapshotEntry::ClassHash(address, hash))
+        })?;
+        for_each_value_at(&self.txn, &nonces_table, block_number, |address, nonce| {
+            chunk_writer.write_entry(SnapshotEntry::Nonce(address, nonce))
+        })?;
+
+        let mut declared_classes = Vec::new();
+        let mut cursor = declared_classes_block_table.cursor(&self.txn)?;
+        while let Some((class_hash, declaration_block_number)) = cursor.next()? {
+            if declaration_block_number <= block_number {
+                declared_classes.push(class_hash);
+                let class = self.get_class(&class_hash)?.ok_or(StorageError::DBInconsistency {
+                    msg: format!("Missing class {class_hash}."),
+                })?;
+                chunk_writer.write_entry(SnapshotEntry::Class(
+                    class_hash,
+                    declaration_block_number,
+                    class,
+                ))?;
+            }
+        }
+        let mut cursor = deprecated_declared_classes_table.cursor(&self.txn)?;
+        while let Some((class_hash, indexed_class)) = cursor.next()? {
+            if indexed_class.block_number <= block_number {
+                let class = self
+                    .file_handlers
+                    .get_deprecated_contract_class_unchecked(indexed_class.location_in_file)?;
+                chunk_writer.write_entry(SnapshotEntry::DeprecatedClass(
+                    class_hash,
+                    indexed_class.block_number,
+                    class,
+                ))?;
+            }
+        }
+        for class_hash in &declared_classes {
+            if let Some(casm) = self.get_casm(class_hash)? {
+                chunk_writer.write_entry(SnapshotEntry::Casm(*class_hash, casm))?;
+            }
+        }
+        let state_reader = self.get_state_reader()?;
+        for class_hash in declared_classes {
+            let compiled_class_hash = state_reader.get_compiled_class_hash(&class_hash)?.ok_or(
+                StorageError::DBInconsistency {
+                    msg: format!("Missing the compiled class hash of class {class_hash}."),
+                },
+            )?;
+            chunk_writer
+                .write_entry(SnapshotEntry::CompiledClassHash(class_hash, compiled_class_hash))?;
+        }
+        Ok(())
+    }
+}
+
+impl<'env> StorageTxn<'env, RW> {
+    fn write_state_chunk(
+        self,
+        block_number: BlockNumber,
+        chunk_kind: u8,
+        n_entries: usize,
+        mut chunk: &[u8],
+    ) -> StorageResult<Self> {
+        let file_offset_table = self.txn.open_table(&self.tables.file_offsets)?;
+        let storage_table = self.open_table(&self.tables.contract_storage)?;
+        let deployed_contracts_table = self.open_table(&self.tables.deployed_contracts)?;
+        let nonces_table = self.open_table(&self.tables.nonces)?;
+        let declared_classes_table = self.open_table(&self.tables.declared_classes)?;
+        let declared_classes_block_table = self.open_table(&self.tables.declared_classes_block)?;
+        let deprecated_declared_classes_table =
+            self.open_table(&self.tables.deprecated_declared_classes)?;
+        let casms_table = self.open_table(&self.tables.casms)?;
+        let compiled_class_hashes_table = self.open_table(&self.tables.compiled_class_hashes)?;
+
+        for _ in 0..n_entries {
+            let entry = SnapshotEntry::deserialize_from(chunk_kind, &mut chunk)
+                .ok_or(corrupted("invalid entry"))?;
+            match entry {
+                SnapshotEntry::Storage(address, key, value) => {
+                    storage_table.upsert(&self.txn, &((address, key), block_number), &value)?;
+                }
+                SnapshotEntry::ClassHash(address, class_hash) => {
+                    deployed_contracts_table.upsert(
+                        &self.txn,
+                        &(address, block_number),
+                        &class_hash,
+                    )?;
+                }
+                SnapshotEntry::Nonce(address, nonce) => {
+                    nonces_table.upsert(&self.txn, &(address, block_number), &nonce)?;
+                }
+                SnapshotEntry::Class(class_hash, declaration_block_number, class) => {
+                    let location = self.file_handlers.append_contract_class(&class);
+                    declared_classes_table.upsert(&self.txn, &class_hash, &location)?;
+                    declared_classes_block_table.upsert(
+                        &self.txn,
+                        &class_hash,
+                        &declaration_block_number,
+                    )?;
+                    file_offset_table.upsert(
+                        &self.txn,
+                        &OffsetKind::ContractClass,
+                        &location.next_offset(),
+                    )?;
+                }
+                SnapshotEntry::DeprecatedClass(class_hash, declaration_block_number, class) => {
+                    let location = self.file_handlers.append_deprecated_contract_class(&class);
+                    deprecated_declared_classes_table.upsert(
+                        &self.txn,
+                        &class_hash,
+                        &IndexedDeprecatedContractClass {
+                            block_number: declaration_block_number,
+                            location_in_file: location,
+                        },
+                    )?;
+                    file_offset_table.upsert(
+                        &self.txn,
+                        &OffsetKind::DeprecatedContractClass,
+                        &location.next_offset(),
+                    )?;
+                }
+                SnapshotEntry::Casm(class_hash, casm) => {
+                    let location = self.file_handlers.append_casm(&casm);
+                    casms_table.upsert(&self.txn, &class_hash, &location)?;
+                    file_offset_table.upsert(
+                        &self.txn,
+                        &OffsetKind::Casm,
+                        &location.next_offset(),
+                    )?;
+                }
+                SnapshotEntry::CompiledClassHash(class_hash, compiled_class_hash) => {
+                    compiled_class_hashes_table.upsert(
+                        &self.txn,
+                        &class_hash,
+                        &compiled_class_hash,
+                    )?;
+                }
+            }
+        }
+        if !chunk.is_empty() {
+            return Err(corrupted("unexpected data at the end of a chunk"));
+        }
+        Ok(self)
+    }
+
+    // Writes the header of the snapshot block, and points the markers at the following block. The
+    // history of the blocks up to the snapshot block is marked as pruned; the header is kept to
+    // verify the following block.
+    fn set_snapshot_markers(self, block_header: &BlockHeader) -> StorageResult<Self> {
+        let block_number = block_header.block_header_without_hash.block_number;
+        let markers_table = self.open_table(&self.tables.markers)?;
+        markers_table.upsert(&self.txn, &MarkerKind::Header, &block_number)?;
+        let txn = self.append_header(block_number, block_header)?;
+
+        let markers_table = txn.open_table(&txn.tables.markers)?;
+        let next_block_number = block_number.unchecked_next();
+        for marker_kind in [
+            MarkerKind::Body,
+            MarkerKind::State,
+            MarkerKind::Class,
+            MarkerKind::CompiledClass,
+            MarkerKind::Pruning,
+        ] {
+            markers_table.upsert(&txn.txn, &marker_kind, &next_block_number)?;
+        }
+        Ok(txn)
+    }
+}
+
+// Calls `f` with the latest value at the given block of each key in a table of values by block.
+fn for_each_value_at<'env, K, T>(
+    txn: &'env DbTransaction<'env, RO>,
+    table: &'env T,
+    block_number: BlockNumber,
+    mut f: impl FnMut(K, <T::Value as ValueSerde>::Value) -> StorageResult<()>,
+) -> StorageResult<()>
+where
+    K: Copy + Debug + Eq,
+    T: Table<'env, Key = (K, BlockNumber)>,
+    DbCursor<'env, RO, T::Key, T::Value, T::TableVariant>:
+        DbCursorTrait<Key = T::Key, Value = T::Value>,
+{
+    let mut latest_value = None;
+    let mut cursor = table.cursor(txn)?;
+    while let Some(((key, entry_block_number), value)) = cursor.next()? {
+        if let Some((latest_key, _)) = &latest_value {
+            if *latest_key != key {
+                let (latest_key, value) = latest_value.take().expect("Checked above.");
+                f(latest_key, value)?;
+            }
+        }
+        if entry_block_number <= block_number {
+            latest_value = Some((key, value));
+        }
+    }
+    if let Some((latest_key, value)) = latest_value {
+        f(latest_key, value)?;
+    }
+    Ok(())
+}
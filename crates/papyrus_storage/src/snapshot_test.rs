@@ -0,0 +1,237 @@
+use assert_matches::assert_matches;
+use cairo_lang_starknet_classes::casm_contract_class::CasmContractClass;
+use indexmap::indexmap;
+use pretty_assertions::assert_eq;
+use starknet_api::block::{BlockHash, BlockHeader, BlockHeaderWithoutHash, BlockNumber};
+use starknet_api::core::{ChainId, ClassHash, CompiledClassHash, ContractAddress, Nonce};
+use starknet_api::deprecated_contract_class::ContractClass as DeprecatedContractClass;
+use starknet_api::state::{SierraContractClass, StateNumber, StorageKey, ThinStateDiff};
+use starknet_api::{class_hash, contract_address, felt, storage_key};
+use starknet_types_core::felt::Felt;
+use test_case::test_case;
+
+use crate::class::ClassStorageWriter;
+use crate::compiled_class::{CasmStorageReader, CasmStorageWriter};
+use crate::header::{HeaderStorageReader, HeaderStorageWriter};
+use crate::pruning::PruningStorageReader;
+use crate::snapshot::{read_snapshot, write_snapshot, SnapshotError};
+use crate::state::{StateStorageReader, StateStorageWriter};
+use crate::test_utils::{get_test_storage, get_test_storage_by_scope, CHAIN_ID_FOR_TESTS};
+use crate::{StorageError, StorageReader, StorageScope};
+
+fn contract() -> ContractAddress {
+    contract_address!(0x1_u8)
+}
+
+fn key() -> StorageKey {
+    storage_key!(0x2_u8)
+}
+
+fn class() -> ClassHash {
+    class_hash!(0x3_u8)
+}
+
+fn deprecated_class() -> ClassHash {
+    class_hash!(0x4_u8)
+}
+
+fn compiled_class_hash() -> CompiledClassHash {
+    CompiledClassHash(felt!(0x5_u8))
+}
+
+fn casm() -> CasmContractClass {
+    CasmContractClass {
+        prime: Default::default(),
+        compiler_version: Default::default(),
+        bytecode: Default::default(),
+        bytecode_segment_lengths: Default::default(),
+        hints: Default::default(),
+        pythonic_hints: Default::default(),
+        entry_points_by_type: Default::default(),
+    }
+}
+
+fn block_header(block_number: BlockNumber) -> BlockHeader {
+    BlockHeader {
+        block_hash: BlockHash(Felt::from(block_number.0 + 1)),
+        block_header_without_hash: BlockHeaderWithoutHash { block_number, ..Default::default() },
+        ..Default::default()
+    }
+}
+
+// Returns a storage with 3 blocks: block 0 deploys a contract of a deprecated class and declares a
+// class, block 1 updates the nonce of the contract, and each block updates its storage.
+fn get_source_storage() -> (StorageReader, tempfile::TempDir) {
+    let ((reader, mut writer), temp_dir) = get_test_storage();
+    for block_number in 0..3_u8 {
+        let mut state_diff = ThinStateDiff {
+            storage_diffs: indexmap! { contract() => indexmap! { key() => felt!(block_number) } },
+            ..Default::default()
+        };
+        let mut classes = vec![];
+        let mut deprecated_classes = vec![];
+        if block_number == 0 {
+            state_diff.deployed_contracts = indexmap! { contract() => deprecated_class() };
+            state_diff.deprecated_declared_classes = vec![deprecated_class()];
+            state_diff.declared_classes = indexmap! { class() => compiled_class_hash() };
+            classes.push((class(), SierraContractClass::default()));
+            deprecated_classes.push((deprecated_class(), DeprecatedContractClass::default()));
+        }
+        if block_number == 1 {
+            state_diff.nonces = indexmap! { contract() => Nonce(felt!(1_u8)) };
+        }
+        let block_number = BlockNumber(block_number.into());
+        let classes: Vec<_> = classes.iter().map(|(hash, class)| (*hash, class)).collect();
+        let deprecated_classes: Vec<_> =
+            deprecated_classes.iter().map(|(hash, class)| (*hash, class)).collect();
+        let mut txn = writer
+            .begin_rw_txn()
+            .unwrap()
+            .append_header(block_number, &block_header(block_number))
+            .unwrap()
+            .append_state_diff(block_number, state_diff)
+            .unwrap()
+            .append_classes(block_number, &classes, &deprecated_classes)
+            .unwrap();
+        if block_number == BlockNumber(0) {
+            txn = txn.append_casm(&class(), &casm()).unwrap();
+        }
+        txn.commit().unwrap();
+    }
+    (reader, temp_dir)
+}
+
+fn export_snapshot(reader: &StorageReader, block_number: BlockNumber) -> Vec<u8> {
+    let mut snapshot = Vec::new();
+    write_snapshot(
+        &reader.begin_ro_txn().unwrap(),
+        block_number,
+        &CHAIN_ID_FOR_TESTS,
+        &mut snapshot,
+    )
+    .unwrap();
+    snapshot
+}
+
+#[test_case(StorageScope::StateOnly; "state only")]
+#[test_case(StorageScope::Pruned { retained_blocks: 10 }; "pruned")]
+fn export_and_import(scope: StorageScope) {
+    let (source_reader, _source_temp_dir) = get_source_storage();
+    let snapshot = export_snapshot(&source_reader, BlockNumber(1));
+
+    let ((reader, mut writer), _temp_dir) = get_test_storage_by_scope(scope);
+    let block_number =
+        read_snapshot(&mut writer, &CHAIN_ID_FOR_TESTS, snapshot.as_slice()).unwrap();
+    assert_eq!(block_number, BlockNumber(1));
+
+    let txn = reader.begin_ro_txn().unwrap();
+    assert_eq!(txn.get_header_marker().unwrap(), BlockNumber(2));
+    assert_eq!(txn.get_state_marker().unwrap(), BlockNumber(2));
+    assert_eq!(txn.get_compiled_class_marker().unwrap(), BlockNumber(2));
+    assert_eq!(txn.get_pruning_marker().unwrap(), BlockNumber(2));
+    assert_eq!(txn.get_block_header(BlockNumber(1)).unwrap(), Some(block_header(BlockNumber(1))));
+    assert_eq!(txn.get_block_header(BlockNumber(0)).unwrap(), None);
+
+    // The state right after the snapshot block is the same as in the source storage.
+    let state_number = StateNumber(BlockNumber(2));
+    let state_reader = txn.get_state_reader().unwrap();
+    assert_eq!(
+        state_reader.get_storage_at(state_number, &contract(), &key()).unwrap(),
+        felt!(1_u8)
+    );
+    assert_eq!(
+        state_reader.get_class_hash_at(state_number, &contract()).unwrap(),
+        Some(deprecated_class())
+    );
+    assert_eq!(
+        state_reader.get_nonce_at(state_number, &contract()).unwrap(),
+        Some(Nonce(felt!(1_u8)))
+    );
+    assert_eq!(
+        state_reader.get_class_definition_at(state_number, &class()).unwrap(),
+        Some(SierraContractClass::default())
+    );
+    assert_eq!(
+        state_reader.get_deprecated_class_definition_at(state_number, &deprecated_class()).unwrap(),
+        Some(DeprecatedContractClass::default())
+    );
+    assert_eq!(
+        state_reader.get_compiled_class_hash(&class()).unwrap(),
+        Some(compiled_class_hash())
+    );
+    assert_eq!(txn.get_casm(&class()).unwrap(), Some(casm()));
+}
+
+#[test]
+fn import_into_full_archive_storage() {
+    let (source_reader, _source_temp_dir) = get_source_storage();
+    let snapshot = export_snapshot(&source_reader, BlockNumber(1));
+
+    let ((_reader, mut writer), _temp_dir) = get_test_storage();
+    assert_matches!(
+        read_snapshot(&mut writer, &CHAIN_ID_FOR_TESTS, snapshot.as_slice()),
+        Err(StorageError::SnapshotError(SnapshotError::UnsupportedScope(
+            StorageScope::FullArchive
+        )))
+    );
+}
+
+#[test]
+fn import_into_non_empty_storage() {
+    let (source_reader, _source_temp_dir) = get_source_storage();
+    let snapshot = export_snapshot(&source_reader, BlockNumber(1));
+
+    let ((_reader, mut writer), _temp_dir) = get_test_storage_by_scope(StorageScope::StateOnly);
+    read_snapshot(&mut writer, &CHAIN_ID_FOR_TESTS, snapshot.as_slice()).unwrap();
+    assert_matches!(
+        read_snapshot(&mut writer, &CHAIN_ID_FOR_TESTS, snapshot.as_slice()),
+        Err(StorageError::SnapshotError(SnapshotError::NonEmptyStorage))
+    );
+}
+
+#[test]
+fn import_snapshot_of_other_chain() {
+    let (source_reader, _source_temp_dir) = get_source_storage();
+    let snapshot = export_snapshot(&source_reader, BlockNumber(1));
+
+    let ((_reader, mut writer), _temp_dir) = get_test_storage_by_scope(StorageScope::StateOnly);
+    assert_matches!(
+        read_snapshot(&mut writer, &ChainId::Sepolia, snapshot.as_slice()),
+        Err(StorageError::SnapshotError(SnapshotError::ChainIdMismatch { .. }))
+    );
+}
+
+// The snapshot ends with the last chunk, followed by 10 bytes marking the end of the chunks.
+#[test_case(|snapshot| snapshot.truncate(snapshot.len() - 20); "truncated")]
+#[test_case(|snapshot| { let i = snapshot.len() - 11; snapshot[i] ^= 1; }; "modified chunk")]
+fn import_corrupted_snapshot(corrupt: fn(&mut Vec<u8>)) {
+    let (source_reader, _source_temp_dir) = get_source_storage();
+    let mut snapshot = export_snapshot(&source_reader, BlockNumber(1));
+    corrupt(&mut snapshot);
+
+    let ((reader, mut writer), _temp_dir) = get_test_storage_by_scope(StorageScope::StateOnly);
+    assert_matches!(
+        read_snapshot(&mut writer, &CHAIN_ID_FOR_TESTS, snapshot.as_slice()),
+        Err(StorageError::SnapshotError(SnapshotError::Corrupted { .. }))
+    );
+    // The storage is left without markers, to be imported again.
+    assert_eq!(reader.begin_ro_txn().unwrap().get_state_marker().unwrap(), BlockNumber(0));
+}
+
+#[test_case(BlockNumber(3); "missing block")]
+#[test_case(BlockNumber(100); "future block")]
+fn export_unavailable_block(block_number: BlockNumber) {
+    let (source_reader, _source_temp_dir) = get_source_storage();
+    assert_matches!(
+        write_snapshot(
+            &source_reader.begin_ro_txn().unwrap(),
+            block_number,
+            &CHAIN_ID_FOR_TESTS,
+            &mut Vec::new()
+        ),
+        Err(StorageError::SnapshotError(SnapshotError::BlockNotAvailable {
+            marker: BlockNumber(3),
+            ..
+        }))
+    );
+}
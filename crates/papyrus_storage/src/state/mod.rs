@@ -60,7 +60,7 @@ use cairo_lang_starknet_classes::casm_contract_class::CasmContractClass;
 use indexmap::IndexMap;
 use papyrus_proc_macros::latency_histogram;
 use starknet_api::block::BlockNumber;
-use starknet_api::core::{ClassHash, ContractAddress, Nonce};
+use starknet_api::core::{ClassHash, CompiledClassHash, ContractAddress, Nonce};
 use starknet_api::deprecated_contract_class::ContractClass as DeprecatedContractClass;
 use starknet_api::state::{SierraContractClass, StateNumber, StorageKey, ThinStateDiff};
 use starknet_types_core::felt::Felt;
@@ -72,6 +72,7 @@ use crate::db::{DbTransaction, TableHandle, TransactionKind, RW};
 #[cfg(feature = "document_calls")]
 use crate::document_calls::{add_query, StorageQuery};
 use crate::mmap_file::LocationInFile;
+use crate::pruning::PruningStorageReader;
 use crate::state::data::IndexedDeprecatedContractClass;
 use crate::{
     FileHandlers,
@@ -94,6 +95,10 @@ pub(crate) type DeprecatedDeclaredClassesTable<'env> =
     TableHandle<'env, ClassHash, VersionZeroWrapper<IndexedDeprecatedContractClass>, SimpleTable>;
 pub(crate) type CompiledClassesTable<'env> =
     TableHandle<'env, ClassHash, VersionZeroWrapper<LocationInFile>, SimpleTable>;
+pub(crate) type CompiledClassHashesTable<'env> =
+    TableHandle<'env, ClassHash, NoVersionValueWrapper<CompiledClassHash>, SimpleTable>;
+pub(crate) type StateDiffsTable<'env> =
+    TableHandle<'env, BlockNumber, VersionZeroWrapper<LocationInFile>, SimpleTable>;
 pub(crate) type DeployedContractsTable<'env> =
     TableHandle<'env, (ContractAddress, BlockNumber), VersionZeroWrapper<ClassHash>, SimpleTable>;
 pub(crate) type ContractStorageTable<'env> = TableHandle<
@@ -123,6 +128,9 @@ pub(crate) type NoncesTable<'env> =
 //   block_num.
 // * nonces_table: (contract_address, block_num) -> (nonce). Specifies that at `block_num`, the
 //   nonce of `contract_address` was changed to `nonce`.
+// * compiled_class_hashes_table: (class_hash) -> (compiled_class_hash). The compiled class hashes
+//   of the Cairo 1 classes whose declaring state diff is not stored. The compiled class hashes of
+//   the other classes are read from their declaring state diff.
 
 pub trait StateStorageReader<Mode: TransactionKind> {
     /// The state marker is the first block number that doesn't exist yet.
@@ -189,11 +197,15 @@ pub struct StateReader<'env, Mode: TransactionKind> {
     declared_classes_table: DeclaredClassesTable<'env>,
     declared_classes_block_table: DeclaredClassesBlockTable<'env>,
     deprecated_declared_classes_table: DeprecatedDeclaredClassesTable<'env>,
+    compiled_class_hashes_table: CompiledClassHashesTable<'env>,
+    state_diffs_table: StateDiffsTable<'env>,
     deployed_contracts_table: DeployedContractsTable<'env>,
     nonces_table: NoncesTable<'env>,
     storage_table: ContractStorageTable<'env>,
     markers_table: MarkersTable<'env>,
     file_handlers: &'env FileHandlers<Mode>,
+    // The states before the pruning marker cannot be read.
+    pruning_marker: BlockNumber,
 }
 
 impl<'env, Mode: TransactionKind> StateReader<'env, Mode> {
@@ -212,23 +224,40 @@ impl<'env, Mode: TransactionKind> StateReader<'env, Mode> {
             txn.txn.open_table(&txn.tables.declared_classes_block)?;
         let deprecated_declared_classes_table =
             txn.txn.open_table(&txn.tables.deprecated_declared_classes)?;
+        let compiled_class_hashes_table = txn.txn.open_table(&txn.tables.compiled_class_hashes)?;
+        let state_diffs_table = txn.txn.open_table(&txn.tables.state_diffs)?;
         let deployed_contracts_table = txn.txn.open_table(&txn.tables.deployed_contracts)?;
         let nonces_table = txn.txn.open_table(&txn.tables.nonces)?;
         let storage_table = txn.txn.open_table(&txn.tables.contract_storage)?;
         let markers_table = txn.txn.open_table(&txn.tables.markers)?;
+        let pruning_marker = txn.get_pruning_marker()?;
         Ok(StateReader {
             txn: &txn.txn,
             declared_classes_table,
             declared_classes_block_table,
             deprecated_declared_classes_table,
+            compiled_class_hashes_table,
+            state_diffs_table,
             deployed_contracts_table,
             nonces_table,
             storage_table,
             markers_table,
             file_handlers: &txn.file_handlers,
+            pruning_marker,
         })
     }
 
+    // Returns an error if the values overridden after the state number may have been pruned.
+    fn verify_state_not_pruned(&self, state_number: StateNumber) -> StorageResult<()> {
+        if state_number.0 < self.pruning_marker {
+            return Err(StorageError::PrunedBlock {
+                block_number: state_number.0,
+                pruning_marker: self.pruning_marker,
+            });
+        }
+        Ok(())
+    }
+
     /// Returns the class hash at a given state number.
     /// If class hash is not found, returns `None`.
     ///
@@ -238,6 +267,8 @@ impl<'env, Mode: TransactionKind> StateReader<'env, Mode> {
     ///
     /// # Errors
     /// Returns [`StorageError`] if there was an error searching the table.
+    ///
+    /// Returns [`StorageError`]::PrunedBlock if the state number precedes the pruning marker.
     pub fn get_class_hash_at(
         &self,
         state_number: StateNumber,
@@ -246,6 +277,7 @@ impl<'env, Mode: TransactionKind> StateReader<'env, Mode> {
         // TODO(dvir): create an attribute instead of this.
         #[cfg(feature = "document_calls")]
         add_query(StorageQuery::GetClassHashAt(state_number, *address));
+        self.verify_state_not_pruned(state_number)?;
 
         let first_irrelevant_block: BlockNumber = state_number.block_after();
         let db_key = (*address, first_irrelevant_block);
@@ -269,6 +301,8 @@ impl<'env, Mode: TransactionKind> StateReader<'env, Mode> {
     ///
     /// # Errors
     /// Returns [`StorageError`] if there was an error searching the table.
+    ///
+    /// Returns [`StorageError`]::PrunedBlock if the state number precedes the pruning marker.
     pub fn get_nonce_at(
         &self,
         state_number: StateNumber,
@@ -276,6 +310,7 @@ impl<'env, Mode: TransactionKind> StateReader<'env, Mode> {
     ) -> StorageResult<Option<Nonce>> {
         #[cfg(feature = "document_calls")]
         add_query(StorageQuery::GetNonceAt(state_number, *address));
+        self.verify_state_not_pruned(state_number)?;
 
         // State diff updates are indexed by the block_number at which they occurred.
         let first_irrelevant_block: BlockNumber = state_number.block_after();
@@ -309,6 +344,8 @@ impl<'env, Mode: TransactionKind> StateReader<'env, Mode> {
     ///
     /// # Errors
     /// Returns [`StorageError`] if there was an error searching the table.
+    ///
+    /// Returns [`StorageError`]::PrunedBlock if the state number precedes the pruning marker.
     pub fn get_storage_at(
         &self,
         state_number: StateNumber,
@@ -317,6 +354,7 @@ impl<'env, Mode: TransactionKind> StateReader<'env, Mode> {
     ) -> StorageResult<Felt> {
         #[cfg(feature = "document_calls")]
         add_query(StorageQuery::GetStorageAt(state_number, *address, *key));
+        self.verify_state_not_pruned(state_number)?;
 
         // The updates to the storage key are indexed by the block_number at which they occurred.
         let first_irrelevant_block: BlockNumber = state_number.block_after();
@@ -397,6 +435,47 @@ impl<'env, Mode: TransactionKind> StateReader<'env, Mode> {
         Ok(self.declared_classes_block_table.get(self.txn, class_hash)?)
     }
 
+    /// Returns the compiled class hash of a given class hash.
+    /// If class is not declared, returns `None`.
+    ///
+    /// # Arguments
+    /// * class_hash - class hash to search for.
+    ///
+    /// # Errors
+    /// Returns [`StorageError`] if there was an error searching the table.
+    ///
+    /// Returns [`StorageError`]::DBInconsistency if the class is declared but its compiled class
+    /// hash was not found.
+    pub fn get_compiled_class_hash(
+        &self,
+        class_hash: &ClassHash,
+    ) -> StorageResult<Option<CompiledClassHash>> {
+        let Some(block_number) = self.declared_classes_block_table.get(self.txn, class_hash)?
+        else {
+            return Ok(None);
+        };
+        if let Some(compiled_class_hash) =
+            self.compiled_class_hashes_table.get(self.txn, class_hash)?
+        {
+            return Ok(Some(compiled_class_hash));
+        }
+        let compiled_class_hash = match self.state_diffs_table.get(self.txn, &block_number)? {
+            Some(state_diff_location) => self
+                .file_handlers
+                .get_thin_state_diff_unchecked(state_diff_location)?
+                .declared_classes
+                .get(class_hash)
+                .copied(),
+            None => None,
+        };
+        compiled_class_hash.map(Some).ok_or(StorageError::DBInconsistency {
+            msg: format!(
+                "Missing the compiled class hash of class {class_hash}, declared in block \
+                 {block_number}."
+            ),
+        })
+    }
+
     /// Returns the deprecated contract class at a given state number for a given class hash.
     /// If class is not found, returns `None`.
     /// If class is defined but in a block after given state number, returns `None`.
@@ -493,6 +572,7 @@ impl<'env> StateStorageWriter for StorageTxn<'env, RW> {
         let markers_table = self.open_table(&self.tables.markers)?;
         let declared_classes_table = self.open_table(&self.tables.declared_classes)?;
         let declared_classes_block_table = self.open_table(&self.tables.declared_classes_block)?;
+        let classes_missing_sierra_table = self.open_table(&self.tables.classes_missing_sierra)?;
         let deprecated_declared_classes_table =
             self.open_table(&self.tables.deprecated_declared_classes)?;
         // TODO(yair): Consider reverting the compiled classes in their own module.
@@ -501,6 +581,7 @@ impl<'env> StateStorageWriter for StorageTxn<'env, RW> {
         let nonces_table = self.open_table(&self.tables.nonces)?;
         let storage_table = self.open_table(&self.tables.contract_storage)?;
         let state_diffs_table = self.open_table(&self.tables.state_diffs)?;
+        let l2_gas_table = self.open_table(&self.tables.l2_gas)?;
 
         let current_state_marker = self.get_state_marker()?;
 
@@ -517,6 +598,7 @@ impl<'env> StateStorageWriter for StorageTxn<'env, RW> {
             return Ok((self, None));
         };
 
+        self.verify_block_not_pruned(block_number)?;
         let thin_state_diff = self
             .get_state_diff(block_number)?
             .unwrap_or_else(|| panic!("Missing state diff for block {block_number}."));
@@ -537,6 +619,9 @@ impl<'env> StateStorageWriter for StorageTxn<'env, RW> {
             &declared_classes_block_table,
             &self.file_handlers,
         )?;
+        for class_hash in thin_state_diff.declared_classes.keys() {
+            classes_missing_sierra_table.delete(&self.txn, class_hash)?;
+        }
         let deleted_deprecated_classes = delete_deprecated_declared_classes(
             &self.txn,
             block_number,
@@ -560,6 +645,8 @@ impl<'env> StateStorageWriter for StorageTxn<'env, RW> {
         delete_storage_diffs(&self.txn, block_number, &thin_state_diff, &storage_table)?;
         delete_nonces(&self.txn, block_number, &thin_state_diff, &nonces_table)?;
         state_diffs_table.delete(&self.txn, &block_number)?;
+        // The L2 gas data is written together with the state diff of the block.
+        l2_gas_table.delete(&self.txn, &block_number)?;
         delete_replaced_classes(
             &self.txn,
             block_number,
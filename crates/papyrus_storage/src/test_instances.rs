@@ -1,14 +1,19 @@
+use std::collections::HashMap;
+
 use papyrus_test_utils::{auto_impl_get_test_instance, get_number_of_variants, GetTestInstance};
-use starknet_api::block::{BlockHash, BlockNumber, BlockTimestamp, GasPricePerToken};
+use starknet_api::block::{BlockHash, BlockNumber, BlockTimestamp, GasPrice, GasPricePerToken};
 use starknet_api::core::{
+    ContractAddress,
     EventCommitment,
     GlobalRoot,
+    Nonce,
     ReceiptCommitment,
     SequencerContractAddress,
     StateDiffCommitment,
     TransactionCommitment,
 };
 use starknet_api::data_availability::L1DataAvailabilityMode;
+use starknet_api::execution_resources::GasAmount;
 use starknet_api::transaction::{
     EventIndexInTransactionOutput,
     TransactionHash,
@@ -18,6 +23,8 @@ use starknet_api::transaction::{
 use crate::body::TransactionIndex;
 use crate::compression_utils::IsCompressed;
 use crate::header::StorageBlockHeader;
+use crate::l2_gas::L2GasInfo;
+use crate::mempool_commit::MempoolCommit;
 use crate::mmap_file::LocationInFile;
 use crate::state::data::IndexedDeprecatedContractClass;
 use crate::version::Version;
@@ -53,6 +60,10 @@ auto_impl_get_test_instance! {
         No = 0,
         Yes = 1,
     }
+    pub struct L2GasInfo {
+        pub l2_gas_used: GasAmount,
+        pub l2_gas_price: GasPrice,
+    }
     enum MarkerKind {
         Header = 0,
         Body = 1,
@@ -62,6 +73,11 @@ auto_impl_get_test_instance! {
         CompiledClass = 5,
         BaseLayerBlock = 6,
     }
+    pub struct MempoolCommit {
+        pub address_to_nonce: HashMap<ContractAddress, Nonce>,
+        pub tx_hashes: Vec<TransactionHash>,
+        pub rejected_tx_hashes: Vec<TransactionHash>,
+    }
     pub enum OffsetKind {
         ThinStateDiff = 0,
         ContractClass = 1,
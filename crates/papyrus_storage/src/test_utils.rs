@@ -35,6 +35,7 @@ pub(crate) fn get_test_config(storage_scope: Option<StorageScope>) -> (StorageCo
             },
             scope: storage_scope,
             mmap_file_config: get_mmap_file_test_config(),
+            index_event_keys: false,
         },
         dir,
     )
@@ -100,6 +101,12 @@ impl TestStorageBuilder {
         self
     }
 
+    /// Sets whether to index the events by their first key.
+    pub fn index_event_keys(mut self, index_event_keys: bool) -> Self {
+        self.config.index_event_keys = index_event_keys;
+        self
+    }
+
     /// Finishes the building and returns [`StorageReader`], [`StorageWriter`] and [`StorageConfig`]
     /// that were built, and the temporary directory that holds a db for testing purposes. The
     /// returned [`StorageConfig`] can be used to open the exact same storage again (same DB
@@ -2,12 +2,16 @@
 #[path = "version_test.rs"]
 mod version_test;
 
+use tracing::debug;
+
 use crate::db::table_types::Table;
 use crate::db::{TransactionKind, RW};
-use crate::{StorageError, StorageResult, StorageTxn};
+use crate::{StorageError, StorageResult, StorageTxn, StorageWriter};
 
 const VERSION_STATE_KEY: &str = "storage_version_state";
 const VERSION_BLOCKS_KEY: &str = "storage_version_blocks";
+// Bounds the time a migration holds the write lock of the storage.
+const MAX_BLOCKS_MIGRATED_PER_TXN: u64 = 1000;
 
 #[derive(Clone, Debug, Default, Eq, PartialEq)]
 pub struct Version {
@@ -39,6 +43,9 @@ pub enum StorageVersionError {
          existing one {storage_version}."
     )]
     SetMajorVersion { crate_version: Version, storage_version: Version },
+
+    #[error("The storage has no version, as it was never opened for writing.")]
+    MissingStorageVersion,
 }
 
 pub trait VersionStorageReader {
@@ -124,6 +131,30 @@ impl<'env> VersionStorageWriter for StorageTxn<'env, RW> {
     }
 }
 
+// Brings the blocks data written by an older minor version of the storage up to date with the
+// current minor version. Each migration runs if the storage predates the minor version that
+// introduced it, in bounded write transactions. A migration that was interrupted resumes from its
+// progress, which is recorded in the storage.
+pub(crate) fn migrate_blocks(
+    writer: &mut StorageWriter,
+    blocks_version: &Version,
+) -> StorageResult<()> {
+    // Version 3.2 indexes the transactions by their sender and nonce.
+    if blocks_version.minor < 2 {
+        debug!("Migrating the storage blocks data from version {blocks_version}.");
+        loop {
+            let (txn, done) = writer
+                .begin_rw_txn()?
+                .index_transactions_by_sender_and_nonce(MAX_BLOCKS_MIGRATED_PER_TXN)?;
+            txn.commit()?;
+            if done {
+                break;
+            }
+        }
+    }
+    Ok(())
+}
+
 impl std::fmt::Display for Version {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         let major = self.major.to_string();
@@ -1,8 +1,22 @@
 use assert_matches::assert_matches;
 use pretty_assertions::assert_eq;
 use rand::Rng;
+use starknet_api::block::{BlockBody, BlockNumber};
+use starknet_api::core::Nonce;
+use starknet_api::transaction::{
+    InvokeTransaction,
+    InvokeTransactionOutput,
+    InvokeTransactionV1,
+    Transaction,
+    TransactionHash,
+    TransactionOffsetInBlock,
+    TransactionOutput,
+};
+use starknet_api::{contract_address, felt};
 
+use crate::body::{BodyStorageReader, BodyStorageWriter, TransactionIndex};
 use crate::db::table_types::Table;
+use crate::db::{DbConfig, DbError};
 use crate::test_utils::{
     get_test_storage,
     get_test_storage_by_scope,
@@ -18,6 +32,7 @@ use crate::version::{
 };
 use crate::{
     open_storage,
+    open_storage_read_only,
     set_version_if_needed,
     verify_storage_version,
     StorageError,
@@ -115,6 +130,122 @@ fn version_migration() {
     assert_eq!(version_blocks.unwrap(), STORAGE_VERSION_BLOCKS);
 }
 
+#[test]
+fn open_storage_read_only_reads_scope_from_version() {
+    for scope in [StorageScope::FullArchive, StorageScope::StateOnly] {
+        let ((reader, writer), config, _temp_dir) = get_test_storage_with_config_by_scope(scope);
+        drop(reader);
+        drop(writer);
+
+        let reader = open_storage_read_only(config.db_config, config.mmap_file_config).unwrap();
+        assert_eq!(reader.get_scope(), scope);
+    }
+}
+
+#[test]
+fn open_storage_read_only_accepts_older_minor_version_without_migrating() {
+    let ((reader, mut writer), config, _temp_dir) =
+        get_test_storage_with_config_by_scope(StorageScope::FullArchive);
+    let lower_blocks_version = Version { major: STORAGE_VERSION_BLOCKS.major, minor: 0 };
+    change_storage_version(&mut writer, VERSION_BLOCKS_KEY, &lower_blocks_version);
+    drop(reader);
+    drop(writer);
+
+    let reader =
+        open_storage_read_only(config.db_config.clone(), config.mmap_file_config.clone()).unwrap();
+    let version_blocks = reader.begin_ro_txn().unwrap().get_blocks_version().unwrap();
+    assert_eq!(version_blocks.unwrap(), lower_blocks_version);
+    drop(reader);
+
+    // A different major version is rejected.
+    let ((reader, mut writer), config, _temp_dir) =
+        get_test_storage_with_config_by_scope(StorageScope::FullArchive);
+    let different_major_version = get_different_major_version(STORAGE_VERSION_STATE);
+    change_storage_version(&mut writer, VERSION_STATE_KEY, &different_major_version);
+    drop(reader);
+    drop(writer);
+
+    assert_matches!(
+        open_storage_read_only(config.db_config, config.mmap_file_config),
+        Err(StorageError::StorageVersionInconsistency(
+            StorageVersionError::InconsistentStorageVersion {
+                crate_version: STORAGE_VERSION_STATE,
+                storage_version,
+            },
+        )) if storage_version == different_major_version
+    );
+}
+
+#[test]
+fn open_storage_read_only_does_not_create_storage() {
+    let ((reader, writer), config, temp_dir) =
+        get_test_storage_with_config_by_scope(StorageScope::FullArchive);
+    drop(reader);
+    drop(writer);
+    let casm_file_size = config.db_config.path().join("casm.dat").metadata().unwrap().len();
+
+    // The existing files are neither grown nor replaced.
+    open_storage_read_only(config.db_config.clone(), config.mmap_file_config.clone()).unwrap();
+    assert_eq!(config.db_config.path().join("casm.dat").metadata().unwrap().len(), casm_file_size);
+
+    // A storage that does not exist is not created.
+    let db_config = DbConfig { path_prefix: temp_dir.path().join("missing"), ..config.db_config };
+    assert_matches!(
+        open_storage_read_only(db_config.clone(), config.mmap_file_config),
+        Err(StorageError::InnerError(DbError::FileDoesNotExist(_)))
+    );
+    assert!(!db_config.path().exists());
+}
+
+#[test]
+fn blocks_version_migration_indexes_transactions_by_sender_and_nonce() {
+    let ((reader, mut writer), config, _temp_dir) =
+        get_test_storage_with_config_by_scope(StorageScope::FullArchive);
+    let sender_address = contract_address!(1_u8);
+    let nonce = Nonce(felt!(3_u8));
+    let invoke_tx = InvokeTransactionV1 { sender_address, nonce, ..Default::default() };
+    let body = BlockBody {
+        transactions: vec![Transaction::Invoke(InvokeTransaction::V1(invoke_tx))],
+        transaction_outputs: vec![TransactionOutput::Invoke(InvokeTransactionOutput::default())],
+        transaction_hashes: vec![TransactionHash::default()],
+    };
+    writer.begin_rw_txn().unwrap().append_body(BlockNumber(0), body).unwrap().commit().unwrap();
+
+    // Simulate a storage that was written before the transactions were indexed by their sender
+    // and nonce.
+    let wtxn = writer.begin_rw_txn().unwrap();
+    let transaction_sender_nonce_to_idx_table =
+        wtxn.open_table(&wtxn.tables.transaction_sender_nonce_to_idx).unwrap();
+    transaction_sender_nonce_to_idx_table.delete(&wtxn.txn, &(sender_address, nonce)).unwrap();
+    wtxn.commit().unwrap();
+    change_storage_version(
+        &mut writer,
+        VERSION_BLOCKS_KEY,
+        &Version { major: STORAGE_VERSION_BLOCKS.major, minor: 1 },
+    );
+    assert!(reader
+        .begin_ro_txn()
+        .unwrap()
+        .get_transaction_idx_by_sender_and_nonce(&sender_address, &nonce)
+        .unwrap()
+        .is_none());
+    drop(reader);
+    drop(writer);
+
+    // Reopen the storage and verify that the transaction was indexed.
+    let (reader, _) = open_storage(config).unwrap();
+    assert_eq!(
+        reader
+            .begin_ro_txn()
+            .unwrap()
+            .get_transaction_idx_by_sender_and_nonce(&sender_address, &nonce)
+            .unwrap(),
+        Some(TransactionIndex(BlockNumber(0), TransactionOffsetInBlock(0)))
+    );
+    let version_blocks = reader.begin_ro_txn().unwrap().get_blocks_version().unwrap();
+    assert_eq!(version_blocks.unwrap(), STORAGE_VERSION_BLOCKS);
+}
+
 #[test]
 fn open_storage_full_archive_different_state_major_versions() {
     let ((reader, mut writer), config, _temp_dir) =
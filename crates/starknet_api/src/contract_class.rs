@@ -3,6 +3,7 @@ use serde::{Deserialize, Serialize};
 
 use crate::core::CompiledClassHash;
 use crate::deprecated_contract_class::ContractClass as DeprecatedContractClass;
+use crate::state::SierraContractClass;
 use crate::StarknetApiError;
 
 /// One Felt fits into 32 bytes.
@@ -51,6 +52,10 @@ pub struct ClassInfo {
     pub contract_class: ContractClass,
     pub sierra_program_length: usize,
     pub abi_length: usize,
+    /// The Sierra class of a Cairo 1 class, when known. It is not needed for execution, but is
+    /// stored once the declaring transaction is included in a block.
+    #[serde(default)]
+    pub sierra_contract_class: Option<SierraContractClass>,
 }
 
 impl ClassInfo {
@@ -91,7 +96,12 @@ impl ClassInfo {
         };
 
         if condition {
-            Ok(Self { contract_class: contract_class.clone(), sierra_program_length, abi_length })
+            Ok(Self {
+                contract_class: contract_class.clone(),
+                sierra_program_length,
+                abi_length,
+                sierra_contract_class: None,
+            })
         } else {
             Err(StarknetApiError::ContractClassVersionSierraProgramLengthMismatch {
                 contract_class_version,
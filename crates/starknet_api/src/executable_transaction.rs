@@ -68,6 +68,14 @@ impl AccountTransaction {
         }
     }
 
+    pub fn signature(&self) -> TransactionSignature {
+        match self {
+            AccountTransaction::Declare(tx_data) => tx_data.tx.signature(),
+            AccountTransaction::DeployAccount(tx_data) => tx_data.tx.signature(),
+            AccountTransaction::Invoke(tx_data) => tx_data.tx.signature(),
+        }
+    }
+
     pub fn tx_hash(&self) -> TransactionHash {
         match self {
             AccountTransaction::Declare(tx_data) => tx_data.tx_hash,
@@ -2,9 +2,12 @@
 #[path = "rpc_transaction_test.rs"]
 mod rpc_transaction_test;
 
+use std::collections::HashMap;
+
 use serde::{Deserialize, Serialize};
 use starknet_types_core::felt::Felt;
 
+use crate::contract_class::EntryPointType;
 use crate::core::{
     calculate_contract_address,
     ClassHash,
@@ -13,7 +16,7 @@ use crate::core::{
     Nonce,
 };
 use crate::data_availability::DataAvailabilityMode;
-use crate::state::EntryPoint;
+use crate::state::{EntryPoint, SierraContractClass};
 use crate::transaction::fields::{
     AccountDeploymentData,
     AllResourceBounds,
@@ -278,6 +281,22 @@ pub struct ContractClass {
     pub abi: String,
 }
 
+impl From<ContractClass> for SierraContractClass {
+    fn from(contract_class: ContractClass) -> Self {
+        let EntryPointByType { constructor, external, l1handler } =
+            contract_class.entry_points_by_type;
+        Self {
+            sierra_program: contract_class.sierra_program,
+            entry_points_by_type: HashMap::from([
+                (EntryPointType::Constructor, constructor),
+                (EntryPointType::External, external),
+                (EntryPointType::L1Handler, l1handler),
+            ]),
+            abi: contract_class.abi,
+        }
+    }
+}
+
 #[derive(Debug, Clone, Default, Eq, PartialEq, Deserialize, Serialize, Hash)]
 pub struct EntryPointByType {
     #[serde(rename = "CONSTRUCTOR")]
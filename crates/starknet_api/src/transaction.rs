@@ -116,8 +116,8 @@ impl Transaction {
 impl From<crate::executable_transaction::Transaction> for Transaction {
     fn from(tx: crate::executable_transaction::Transaction) -> Self {
         match tx {
-            crate::executable_transaction::Transaction::L1Handler(_) => {
-                unimplemented!("L1Handler transactions are not supported yet.")
+            crate::executable_transaction::Transaction::L1Handler(tx) => {
+                Transaction::L1Handler(tx.tx)
             }
             crate::executable_transaction::Transaction::Account(account_tx) => match account_tx {
                 crate::executable_transaction::AccountTransaction::Declare(tx) => {
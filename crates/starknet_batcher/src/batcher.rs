@@ -6,8 +6,19 @@ use blockifier::state::global_cache::GlobalContractCache;
 use chrono::Utc;
 #[cfg(test)]
 use mockall::automock;
+use papyrus_storage::body::BodyStorageWriter;
+use papyrus_storage::class::{ClassStorageReader, ClassStorageWriter};
+use papyrus_storage::compiled_class::CasmStorageWriter;
+use papyrus_storage::header::{HeaderStorageReader, HeaderStorageWriter};
+use papyrus_storage::l2_gas::{L2GasInfo, L2GasStorageWriter};
+use papyrus_storage::mempool_commit::{
+    MempoolCommit,
+    MempoolCommitStorageReader,
+    MempoolCommitStorageWriter,
+};
 use papyrus_storage::state::{StateStorageReader, StateStorageWriter};
-use starknet_api::block::{BlockHashAndNumber, BlockNumber};
+use papyrus_storage::StorageError;
+use starknet_api::block::{BlockHash, BlockHashAndNumber, BlockNumber};
 use starknet_api::executable_transaction::Transaction;
 use starknet_api::state::ThinStateDiff;
 use starknet_batcher_types::batcher_types::{
@@ -29,10 +40,12 @@ use starknet_batcher_types::errors::BatcherError;
 use starknet_mempool_types::communication::SharedMempoolClient;
 use starknet_mempool_types::mempool_types::CommitBlockArgs;
 use starknet_sequencer_infra::component_definitions::ComponentStarter;
-use tracing::{debug, error, info, instrument, trace};
+use tracing::{debug, error, info, instrument, trace, warn};
 
 use crate::block_builder::{BlockBuilderError, BlockBuilderFactory};
+use crate::block_data::BlockData;
 use crate::config::BatcherConfig;
+use crate::l1_gas_price_oracle::FixedL1GasPriceOracle;
 use crate::proposal_manager::{
     GenerateProposalError,
     GetProposalResultError,
@@ -103,6 +116,10 @@ impl Batcher {
             });
         }
 
+        // Building on top of a mempool that missed a committed block would re-propose its
+        // transactions.
+        self.commit_pending_blocks_to_mempool().await?;
+
         // Clear all the proposals from the previous height.
         self.proposal_manager.reset().await;
         self.propose_tx_streams.clear();
@@ -131,6 +148,7 @@ impl Batcher {
             // TODO: use a real L1 provider client.
             Arc::new(DummyL1ProviderClient),
             self.config.max_l1_handler_txs_per_block_proposal,
+            deadline,
         );
 
         self.proposal_manager
@@ -299,8 +317,15 @@ impl Batcher {
     pub async fn decision_reached(&mut self, input: DecisionReachedInput) -> BatcherResult<()> {
         let proposal_id = input.proposal_id;
         let proposal_output = self.proposal_manager.take_proposal_result(proposal_id).await?;
-        let ProposalOutput { state_diff, nonces: address_to_nonce, tx_hashes, .. } =
-            proposal_output;
+        let ProposalOutput {
+            state_diff,
+            nonces: address_to_nonce,
+            tx_hashes,
+            rejected_tx_hashes,
+            l2_gas_info,
+            block_data,
+            ..
+        } = proposal_output;
         // TODO: Keep the height from start_height or get it from the input.
         let height = self.storage_reader.height().map_err(|err| {
             error!("Failed to get height from storage: {}", err);
@@ -311,15 +336,53 @@ impl Batcher {
             proposal_id, height
         );
         trace!("Transactions: {:#?}, State diff: {:#?}.", tx_hashes, state_diff);
-        self.storage_writer.commit_proposal(height, state_diff).map_err(|err| {
-            error!("Failed to commit proposal to storage: {}", err);
-            BatcherError::InternalError
-        })?;
-        if let Err(mempool_err) =
-            self.mempool_client.commit_block(CommitBlockArgs { address_to_nonce, tx_hashes }).await
-        {
-            error!("Failed to commit block to mempool: {}", mempool_err);
-            // TODO: Should we rollback the state diff and return an error?
+        // The mempool commit is stored with the block, so it is not lost if the mempool fails to
+        // commit it or the batcher restarts before it does.
+        let mempool_commit = MempoolCommit {
+            address_to_nonce,
+            tx_hashes: tx_hashes.into_iter().collect(),
+            rejected_tx_hashes: rejected_tx_hashes.into_iter().collect(),
+        };
+        self.storage_writer
+            .commit_proposal(height, state_diff, block_data, l2_gas_info, mempool_commit)
+            .map_err(|err| {
+                error!("Failed to commit proposal to storage: {}", err);
+                BatcherError::InternalError
+            })?;
+        // The block is already in storage, so a mempool failure doesn't fail the decision; the
+        // commit is retried before the next height starts.
+        if let Err(err) = self.commit_pending_blocks_to_mempool().await {
+            warn!("{} Will retry before starting the next height.", err);
+        }
+        Ok(())
+    }
+
+    /// Commits the blocks whose mempool commit is pending to the mempool in height order, stopping
+    /// at the first failure.
+    // The mempool ignores blocks it already committed, so a commit whose response was lost may be
+    // safely retried.
+    async fn commit_pending_blocks_to_mempool(&mut self) -> BatcherResult<()> {
+        let pending_mempool_commits =
+            self.storage_reader.pending_mempool_commits().map_err(|err| {
+                error!("Failed to read the pending mempool commits from storage: {}", err);
+                BatcherError::InternalError
+            })?;
+        for (height, mempool_commit) in pending_mempool_commits {
+            let MempoolCommit { address_to_nonce, tx_hashes, rejected_tx_hashes } = mempool_commit;
+            let commit_block_args = CommitBlockArgs {
+                block_number: height,
+                address_to_nonce,
+                tx_hashes: tx_hashes.into_iter().collect(),
+                rejected_tx_hashes: rejected_tx_hashes.into_iter().collect(),
+            };
+            if let Err(mempool_err) = self.mempool_client.commit_block(commit_block_args).await {
+                error!("Failed to commit block {} to mempool: {}", height, mempool_err);
+                return Err(BatcherError::MempoolNotSynced { height });
+            }
+            self.storage_writer.remove_pending_mempool_commit(height).map_err(|err| {
+                error!("Failed to remove the pending mempool commit from storage: {}", err);
+                BatcherError::InternalError
+            })?;
         }
         Ok(())
     }
@@ -333,6 +396,8 @@ pub fn create_batcher(config: BatcherConfig, mempool_client: SharedMempoolClient
         block_builder_config: config.block_builder_config.clone(),
         storage_reader: storage_reader.clone(),
         global_class_hash_to_class: GlobalContractCache::new(config.global_contract_cache_size),
+        l1_gas_price_oracle: Arc::new(FixedL1GasPriceOracle::new(config.l1_gas_prices)),
+        commit_observer: None,
     });
     let storage_reader = Arc::new(storage_reader);
     let storage_writer = Box::new(storage_writer);
@@ -344,20 +409,42 @@ pub fn create_batcher(config: BatcherConfig, mempool_client: SharedMempoolClient
 pub trait BatcherStorageReaderTrait: Send + Sync {
     /// Returns the next height that the batcher should work on.
     fn height(&self) -> papyrus_storage::StorageResult<BlockNumber>;
+
+    /// Returns the mempool commits of the blocks that the mempool did not commit yet, by height.
+    fn pending_mempool_commits(
+        &self,
+    ) -> papyrus_storage::StorageResult<Vec<(BlockNumber, MempoolCommit)>>;
 }
 
 impl BatcherStorageReaderTrait for papyrus_storage::StorageReader {
     fn height(&self) -> papyrus_storage::StorageResult<BlockNumber> {
         self.begin_ro_txn()?.get_state_marker()
     }
+
+    fn pending_mempool_commits(
+        &self,
+    ) -> papyrus_storage::StorageResult<Vec<(BlockNumber, MempoolCommit)>> {
+        self.begin_ro_txn()?.get_pending_mempool_commits()
+    }
 }
 
 #[cfg_attr(test, automock)]
 pub trait BatcherStorageWriterTrait: Send + Sync {
+    /// Writes the block at the given height, on top of the previous block, along with its pending
+    /// mempool commit.
     fn commit_proposal(
         &mut self,
         height: BlockNumber,
         state_diff: ThinStateDiff,
+        block_data: BlockData,
+        l2_gas_info: L2GasInfo,
+        mempool_commit: MempoolCommit,
+    ) -> papyrus_storage::StorageResult<()>;
+
+    /// Removes the pending mempool commit of the given height, once the mempool committed it.
+    fn remove_pending_mempool_commit(
+        &mut self,
+        height: BlockNumber,
     ) -> papyrus_storage::StorageResult<()>;
 }
 
@@ -366,9 +453,77 @@ impl BatcherStorageWriterTrait for papyrus_storage::StorageWriter {
         &mut self,
         height: BlockNumber,
         state_diff: ThinStateDiff,
+        block_data: BlockData,
+        l2_gas_info: L2GasInfo,
+        mempool_commit: MempoolCommit,
+    ) -> papyrus_storage::StorageResult<()> {
+        let mut txn = self.begin_rw_txn()?;
+        // The header commits to the state root, so it is only written once the state root is
+        // known, instead of writing a header with a placeholder root. The body, with the
+        // transaction outputs, doesn't depend on it and is always written.
+        if let Some(state_root) = block_data.state_root {
+            let parent_hash = match height.prev() {
+                None => BlockHash::default(),
+                Some(parent_height) => {
+                    txn.get_block_header(parent_height)?
+                        .ok_or_else(|| StorageError::DBInconsistency {
+                            msg: format!(
+                                "Missing the header of block {parent_height}, the parent of block \
+                                 {height}."
+                            ),
+                        })?
+                        .block_hash
+                }
+            };
+            let header = block_data.header(parent_hash, state_root);
+            txn = txn.append_header(height, &header)?;
+        }
+        txn = txn.append_body(height, block_data.body)?.append_state_diff(height, state_diff)?;
+
+        // The known classes of the block are written, and the classes whose Sierra is unknown are
+        // recorded as missing, so they can be filled later without holding back the class marker.
+        let class_marker = txn.get_class_marker()?;
+        if class_marker == height {
+            let classes: Vec<_> =
+                block_data.classes.iter().map(|(class_hash, class)| (*class_hash, class)).collect();
+            let deprecated_classes: Vec<_> = block_data
+                .deprecated_classes
+                .iter()
+                .map(|(class_hash, deprecated_class)| (*class_hash, deprecated_class))
+                .collect();
+            let missing_sierra: Vec<_> = block_data
+                .casms
+                .iter()
+                .map(|(class_hash, _)| *class_hash)
+                .filter(|class_hash| {
+                    !block_data.classes.iter().any(|(known_hash, _)| known_hash == class_hash)
+                })
+                .collect();
+            if !missing_sierra.is_empty() {
+                warn!(
+                    "Missing the Sierra of classes {missing_sierra:?} declared in block {height}."
+                );
+            }
+            txn = txn
+                .append_classes(height, &classes, &deprecated_classes)?
+                .set_classes_missing_sierra(height, &missing_sierra)?;
+        } else {
+            warn!("Not writing the classes of block {height}. Class marker: {class_marker}.");
+        }
+        // The compiled classes are written after the state diff, which declares them.
+        for (class_hash, casm) in &block_data.casms {
+            txn = txn.append_casm(class_hash, casm)?;
+        }
+        txn.set_l2_gas_info(height, &l2_gas_info)?
+            .set_pending_mempool_commit(height, &mempool_commit)?
+            .commit()
+    }
+
+    fn remove_pending_mempool_commit(
+        &mut self,
+        height: BlockNumber,
     ) -> papyrus_storage::StorageResult<()> {
-        // TODO: write casms.
-        self.begin_rw_txn()?.append_state_diff(height, state_diff)?.commit()
+        self.begin_rw_txn()?.delete_pending_mempool_commit(height)?.commit()
     }
 }
 
@@ -7,17 +7,42 @@ use blockifier::abi::constants;
 use chrono::Utc;
 use futures::future::BoxFuture;
 use futures::FutureExt;
-use mockall::automock;
+use indexmap::IndexMap;
 use mockall::predicate::{always, eq};
+use mockall::{automock, Sequence};
+use papyrus_storage::body::BodyStorageReader;
+use papyrus_storage::class::ClassStorageReader;
+use papyrus_storage::compiled_class::CasmStorageReader;
+use papyrus_storage::header::HeaderStorageReader;
+use papyrus_storage::l2_gas::L2GasInfo;
+use papyrus_storage::mempool_commit::{MempoolCommit, MempoolCommitStorageReader};
+use papyrus_storage::state::StateStorageReader;
+use papyrus_storage::test_utils::get_test_storage;
 use rstest::{fixture, rstest};
-use starknet_api::block::{BlockHashAndNumber, BlockNumber};
-use starknet_api::core::{ContractAddress, Nonce, StateDiffCommitment};
+use starknet_api::block::{
+    BlockBody,
+    BlockHashAndNumber,
+    BlockHeaderWithoutHash,
+    BlockNumber,
+    GasPrice,
+    StarknetVersion,
+};
+use starknet_api::core::{
+    ClassHash,
+    CompiledClassHash,
+    ContractAddress,
+    GlobalRoot,
+    Nonce,
+    StateDiffCommitment,
+};
 use starknet_api::executable_transaction::Transaction;
+use starknet_api::execution_resources::GasAmount;
 use starknet_api::hash::PoseidonHash;
-use starknet_api::state::ThinStateDiff;
+use starknet_api::state::{SierraContractClass, ThinStateDiff};
 use starknet_api::transaction::TransactionHash;
 use starknet_api::{contract_address, felt, nonce};
 use starknet_batcher_types::batcher_types::{
+    BatcherResult,
     DecisionReachedInput,
     GetProposalContent,
     GetProposalContentInput,
@@ -33,11 +58,18 @@ use starknet_batcher_types::batcher_types::{
     ValidateBlockInput,
 };
 use starknet_batcher_types::errors::BatcherError;
-use starknet_mempool_types::communication::MockMempoolClient;
+use starknet_mempool_types::communication::{MempoolClientError, MockMempoolClient};
 use starknet_mempool_types::mempool_types::CommitBlockArgs;
+use starknet_sequencer_infra::component_client::ClientError;
 
-use crate::batcher::{Batcher, MockBatcherStorageReaderTrait, MockBatcherStorageWriterTrait};
+use crate::batcher::{
+    Batcher,
+    BatcherStorageWriterTrait,
+    MockBatcherStorageReaderTrait,
+    MockBatcherStorageWriterTrait,
+};
 use crate::block_builder::{BlockBuilderError, FailOnErrorCause};
+use crate::block_data::BlockData;
 use crate::config::BatcherConfig;
 use crate::proposal_manager::{
     GenerateProposalError,
@@ -47,7 +79,7 @@ use crate::proposal_manager::{
     ProposalOutput,
     ProposalResult,
 };
-use crate::test_utils::test_txs;
+use crate::test_utils::{test_casm, test_txs};
 use crate::transaction_provider::{ProposeTransactionProvider, ValidateTransactionProvider};
 
 const INITIAL_HEIGHT: BlockNumber = BlockNumber(3);
@@ -69,6 +101,7 @@ fn deadline() -> chrono::DateTime<Utc> {
 fn storage_reader() -> MockBatcherStorageReaderTrait {
     let mut storage = MockBatcherStorageReaderTrait::new();
     storage.expect_height().returning(|| Ok(INITIAL_HEIGHT));
+    storage.expect_pending_mempool_commits().returning(|| Ok(vec![]));
     storage
 }
 
@@ -420,6 +453,7 @@ async fn propose_block_without_retrospective_block_hash() {
     storage_reader
         .expect_height()
         .returning(|| Ok(BlockNumber(constants::STORED_BLOCK_HASH_BUFFER)));
+    storage_reader.expect_pending_mempool_commits().returning(|| Ok(vec![]));
 
     let mut batcher = Batcher::new(
         batcher_config(),
@@ -461,17 +495,34 @@ async fn get_content_from_unknown_proposal() {
 #[tokio::test]
 async fn decision_reached(
     batcher_config: BatcherConfig,
-    storage_reader: MockBatcherStorageReaderTrait,
     mut storage_writer: MockBatcherStorageWriterTrait,
     mut mempool_client: MockMempoolClient,
 ) {
     let expected_state_diff = ThinStateDiff::default();
     let state_diff_clone = expected_state_diff.clone();
     let expected_proposal_commitment = ProposalCommitment::default();
-    let tx_hashes = test_tx_hashes(0..5);
+    // A single transaction of each kind, so the order of the stored hashes is known.
+    let tx_hashes = test_tx_hashes(0..1);
     let tx_hashes_clone = tx_hashes.clone();
     let address_to_nonce = test_contract_nonces(0..3);
     let nonces_clone = address_to_nonce.clone();
+    let rejected_tx_hashes = test_tx_hashes(5..6);
+    let rejected_tx_hashes_clone = rejected_tx_hashes.clone();
+    let mempool_commit = MempoolCommit {
+        address_to_nonce: address_to_nonce.clone(),
+        tx_hashes: tx_hashes.iter().copied().collect(),
+        rejected_tx_hashes: rejected_tx_hashes.iter().copied().collect(),
+    };
+    let mempool_commit_clone = mempool_commit.clone();
+    let l2_gas_info = L2GasInfo { l2_gas_used: GasAmount(7), l2_gas_price: GasPrice(100000) };
+    let block_data = BlockData {
+        body: BlockBody {
+            transaction_hashes: vec![TransactionHash(felt!(1_u8))],
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+    let block_data_clone = block_data.clone();
 
     let mut proposal_manager = MockProposalManagerTraitWrapper::new();
     proposal_manager.expect_wrap_take_proposal_result().times(1).with(eq(PROPOSAL_ID)).return_once(
@@ -482,6 +533,9 @@ async fn decision_reached(
                     commitment: expected_proposal_commitment,
                     tx_hashes: tx_hashes_clone,
                     nonces: nonces_clone,
+                    rejected_tx_hashes: rejected_tx_hashes_clone,
+                    l2_gas_info,
+                    block_data: block_data_clone,
                 })
             }
             .boxed()
@@ -489,13 +543,39 @@ async fn decision_reached(
     );
     mempool_client
         .expect_commit_block()
-        .with(eq(CommitBlockArgs { address_to_nonce, tx_hashes }))
+        .with(eq(CommitBlockArgs {
+            block_number: INITIAL_HEIGHT,
+            address_to_nonce,
+            tx_hashes,
+            rejected_tx_hashes,
+        }))
         .returning(|_| Ok(()));
 
+    // The mempool commit is stored with the block, and removed once the mempool committed it.
+    let mut seq = Sequence::new();
     storage_writer
         .expect_commit_proposal()
-        .with(eq(INITIAL_HEIGHT), eq(expected_state_diff))
-        .returning(|_, _| Ok(()));
+        .times(1)
+        .in_sequence(&mut seq)
+        .with(
+            eq(INITIAL_HEIGHT),
+            eq(expected_state_diff),
+            eq(block_data),
+            eq(l2_gas_info),
+            eq(mempool_commit),
+        )
+        .returning(|_, _, _, _, _| Ok(()));
+    storage_writer
+        .expect_remove_pending_mempool_commit()
+        .times(1)
+        .in_sequence(&mut seq)
+        .with(eq(INITIAL_HEIGHT))
+        .returning(|_| Ok(()));
+    let mut storage_reader = MockBatcherStorageReaderTrait::new();
+    storage_reader.expect_height().returning(|| Ok(INITIAL_HEIGHT));
+    storage_reader
+        .expect_pending_mempool_commits()
+        .returning(move || Ok(vec![(INITIAL_HEIGHT, mempool_commit_clone.clone())]));
 
     let mut batcher = Batcher::new(
         batcher_config,
@@ -507,6 +587,155 @@ async fn decision_reached(
     batcher.decision_reached(DecisionReachedInput { proposal_id: PROPOSAL_ID }).await.unwrap();
 }
 
+fn declaring_state_diff(class_hash: ClassHash) -> ThinStateDiff {
+    ThinStateDiff {
+        declared_classes: IndexMap::from([(class_hash, CompiledClassHash::default())]),
+        ..Default::default()
+    }
+}
+
+#[test]
+fn commit_proposal_without_state_root_and_sierra_classes() {
+    let ((storage_reader, mut storage_writer), _temp_dir) = get_test_storage();
+    let class_hash = ClassHash(felt!(1_u8));
+    let block_data = BlockData { casms: vec![(class_hash, test_casm())], ..Default::default() };
+
+    storage_writer
+        .commit_proposal(
+            BlockNumber(0),
+            declaring_state_diff(class_hash),
+            block_data,
+            L2GasInfo::default(),
+            MempoolCommit::default(),
+        )
+        .unwrap();
+
+    // Everything but the header is written, and the class without Sierra is marked as missing.
+    let txn = storage_reader.begin_ro_txn().unwrap();
+    assert_eq!(txn.get_state_marker().unwrap(), BlockNumber(1));
+    assert_eq!(txn.get_header_marker().unwrap(), BlockNumber(0));
+    assert_eq!(txn.get_body_marker().unwrap(), BlockNumber(1));
+    assert_eq!(txn.get_class_marker().unwrap(), BlockNumber(1));
+    assert_eq!(txn.get_class(&class_hash).unwrap(), None);
+    assert_eq!(txn.get_classes_missing_sierra().unwrap(), vec![(class_hash, BlockNumber(0))]);
+    assert_eq!(txn.get_casm(&class_hash).unwrap(), Some(test_casm()));
+}
+
+#[test]
+fn commit_proposal_with_state_root_and_sierra_classes() {
+    let ((storage_reader, mut storage_writer), _temp_dir) = get_test_storage();
+    let class_hash = ClassHash(felt!(1_u8));
+    let class = SierraContractClass { abi: "abi".to_string(), ..Default::default() };
+    let state_root = GlobalRoot(felt!(2_u8));
+    let block_data = BlockData {
+        header_without_hash: BlockHeaderWithoutHash {
+            starknet_version: StarknetVersion::LATEST,
+            ..Default::default()
+        },
+        state_root: Some(state_root),
+        classes: vec![(class_hash, class.clone())],
+        casms: vec![(class_hash, test_casm())],
+        ..Default::default()
+    };
+
+    storage_writer
+        .commit_proposal(
+            BlockNumber(0),
+            declaring_state_diff(class_hash),
+            block_data,
+            L2GasInfo::default(),
+            MempoolCommit::default(),
+        )
+        .unwrap();
+
+    let txn = storage_reader.begin_ro_txn().unwrap();
+    assert_eq!(txn.get_header_marker().unwrap(), BlockNumber(1));
+    assert_eq!(txn.get_body_marker().unwrap(), BlockNumber(1));
+    assert_eq!(
+        txn.get_block_header(BlockNumber(0)).unwrap().unwrap().block_header_without_hash.state_root,
+        state_root
+    );
+    assert_eq!(txn.get_class_marker().unwrap(), BlockNumber(1));
+    assert_eq!(txn.get_class(&class_hash).unwrap(), Some(class));
+    assert!(txn.get_classes_missing_sierra().unwrap().is_empty());
+}
+
+#[rstest]
+#[case::retry_succeeds(true, Ok(()))]
+#[case::retry_fails(false, Err(BatcherError::MempoolNotSynced { height: BlockNumber(0) }))]
+#[tokio::test]
+async fn decision_reached_retries_failed_mempool_commit(
+    batcher_config: BatcherConfig,
+    mut mempool_client: MockMempoolClient,
+    #[case] retry_succeeds: bool,
+    #[case] expected_start_height_result: BatcherResult<()>,
+) {
+    let ((storage_reader, storage_writer), _temp_dir) = get_test_storage();
+    let commit_block_args = CommitBlockArgs {
+        block_number: BlockNumber(0),
+        address_to_nonce: test_contract_nonces(0..3),
+        tx_hashes: test_tx_hashes(0..5),
+        rejected_tx_hashes: HashSet::new(),
+    };
+    let proposal_output = ProposalOutput {
+        state_diff: ThinStateDiff::default(),
+        commitment: ProposalCommitment::default(),
+        tx_hashes: commit_block_args.tx_hashes.clone(),
+        nonces: commit_block_args.address_to_nonce.clone(),
+        rejected_tx_hashes: HashSet::new(),
+        l2_gas_info: L2GasInfo::default(),
+        block_data: BlockData::default(),
+    };
+
+    let mut proposal_manager = MockProposalManagerTraitWrapper::new();
+    proposal_manager
+        .expect_wrap_take_proposal_result()
+        .times(1)
+        .with(eq(PROPOSAL_ID))
+        .return_once(move |_| async move { Ok(proposal_output) }.boxed());
+    proposal_manager.expect_wrap_reset().returning(|| async {}.boxed());
+
+    // The commit fails on decision, and is retried when the next height starts.
+    let mut seq = Sequence::new();
+    mempool_client
+        .expect_commit_block()
+        .times(1)
+        .in_sequence(&mut seq)
+        .with(eq(commit_block_args.clone()))
+        .returning(|_| Err(mempool_client_error()));
+    mempool_client
+        .expect_commit_block()
+        .times(1)
+        .in_sequence(&mut seq)
+        .with(eq(commit_block_args))
+        .returning(move |_| if retry_succeeds { Ok(()) } else { Err(mempool_client_error()) });
+
+    let mut batcher = Batcher::new(
+        batcher_config,
+        Arc::new(storage_reader.clone()),
+        Box::new(storage_writer),
+        Arc::new(mempool_client),
+        Box::new(proposal_manager),
+    );
+    assert_eq!(
+        batcher.decision_reached(DecisionReachedInput { proposal_id: PROPOSAL_ID }).await,
+        Ok(())
+    );
+    // The failed commit is kept in storage, so it survives a restart of the batcher.
+    let pending_mempool_commits =
+        storage_reader.begin_ro_txn().unwrap().get_pending_mempool_commits().unwrap();
+    assert_eq!(pending_mempool_commits.len(), 1);
+    assert_eq!(pending_mempool_commits[0].0, BlockNumber(0));
+
+    assert_eq!(
+        batcher.start_height(StartHeightInput { height: BlockNumber(1) }).await,
+        expected_start_height_result
+    );
+    let n_pending_mempool_commits =
+        storage_reader.begin_ro_txn().unwrap().get_pending_mempool_commits().unwrap().len();
+    assert_eq!(n_pending_mempool_commits, if retry_succeeds { 0 } else { 1 });
+}
+
 #[rstest]
 #[tokio::test]
 async fn decision_reached_no_executed_proposal() {
@@ -646,6 +875,10 @@ impl<T: ProposalManagerTraitWrapper> ProposalManagerTrait for T {
     }
 }
 
+fn mempool_client_error() -> MempoolClientError {
+    MempoolClientError::ClientError(ClientError::UnexpectedResponse("Unavailable".to_string()))
+}
+
 fn test_tx_hashes(range: std::ops::Range<u128>) -> HashSet<TransactionHash> {
     range.map(|i| TransactionHash(felt!(i))).collect()
 }
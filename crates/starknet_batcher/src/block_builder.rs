@@ -1,9 +1,11 @@
 use std::collections::BTreeMap;
+use std::sync::Arc;
 
 use async_trait::async_trait;
 use blockifier::blockifier::block::{BlockInfo, GasPrices};
 use blockifier::blockifier::config::TransactionExecutorConfig;
 use blockifier::blockifier::transaction_executor::{
+    TransactionCommitObserver,
     TransactionExecutor,
     TransactionExecutorError as BlockifierTransactionExecutorError,
     TransactionExecutorResult,
@@ -24,16 +26,30 @@ use mockall::automock;
 use papyrus_config::dumping::{append_sub_config_name, ser_param, SerializeConfig};
 use papyrus_config::{ParamPath, ParamPrivacyInput, SerializedParam};
 use papyrus_state_reader::papyrus_state::PapyrusReader;
-use papyrus_storage::StorageReader;
+use papyrus_storage::l2_gas::{L2GasInfo, L2GasStorageReader};
+use papyrus_storage::{StorageError, StorageReader};
 use serde::{Deserialize, Serialize};
-use starknet_api::block::{BlockHashAndNumber, BlockNumber, BlockTimestamp, NonzeroGasPrice};
+use starknet_api::block::{
+    BlockHashAndNumber,
+    BlockHeaderWithoutHash,
+    BlockNumber,
+    BlockTimestamp,
+    GasPrice,
+    NonzeroGasPrice,
+};
 use starknet_api::core::ContractAddress;
 use starknet_api::executable_transaction::Transaction;
+use starknet_api::execution_resources::GasAmount;
 use starknet_api::transaction::TransactionHash;
+use starknet_api::StarknetApiError;
 use thiserror::Error;
 use tokio::sync::Mutex;
 use tracing::{debug, error, info, trace};
+use validator::Validate;
 
+use crate::block_data::block_header_without_hash;
+use crate::fee_market::{calculate_next_base_gas_price, FeeMarketConfig};
+use crate::l1_gas_price_oracle::{L1GasPriceOracle, L1GasPriceOracleError};
 use crate::transaction_executor::TransactionExecutorTrait;
 use crate::transaction_provider::{NextTxs, TransactionProvider, TransactionProviderError};
 
@@ -48,6 +64,12 @@ pub enum BlockBuilderError {
     #[error(transparent)]
     GetTransactionError(#[from] TransactionProviderError),
     #[error(transparent)]
+    InvalidGasPrice(#[from] StarknetApiError),
+    #[error(transparent)]
+    L1GasPriceOracleError(#[from] L1GasPriceOracleError),
+    #[error(transparent)]
+    StorageError(#[from] StorageError),
+    #[error(transparent)]
     StreamTransactionsError(#[from] tokio::sync::mpsc::error::SendError<Transaction>),
     #[error(transparent)]
     FailOnError(FailOnErrorCause),
@@ -70,10 +92,30 @@ pub enum FailOnErrorCause {
 #[cfg_attr(test, derive(Clone))]
 #[derive(Debug, PartialEq)]
 pub struct BlockExecutionArtifacts {
+    // The transactions that were added to the block, in their execution order.
+    pub executed_txs: Vec<Transaction>,
     pub execution_infos: IndexMap<TransactionHash, TransactionExecutionInfo>,
     pub commitment_state_diff: CommitmentStateDiff,
     pub visited_segments_mapping: VisitedSegmentsMapping,
     pub bouncer_weights: BouncerWeights,
+    pub l2_gas_used: GasAmount,
+    pub block_header_without_hash: BlockHeaderWithoutHash,
+    // The transactions that failed execution and were left out of the block.
+    pub rejected_txs: Vec<RejectedTransaction>,
+}
+
+/// A transaction that failed execution, with the executor's error.
+#[derive(Clone, Debug)]
+pub struct RejectedTransaction {
+    pub tx: Transaction,
+    pub error: Arc<BlockifierTransactionExecutorError>,
+}
+
+// The executor error isn't comparable, so it is compared by its message.
+impl PartialEq for RejectedTransaction {
+    fn eq(&self, other: &Self) -> bool {
+        self.tx == other.tx && self.error.to_string() == other.error.to_string()
+    }
 }
 
 /// The BlockBuilderTrait is responsible for building a new block from transactions provided by the
@@ -96,6 +138,8 @@ pub struct BlockBuilder {
     tx_provider: Box<dyn TransactionProvider>,
     output_content_sender: Option<tokio::sync::mpsc::UnboundedSender<Transaction>>,
     abort_signal_receiver: tokio::sync::oneshot::Receiver<()>,
+    // The header fields of the block that are known before it is built, e.g., its gas prices.
+    block_header_without_hash: BlockHeaderWithoutHash,
 
     // Parameters to configure the block builder behavior.
     tx_chunk_size: usize,
@@ -108,6 +152,7 @@ impl BlockBuilder {
         tx_provider: Box<dyn TransactionProvider>,
         output_content_sender: Option<tokio::sync::mpsc::UnboundedSender<Transaction>>,
         abort_signal_receiver: tokio::sync::oneshot::Receiver<()>,
+        block_header_without_hash: BlockHeaderWithoutHash,
         tx_chunk_size: usize,
         execution_params: BlockBuilderExecutionParams,
     ) -> Self {
@@ -116,6 +161,7 @@ impl BlockBuilder {
             tx_provider,
             output_content_sender,
             abort_signal_receiver,
+            block_header_without_hash,
             tx_chunk_size,
             execution_params,
         }
@@ -126,8 +172,11 @@ impl BlockBuilder {
 impl BlockBuilderTrait for BlockBuilder {
     async fn build_block(&mut self) -> BlockBuilderResult<BlockExecutionArtifacts> {
         let mut block_is_full = false;
+        let mut executed_txs = Vec::new();
         let mut execution_infos = IndexMap::new();
-        // TODO(yael 6/10/2024): delete the timeout condition once the executor has a timeout
+        let mut rejected_txs = Vec::new();
+        // The executor only checks the deadline while executing transactions; this check stops the
+        // block builder while it waits for transactions.
         while !block_is_full {
             if tokio::time::Instant::now() >= self.execution_params.deadline {
                 info!("Block builder deadline reached.");
@@ -146,9 +195,8 @@ impl BlockBuilderTrait for BlockBuilder {
                 NextTxs::End => break,
             };
             debug!("Got {} transactions from the transaction provider.", next_tx_chunk.len());
+            // The provider returns an empty chunk only after waiting for new transactions.
             if next_tx_chunk.is_empty() {
-                // TODO: Consider what is the best sleep duration.
-                tokio::time::sleep(tokio::time::Duration::from_secs(1)).await;
                 continue;
             }
 
@@ -162,7 +210,9 @@ impl BlockBuilderTrait for BlockBuilder {
             block_is_full = collect_execution_results_and_stream_txs(
                 next_tx_chunk,
                 results,
+                &mut executed_txs,
                 &mut execution_infos,
+                &mut rejected_txs,
                 &self.output_content_sender,
                 self.execution_params.fail_on_err,
             )
@@ -170,20 +220,29 @@ impl BlockBuilderTrait for BlockBuilder {
         }
         let (commitment_state_diff, visited_segments_mapping, bouncer_weights) =
             self.executor.lock().await.close_block()?;
+        let l2_gas_used =
+            GasAmount(execution_infos.values().map(|info| info.receipt.gas.l2_gas.0).sum());
         Ok(BlockExecutionArtifacts {
+            executed_txs,
             execution_infos,
             commitment_state_diff,
             visited_segments_mapping,
             bouncer_weights,
+            l2_gas_used,
+            block_header_without_hash: self.block_header_without_hash.clone(),
+            rejected_txs,
         })
     }
 }
 
-/// Returns true if the block is full and should be closed, false otherwise.
+/// Returns true if the block is full or its deadline was reached and it should be closed, false
+/// otherwise.
 async fn collect_execution_results_and_stream_txs(
     tx_chunk: Vec<Transaction>,
     results: Vec<TransactionExecutorResult<TransactionExecutionInfo>>,
+    executed_txs: &mut Vec<Transaction>,
     execution_infos: &mut IndexMap<TransactionHash, TransactionExecutionInfo>,
+    rejected_txs: &mut Vec<RejectedTransaction>,
     output_content_sender: &Option<tokio::sync::mpsc::UnboundedSender<Transaction>>,
     fail_on_err: bool,
 ) -> BlockBuilderResult<bool> {
@@ -192,11 +251,10 @@ async fn collect_execution_results_and_stream_txs(
             Ok(tx_execution_info) => {
                 execution_infos.insert(input_tx.tx_hash(), tx_execution_info);
                 if let Some(output_content_sender) = output_content_sender {
-                    output_content_sender.send(input_tx)?;
+                    output_content_sender.send(input_tx.clone())?;
                 }
+                executed_txs.push(input_tx);
             }
-            // TODO(yael 18/9/2024): add timeout error handling here once this
-            // feature is added.
             Err(BlockifierTransactionExecutorError::BlockFull) => {
                 info!("Block is full");
                 if fail_on_err {
@@ -204,6 +262,14 @@ async fn collect_execution_results_and_stream_txs(
                 }
                 return Ok(true);
             }
+            // The transaction was not added to the block, but may fit in the next one.
+            Err(BlockifierTransactionExecutorError::Timeout) => {
+                info!("Block builder deadline reached during transaction execution.");
+                if fail_on_err {
+                    return Err(BlockBuilderError::FailOnError(FailOnErrorCause::DeadlineReached));
+                }
+                return Ok(true);
+            }
             Err(err) => {
                 debug!("Transaction {:?} failed with error: {}.", input_tx, err);
                 if fail_on_err {
@@ -211,6 +277,7 @@ async fn collect_execution_results_and_stream_txs(
                         FailOnErrorCause::TransactionFailed(err),
                     ));
                 }
+                rejected_txs.push(RejectedTransaction { tx: input_tx, error: Arc::new(err) });
             }
         }
     }
@@ -235,7 +302,7 @@ pub trait BlockBuilderFactoryTrait {
     ) -> BlockBuilderResult<Box<dyn BlockBuilderTrait>>;
 }
 
-#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize, Validate)]
 pub struct BlockBuilderConfig {
     // TODO(Yael 1/10/2024): add to config pointers
     pub chain_info: ChainInfo,
@@ -245,6 +312,8 @@ pub struct BlockBuilderConfig {
     pub use_kzg_da: bool,
     pub tx_chunk_size: usize,
     pub versioned_constants_overrides: VersionedConstantsOverrides,
+    #[validate]
+    pub fee_market_config: FeeMarketConfig,
 }
 
 impl Default for BlockBuilderConfig {
@@ -258,10 +327,21 @@ impl Default for BlockBuilderConfig {
             use_kzg_da: true,
             tx_chunk_size: 100,
             versioned_constants_overrides: VersionedConstantsOverrides::default(),
+            fee_market_config: FeeMarketConfig::default(),
         }
     }
 }
 
+impl BlockBuilderConfig {
+    /// Returns the maximal L2 gas usage of a block, i.e., the L2 gas cost of the bouncer's step
+    /// capacity.
+    pub fn max_block_l2_gas(&self, versioned_constants: &VersionedConstants) -> u64 {
+        let max_n_steps =
+            u64::try_from(self.bouncer_config.block_max_capacity.n_steps).unwrap_or(u64::MAX);
+        max_n_steps.saturating_mul(versioned_constants.os_constants.gas_costs.step_gas_cost)
+    }
+}
+
 impl SerializeConfig for BlockBuilderConfig {
     fn dump(&self) -> BTreeMap<ParamPath, SerializedParam> {
         let mut dump = append_sub_config_name(self.chain_info.dump(), "chain_info");
@@ -289,6 +369,10 @@ impl SerializeConfig for BlockBuilderConfig {
             self.versioned_constants_overrides.dump(),
             "versioned_constants_overrides",
         ));
+        dump.append(&mut append_sub_config_name(
+            self.fee_market_config.dump(),
+            "fee_market_config",
+        ));
         dump
     }
 }
@@ -297,30 +381,23 @@ pub struct BlockBuilderFactory {
     pub block_builder_config: BlockBuilderConfig,
     pub storage_reader: StorageReader,
     pub global_class_hash_to_class: GlobalContractCache<RunnableContractClass>,
+    pub l1_gas_price_oracle: Arc<dyn L1GasPriceOracle>,
+    // Notified of each transaction added to a block as it is built, e.g., to stream its trace.
+    pub commit_observer: Option<Arc<dyn TransactionCommitObserver>>,
 }
 
 impl BlockBuilderFactory {
-    fn preprocess_and_create_transaction_executor(
+    pub(crate) fn preprocess_and_create_transaction_executor(
         &self,
         block_metadata: &BlockMetadata,
+        block_info: BlockInfo,
     ) -> BlockBuilderResult<TransactionExecutor<PapyrusReader>> {
         let block_builder_config = self.block_builder_config.clone();
-        let next_block_info = BlockInfo {
-            block_number: block_metadata.height,
-            block_timestamp: BlockTimestamp(chrono::Utc::now().timestamp().try_into()?),
-            sequencer_address: block_builder_config.sequencer_address,
-            // TODO (yael 7/10/2024): add logic to compute gas prices
-            gas_prices: {
-                let tmp_val = NonzeroGasPrice::MIN;
-                GasPrices::new(tmp_val, tmp_val, tmp_val, tmp_val, tmp_val, tmp_val)
-            },
-            use_kzg_da: block_builder_config.use_kzg_da,
-        };
         let versioned_constants = VersionedConstants::get_versioned_constants(
             block_builder_config.versioned_constants_overrides,
         );
         let block_context = BlockContext::new(
-            next_block_info,
+            block_info,
             block_builder_config.chain_info,
             versioned_constants,
             block_builder_config.bouncer_config,
@@ -341,6 +418,79 @@ impl BlockBuilderFactory {
 
         Ok(executor)
     }
+
+    fn next_block_info(&self, height: BlockNumber) -> BlockBuilderResult<BlockInfo> {
+        let versioned_constants = VersionedConstants::get_versioned_constants(
+            self.block_builder_config.versioned_constants_overrides.clone(),
+        );
+        let l2_gas_price = self.next_l2_gas_price(height, &versioned_constants)?;
+        Ok(BlockInfo {
+            block_number: height,
+            block_timestamp: BlockTimestamp(chrono::Utc::now().timestamp().try_into()?),
+            sequencer_address: self.block_builder_config.sequencer_address,
+            gas_prices: self.gas_prices(height, l2_gas_price, &versioned_constants)?,
+            use_kzg_da: self.block_builder_config.use_kzg_da,
+        })
+    }
+
+    fn gas_prices(
+        &self,
+        height: BlockNumber,
+        strk_l2_gas_price: GasPrice,
+        versioned_constants: &VersionedConstants,
+    ) -> BlockBuilderResult<GasPrices> {
+        let l1_gas_prices = self.l1_gas_price_oracle.l1_gas_prices(height)?;
+        // Only the STRK L2 gas price is set by the fee market; the ETH one follows the L1 gas
+        // price.
+        let eth_l2_gas_price =
+            versioned_constants.convert_l1_to_l2_gas_price_round_up(l1_gas_prices.eth_l1_gas_price);
+
+        Ok(GasPrices::new(
+            NonzeroGasPrice::new(l1_gas_prices.eth_l1_gas_price)?,
+            NonzeroGasPrice::new(l1_gas_prices.strk_l1_gas_price)?,
+            NonzeroGasPrice::new(l1_gas_prices.eth_l1_data_gas_price)?,
+            NonzeroGasPrice::new(l1_gas_prices.strk_l1_data_gas_price)?,
+            NonzeroGasPrice::new(eth_l2_gas_price)?,
+            NonzeroGasPrice::new(strk_l2_gas_price)?,
+        ))
+    }
+
+    /// Derives the base L2 gas price of the block at the given height, in fri, from the L2 gas
+    /// used and the price of the previous block.
+    /// Falls back to the minimal price if the previous block was not built by the batcher, e.g.,
+    /// at genesis.
+    fn next_l2_gas_price(
+        &self,
+        height: BlockNumber,
+        versioned_constants: &VersionedConstants,
+    ) -> BlockBuilderResult<GasPrice> {
+        let fee_market_config = &self.block_builder_config.fee_market_config;
+        let min_gas_price = GasPrice(fee_market_config.min_gas_price.into());
+        let Some(prev_height) = height.prev() else {
+            return Ok(min_gas_price);
+        };
+        let Some(L2GasInfo { l2_gas_used, l2_gas_price }) =
+            self.storage_reader.begin_ro_txn()?.get_l2_gas_info(prev_height)?
+        else {
+            debug!("No L2 gas data for block {prev_height}, using the minimal L2 gas price.");
+            return Ok(min_gas_price);
+        };
+
+        let l2_gas_price =
+            u64::try_from(l2_gas_price.0).expect("L2 gas prices are computed as u64.");
+        // The minimal price may have been raised since the previous block was built.
+        let l2_gas_price = l2_gas_price.max(fee_market_config.min_gas_price);
+        // The bouncer limits blocks by steps rather than by L2 gas, so the L2 gas usage of a block
+        // may exceed the capacity derived from them; bounding it keeps the price change within the
+        // fee market's limits.
+        let max_block_size = self.block_builder_config.max_block_l2_gas(versioned_constants);
+        let l2_gas_used = l2_gas_used.0.min(max_block_size);
+        let gas_target = fee_market_config.gas_target(max_block_size);
+        Ok(GasPrice(
+            calculate_next_base_gas_price(l2_gas_price, l2_gas_used, gas_target, fee_market_config)
+                .into(),
+        ))
+    }
 }
 
 impl BlockBuilderFactoryTrait for BlockBuilderFactory {
@@ -352,12 +502,18 @@ impl BlockBuilderFactoryTrait for BlockBuilderFactory {
         output_content_sender: Option<tokio::sync::mpsc::UnboundedSender<Transaction>>,
         abort_signal_receiver: tokio::sync::oneshot::Receiver<()>,
     ) -> BlockBuilderResult<Box<dyn BlockBuilderTrait>> {
-        let executor = self.preprocess_and_create_transaction_executor(&block_metadata)?;
+        let block_info = self.next_block_info(block_metadata.height)?;
+        let block_header_without_hash = block_header_without_hash(&block_info);
+        let mut executor =
+            self.preprocess_and_create_transaction_executor(&block_metadata, block_info.clone())?;
+        executor.set_deadline(Some(execution_params.deadline.into_std()));
+        executor.set_commit_observer(self.commit_observer.clone());
         Ok(Box::new(BlockBuilder::new(
             Box::new(executor),
             tx_provider,
             output_content_sender,
             abort_signal_receiver,
+            block_header_without_hash,
             self.block_builder_config.tx_chunk_size,
             execution_params,
         )))
@@ -1,15 +1,24 @@
+use std::sync::Arc;
+
 use assert_matches::assert_matches;
 use blockifier::blockifier::transaction_executor::TransactionExecutorError;
-use blockifier::bouncer::BouncerWeights;
+use blockifier::bouncer::{BouncerConfig, BouncerWeights};
 use blockifier::fee::fee_checks::FeeCheckError;
+use blockifier::fee::receipt::TransactionReceipt;
 use blockifier::state::errors::StateError;
+use blockifier::state::global_cache::GlobalContractCache;
 use blockifier::transaction::objects::{RevertError, TransactionExecutionInfo};
 use blockifier::transaction::transaction_execution::Transaction as BlockifierTransaction;
+use blockifier::versioned_constants::VersionedConstants;
 use indexmap::{indexmap, IndexMap};
 use mockall::predicate::eq;
 use mockall::Sequence;
+use papyrus_storage::l2_gas::{L2GasInfo, L2GasStorageWriter};
+use papyrus_storage::test_utils::get_test_storage;
 use rstest::rstest;
+use starknet_api::block::{BlockHeaderWithoutHash, BlockNumber, GasPrice, GasPricePerToken};
 use starknet_api::executable_transaction::Transaction;
+use starknet_api::execution_resources::{GasAmount, GasVector};
 use starknet_api::felt;
 use starknet_api::transaction::fields::Fee;
 use starknet_api::transaction::TransactionHash;
@@ -17,14 +26,19 @@ use tokio::sync::mpsc::{UnboundedReceiver, UnboundedSender};
 
 use crate::block_builder::{
     BlockBuilder,
+    BlockBuilderConfig,
     BlockBuilderError,
     BlockBuilderExecutionParams,
+    BlockBuilderFactory,
     BlockBuilderResult,
     BlockBuilderTrait,
     BlockExecutionArtifacts,
     FailOnErrorCause,
+    RejectedTransaction,
 };
-use crate::test_utils::test_txs;
+use crate::fee_market::{calculate_next_base_gas_price, FeeMarketConfig};
+use crate::l1_gas_price_oracle::{FixedL1GasPriceOracle, L1GasPrices};
+use crate::test_utils::{test_tx, test_txs};
 use crate::transaction_executor::MockTransactionExecutorTrait;
 use crate::transaction_provider::{MockTransactionProvider, NextTxs};
 
@@ -32,6 +46,10 @@ const BLOCK_GENERATION_DEADLINE_SECS: u64 = 1;
 const BLOCK_GENERATION_LONG_DEADLINE_SECS: u64 = 5;
 const TX_CHANNEL_SIZE: usize = 50;
 const TX_CHUNK_SIZE: usize = 3;
+const L2_GAS_USED_PER_TX: u64 = 10;
+const MAX_BLOCK_SIZE: u64 = 1_000_000;
+const GAS_TARGET: u64 = MAX_BLOCK_SIZE * 80 / 100;
+const MIN_GAS_PRICE: u64 = 200_000;
 
 struct TestExpectations {
     mock_transaction_executor: MockTransactionExecutorTrait,
@@ -44,14 +62,30 @@ fn output_channel() -> (UnboundedSender<Transaction>, UnboundedReceiver<Transact
     tokio::sync::mpsc::unbounded_channel()
 }
 
+fn block_header_without_hash() -> BlockHeaderWithoutHash {
+    BlockHeaderWithoutHash {
+        block_number: BlockNumber(1),
+        l2_gas_price: GasPricePerToken {
+            price_in_fri: GasPrice(100000),
+            price_in_wei: GasPrice(100000),
+        },
+        ..Default::default()
+    }
+}
+
 fn block_execution_artifacts(
     execution_infos: IndexMap<TransactionHash, TransactionExecutionInfo>,
 ) -> BlockExecutionArtifacts {
+    let n_txs = u64::try_from(execution_infos.len()).unwrap();
     BlockExecutionArtifacts {
+        executed_txs: execution_infos.keys().copied().map(test_tx).collect(),
         execution_infos,
         commitment_state_diff: Default::default(),
         visited_segments_mapping: Default::default(),
         bouncer_weights: BouncerWeights { gas: 100, ..BouncerWeights::empty() },
+        l2_gas_used: GasAmount(L2_GAS_USED_PER_TX * n_txs),
+        block_header_without_hash: block_header_without_hash(),
+        rejected_txs: Vec::new(),
     }
 }
 
@@ -62,6 +96,10 @@ fn execution_info() -> TransactionExecutionInfo {
             max_fee: Fee(100),
             actual_fee: Fee(101),
         })),
+        receipt: TransactionReceipt {
+            gas: GasVector { l2_gas: GasAmount(L2_GAS_USED_PER_TX), ..Default::default() },
+            ..Default::default()
+        },
         ..Default::default()
     }
 }
@@ -176,6 +214,34 @@ fn block_full_test_expectations() -> TestExpectations {
     }
 }
 
+fn mock_transaction_executor_timeout(input_txs: &[Transaction]) -> MockTransactionExecutorTrait {
+    let input_txs_cloned = input_txs.to_vec();
+    let mut mock_transaction_executor = MockTransactionExecutorTrait::new();
+    mock_transaction_executor
+        .expect_add_txs_to_block()
+        .times(1)
+        .withf(move |blockifier_input| compare_tx_hashes(&input_txs_cloned, blockifier_input))
+        .return_once(move |_| vec![Ok(execution_info()), Err(TransactionExecutorError::Timeout)]);
+    mock_transaction_executor
+}
+
+fn execution_timeout_test_expectations() -> TestExpectations {
+    let input_txs = test_txs(0..3);
+    let mut mock_transaction_executor = mock_transaction_executor_timeout(&input_txs);
+
+    // The timed out transaction is neither added to the block nor rejected.
+    let expected_block_artifacts = set_close_block_expectations(&mut mock_transaction_executor, 1);
+
+    let mock_tx_provider = mock_tx_provider_limited_calls(1, vec![input_txs.clone()]);
+
+    TestExpectations {
+        mock_transaction_executor,
+        mock_tx_provider,
+        expected_block_artifacts,
+        expected_txs_output: vec![input_txs[0].clone()],
+    }
+}
+
 fn mock_transaction_executor_with_delay(input_txs: &[Transaction]) -> MockTransactionExecutorTrait {
     let input_txs_cloned = input_txs.to_vec();
     let mut mock_transaction_executor = MockTransactionExecutorTrait::new();
@@ -250,7 +316,15 @@ fn transaction_failed_test_expectations() -> TestExpectations {
         TransactionHash(felt!(u8::try_from(0).unwrap()))=> execution_info(),
         TransactionHash(felt!(u8::try_from(2).unwrap()))=> execution_info(),
     ];
-    let expected_block_artifacts = block_execution_artifacts(execution_infos_mapping);
+    let expected_block_artifacts = BlockExecutionArtifacts {
+        rejected_txs: vec![RejectedTransaction {
+            tx: input_txs[1].clone(),
+            error: Arc::new(TransactionExecutorError::StateError(
+                StateError::OutOfRangeContractAddress,
+            )),
+        }],
+        ..block_execution_artifacts(execution_infos_mapping)
+    };
     let expected_block_artifacts_copy = expected_block_artifacts.clone();
     mock_transaction_executor.expect_close_block().times(1).return_once(move || {
         Ok((
@@ -341,10 +415,11 @@ fn mock_tx_provider_limitless_calls(
 }
 
 fn add_limitless_empty_calls(mock_tx_provider: &mut MockTransactionProvider) {
-    mock_tx_provider
-        .expect_get_txs()
-        .with(eq(TX_CHUNK_SIZE))
-        .returning(|_n_txs| Ok(NextTxs::Txs(Vec::new())));
+    mock_tx_provider.expect_get_txs().with(eq(TX_CHUNK_SIZE)).returning(|_n_txs| {
+        // Simulate the provider waiting for new transactions.
+        std::thread::sleep(std::time::Duration::from_millis(10));
+        Ok(NextTxs::Txs(Vec::new()))
+    });
 }
 
 fn compare_tx_hashes(input: &[Transaction], blockifier_input: &[BlockifierTransaction]) -> bool {
@@ -387,6 +462,7 @@ async fn run_build_block(
         Box::new(tx_provider),
         output_sender,
         abort_receiver,
+        block_header_without_hash(),
         TX_CHUNK_SIZE,
         BlockBuilderExecutionParams { deadline, fail_on_err },
     );
@@ -400,6 +476,7 @@ async fn run_build_block(
 #[case::empty_block(empty_block_test_expectations())]
 #[case::block_full(block_full_test_expectations())]
 #[case::deadline_reached_after_first_chunk(test_expectations_with_delay())]
+#[case::deadline_reached_during_execution(execution_timeout_test_expectations())]
 #[case::stream_done(stream_done_test_expectations())]
 #[case::transaction_failed(transaction_failed_test_expectations())]
 #[tokio::test]
@@ -452,6 +529,7 @@ async fn test_validate_block() {
 #[rstest]
 #[case::block_full(test_txs(0..3), mock_transaction_executor_block_full(&input_txs), FailOnErrorCause::BlockFull)]
 #[case::deadline_reached(test_txs(0..3), mock_transaction_executor_with_delay(&input_txs), FailOnErrorCause::DeadlineReached)]
+#[case::execution_timeout(test_txs(0..3), mock_transaction_executor_timeout(&input_txs), FailOnErrorCause::DeadlineReached)]
 #[tokio::test]
 async fn test_validate_block_with_error(
     #[case] input_txs: Vec<Transaction>,
@@ -544,3 +622,73 @@ async fn test_build_block_abort_immediately() {
         Err(BlockBuilderError::Aborted)
     );
 }
+
+fn fee_market_config() -> FeeMarketConfig {
+    FeeMarketConfig {
+        min_gas_price: MIN_GAS_PRICE,
+        gas_target_percentage: 80,
+        ..Default::default()
+    }
+}
+
+#[rstest]
+#[case::genesis(BlockNumber(0), None, GasPrice(MIN_GAS_PRICE.into()))]
+#[case::previous_block_not_built_by_batcher(BlockNumber(1), None, GasPrice(MIN_GAS_PRICE.into()))]
+#[case::congested_previous_block(
+    BlockNumber(1),
+    Some(L2GasInfo { l2_gas_used: GasAmount(MAX_BLOCK_SIZE), l2_gas_price: GasPrice(1_000_000) }),
+    GasPrice(calculate_next_base_gas_price(1_000_000, MAX_BLOCK_SIZE, GAS_TARGET, &fee_market_config()).into())
+)]
+#[case::gas_used_above_capacity(
+    BlockNumber(1),
+    Some(L2GasInfo { l2_gas_used: GasAmount(MAX_BLOCK_SIZE * 2), l2_gas_price: GasPrice(1_000_000) }),
+    GasPrice(calculate_next_base_gas_price(1_000_000, MAX_BLOCK_SIZE, GAS_TARGET, &fee_market_config()).into())
+)]
+#[case::previous_price_below_min(
+    BlockNumber(1),
+    Some(L2GasInfo { l2_gas_used: GasAmount(GAS_TARGET), l2_gas_price: GasPrice(1) }),
+    GasPrice(MIN_GAS_PRICE.into())
+)]
+fn next_l2_gas_price(
+    #[case] height: BlockNumber,
+    #[case] prev_l2_gas_info: Option<L2GasInfo>,
+    #[case] expected_l2_gas_price: GasPrice,
+) {
+    let ((storage_reader, mut storage_writer), _temp_dir) = get_test_storage();
+    if let Some(l2_gas_info) = prev_l2_gas_info {
+        storage_writer
+            .begin_rw_txn()
+            .unwrap()
+            .set_l2_gas_info(height.prev().unwrap(), &l2_gas_info)
+            .unwrap()
+            .commit()
+            .unwrap();
+    }
+    let versioned_constants = VersionedConstants::latest_constants();
+    // A step capacity whose L2 gas cost is the maximal block size.
+    let max_n_steps = MAX_BLOCK_SIZE / versioned_constants.os_constants.gas_costs.step_gas_cost;
+    let bouncer_config = BouncerConfig {
+        block_max_capacity: BouncerWeights {
+            n_steps: max_n_steps.try_into().unwrap(),
+            ..Default::default()
+        },
+    };
+    let block_builder_config = BlockBuilderConfig {
+        bouncer_config,
+        fee_market_config: fee_market_config(),
+        ..Default::default()
+    };
+    assert_eq!(block_builder_config.max_block_l2_gas(versioned_constants), MAX_BLOCK_SIZE);
+    let block_builder_factory = BlockBuilderFactory {
+        block_builder_config,
+        storage_reader,
+        global_class_hash_to_class: GlobalContractCache::new(1),
+        l1_gas_price_oracle: Arc::new(FixedL1GasPriceOracle::new(L1GasPrices::default())),
+        commit_observer: None,
+    };
+
+    assert_eq!(
+        block_builder_factory.next_l2_gas_price(height, versioned_constants).unwrap(),
+        expected_l2_gas_price
+    );
+}
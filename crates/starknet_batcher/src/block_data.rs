@@ -0,0 +1,355 @@
+use std::collections::HashMap;
+
+use blockifier::blockifier::block::BlockInfo;
+use blockifier::execution::call_info::CallInfo;
+use blockifier::fee::receipt::TransactionReceipt;
+use blockifier::transaction::objects::{FeeType, TransactionExecutionInfo};
+use blockifier::utils::u64_from_usize;
+use cairo_lang_starknet_classes::casm_contract_class::CasmContractClass;
+use cairo_vm::types::builtin_name::BuiltinName;
+use indexmap::IndexMap;
+use starknet_api::block::{
+    BlockBody,
+    BlockHash,
+    BlockHeader,
+    BlockHeaderWithoutHash,
+    GasPricePerToken,
+    GasPriceVector,
+    NonzeroGasPrice,
+    StarknetVersion,
+};
+use starknet_api::block_hash::block_hash_calculator::{
+    calculate_block_commitments,
+    calculate_block_hash,
+    BlockHeaderCommitments,
+    TransactionHashingData,
+    TransactionOutputForHash,
+};
+use starknet_api::contract_class::ContractClass;
+use starknet_api::core::{ClassHash, CompiledClassHash, GlobalRoot, SequencerContractAddress};
+use starknet_api::data_availability::L1DataAvailabilityMode;
+use starknet_api::deprecated_contract_class::ContractClass as DeprecatedContractClass;
+use starknet_api::executable_transaction::{AccountTransaction, Transaction};
+use starknet_api::execution_resources::{Builtin, ExecutionResources};
+use starknet_api::state::{SierraContractClass, ThinStateDiff};
+use starknet_api::transaction::fields::TransactionSignature;
+use starknet_api::transaction::{
+    DeclareTransactionOutput,
+    DeployAccountTransactionOutput,
+    Event,
+    InvokeTransactionOutput,
+    L1HandlerTransactionOutput,
+    MessageToL1,
+    RevertedTransactionExecutionStatus,
+    TransactionExecutionStatus,
+    TransactionHash,
+    TransactionOutput,
+};
+
+/// The data of a block built by the batcher, written to storage once the block is decided.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct BlockData {
+    /// The header of the block. The parent hash and the state root are only set when the block
+    /// is committed.
+    pub header_without_hash: BlockHeaderWithoutHash,
+    /// The global state root after the block, if it was computed. The header of the block commits
+    /// to it, so the header is only written once it is known.
+    pub state_root: Option<GlobalRoot>,
+    pub commitments: BlockHeaderCommitments,
+    pub state_diff_length: usize,
+    pub body: BlockBody,
+    /// The Cairo 0 classes declared in the block.
+    pub deprecated_classes: Vec<(ClassHash, DeprecatedContractClass)>,
+    /// The Sierra classes declared in the block whose Sierra is known, in the order of the state
+    /// diff.
+    pub classes: Vec<(ClassHash, SierraContractClass)>,
+    /// The compiled classes declared in the block, in the order of the state diff.
+    pub casms: Vec<(ClassHash, CasmContractClass)>,
+}
+
+impl BlockData {
+    /// Assembles the block data from the transactions executed in the block, in their execution
+    /// order, and their execution infos.
+    pub fn new(
+        executed_txs: Vec<Transaction>,
+        execution_infos: &IndexMap<TransactionHash, TransactionExecutionInfo>,
+        state_diff: &ThinStateDiff,
+        header_without_hash: BlockHeaderWithoutHash,
+    ) -> Self {
+        let mut body = BlockBody::default();
+        let mut transactions_data = Vec::with_capacity(executed_txs.len());
+        let mut deprecated_classes = Vec::new();
+        let mut classes = Vec::new();
+        let mut casms = Vec::new();
+        for tx in executed_txs {
+            let tx_hash = tx.tx_hash();
+            let execution_info = execution_infos
+                .get(&tx_hash)
+                .expect("Executed transactions should have an execution info.");
+            let transaction_output = transaction_output(&tx, execution_info);
+            transactions_data.push(TransactionHashingData {
+                transaction_signature: transaction_signature(&tx),
+                transaction_output: TransactionOutputForHash {
+                    actual_fee: transaction_output.actual_fee(),
+                    events: transaction_output.events().to_vec(),
+                    execution_status: transaction_output.execution_status().clone(),
+                    gas_consumed: execution_info.receipt.gas,
+                    messages_sent: transaction_output.messages_sent().clone(),
+                },
+                transaction_hash: tx_hash,
+            });
+            if let Transaction::Account(AccountTransaction::Declare(declare_tx)) = &tx {
+                match &declare_tx.class_info.contract_class {
+                    ContractClass::V0(class) => {
+                        deprecated_classes.push((declare_tx.class_hash(), class.clone()))
+                    }
+                    ContractClass::V1(casm) => {
+                        if let Some(class) = &declare_tx.class_info.sierra_contract_class {
+                            classes.push((declare_tx.class_hash(), class.clone()));
+                        }
+                        casms.push((declare_tx.class_hash(), casm.clone()))
+                    }
+                }
+            }
+            body.transactions.push(tx.into());
+            body.transaction_outputs.push(transaction_output);
+            body.transaction_hashes.push(tx_hash);
+        }
+
+        let commitments = calculate_block_commitments(
+            &transactions_data,
+            state_diff,
+            header_without_hash.l1_da_mode,
+            &header_without_hash.starknet_version,
+        );
+
+        Self {
+            header_without_hash,
+            // TODO: Set the state root once the batcher computes it.
+            state_root: None,
+            commitments,
+            state_diff_length: state_diff.len(),
+            body,
+            deprecated_classes,
+            classes,
+            casms,
+        }
+    }
+
+    /// Returns the header of the block with the given state root, on top of the block with the
+    /// given hash.
+    pub fn header(&self, parent_hash: BlockHash, state_root: GlobalRoot) -> BlockHeader {
+        let block_header_without_hash =
+            BlockHeaderWithoutHash { parent_hash, state_root, ..self.header_without_hash.clone() };
+        let block_hash =
+            calculate_block_hash(block_header_without_hash.clone(), self.commitments.clone())
+                .expect("The block hash of the latest Starknet version should be computable.");
+
+        BlockHeader {
+            block_hash,
+            block_header_without_hash,
+            state_diff_commitment: Some(self.commitments.state_diff_commitment),
+            state_diff_length: Some(self.state_diff_length),
+            transaction_commitment: Some(self.commitments.transaction_commitment),
+            event_commitment: Some(self.commitments.event_commitment),
+            n_transactions: self.body.transactions.len(),
+            n_events: self
+                .body
+                .transaction_outputs
+                .iter()
+                .map(|output| output.events().len())
+                .sum(),
+            receipt_commitment: Some(self.commitments.receipt_commitment),
+        }
+    }
+}
+
+/// Returns the header fields of the block with the given info that are known before the block is
+/// built.
+pub fn block_header_without_hash(block_info: &BlockInfo) -> BlockHeaderWithoutHash {
+    let l1_da_mode = if block_info.use_kzg_da {
+        L1DataAvailabilityMode::Blob
+    } else {
+        L1DataAvailabilityMode::Calldata
+    };
+
+    BlockHeaderWithoutHash {
+        block_number: block_info.block_number,
+        l1_gas_price: gas_price_per_token(block_info, |prices| prices.l1_gas_price),
+        l1_data_gas_price: gas_price_per_token(block_info, |prices| prices.l1_data_gas_price),
+        l2_gas_price: gas_price_per_token(block_info, |prices| prices.l2_gas_price),
+        sequencer: SequencerContractAddress(block_info.sequencer_address),
+        timestamp: block_info.block_timestamp,
+        l1_da_mode,
+        starknet_version: StarknetVersion::LATEST,
+        ..Default::default()
+    }
+}
+
+/// Returns the classes declared by the given transactions, as they appear in a state diff.
+pub(crate) fn declared_classes(
+    txs: &[Transaction],
+) -> (IndexMap<ClassHash, CompiledClassHash>, Vec<ClassHash>) {
+    let mut declared_classes = IndexMap::new();
+    let mut deprecated_declared_classes = Vec::new();
+    for tx in txs {
+        let Transaction::Account(AccountTransaction::Declare(declare_tx)) = tx else {
+            continue;
+        };
+        match declare_tx.class_info.contract_class {
+            ContractClass::V0(_) => deprecated_declared_classes.push(declare_tx.class_hash()),
+            ContractClass::V1(_) => {
+                declared_classes
+                    .insert(declare_tx.class_hash(), declare_tx.tx.compiled_class_hash());
+            }
+        }
+    }
+
+    (declared_classes, deprecated_declared_classes)
+}
+
+fn gas_price_per_token(
+    block_info: &BlockInfo,
+    gas_price: impl Fn(&GasPriceVector) -> NonzeroGasPrice,
+) -> GasPricePerToken {
+    let gas_prices = &block_info.gas_prices;
+    GasPricePerToken {
+        price_in_fri: gas_price(gas_prices.get_gas_prices_by_fee_type(&FeeType::Strk)).into(),
+        price_in_wei: gas_price(gas_prices.get_gas_prices_by_fee_type(&FeeType::Eth)).into(),
+    }
+}
+
+fn transaction_signature(tx: &Transaction) -> TransactionSignature {
+    match tx {
+        Transaction::Account(account_tx) => account_tx.signature(),
+        Transaction::L1Handler(_) => TransactionSignature::default(),
+    }
+}
+
+pub(crate) fn transaction_output(
+    tx: &Transaction,
+    execution_info: &TransactionExecutionInfo,
+) -> TransactionOutput {
+    let actual_fee = execution_info.receipt.fee;
+    let events = execution_info.non_optional_call_infos().flat_map(call_events).collect();
+    let messages_sent = execution_info.non_optional_call_infos().flat_map(call_messages).collect();
+    let execution_status = match &execution_info.revert_error {
+        None => TransactionExecutionStatus::Succeeded,
+        Some(revert_error) => {
+            TransactionExecutionStatus::Reverted(RevertedTransactionExecutionStatus {
+                revert_reason: revert_error.to_string(),
+            })
+        }
+    };
+    let execution_resources = execution_resources(&execution_info.receipt);
+
+    match tx {
+        Transaction::Account(AccountTransaction::Declare(_)) => {
+            TransactionOutput::Declare(DeclareTransactionOutput {
+                actual_fee,
+                messages_sent,
+                events,
+                execution_status,
+                execution_resources,
+            })
+        }
+        Transaction::Account(AccountTransaction::DeployAccount(deploy_account_tx)) => {
+            TransactionOutput::DeployAccount(DeployAccountTransactionOutput {
+                actual_fee,
+                messages_sent,
+                events,
+                contract_address: deploy_account_tx.contract_address,
+                execution_status,
+                execution_resources,
+            })
+        }
+        Transaction::Account(AccountTransaction::Invoke(_)) => {
+            TransactionOutput::Invoke(InvokeTransactionOutput {
+                actual_fee,
+                messages_sent,
+                events,
+                execution_status,
+                execution_resources,
+            })
+        }
+        Transaction::L1Handler(_) => TransactionOutput::L1Handler(L1HandlerTransactionOutput {
+            actual_fee,
+            messages_sent,
+            events,
+            execution_status,
+            execution_resources,
+        }),
+    }
+}
+
+/// Returns the events emitted in the call tree, in their emission order.
+fn call_events(call_info: &CallInfo) -> Vec<Event> {
+    let mut ordered_events: Vec<_> = call_info
+        .iter()
+        .flat_map(|call_info| {
+            call_info.execution.events.iter().map(|ordered_event| {
+                let event = Event {
+                    from_address: call_info.call.storage_address,
+                    content: ordered_event.event.clone(),
+                };
+                (ordered_event.order, event)
+            })
+        })
+        .collect();
+    ordered_events.sort_by_key(|(order, _)| *order);
+    ordered_events.into_iter().map(|(_, event)| event).collect()
+}
+
+/// Returns the L2 to L1 messages sent in the call tree, in their sending order.
+fn call_messages(call_info: &CallInfo) -> Vec<MessageToL1> {
+    let mut ordered_messages: Vec<_> = call_info
+        .iter()
+        .flat_map(|call_info| {
+            call_info.execution.l2_to_l1_messages.iter().map(|ordered_message| {
+                let message = MessageToL1 {
+                    from_address: call_info.call.storage_address,
+                    to_address: ordered_message.message.to_address,
+                    payload: ordered_message.message.payload.clone(),
+                };
+                (ordered_message.order, message)
+            })
+        })
+        .collect();
+    ordered_messages.sort_by_key(|(order, _)| *order);
+    ordered_messages.into_iter().map(|(_, message)| message).collect()
+}
+
+fn execution_resources(receipt: &TransactionReceipt) -> ExecutionResources {
+    let vm_resources = &receipt.resources.computation.vm_resources;
+    let builtin_instance_counter: HashMap<_, _> = vm_resources
+        .builtin_instance_counter
+        .iter()
+        .filter(|(_, count)| **count > 0)
+        .filter_map(|(builtin_name, count)| Some((builtin(*builtin_name)?, u64_from_usize(*count))))
+        .collect();
+
+    ExecutionResources {
+        steps: u64_from_usize(vm_resources.n_steps),
+        builtin_instance_counter,
+        memory_holes: u64_from_usize(vm_resources.n_memory_holes),
+        da_gas_consumed: receipt.da_gas,
+        gas_consumed: receipt.gas,
+    }
+}
+
+fn builtin(builtin_name: BuiltinName) -> Option<Builtin> {
+    match builtin_name {
+        BuiltinName::output => None,
+        BuiltinName::range_check => Some(Builtin::RangeCheck),
+        BuiltinName::pedersen => Some(Builtin::Pedersen),
+        BuiltinName::ecdsa => Some(Builtin::Ecdsa),
+        BuiltinName::keccak => Some(Builtin::Keccak),
+        BuiltinName::bitwise => Some(Builtin::Bitwise),
+        BuiltinName::ec_op => Some(Builtin::EcOp),
+        BuiltinName::poseidon => Some(Builtin::Poseidon),
+        BuiltinName::segment_arena => Some(Builtin::SegmentArena),
+        BuiltinName::range_check96 => Some(Builtin::RangeCheck96),
+        BuiltinName::add_mod => Some(Builtin::AddMod),
+        BuiltinName::mul_mod => Some(Builtin::MulMod),
+    }
+}
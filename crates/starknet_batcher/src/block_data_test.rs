@@ -0,0 +1,278 @@
+use std::collections::HashMap;
+
+use assert_matches::assert_matches;
+use blockifier::blockifier::block::BlockInfo;
+use blockifier::execution::call_info::{
+    CallExecution,
+    CallInfo,
+    MessageToL1 as BlockifierMessageToL1,
+    OrderedEvent,
+    OrderedL2ToL1Message,
+};
+use blockifier::execution::entry_point::CallEntryPoint;
+use blockifier::fee::fee_checks::FeeCheckError;
+use blockifier::fee::receipt::TransactionReceipt;
+use blockifier::fee::resources::{ComputationResources, TransactionResources};
+use blockifier::transaction::objects::{RevertError, TransactionExecutionInfo};
+use cairo_vm::types::builtin_name::BuiltinName;
+use cairo_vm::vm::runners::cairo_runner::ExecutionResources as VmExecutionResources;
+use indexmap::IndexMap;
+use starknet_api::block::{BlockHash, BlockHeaderWithoutHash, BlockNumber};
+use starknet_api::contract_class::{ClassInfo, ContractClass};
+use starknet_api::core::{ClassHash, CompiledClassHash, EthAddress, GlobalRoot};
+use starknet_api::data_availability::L1DataAvailabilityMode;
+use starknet_api::deprecated_contract_class::ContractClass as DeprecatedContractClass;
+use starknet_api::executable_transaction::{AccountTransaction, L1HandlerTransaction, Transaction};
+use starknet_api::execution_resources::{Builtin, GasAmount, GasVector};
+use starknet_api::state::{SierraContractClass, ThinStateDiff};
+use starknet_api::test_utils::declare::executable_declare_tx;
+use starknet_api::test_utils::l1_handler::executable_l1_handler_tx;
+use starknet_api::transaction::fields::Fee;
+use starknet_api::transaction::{
+    Event,
+    EventContent,
+    EventData,
+    EventKey,
+    L2ToL1Payload,
+    MessageToL1,
+    TransactionExecutionStatus,
+    TransactionHash,
+    TransactionOutput,
+};
+use starknet_api::{contract_address, declare_tx_args, felt, l1_handler_tx_args};
+
+use crate::block_data::{block_header_without_hash, declared_classes, BlockData};
+use crate::test_utils::{test_casm, test_tx};
+
+fn ordered_event(order: usize, key: u8) -> OrderedEvent {
+    OrderedEvent {
+        order,
+        event: EventContent { keys: vec![EventKey(felt!(key))], data: EventData::default() },
+    }
+}
+
+fn event(from_address: u8, key: u8) -> Event {
+    Event {
+        from_address: contract_address!(from_address),
+        content: EventContent { keys: vec![EventKey(felt!(key))], data: EventData::default() },
+    }
+}
+
+fn call_info(
+    storage_address: u8,
+    events: Vec<OrderedEvent>,
+    l2_to_l1_messages: Vec<OrderedL2ToL1Message>,
+    inner_calls: Vec<CallInfo>,
+) -> CallInfo {
+    CallInfo {
+        call: CallEntryPoint {
+            storage_address: contract_address!(storage_address),
+            ..Default::default()
+        },
+        execution: CallExecution { events, l2_to_l1_messages, ..Default::default() },
+        inner_calls,
+        ..Default::default()
+    }
+}
+
+fn declare_tx(
+    tx_hash: u8,
+    class_hash: u8,
+    contract_class: ContractClass,
+    sierra_contract_class: Option<SierraContractClass>,
+) -> Transaction {
+    let class_info = ClassInfo {
+        contract_class,
+        sierra_program_length: 1,
+        abi_length: 0,
+        sierra_contract_class,
+    };
+    Transaction::Account(AccountTransaction::Declare(executable_declare_tx(
+        declare_tx_args!(
+            tx_hash: TransactionHash(felt!(tx_hash)),
+            class_hash: ClassHash(felt!(class_hash)),
+            compiled_class_hash: CompiledClassHash(felt!(class_hash)),
+        ),
+        class_info,
+    )))
+}
+
+fn l1_handler_tx(tx_hash: u8) -> Transaction {
+    let tx: L1HandlerTransaction =
+        executable_l1_handler_tx(l1_handler_tx_args!(tx_hash: TransactionHash(felt!(tx_hash))));
+    Transaction::L1Handler(tx)
+}
+
+fn block_data(
+    executed_txs: Vec<Transaction>,
+    execution_infos: Vec<TransactionExecutionInfo>,
+) -> BlockData {
+    let execution_infos: IndexMap<_, _> =
+        executed_txs.iter().map(Transaction::tx_hash).zip(execution_infos).collect();
+    let (declared_classes, deprecated_declared_classes) = declared_classes(&executed_txs);
+    let state_diff =
+        ThinStateDiff { declared_classes, deprecated_declared_classes, ..Default::default() };
+    BlockData::new(
+        executed_txs,
+        &execution_infos,
+        &state_diff,
+        BlockHeaderWithoutHash { block_number: BlockNumber(1), ..Default::default() },
+    )
+}
+
+#[test]
+fn transaction_outputs() {
+    // The events and messages are ordered across the call tree, and taken from all the
+    // transaction's calls.
+    let execute_call_info = call_info(
+        1,
+        vec![ordered_event(2, 3)],
+        vec![OrderedL2ToL1Message {
+            order: 0,
+            message: BlockifierMessageToL1 {
+                to_address: EthAddress::default(),
+                payload: L2ToL1Payload(vec![felt!(4_u8)]),
+            },
+        }],
+        vec![call_info(2, vec![ordered_event(0, 1), ordered_event(1, 2)], vec![], vec![])],
+    );
+    let fee_transfer_call_info = call_info(3, vec![ordered_event(0, 4)], vec![], vec![]);
+    let vm_resources = VmExecutionResources {
+        n_steps: 10,
+        n_memory_holes: 2,
+        builtin_instance_counter: HashMap::from([
+            (BuiltinName::output, 1),
+            (BuiltinName::pedersen, 0),
+            (BuiltinName::range_check, 3),
+        ]),
+    };
+    let invoke_execution_info = TransactionExecutionInfo {
+        execute_call_info: Some(execute_call_info),
+        fee_transfer_call_info: Some(fee_transfer_call_info),
+        receipt: TransactionReceipt {
+            fee: Fee(5),
+            gas: GasVector { l2_gas: GasAmount(6), ..Default::default() },
+            resources: TransactionResources {
+                computation: ComputationResources { vm_resources, ..Default::default() },
+                ..Default::default()
+            },
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+    let reverted_execution_info = TransactionExecutionInfo {
+        revert_error: Some(RevertError::PostExecution(FeeCheckError::MaxFeeExceeded {
+            max_fee: Fee(100),
+            actual_fee: Fee(101),
+        })),
+        ..Default::default()
+    };
+
+    let block_data = block_data(
+        vec![test_tx(TransactionHash(felt!(1_u8))), l1_handler_tx(2)],
+        vec![invoke_execution_info, reverted_execution_info],
+    );
+
+    assert_eq!(
+        block_data.body.transaction_hashes,
+        vec![TransactionHash(felt!(1_u8)), TransactionHash(felt!(2_u8))]
+    );
+    let [TransactionOutput::Invoke(invoke_output), TransactionOutput::L1Handler(l1_handler_output)] =
+        block_data.body.transaction_outputs.as_slice()
+    else {
+        panic!("Unexpected transaction outputs: {:?}.", block_data.body.transaction_outputs);
+    };
+    assert_eq!(invoke_output.actual_fee, Fee(5));
+    assert_eq!(invoke_output.events, vec![event(2, 1), event(2, 2), event(1, 3), event(3, 4)]);
+    assert_eq!(
+        invoke_output.messages_sent,
+        vec![MessageToL1 {
+            from_address: contract_address!(1_u8),
+            to_address: EthAddress::default(),
+            payload: L2ToL1Payload(vec![felt!(4_u8)]),
+        }]
+    );
+    assert_eq!(invoke_output.execution_status, TransactionExecutionStatus::Succeeded);
+    assert_eq!(invoke_output.execution_resources.steps, 10);
+    assert_eq!(invoke_output.execution_resources.memory_holes, 2);
+    assert_eq!(
+        invoke_output.execution_resources.builtin_instance_counter,
+        HashMap::from([(Builtin::RangeCheck, 3)])
+    );
+    assert_eq!(invoke_output.execution_resources.gas_consumed.l2_gas, GasAmount(6));
+    assert_matches!(l1_handler_output.execution_status, TransactionExecutionStatus::Reverted(_));
+}
+
+#[test]
+fn declared_classes_in_state_diff_order() {
+    let deprecated_class = DeprecatedContractClass::default();
+    let class = SierraContractClass { abi: "abi".to_string(), ..Default::default() };
+    let casm = test_casm();
+    let block_data = block_data(
+        vec![
+            declare_tx(1, 10, ContractClass::V1(casm.clone()), Some(class.clone())),
+            declare_tx(2, 20, ContractClass::V0(deprecated_class.clone()), None),
+            declare_tx(3, 30, ContractClass::V1(casm.clone()), Some(class.clone())),
+        ],
+        (0..3).map(|_| TransactionExecutionInfo::default()).collect(),
+    );
+
+    assert_eq!(block_data.deprecated_classes, vec![(ClassHash(felt!(20_u8)), deprecated_class)]);
+    assert_eq!(
+        block_data.classes,
+        vec![(ClassHash(felt!(10_u8)), class.clone()), (ClassHash(felt!(30_u8)), class)]
+    );
+    assert_eq!(
+        block_data.casms,
+        vec![(ClassHash(felt!(10_u8)), casm.clone()), (ClassHash(felt!(30_u8)), casm)]
+    );
+    assert_eq!(block_data.state_diff_length, 3);
+}
+
+#[test]
+fn declared_class_without_sierra() {
+    let block_data = block_data(
+        vec![declare_tx(1, 10, ContractClass::V1(test_casm()), None)],
+        vec![TransactionExecutionInfo::default()],
+    );
+
+    assert!(block_data.classes.is_empty());
+    assert_eq!(block_data.casms.len(), 1);
+}
+
+#[test]
+fn header() {
+    let block_data = block_data(
+        vec![test_tx(TransactionHash(felt!(1_u8)))],
+        vec![TransactionExecutionInfo::default()],
+    );
+
+    // The state root isn't computed by the batcher yet.
+    assert_eq!(block_data.state_root, None);
+
+    let state_root = GlobalRoot(felt!(3_u8));
+    let header = block_data.header(BlockHash(felt!(1_u8)), state_root);
+    assert_eq!(header.block_header_without_hash.parent_hash, BlockHash(felt!(1_u8)));
+    assert_eq!(header.block_header_without_hash.state_root, state_root);
+    assert_eq!(header.block_header_without_hash.block_number, BlockNumber(1));
+    assert_eq!(header.n_transactions, 1);
+    assert_eq!(header.transaction_commitment, Some(block_data.commitments.transaction_commitment));
+    // The block hash commits to the parent block and to the state root.
+    assert_ne!(header.block_hash, block_data.header(BlockHash(felt!(2_u8)), state_root).block_hash);
+    assert_ne!(
+        header.block_hash,
+        block_data.header(BlockHash(felt!(1_u8)), GlobalRoot(felt!(4_u8))).block_hash
+    );
+}
+
+#[test]
+fn header_from_block_info() {
+    let block_info = BlockInfo { use_kzg_da: true, ..BlockInfo::create_for_testing() };
+
+    let header = block_header_without_hash(&block_info);
+    assert_eq!(header.block_number, block_info.block_number);
+    assert_eq!(header.timestamp, block_info.block_timestamp);
+    assert_eq!(header.sequencer.0, block_info.sequencer_address);
+    assert_eq!(header.l1_da_mode, L1DataAvailabilityMode::Blob);
+    assert_eq!(header.parent_hash, BlockHash::default());
+}
@@ -6,6 +6,7 @@ use serde::{Deserialize, Serialize};
 use validator::Validate;
 
 use crate::block_builder::BlockBuilderConfig;
+use crate::l1_gas_price_oracle::L1GasPrices;
 
 /// The batcher related configuration.
 #[derive(Clone, Debug, Serialize, Deserialize, Validate, PartialEq)]
@@ -13,9 +14,11 @@ pub struct BatcherConfig {
     pub storage: papyrus_storage::StorageConfig,
     pub outstream_content_buffer_size: usize,
     pub input_stream_content_buffer_size: usize,
+    #[validate]
     pub block_builder_config: BlockBuilderConfig,
     pub global_contract_cache_size: usize,
     pub max_l1_handler_txs_per_block_proposal: usize,
+    pub l1_gas_prices: L1GasPrices,
 }
 
 impl SerializeConfig for BatcherConfig {
@@ -54,6 +57,7 @@ impl SerializeConfig for BatcherConfig {
             self.block_builder_config.dump(),
             "block_builder_config",
         ));
+        dump.append(&mut append_sub_config_name(self.l1_gas_prices.dump(), "l1_gas_prices"));
         dump
     }
 }
@@ -77,6 +81,7 @@ impl Default for BatcherConfig {
             block_builder_config: BlockBuilderConfig::default(),
             global_contract_cache_size: 400,
             max_l1_handler_txs_per_block_proposal: 3,
+            l1_gas_prices: L1GasPrices::default(),
         }
     }
 }
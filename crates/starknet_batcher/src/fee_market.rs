@@ -1,37 +1,110 @@
 use std::cmp::max;
+use std::collections::BTreeMap;
+
+use papyrus_config::dumping::{ser_param, SerializeConfig};
+use papyrus_config::{ParamPath, ParamPrivacyInput, SerializedParam};
+use serde::{Deserialize, Serialize};
+use validator::{Validate, ValidationError};
 
 #[cfg(test)]
 #[path = "fee_market_test.rs"]
 pub mod fee_market_test;
 
-//  This constant is used to calculate the base gas price for the next block according to EIP-1559
-// and serves as a sensitivity parameter that limits the maximum rate of change of the gas price
-// between consecutive blocks.
-const GAS_PRICE_MAX_CHANGE_DENOMINATOR: u128 = 48;
-const MIN_GAS_PRICE: u64 = 100000; // In fri.
-// TODO(Mohammad): Check the exact value for maximum block size in StarkNet.
-const MAX_BLOCK_SIZE: u64 = 4000000000; // In gas units. It's equivalent to 40M gas steps, with 100 gas units per step.
+/// The fee market configuration, used to calculate the base gas price of blocks according to
+/// EIP-1559.
+#[derive(Clone, Copy, Debug, Serialize, Deserialize, Validate, PartialEq)]
+#[validate(schema(function = "validate_fee_market_config"))]
+pub struct FeeMarketConfig {
+    // Serves as a sensitivity parameter that limits the maximum rate of change of the gas price
+    // between consecutive blocks.
+    pub gas_price_max_change_denominator: u64,
+    // The minimal L2 gas price, in fri.
+    pub min_gas_price: u64,
+    // The target gas usage per block, as a percentage of the maximal block size.
+    pub gas_target_percentage: u8,
+}
+
+impl Default for FeeMarketConfig {
+    fn default() -> Self {
+        Self {
+            gas_price_max_change_denominator: 48,
+            min_gas_price: 100000,
+            // Setting the target at 50% of the max block size balances the rate of gas price
+            // changes, helping to prevent sudden spikes, particularly during increases, for a
+            // better user experience.
+            gas_target_percentage: 50,
+        }
+    }
+}
+
+impl SerializeConfig for FeeMarketConfig {
+    fn dump(&self) -> BTreeMap<ParamPath, SerializedParam> {
+        BTreeMap::from_iter([
+            ser_param(
+                "gas_price_max_change_denominator",
+                &self.gas_price_max_change_denominator,
+                "Limits the rate of change of the gas price between consecutive blocks; the price \
+                 changes by at most 1/denominator of its value per block. Must be positive.",
+                ParamPrivacyInput::Public,
+            ),
+            ser_param(
+                "min_gas_price",
+                &self.min_gas_price,
+                "The minimal L2 gas price, in fri. Must be positive.",
+                ParamPrivacyInput::Public,
+            ),
+            ser_param(
+                "gas_target_percentage",
+                &self.gas_target_percentage,
+                "The target L2 gas usage per block, as a percentage of the maximal block size, \
+                 which is derived from the bouncer's step capacity. Must be between 1 and 100.",
+                ParamPrivacyInput::Public,
+            ),
+        ])
+    }
+}
+
+impl FeeMarketConfig {
+    /// Returns the target gas usage of a block, given its maximal gas usage.
+    pub fn gas_target(&self, max_block_size: u64) -> u64 {
+        let gas_target = u128::from(max_block_size) * u128::from(self.gas_target_percentage) / 100;
+        // A zero target is avoided, as the price change is relative to it.
+        u64::try_from(gas_target).expect("A percentage of a u64 value fits in a u64.").max(1)
+    }
+}
+
+fn validate_fee_market_config(config: &FeeMarketConfig) -> Result<(), ValidationError> {
+    if config.gas_price_max_change_denominator == 0 {
+        return Err(ValidationError::new("gas_price_max_change_denominator must be positive"));
+    }
+    if config.min_gas_price == 0 {
+        return Err(ValidationError::new("min_gas_price must be positive"));
+    }
+    if !(1..=100).contains(&config.gas_target_percentage) {
+        return Err(ValidationError::new("gas_target_percentage must be between 1 and 100"));
+    }
+    Ok(())
+}
 
 /// Calculate the base gas price for the next block according to EIP-1559.
 ///
 /// # Parameters
 /// - `price`: The base fee of the current block.
 /// - `gas_used`: The total gas used in the current block.
-/// - `gas_target`: The target gas usage per block (usually half of the gas limit).
-pub fn calculate_next_base_gas_price(price: u64, gas_used: u64, gas_target: u64) -> u64 {
-    // Setting the target at 50% of the max block size balances the rate of gas price changes,
-    // helping to prevent sudden spikes, particularly during increases, for a better user
-    // experience.
-    assert_eq!(
-        gas_target,
-        MAX_BLOCK_SIZE / 2,
-        "Gas target must be 50% of max block size to balance price changes."
-    );
+/// - `gas_target`: The target gas usage per block, see [`FeeMarketConfig::gas_target`].
+/// - `config`: The fee market configuration.
+pub fn calculate_next_base_gas_price(
+    price: u64,
+    gas_used: u64,
+    gas_target: u64,
+    config: &FeeMarketConfig,
+) -> u64 {
+    assert!(gas_target > 0, "The gas target must be positive.");
     // To prevent precision loss during multiplication and division, we set a minimum gas price.
     // Additionally, a minimum gas price is established to prevent prolonged periods before the
     // price reaches a higher value.
     assert!(
-        price >= MIN_GAS_PRICE,
+        price >= config.min_gas_price,
         "The gas price must be at least the minimum to prevent precision loss during \
          multiplication and division."
     );
@@ -53,22 +126,25 @@ pub fn calculate_next_base_gas_price(price: u64, gas_used: u64, gas_target: u64)
     // Calculate the price change, maintaining precision by dividing after scaling up.
     // This avoids significant precision loss that would occur if dividing before
     // multiplication.
-    let price_change_u128 = gas_delta_cost / gas_target_u128 / GAS_PRICE_MAX_CHANGE_DENOMINATOR;
+    let price_change_u128 =
+        gas_delta_cost / gas_target_u128 / u128::from(config.gas_price_max_change_denominator);
 
     // Convert back to u64, as the price change should fit within the u64 range.
-    // Since the target is half the maximum block size (which fits within a u64), the gas delta
-    // is bounded by half the maximum block size. Therefore, after dividing by the gas target
-    // (which is half the maximum block size), the result is guaranteed to fit within a u64.
-    let price_change = u64::try_from(price_change_u128)
-        .expect("Result fits u64 after division of a bounded gas delta");
+    // When the gas used is at most twice the gas target, the gas delta is bounded by the gas
+    // target, so the price change is bounded by the price. Otherwise, with a low gas target, the
+    // price change might exceed u64, in which case it saturates.
+    let price_change = u64::try_from(price_change_u128).unwrap_or(u64::MAX);
 
-    let adjusted_price =
-        if gas_used > gas_target { price + price_change } else { price - price_change };
+    let adjusted_price = if gas_used > gas_target {
+        price.saturating_add(price_change)
+    } else {
+        price - price_change
+    };
 
     assert!(
         gas_used > gas_target && adjusted_price >= price
             || gas_used <= gas_target && adjusted_price <= price
     );
 
-    max(adjusted_price, MIN_GAS_PRICE)
+    max(adjusted_price, config.min_gas_price)
 }
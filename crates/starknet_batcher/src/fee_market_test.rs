@@ -1,9 +1,10 @@
-use crate::fee_market::{
-    calculate_next_base_gas_price,
-    GAS_PRICE_MAX_CHANGE_DENOMINATOR,
-    MAX_BLOCK_SIZE,
-    MIN_GAS_PRICE,
-};
+use rstest::rstest;
+use validator::Validate;
+
+use crate::fee_market::{calculate_next_base_gas_price, FeeMarketConfig};
+
+// In gas units. It's equivalent to 40M gas steps, with 100 gas units per step.
+const MAX_BLOCK_SIZE: u64 = 4000000000;
 
 #[test]
 fn test_price_calculation_snapshot() {
@@ -13,6 +14,7 @@ fn test_price_calculation_snapshot() {
     const HIGH_CONGESTION_GAS_USED: u64 = MAX_BLOCK_SIZE * 3 / 4;
     const LOW_CONGESTION_GAS_USED: u64 = MAX_BLOCK_SIZE / 4;
     const STABLE_CONGESTION_GAS_USED: u64 = GAS_TARGET;
+    let config = FeeMarketConfig::default();
 
     // Fixed expected output values.
     let increased_price = 1000000 + 10416; // 1000000 + (1000000 * 1 / 4 * MAX_BLOCK_SIZE) / (0.5 * MAX_BLOCK_SIZE * 48);
@@ -20,15 +22,15 @@ fn test_price_calculation_snapshot() {
 
     // Assert.
     assert_eq!(
-        calculate_next_base_gas_price(INIT_PRICE, HIGH_CONGESTION_GAS_USED, GAS_TARGET),
+        calculate_next_base_gas_price(INIT_PRICE, HIGH_CONGESTION_GAS_USED, GAS_TARGET, &config),
         increased_price
     );
     assert_eq!(
-        calculate_next_base_gas_price(INIT_PRICE, LOW_CONGESTION_GAS_USED, GAS_TARGET),
+        calculate_next_base_gas_price(INIT_PRICE, LOW_CONGESTION_GAS_USED, GAS_TARGET, &config),
         decreased_price
     );
     assert_eq!(
-        calculate_next_base_gas_price(INIT_PRICE, STABLE_CONGESTION_GAS_USED, GAS_TARGET),
+        calculate_next_base_gas_price(INIT_PRICE, STABLE_CONGESTION_GAS_USED, GAS_TARGET, &config),
         INIT_PRICE
     );
 }
@@ -36,27 +38,94 @@ fn test_price_calculation_snapshot() {
 #[test]
 // This test ensures that the gas price calculation does not overflow with extreme values,
 fn test_gas_price_with_extreme_values() {
-    let price = MIN_GAS_PRICE;
+    let config = FeeMarketConfig::default();
+    let min_gas_price = config.min_gas_price;
+
+    let price = min_gas_price;
     let gas_target = MAX_BLOCK_SIZE / 2;
     let gas_used = 0;
-    assert_eq!(calculate_next_base_gas_price(price, gas_used, gas_target), MIN_GAS_PRICE);
+    assert_eq!(calculate_next_base_gas_price(price, gas_used, gas_target, &config), min_gas_price);
 
-    let price = MIN_GAS_PRICE;
+    let price = min_gas_price;
     let gas_target = MAX_BLOCK_SIZE / 2;
     let gas_used = MAX_BLOCK_SIZE;
-    assert!(calculate_next_base_gas_price(price, gas_used, gas_target) > MIN_GAS_PRICE);
+    assert!(calculate_next_base_gas_price(price, gas_used, gas_target, &config) > min_gas_price);
 
     let price = u64::MAX;
     let gas_target = MAX_BLOCK_SIZE / 2;
     let gas_used = 0;
-    calculate_next_base_gas_price(price, gas_used, gas_target); // Should not panic.
+    calculate_next_base_gas_price(price, gas_used, gas_target, &config); // Should not panic.
 
     // To avoid overflow when updating the price, the value is set below a certain threshold so that
     // the new price does not exceed u64::MAX.
     let max_u128 = u128::from(u64::MAX);
-    let price_u128 =
-        max_u128 * GAS_PRICE_MAX_CHANGE_DENOMINATOR / (GAS_PRICE_MAX_CHANGE_DENOMINATOR + 1);
+    let denominator = u128::from(config.gas_price_max_change_denominator);
+    let price_u128 = max_u128 * denominator / (denominator + 1);
     let gas_target = MAX_BLOCK_SIZE / 2;
     let gas_used = MAX_BLOCK_SIZE;
-    calculate_next_base_gas_price(u64::try_from(price_u128).unwrap(), gas_used, gas_target); // Should not panic.
+    calculate_next_base_gas_price(
+        u64::try_from(price_u128).unwrap(),
+        gas_used,
+        gas_target,
+        &config,
+    ); // Should not panic.
+
+    // With a low gas target, the price saturates instead of overflowing.
+    let price = u64::MAX;
+    let gas_target = 1;
+    let gas_used = MAX_BLOCK_SIZE;
+    assert_eq!(calculate_next_base_gas_price(price, gas_used, gas_target, &config), u64::MAX);
+}
+
+#[rstest]
+#[case::half(MAX_BLOCK_SIZE, 50, MAX_BLOCK_SIZE / 2)]
+#[case::full(MAX_BLOCK_SIZE, 100, MAX_BLOCK_SIZE)]
+#[case::small_appchain_block(2_500_000, 80, 2_000_000)]
+#[case::max_block_size(u64::MAX, 100, u64::MAX)]
+fn gas_target_from_block_size(
+    #[case] max_block_size: u64,
+    #[case] gas_target_percentage: u8,
+    #[case] expected_gas_target: u64,
+) {
+    let config = FeeMarketConfig { gas_target_percentage, ..Default::default() };
+    assert_eq!(config.gas_target(max_block_size), expected_gas_target);
+}
+
+#[test]
+fn gas_target_percentage_changes_price_direction() {
+    // The same gas usage increases the price under a low target, and decreases it under a high one.
+    let gas_used = MAX_BLOCK_SIZE / 2;
+    let price = 1_000_000;
+    let low_target_config = FeeMarketConfig { gas_target_percentage: 25, ..Default::default() };
+    let high_target_config = FeeMarketConfig { gas_target_percentage: 75, ..Default::default() };
+
+    let low_target = low_target_config.gas_target(MAX_BLOCK_SIZE);
+    assert!(calculate_next_base_gas_price(price, gas_used, low_target, &low_target_config) > price);
+    let high_target = high_target_config.gas_target(MAX_BLOCK_SIZE);
+    assert!(
+        calculate_next_base_gas_price(price, gas_used, high_target, &high_target_config) < price
+    );
+}
+
+#[rstest]
+#[case::default(FeeMarketConfig::default(), true)]
+#[case::zero_denominator(
+    FeeMarketConfig { gas_price_max_change_denominator: 0, ..Default::default() },
+    false
+)]
+#[case::zero_min_gas_price(FeeMarketConfig { min_gas_price: 0, ..Default::default() }, false)]
+#[case::zero_gas_target_percentage(
+    FeeMarketConfig { gas_target_percentage: 0, ..Default::default() },
+    false
+)]
+#[case::full_gas_target_percentage(
+    FeeMarketConfig { gas_target_percentage: 100, ..Default::default() },
+    true
+)]
+#[case::gas_target_percentage_above_100(
+    FeeMarketConfig { gas_target_percentage: 101, ..Default::default() },
+    false
+)]
+fn validate_fee_market_config(#[case] config: FeeMarketConfig, #[case] is_valid: bool) {
+    assert_eq!(config.validate().is_ok(), is_valid);
 }
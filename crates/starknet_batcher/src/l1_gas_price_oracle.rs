@@ -0,0 +1,93 @@
+use std::collections::BTreeMap;
+
+#[cfg(test)]
+use mockall::automock;
+use papyrus_config::dumping::{ser_param, SerializeConfig};
+use papyrus_config::{ParamPath, ParamPrivacyInput, SerializedParam};
+use serde::{Deserialize, Serialize};
+use starknet_api::block::{BlockNumber, GasPrice};
+use thiserror::Error;
+use validator::Validate;
+
+#[derive(Clone, Debug, Error)]
+pub enum L1GasPriceOracleError {
+    #[error("Failed to get the L1 gas prices for block {block_number}: {message}")]
+    Unavailable { block_number: BlockNumber, message: String },
+}
+
+pub type L1GasPriceOracleResult<T> = Result<T, L1GasPriceOracleError>;
+
+/// The L1 gas prices of a block, in wei (ETH) and in fri (STRK).
+#[derive(Clone, Copy, Debug, Serialize, Deserialize, Validate, PartialEq)]
+pub struct L1GasPrices {
+    pub eth_l1_gas_price: GasPrice,
+    pub strk_l1_gas_price: GasPrice,
+    pub eth_l1_data_gas_price: GasPrice,
+    pub strk_l1_data_gas_price: GasPrice,
+}
+
+impl Default for L1GasPrices {
+    fn default() -> Self {
+        Self {
+            eth_l1_gas_price: GasPrice(100 * u128::pow(10, 9)),
+            strk_l1_gas_price: GasPrice(100 * u128::pow(10, 9)),
+            eth_l1_data_gas_price: GasPrice(u128::pow(10, 6)),
+            strk_l1_data_gas_price: GasPrice(u128::pow(10, 9)),
+        }
+    }
+}
+
+impl SerializeConfig for L1GasPrices {
+    fn dump(&self) -> BTreeMap<ParamPath, SerializedParam> {
+        BTreeMap::from_iter([
+            ser_param(
+                "eth_l1_gas_price",
+                &self.eth_l1_gas_price,
+                "The L1 gas price, in wei.",
+                ParamPrivacyInput::Public,
+            ),
+            ser_param(
+                "strk_l1_gas_price",
+                &self.strk_l1_gas_price,
+                "The L1 gas price, in fri.",
+                ParamPrivacyInput::Public,
+            ),
+            ser_param(
+                "eth_l1_data_gas_price",
+                &self.eth_l1_data_gas_price,
+                "The L1 data gas price, in wei.",
+                ParamPrivacyInput::Public,
+            ),
+            ser_param(
+                "strk_l1_data_gas_price",
+                &self.strk_l1_data_gas_price,
+                "The L1 data gas price, in fri.",
+                ParamPrivacyInput::Public,
+            ),
+        ])
+    }
+}
+
+/// Provides the L1 gas prices to use in new blocks.
+#[cfg_attr(test, automock)]
+pub trait L1GasPriceOracle: Send + Sync {
+    fn l1_gas_prices(&self, block_number: BlockNumber) -> L1GasPriceOracleResult<L1GasPrices>;
+}
+
+/// An oracle that provides the same, configured, L1 gas prices for all blocks.
+// TODO: add an oracle that samples the prices from the base layer.
+pub struct FixedL1GasPriceOracle {
+    l1_gas_prices: L1GasPrices,
+}
+
+impl FixedL1GasPriceOracle {
+    pub fn new(l1_gas_prices: L1GasPrices) -> Self {
+        Self { l1_gas_prices }
+    }
+}
+
+impl L1GasPriceOracle for FixedL1GasPriceOracle {
+    fn l1_gas_prices(&self, _block_number: BlockNumber) -> L1GasPriceOracleResult<L1GasPrices> {
+        Ok(self.l1_gas_prices)
+    }
+}
@@ -4,12 +4,19 @@ mod batcher_test;
 pub mod block_builder;
 #[cfg(test)]
 mod block_builder_test;
+pub mod block_data;
+#[cfg(test)]
+mod block_data_test;
 pub mod communication;
 pub mod config;
 pub mod fee_market;
+pub mod l1_gas_price_oracle;
 mod proposal_manager;
 #[cfg(test)]
 mod proposal_manager_test;
+pub mod replay;
+#[cfg(test)]
+mod replay_test;
 #[cfg(test)]
 mod test_utils;
 mod transaction_executor;
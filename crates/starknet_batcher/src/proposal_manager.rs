@@ -3,6 +3,7 @@ use std::sync::Arc;
 
 use async_trait::async_trait;
 use indexmap::IndexMap;
+use papyrus_storage::l2_gas::L2GasInfo;
 use starknet_api::block::{BlockHashAndNumber, BlockNumber};
 use starknet_api::block_hash::state_diff_hash::calculate_state_diff_hash;
 use starknet_api::core::{ContractAddress, Nonce};
@@ -22,6 +23,7 @@ use crate::block_builder::{
     BlockExecutionArtifacts,
     BlockMetadata,
 };
+use crate::block_data::{declared_classes, BlockData};
 use crate::transaction_provider::{ProposeTransactionProvider, ValidateTransactionProvider};
 
 #[derive(Debug, Error)]
@@ -131,6 +133,10 @@ pub struct ProposalOutput {
     pub commitment: ProposalCommitment,
     pub tx_hashes: HashSet<TransactionHash>,
     pub nonces: HashMap<ContractAddress, Nonce>,
+    // The transactions that failed execution, to be dropped from the mempool.
+    pub rejected_tx_hashes: HashSet<TransactionHash>,
+    pub l2_gas_info: L2GasInfo,
+    pub block_data: BlockData,
 }
 
 #[async_trait]
@@ -357,20 +363,40 @@ impl From<BlockExecutionArtifacts> for ProposalOutput {
 
         // TODO: Get these from the transactions.
         let deployed_contracts = IndexMap::new();
-        let declared_classes = IndexMap::new();
+        let (declared_classes, deprecated_declared_classes) =
+            declared_classes(&artifacts.executed_txs);
         let state_diff = ThinStateDiff {
             deployed_contracts,
             storage_diffs: commitment_state_diff.storage_updates,
             declared_classes,
             nonces: commitment_state_diff.address_to_nonce,
-            // TODO: Remove this when the structure of storage diffs changes.
-            deprecated_declared_classes: Vec::new(),
+            deprecated_declared_classes,
             replaced_classes: IndexMap::new(),
         };
         let commitment =
             ProposalCommitment { state_diff_commitment: calculate_state_diff_hash(&state_diff) };
         let tx_hashes = HashSet::from_iter(artifacts.execution_infos.keys().copied());
+        let rejected_tx_hashes =
+            artifacts.rejected_txs.iter().map(|rejected_tx| rejected_tx.tx.tx_hash()).collect();
+        let l2_gas_info = L2GasInfo {
+            l2_gas_used: artifacts.l2_gas_used,
+            l2_gas_price: artifacts.block_header_without_hash.l2_gas_price.price_in_fri,
+        };
+        let block_data = BlockData::new(
+            artifacts.executed_txs,
+            &artifacts.execution_infos,
+            &state_diff,
+            artifacts.block_header_without_hash,
+        );
 
-        Self { state_diff, commitment, tx_hashes, nonces }
+        Self {
+            state_diff,
+            commitment,
+            tx_hashes,
+            nonces,
+            rejected_tx_hashes,
+            l2_gas_info,
+            block_data,
+        }
     }
 }
@@ -91,6 +91,7 @@ fn propose_tx_provider() -> ProposeTransactionProvider {
         Arc::new(MockMempoolClient::new()),
         Arc::new(MockL1ProviderClient::new()),
         MAX_L1_HANDLER_TXS_PER_BLOCK_PROPOSAL,
+        tokio::time::Instant::now(),
     )
 }
 
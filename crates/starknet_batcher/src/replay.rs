@@ -0,0 +1,436 @@
+//! Replays blocks from storage through the block builder, to compare their re-execution with the
+//! stored results, e.g., before deploying a new blockifier version.
+
+use std::hash::Hash;
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use blockifier::abi::constants::STORED_BLOCK_HASH_BUFFER;
+use blockifier::blockifier::block::{BlockInfo, GasPrices};
+use blockifier::state::cached_state::CommitmentStateDiff;
+use blockifier::state::global_cache::GlobalContractCache;
+use indexmap::IndexMap;
+use papyrus_common::deprecated_class_abi::calculate_deprecated_class_abi_length;
+use papyrus_storage::body::BodyStorageReader;
+use papyrus_storage::compiled_class::CasmStorageReader;
+use papyrus_storage::db::RO;
+use papyrus_storage::header::HeaderStorageReader;
+use papyrus_storage::state::StateStorageReader;
+use papyrus_storage::{StorageError, StorageReader, StorageTxn};
+use starknet_api::block::{
+    BlockHashAndNumber,
+    BlockHeaderWithoutHash,
+    BlockNumber,
+    GasPrice,
+    NonzeroGasPrice,
+};
+use starknet_api::contract_class::{ClassInfo, ContractClass};
+use starknet_api::core::{calculate_contract_address, ClassHash, ContractAddress};
+use starknet_api::data_availability::L1DataAvailabilityMode;
+use starknet_api::executable_transaction::{
+    AccountTransaction,
+    DeclareTransaction,
+    DeployAccountTransaction,
+    InvokeTransaction,
+    L1HandlerTransaction,
+    Transaction,
+};
+use starknet_api::state::{StateNumber, StorageKey, ThinStateDiff};
+use starknet_api::transaction::fields::Fee;
+use starknet_api::transaction::{
+    Transaction as StoredTransaction,
+    TransactionHash,
+    TransactionOutput,
+};
+use starknet_api::StarknetApiError;
+use starknet_types_core::felt::Felt;
+use thiserror::Error;
+use tracing::info;
+
+use crate::block_builder::{
+    BlockBuilder,
+    BlockBuilderError,
+    BlockBuilderExecutionParams,
+    BlockBuilderFactory,
+    BlockBuilderTrait,
+    BlockMetadata,
+};
+use crate::block_data::transaction_output;
+use crate::config::BatcherConfig;
+use crate::l1_gas_price_oracle::FixedL1GasPriceOracle;
+use crate::transaction_provider::{NextTxs, TransactionProvider, TransactionProviderError};
+
+// The fee paid on L1 for L1 handler transactions isn't stored; it only has to cover their
+// execution.
+const L1_HANDLER_PAID_FEE_ON_L1: Fee = Fee(u128::MAX);
+
+#[derive(Debug, Error)]
+pub enum ReplayError {
+    #[error(transparent)]
+    BlockBuilderError(#[from] BlockBuilderError),
+    #[error("Block {0} is not in storage.")]
+    BlockNotFound(BlockNumber),
+    #[error("Class {class_hash}, declared in block {height}, is not in storage.")]
+    ClassNotFound { class_hash: ClassHash, height: BlockNumber },
+    #[error(transparent)]
+    DeprecatedClassAbiError(#[from] serde_json::Error),
+    #[error(transparent)]
+    InvalidTransaction(#[from] StarknetApiError),
+    #[error(transparent)]
+    StorageError(#[from] StorageError),
+    #[error("Transaction {0} is a deploy transaction, which can't be re-executed.")]
+    UnsupportedTransaction(TransactionHash),
+}
+
+pub type ReplayResult<T> = Result<T, ReplayError>;
+
+/// The differences between the re-execution of a block and the block in storage.
+#[derive(Debug, Default, PartialEq)]
+pub struct BlockReplayDiff {
+    pub state_diff: Vec<StateDiffMismatch>,
+    pub transaction_outputs: Vec<TransactionOutputMismatch>,
+}
+
+impl BlockReplayDiff {
+    /// Returns true if the re-execution reproduced the stored block.
+    pub fn is_empty(&self) -> bool {
+        self.state_diff.is_empty() && self.transaction_outputs.is_empty()
+    }
+}
+
+/// A state diff entry whose value differs between the stored and the re-executed block. `None`
+/// means the entry is missing from the respective state diff.
+#[derive(Debug, PartialEq)]
+pub struct StateDiffMismatch {
+    pub key: StateDiffKey,
+    pub stored: Option<Felt>,
+    pub replayed: Option<Felt>,
+}
+
+#[derive(Debug, PartialEq)]
+pub enum StateDiffKey {
+    ClassHash(ContractAddress),
+    CompiledClassHash(ClassHash),
+    Nonce(ContractAddress),
+    Storage(ContractAddress, StorageKey),
+}
+
+#[derive(Debug, PartialEq)]
+pub struct TransactionOutputMismatch {
+    pub tx_hash: TransactionHash,
+    pub stored: TransactionOutput,
+    pub replayed: TransactionOutput,
+}
+
+/// Re-executes blocks from storage at their height's state, in validate mode.
+/// The storage should hold full blocks, i.e., not be limited to the state scope.
+/// The blocks are executed with the batcher's configuration, e.g., its versioned constants, so
+/// blocks of older Starknet versions are not expected to be reproduced.
+pub struct BlockReplayer {
+    block_builder_factory: BlockBuilderFactory,
+}
+
+impl BlockReplayer {
+    pub fn new(config: &BatcherConfig, storage_reader: StorageReader) -> Self {
+        Self {
+            block_builder_factory: BlockBuilderFactory {
+                block_builder_config: config.block_builder_config.clone(),
+                storage_reader,
+                global_class_hash_to_class: GlobalContractCache::new(
+                    config.global_contract_cache_size,
+                ),
+                // The gas prices are taken from the stored block headers.
+                l1_gas_price_oracle: Arc::new(FixedL1GasPriceOracle::new(config.l1_gas_prices)),
+                commit_observer: None,
+            },
+        }
+    }
+
+    /// Re-executes the block at the given height and compares the resulting state diff and
+    /// transaction outputs with the stored ones.
+    pub async fn replay_block(
+        &self,
+        height: BlockNumber,
+        deadline: tokio::time::Instant,
+    ) -> ReplayResult<BlockReplayDiff> {
+        let StoredBlock {
+            header_without_hash,
+            txs,
+            transaction_outputs: stored_outputs,
+            state_diff,
+        } = self.read_block(height)?;
+        info!("Replaying block {} with {} transactions.", height, txs.len());
+
+        let block_metadata = BlockMetadata {
+            height,
+            retrospective_block_hash: self.retrospective_block_hash(height)?,
+        };
+        let mut executor = self.block_builder_factory.preprocess_and_create_transaction_executor(
+            &block_metadata,
+            block_info(&header_without_hash),
+        )?;
+        executor.set_deadline(Some(deadline.into_std()));
+        let (_abort_signal_sender, abort_signal_receiver) = tokio::sync::oneshot::channel();
+        let mut block_builder = BlockBuilder::new(
+            Box::new(executor),
+            Box::new(ReplayTransactionProvider { txs: txs.into_iter() }),
+            None,
+            abort_signal_receiver,
+            header_without_hash,
+            self.block_builder_factory.block_builder_config.tx_chunk_size,
+            BlockBuilderExecutionParams { deadline, fail_on_err: true },
+        );
+        let artifacts = block_builder.build_block().await?;
+
+        // In validate mode, the block builder fails unless all the transactions are executed.
+        let transaction_outputs = artifacts
+            .executed_txs
+            .iter()
+            .zip(stored_outputs)
+            .filter_map(|(tx, stored)| {
+                let tx_hash = tx.tx_hash();
+                let replayed = transaction_output(tx, &artifacts.execution_infos[&tx_hash]);
+                if replayed == stored {
+                    return None;
+                }
+                Some(TransactionOutputMismatch { tx_hash, stored, replayed })
+            })
+            .collect();
+
+        Ok(BlockReplayDiff {
+            state_diff: state_diff_mismatches(&state_diff, &artifacts.commitment_state_diff),
+            transaction_outputs,
+        })
+    }
+
+    fn read_block(&self, height: BlockNumber) -> ReplayResult<StoredBlock> {
+        let txn = self.block_builder_factory.storage_reader.begin_ro_txn()?;
+        let block_not_found = || ReplayError::BlockNotFound(height);
+        let header = txn.get_block_header(height)?.ok_or_else(block_not_found)?;
+        let stored_txs = txn.get_block_transactions(height)?.ok_or_else(block_not_found)?;
+        let tx_hashes = txn.get_block_transaction_hashes(height)?.ok_or_else(block_not_found)?;
+        let transaction_outputs =
+            txn.get_block_transaction_outputs(height)?.ok_or_else(block_not_found)?;
+        let state_diff = txn.get_state_diff(height)?.ok_or_else(block_not_found)?;
+
+        let txs = stored_txs
+            .into_iter()
+            .zip(tx_hashes)
+            .map(|(tx, tx_hash)| executable_tx(&txn, height, tx, tx_hash))
+            .collect::<ReplayResult<_>>()?;
+
+        Ok(StoredBlock {
+            header_without_hash: header.block_header_without_hash,
+            txs,
+            transaction_outputs,
+            state_diff,
+        })
+    }
+
+    fn retrospective_block_hash(
+        &self,
+        height: BlockNumber,
+    ) -> ReplayResult<Option<BlockHashAndNumber>> {
+        let Some(number) = height.0.checked_sub(STORED_BLOCK_HASH_BUFFER).map(BlockNumber) else {
+            return Ok(None);
+        };
+        let header = self
+            .block_builder_factory
+            .storage_reader
+            .begin_ro_txn()?
+            .get_block_header(number)?
+            .ok_or(ReplayError::BlockNotFound(number))?;
+        Ok(Some(BlockHashAndNumber { number, hash: header.block_hash }))
+    }
+}
+
+pub fn create_block_replayer(config: &BatcherConfig) -> BlockReplayer {
+    let (storage_reader, _storage_writer) = papyrus_storage::open_storage(config.storage.clone())
+        .expect("Failed to open batcher's storage");
+    BlockReplayer::new(config, storage_reader)
+}
+
+struct StoredBlock {
+    header_without_hash: BlockHeaderWithoutHash,
+    txs: Vec<Transaction>,
+    transaction_outputs: Vec<TransactionOutput>,
+    state_diff: ThinStateDiff,
+}
+
+/// Provides the transactions of a stored block, in their order in the block.
+struct ReplayTransactionProvider {
+    txs: std::vec::IntoIter<Transaction>,
+}
+
+#[async_trait]
+impl TransactionProvider for ReplayTransactionProvider {
+    async fn get_txs(&mut self, n_txs: usize) -> Result<NextTxs, TransactionProviderError> {
+        let txs: Vec<_> = self.txs.by_ref().take(n_txs).collect();
+        if txs.is_empty() {
+            return Ok(NextTxs::End);
+        }
+        Ok(NextTxs::Txs(txs))
+    }
+}
+
+fn block_info(header: &BlockHeaderWithoutHash) -> BlockInfo {
+    // Old blocks may have zero gas prices, which the blockifier doesn't accept.
+    let gas_price = |price: GasPrice| NonzeroGasPrice::new(price).unwrap_or(NonzeroGasPrice::MIN);
+    BlockInfo {
+        block_number: header.block_number,
+        block_timestamp: header.timestamp,
+        sequencer_address: header.sequencer.0,
+        gas_prices: GasPrices::new(
+            gas_price(header.l1_gas_price.price_in_wei),
+            gas_price(header.l1_gas_price.price_in_fri),
+            gas_price(header.l1_data_gas_price.price_in_wei),
+            gas_price(header.l1_data_gas_price.price_in_fri),
+            gas_price(header.l2_gas_price.price_in_wei),
+            gas_price(header.l2_gas_price.price_in_fri),
+        ),
+        use_kzg_da: header.l1_da_mode == L1DataAvailabilityMode::Blob,
+    }
+}
+
+fn executable_tx(
+    txn: &StorageTxn<'_, RO>,
+    height: BlockNumber,
+    tx: StoredTransaction,
+    tx_hash: TransactionHash,
+) -> ReplayResult<Transaction> {
+    Ok(match tx {
+        StoredTransaction::Declare(tx) => {
+            let class_info = class_info(txn, height, tx.class_hash())?;
+            Transaction::Account(AccountTransaction::Declare(DeclareTransaction {
+                tx,
+                tx_hash,
+                class_info,
+            }))
+        }
+        StoredTransaction::DeployAccount(tx) => {
+            let contract_address = calculate_contract_address(
+                tx.contract_address_salt(),
+                tx.class_hash(),
+                &tx.constructor_calldata(),
+                ContractAddress::default(),
+            )?;
+            Transaction::Account(AccountTransaction::DeployAccount(DeployAccountTransaction {
+                tx,
+                tx_hash,
+                contract_address,
+            }))
+        }
+        StoredTransaction::Invoke(tx) => {
+            Transaction::Account(AccountTransaction::Invoke(InvokeTransaction { tx, tx_hash }))
+        }
+        StoredTransaction::L1Handler(tx) => Transaction::L1Handler(L1HandlerTransaction {
+            tx,
+            tx_hash,
+            paid_fee_on_l1: L1_HANDLER_PAID_FEE_ON_L1,
+        }),
+        StoredTransaction::Deploy(_) => return Err(ReplayError::UnsupportedTransaction(tx_hash)),
+    })
+}
+
+/// Returns the info of the class declared in the block at the given height.
+fn class_info(
+    txn: &StorageTxn<'_, RO>,
+    height: BlockNumber,
+    class_hash: ClassHash,
+) -> ReplayResult<ClassInfo> {
+    let class_not_found = || ReplayError::ClassNotFound { class_hash, height };
+    let state_number = StateNumber::unchecked_right_after_block(height);
+    let state_reader = txn.get_state_reader()?;
+    if let Some(class) = state_reader.get_class_definition_at(state_number, &class_hash)? {
+        let casm = txn.get_casm(&class_hash)?.ok_or_else(class_not_found)?;
+        return Ok(ClassInfo::new(
+            &ContractClass::V1(casm),
+            class.sierra_program.len(),
+            class.abi.len(),
+        )?);
+    }
+    let deprecated_class = state_reader
+        .get_deprecated_class_definition_at(state_number, &class_hash)?
+        .ok_or_else(class_not_found)?;
+    let abi_length = calculate_deprecated_class_abi_length(&deprecated_class)?;
+    Ok(ClassInfo::new(&ContractClass::V0(deprecated_class), 0, abi_length)?)
+}
+
+fn state_diff_mismatches(
+    stored: &ThinStateDiff,
+    replayed: &CommitmentStateDiff,
+) -> Vec<StateDiffMismatch> {
+    let mut mismatches = map_mismatches(
+        stored
+            .deployed_contracts
+            .iter()
+            .chain(&stored.replaced_classes)
+            .map(|(address, class_hash)| (*address, class_hash.0))
+            .collect(),
+        replayed
+            .address_to_class_hash
+            .iter()
+            .map(|(address, class_hash)| (*address, class_hash.0))
+            .collect(),
+        StateDiffKey::ClassHash,
+    );
+    mismatches.extend(map_mismatches(
+        stored
+            .declared_classes
+            .iter()
+            .map(|(class_hash, compiled_class_hash)| (*class_hash, compiled_class_hash.0))
+            .collect(),
+        replayed
+            .class_hash_to_compiled_class_hash
+            .iter()
+            .map(|(class_hash, compiled_class_hash)| (*class_hash, compiled_class_hash.0))
+            .collect(),
+        StateDiffKey::CompiledClassHash,
+    ));
+    mismatches.extend(map_mismatches(
+        stored.nonces.iter().map(|(address, nonce)| (*address, nonce.0)).collect(),
+        replayed.address_to_nonce.iter().map(|(address, nonce)| (*address, nonce.0)).collect(),
+        StateDiffKey::Nonce,
+    ));
+    mismatches.extend(map_mismatches(
+        flatten_storage_diffs(&stored.storage_diffs),
+        flatten_storage_diffs(&replayed.storage_updates),
+        |(address, key)| StateDiffKey::Storage(address, key),
+    ));
+    mismatches
+}
+
+fn flatten_storage_diffs(
+    storage_diffs: &IndexMap<ContractAddress, IndexMap<StorageKey, Felt>>,
+) -> IndexMap<(ContractAddress, StorageKey), Felt> {
+    storage_diffs
+        .iter()
+        .flat_map(|(address, diffs)| {
+            diffs.iter().map(move |(key, value)| ((*address, *key), *value))
+        })
+        .collect()
+}
+
+/// Returns the keys whose values differ between the maps, in the order of the stored map.
+fn map_mismatches<K: Copy + Eq + Hash>(
+    stored: IndexMap<K, Felt>,
+    replayed: IndexMap<K, Felt>,
+    state_diff_key: impl Fn(K) -> StateDiffKey,
+) -> Vec<StateDiffMismatch> {
+    stored
+        .keys()
+        .chain(replayed.keys().filter(|key| !stored.contains_key(*key)))
+        .filter_map(|key| {
+            let stored_value = stored.get(key).copied();
+            let replayed_value = replayed.get(key).copied();
+            if stored_value == replayed_value {
+                return None;
+            }
+            Some(StateDiffMismatch {
+                key: state_diff_key(*key),
+                stored: stored_value,
+                replayed: replayed_value,
+            })
+        })
+        .collect()
+}
@@ -0,0 +1,307 @@
+use std::sync::Arc;
+
+use assert_matches::assert_matches;
+use blockifier::state::global_cache::GlobalContractCache;
+use blockifier::test_utils::contracts::FeatureContract;
+use blockifier::test_utils::{create_calldata, CairoVersion};
+use indexmap::indexmap;
+use mockall::Sequence;
+use papyrus_common::deprecated_class_abi::calculate_deprecated_class_abi_length;
+use papyrus_storage::body::BodyStorageWriter;
+use papyrus_storage::class::ClassStorageWriter;
+use papyrus_storage::compiled_class::CasmStorageWriter;
+use papyrus_storage::header::HeaderStorageWriter;
+use papyrus_storage::mempool_commit::MempoolCommit;
+use papyrus_storage::state::StateStorageWriter;
+use papyrus_storage::test_utils::get_test_storage;
+use papyrus_storage::{StorageReader, StorageWriter};
+use rstest::rstest;
+use starknet_api::abi::abi_utils::selector_from_name;
+use starknet_api::block::{BlockBody, BlockHeader, BlockHeaderWithoutHash, BlockNumber};
+use starknet_api::contract_class::{ClassInfo, ContractClass};
+use starknet_api::core::{CompiledClassHash, GlobalRoot, Nonce};
+use starknet_api::executable_transaction::{AccountTransaction, Transaction};
+use starknet_api::state::ThinStateDiff;
+use starknet_api::test_utils::declare::executable_declare_tx;
+use starknet_api::test_utils::invoke::executable_invoke_tx;
+use starknet_api::test_utils::l1_handler::executable_l1_handler_tx;
+use starknet_api::transaction::fields::Fee;
+use starknet_api::transaction::{TransactionHash, TransactionVersion};
+use starknet_api::{
+    calldata,
+    contract_address,
+    declare_tx_args,
+    felt,
+    invoke_tx_args,
+    l1_handler_tx_args,
+};
+
+use crate::batcher::BatcherStorageWriterTrait;
+use crate::block_builder::{
+    BlockBuilderExecutionParams,
+    BlockBuilderFactory,
+    BlockBuilderFactoryTrait,
+    BlockMetadata,
+};
+use crate::config::BatcherConfig;
+use crate::l1_gas_price_oracle::FixedL1GasPriceOracle;
+use crate::proposal_manager::ProposalOutput;
+use crate::replay::{
+    BlockReplayDiff,
+    BlockReplayer,
+    ReplayError,
+    StateDiffKey,
+    StateDiffMismatch,
+    TransactionOutputMismatch,
+};
+use crate::transaction_provider::{MockTransactionProvider, NextTxs};
+
+const REPLAY_DEADLINE_SECS: u64 = 5;
+// The account that sends the transactions of the replayed blocks; it accepts any signature.
+const ACCOUNT_CONTRACT: FeatureContract =
+    FeatureContract::AccountWithoutValidations(CairoVersion::Cairo0);
+// A Cairo 1 contract, called by the invoke and the L1 handler transactions.
+const TEST_CONTRACT: FeatureContract = FeatureContract::TestContract(CairoVersion::Cairo1);
+// The Cairo 0 class declared in the replayed block.
+const DECLARED_CONTRACT: FeatureContract = FeatureContract::Empty(CairoVersion::Cairo0);
+
+fn deadline() -> tokio::time::Instant {
+    tokio::time::Instant::now() + tokio::time::Duration::from_secs(REPLAY_DEADLINE_SECS)
+}
+
+// Writes an empty genesis block with the given state diff.
+fn write_empty_block(storage_writer: &mut StorageWriter, state_diff: ThinStateDiff) {
+    let height = BlockNumber(0);
+    let header = BlockHeader {
+        block_header_without_hash: BlockHeaderWithoutHash {
+            block_number: height,
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+    storage_writer
+        .begin_rw_txn()
+        .unwrap()
+        .append_header(height, &header)
+        .unwrap()
+        .append_body(height, BlockBody::default())
+        .unwrap()
+        .append_state_diff(height, state_diff)
+        .unwrap()
+        .commit()
+        .unwrap();
+}
+
+// Deploys the account and the test contract in the genesis block, and writes their classes.
+fn write_genesis_contracts(storage_writer: &mut StorageWriter) {
+    let ContractClass::V0(account_class) = ACCOUNT_CONTRACT.get_class() else {
+        panic!("The account contract should be a Cairo 0 contract.");
+    };
+    let ContractClass::V1(test_contract_casm) = TEST_CONTRACT.get_class() else {
+        panic!("The test contract should be a Cairo 1 contract.");
+    };
+    let state_diff = ThinStateDiff {
+        deployed_contracts: indexmap! {
+            ACCOUNT_CONTRACT.get_instance_address(0) => ACCOUNT_CONTRACT.get_class_hash(),
+            TEST_CONTRACT.get_instance_address(0) => TEST_CONTRACT.get_class_hash(),
+        },
+        declared_classes: indexmap! {
+            TEST_CONTRACT.get_class_hash() => CompiledClassHash::default(),
+        },
+        deprecated_declared_classes: vec![ACCOUNT_CONTRACT.get_class_hash()],
+        ..Default::default()
+    };
+    write_empty_block(storage_writer, state_diff);
+    storage_writer
+        .begin_rw_txn()
+        .unwrap()
+        .append_classes(BlockNumber(0), &[], &[(ACCOUNT_CONTRACT.get_class_hash(), &account_class)])
+        .unwrap()
+        .append_casm(&TEST_CONTRACT.get_class_hash(), &test_contract_casm)
+        .unwrap()
+        .commit()
+        .unwrap();
+}
+
+// An invoke, a declare and an L1 handler transaction, calling the genesis contracts.
+fn block_txs() -> Vec<Transaction> {
+    let sender_address = ACCOUNT_CONTRACT.get_instance_address(0);
+    let test_contract_address = TEST_CONTRACT.get_instance_address(0);
+
+    let invoke_tx = executable_invoke_tx(invoke_tx_args!(
+        sender_address,
+        calldata: create_calldata(
+            test_contract_address,
+            "test_storage_read_write",
+            &[felt!(1_u8), felt!(2_u8)],
+        ),
+        nonce: Nonce(felt!(0_u8)),
+        tx_hash: TransactionHash(felt!(1_u8)),
+    ));
+
+    let ContractClass::V0(declared_class) = DECLARED_CONTRACT.get_class() else {
+        panic!("The declared contract should be a Cairo 0 contract.");
+    };
+    let abi_length = calculate_deprecated_class_abi_length(&declared_class).unwrap();
+    let class_info = ClassInfo::new(&ContractClass::V0(declared_class), 0, abi_length).unwrap();
+    let declare_tx = executable_declare_tx(
+        declare_tx_args!(
+            sender_address,
+            version: TransactionVersion::ONE,
+            class_hash: DECLARED_CONTRACT.get_class_hash(),
+            nonce: Nonce(felt!(1_u8)),
+            tx_hash: TransactionHash(felt!(2_u8)),
+        ),
+        class_info,
+    );
+
+    let l1_handler_tx = executable_l1_handler_tx(l1_handler_tx_args!(
+        version: TransactionVersion::ZERO,
+        contract_address: test_contract_address,
+        entry_point_selector: selector_from_name("l1_handler_set_value"),
+        // The L1 sender, followed by the storage key and value to set.
+        calldata: calldata![felt!(3_u8), felt!(4_u8), felt!(5_u8)],
+        tx_hash: TransactionHash(felt!(3_u8)),
+        paid_fee_on_l1: Fee(1),
+    ));
+
+    vec![
+        Transaction::Account(AccountTransaction::Invoke(invoke_tx)),
+        Transaction::Account(AccountTransaction::Declare(declare_tx)),
+        Transaction::L1Handler(l1_handler_tx),
+    ]
+}
+
+// Builds a block of the given transactions on top of the storage, the way the batcher proposes
+// blocks, and returns its output.
+async fn build_block(
+    config: &BatcherConfig,
+    storage_reader: StorageReader,
+    height: BlockNumber,
+    txs: Vec<Transaction>,
+) -> ProposalOutput {
+    let block_builder_factory = BlockBuilderFactory {
+        block_builder_config: config.block_builder_config.clone(),
+        storage_reader,
+        global_class_hash_to_class: GlobalContractCache::new(config.global_contract_cache_size),
+        l1_gas_price_oracle: Arc::new(FixedL1GasPriceOracle::new(config.l1_gas_prices)),
+        commit_observer: None,
+    };
+    let mut tx_provider = MockTransactionProvider::new();
+    let mut seq = Sequence::new();
+    tx_provider
+        .expect_get_txs()
+        .times(1)
+        .in_sequence(&mut seq)
+        .return_once(move |_n_txs| Ok(NextTxs::Txs(txs)));
+    tx_provider
+        .expect_get_txs()
+        .times(1)
+        .in_sequence(&mut seq)
+        .return_once(|_n_txs| Ok(NextTxs::End));
+    let (_abort_signal_sender, abort_signal_receiver) = tokio::sync::oneshot::channel();
+    let mut block_builder = block_builder_factory
+        .create_block_builder(
+            BlockMetadata { height, retrospective_block_hash: None },
+            BlockBuilderExecutionParams { deadline: deadline(), fail_on_err: true },
+            Box::new(tx_provider),
+            None,
+            abort_signal_receiver,
+        )
+        .unwrap();
+    block_builder.build_block().await.unwrap().into()
+}
+
+#[rstest]
+#[case::reproduced(false)]
+#[case::transaction_output_mismatch(true)]
+#[tokio::test]
+async fn replay_block_with_transactions(#[case] swap_stored_outputs: bool) {
+    let ((storage_reader, mut storage_writer), _temp_dir) = get_test_storage();
+    write_genesis_contracts(&mut storage_writer);
+    let config = BatcherConfig::default();
+    let height = BlockNumber(1);
+    let txs = block_txs();
+    let tx_hashes: Vec<_> = txs.iter().map(|tx| tx.tx_hash()).collect();
+
+    let mut proposal_output = build_block(&config, storage_reader.clone(), height, txs).await;
+    let outputs = proposal_output.block_data.body.transaction_outputs.clone();
+    if swap_stored_outputs {
+        proposal_output.block_data.body.transaction_outputs.swap(0, 1);
+    }
+    // The header, which replay reads, is only written with a state root.
+    proposal_output.block_data.state_root = Some(GlobalRoot::default());
+    storage_writer
+        .commit_proposal(
+            height,
+            proposal_output.state_diff,
+            proposal_output.block_data,
+            proposal_output.l2_gas_info,
+            MempoolCommit::default(),
+        )
+        .unwrap();
+
+    // Replaying the declare transaction requires its class, which is read from storage.
+    let block_replayer = BlockReplayer::new(&config, storage_reader);
+    let diff = block_replayer.replay_block(height, deadline()).await.unwrap();
+    let expected_transaction_outputs = if swap_stored_outputs {
+        vec![
+            TransactionOutputMismatch {
+                tx_hash: tx_hashes[0],
+                stored: outputs[1].clone(),
+                replayed: outputs[0].clone(),
+            },
+            TransactionOutputMismatch {
+                tx_hash: tx_hashes[1],
+                stored: outputs[0].clone(),
+                replayed: outputs[1].clone(),
+            },
+        ]
+    } else {
+        vec![]
+    };
+    assert_eq!(
+        diff,
+        BlockReplayDiff { transaction_outputs: expected_transaction_outputs, ..Default::default() }
+    );
+}
+
+#[rstest]
+#[case::reproduced(ThinStateDiff::default(), BlockReplayDiff::default())]
+#[case::state_diff_mismatch(
+    ThinStateDiff {
+        nonces: indexmap! { contract_address!(1_u8) => Nonce(felt!(1_u8)) },
+        ..Default::default()
+    },
+    BlockReplayDiff {
+        state_diff: vec![StateDiffMismatch {
+            key: StateDiffKey::Nonce(contract_address!(1_u8)),
+            stored: Some(felt!(1_u8)),
+            replayed: None,
+        }],
+        ..Default::default()
+    }
+)]
+#[tokio::test]
+async fn replay_block(
+    #[case] stored_state_diff: ThinStateDiff,
+    #[case] expected_diff: BlockReplayDiff,
+) {
+    let ((storage_reader, mut storage_writer), _temp_dir) = get_test_storage();
+    write_empty_block(&mut storage_writer, stored_state_diff);
+    let block_replayer = BlockReplayer::new(&BatcherConfig::default(), storage_reader);
+
+    let diff = block_replayer.replay_block(BlockNumber(0), deadline()).await.unwrap();
+    assert_eq!(diff.is_empty(), expected_diff.is_empty());
+    assert_eq!(diff, expected_diff);
+}
+
+#[tokio::test]
+async fn replay_block_not_in_storage() {
+    let ((storage_reader, mut storage_writer), _temp_dir) = get_test_storage();
+    write_empty_block(&mut storage_writer, ThinStateDiff::default());
+    let block_replayer = BlockReplayer::new(&BatcherConfig::default(), storage_reader);
+
+    let result = block_replayer.replay_block(BlockNumber(1), deadline()).await;
+    assert_matches!(result, Err(ReplayError::BlockNotFound(BlockNumber(1))));
+}
@@ -3,8 +3,11 @@ use std::ops::Range;
 use blockifier::blockifier::transaction_executor::VisitedSegmentsMapping;
 use blockifier::bouncer::BouncerWeights;
 use blockifier::state::cached_state::CommitmentStateDiff;
+use cairo_lang_starknet_classes::casm_contract_class::CasmContractClass;
 use indexmap::IndexMap;
+use starknet_api::block::BlockHeaderWithoutHash;
 use starknet_api::executable_transaction::{AccountTransaction, Transaction};
+use starknet_api::execution_resources::GasAmount;
 use starknet_api::felt;
 use starknet_api::test_utils::invoke::{executable_invoke_tx, InvokeTxArgs};
 use starknet_api::transaction::TransactionHash;
@@ -12,23 +15,39 @@ use starknet_api::transaction::TransactionHash;
 use crate::block_builder::BlockExecutionArtifacts;
 
 pub fn test_txs(tx_hash_range: Range<usize>) -> Vec<Transaction> {
-    tx_hash_range
-        .map(|i| {
-            Transaction::Account(AccountTransaction::Invoke(executable_invoke_tx(InvokeTxArgs {
-                tx_hash: TransactionHash(felt!(u128::try_from(i).unwrap())),
-                ..Default::default()
-            })))
-        })
-        .collect()
+    tx_hash_range.map(|i| test_tx(TransactionHash(felt!(u128::try_from(i).unwrap())))).collect()
+}
+
+pub fn test_tx(tx_hash: TransactionHash) -> Transaction {
+    Transaction::Account(AccountTransaction::Invoke(executable_invoke_tx(InvokeTxArgs {
+        tx_hash,
+        ..Default::default()
+    })))
+}
+
+pub fn test_casm() -> CasmContractClass {
+    CasmContractClass {
+        compiler_version: "0.1.0".to_string(),
+        prime: Default::default(),
+        bytecode: Default::default(),
+        bytecode_segment_lengths: Default::default(),
+        hints: Default::default(),
+        pythonic_hints: Default::default(),
+        entry_points_by_type: Default::default(),
+    }
 }
 
 impl BlockExecutionArtifacts {
     pub fn create_for_testing() -> Self {
         Self {
+            executed_txs: Vec::new(),
             execution_infos: IndexMap::default(),
             commitment_state_diff: CommitmentStateDiff::default(),
             visited_segments_mapping: VisitedSegmentsMapping::default(),
             bouncer_weights: BouncerWeights::empty(),
+            l2_gas_used: GasAmount::default(),
+            block_header_without_hash: BlockHeaderWithoutHash::default(),
+            rejected_txs: Vec::new(),
         }
     }
 }
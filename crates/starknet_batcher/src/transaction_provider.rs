@@ -9,6 +9,7 @@ use starknet_api::executable_transaction::{L1HandlerTransaction, Transaction};
 use starknet_api::transaction::TransactionHash;
 use starknet_mempool_types::communication::{MempoolClientError, SharedMempoolClient};
 use thiserror::Error;
+use tokio::time::Instant;
 use tracing::warn;
 
 #[derive(Clone, Debug, Error)]
@@ -36,6 +37,8 @@ pub struct ProposeTransactionProvider {
     pub mempool_client: SharedMempoolClient,
     pub l1_provider_client: SharedL1ProviderClient,
     pub max_l1_handler_txs_per_block: usize,
+    // Waiting for transactions doesn't extend past the proposal's deadline.
+    deadline: Instant,
     phase: TxProviderPhase,
     n_l1handler_txs_so_far: usize,
 }
@@ -52,11 +55,13 @@ impl ProposeTransactionProvider {
         mempool_client: SharedMempoolClient,
         l1_provider_client: SharedL1ProviderClient,
         max_l1_handler_txs_per_block: usize,
+        deadline: Instant,
     ) -> Self {
         Self {
             mempool_client,
             l1_provider_client,
             max_l1_handler_txs_per_block,
+            deadline,
             phase: TxProviderPhase::L1,
             n_l1handler_txs_so_far: 0,
         }
@@ -78,6 +83,12 @@ impl ProposeTransactionProvider {
             .map(Transaction::Account)
             .collect())
     }
+
+    /// Waits until the mempool has transactions to offer, or until the deadline.
+    async fn wait_for_mempool_txs(&self) -> Result<(), TransactionProviderError> {
+        let timeout = self.deadline.saturating_duration_since(Instant::now());
+        Ok(self.mempool_client.wait_for_txs(timeout).await?)
+    }
 }
 
 #[async_trait]
@@ -106,6 +117,9 @@ impl TransactionProvider for ProposeTransactionProvider {
 
         let mut mempool_txs = self.get_mempool_txs(n_txs - txs.len()).await?;
         txs.append(&mut mempool_txs);
+        if txs.is_empty() {
+            self.wait_for_mempool_txs().await?;
+        }
         Ok(NextTxs::Txs(txs))
     }
 }
@@ -8,6 +8,7 @@ use starknet_api::test_utils::invoke::{executable_invoke_tx, InvokeTxArgs};
 use starknet_api::transaction::TransactionHash;
 use starknet_mempool_types::communication::MockMempoolClient;
 use starknet_types_core::felt::Felt;
+use tokio::time::{Duration, Instant};
 
 use crate::transaction_provider::{
     MockL1ProviderClient,
@@ -21,6 +22,7 @@ use crate::transaction_provider::{
 const MAX_L1_HANDLER_TXS_PER_BLOCK: usize = 15;
 const MAX_TXS_PER_FETCH: usize = 10;
 const VALIDATE_BUFFER_SIZE: usize = 30;
+const DEADLINE: Duration = Duration::from_secs(10);
 
 struct MockDependencies {
     mempool_client: MockMempoolClient,
@@ -59,11 +61,28 @@ impl MockDependencies {
         }
     }
 
+    fn expect_get_no_mempool_txs(&mut self) {
+        self.mempool_client.expect_get_txs().returning(|_| Ok(vec![]));
+    }
+
+    fn expect_wait_for_mempool_txs(&mut self, max_timeout: Duration) {
+        self.mempool_client
+            .expect_wait_for_txs()
+            .once()
+            .withf(move |timeout| *timeout <= max_timeout)
+            .returning(|_| Ok(()));
+    }
+
     fn propose_tx_provider(self) -> ProposeTransactionProvider {
+        self.propose_tx_provider_with_deadline(Instant::now() + DEADLINE)
+    }
+
+    fn propose_tx_provider_with_deadline(self, deadline: Instant) -> ProposeTransactionProvider {
         ProposeTransactionProvider::new(
             Arc::new(self.mempool_client),
             Arc::new(self.l1_provider_client),
             MAX_L1_HANDLER_TXS_PER_BLOCK,
+            deadline,
         )
     }
 
@@ -147,22 +166,42 @@ async fn no_more_l1_handler(mut mock_dependencies: MockDependencies) {
 
     let txs = tx_provider.get_txs(MAX_TXS_PER_FETCH).await.unwrap();
     let data = assert_matches!(txs, NextTxs::Txs(txs) if txs.len() == MAX_TXS_PER_FETCH => txs);
-    assert!(
-        data[..NUM_L1_HANDLER_TXS_IN_PROVIDER]
-            .iter()
-            .all(|tx| matches!(tx, Transaction::L1Handler(_)))
-    );
-    assert!(
-        data[NUM_L1_HANDLER_TXS_IN_PROVIDER..]
-            .iter()
-            .all(|tx| { matches!(tx, Transaction::Account(_)) })
-    );
+    assert!(data[..NUM_L1_HANDLER_TXS_IN_PROVIDER]
+        .iter()
+        .all(|tx| matches!(tx, Transaction::L1Handler(_))));
+    assert!(data[NUM_L1_HANDLER_TXS_IN_PROVIDER..]
+        .iter()
+        .all(|tx| { matches!(tx, Transaction::Account(_)) }));
 
     let txs = tx_provider.get_txs(MAX_TXS_PER_FETCH).await.unwrap();
     let data = assert_matches!(txs, NextTxs::Txs(txs) if txs.len() == MAX_TXS_PER_FETCH => txs);
     assert!(data.iter().all(|tx| matches!(tx, Transaction::Account(_))));
 }
 
+#[rstest]
+#[tokio::test]
+async fn empty_mempool_waits_for_txs(mut mock_dependencies: MockDependencies) {
+    mock_dependencies.expect_get_l1_handler_txs(MAX_TXS_PER_FETCH, 0);
+    mock_dependencies.expect_get_no_mempool_txs();
+    mock_dependencies.expect_wait_for_mempool_txs(DEADLINE);
+    let mut tx_provider = mock_dependencies.propose_tx_provider();
+
+    let txs = tx_provider.get_txs(MAX_TXS_PER_FETCH).await.unwrap();
+    assert_eq!(txs, NextTxs::Txs(vec![]));
+}
+
+#[rstest]
+#[tokio::test]
+async fn empty_mempool_waits_no_later_than_deadline(mut mock_dependencies: MockDependencies) {
+    mock_dependencies.expect_get_l1_handler_txs(MAX_TXS_PER_FETCH, 0);
+    mock_dependencies.expect_get_no_mempool_txs();
+    mock_dependencies.expect_wait_for_mempool_txs(Duration::ZERO);
+    let mut tx_provider = mock_dependencies.propose_tx_provider_with_deadline(Instant::now());
+
+    let txs = tx_provider.get_txs(MAX_TXS_PER_FETCH).await.unwrap();
+    assert_eq!(txs, NextTxs::Txs(vec![]));
+}
+
 #[rstest]
 #[tokio::test]
 async fn validate_flow(mut mock_dependencies: MockDependencies) {
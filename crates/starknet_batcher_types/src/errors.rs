@@ -22,6 +22,8 @@ pub enum BatcherError {
     InternalError,
     #[error("Missing retrospective block hash.")]
     MissingRetrospectiveBlockHash,
+    #[error("Block {height} is committed to storage but not to the mempool.")]
+    MempoolNotSynced { height: BlockNumber },
     #[error("Attempt to start proposal with no active height.")]
     NoActiveHeight,
     #[error(
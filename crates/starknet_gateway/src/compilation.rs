@@ -29,7 +29,7 @@ impl GatewayCompiler {
     }
 
     /// Formats the contract class for compilation, compiles it, and returns the compiled contract
-    /// class wrapped in a [`ClassInfo`].
+    /// class wrapped in a [`ClassInfo`], along with the Sierra class.
     /// Assumes the contract class is of a Sierra program which is compiled to Casm.
     pub(crate) fn process_declare_tx(
         &self,
@@ -45,6 +45,7 @@ impl GatewayCompiler {
             contract_class: ContractClass::V1(casm_contract_class),
             sierra_program_length: rpc_contract_class.sierra_program.len(),
             abi_length: rpc_contract_class.abi.len(),
+            sierra_contract_class: Some(rpc_contract_class.clone().into()),
         })
     }
 
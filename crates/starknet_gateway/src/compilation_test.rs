@@ -9,6 +9,7 @@ use starknet_api::rpc_transaction::{
     RpcDeclareTransactionV3,
     RpcTransaction,
 };
+use starknet_api::state::SierraContractClass;
 use starknet_gateway_types::errors::GatewaySpecError;
 use starknet_sierra_compile::config::SierraToCasmCompilationConfig;
 use starknet_sierra_compile::errors::CompilationUtilError;
@@ -73,6 +74,7 @@ fn test_process_declare_tx_success(
     let contract_class = &declare_tx_v3.contract_class;
     let sierra_program_length = contract_class.sierra_program.len();
     let abi_length = contract_class.abi.len();
+    let sierra_contract_class: SierraContractClass = contract_class.clone().into();
     let declare_tx = RpcDeclareTransaction::V3(declare_tx_v3);
 
     let class_info = gateway_compiler.process_declare_tx(&declare_tx).unwrap();
@@ -80,4 +82,5 @@ fn test_process_declare_tx_success(
     assert_eq!(compiled_class_hash, *COMPILED_CLASS_HASH);
     assert_eq!(class_info.sierra_program_length, sierra_program_length);
     assert_eq!(class_info.abi_length, abi_length);
+    assert_eq!(class_info.sierra_contract_class, Some(sierra_contract_class));
 }
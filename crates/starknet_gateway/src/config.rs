@@ -6,6 +6,7 @@ use papyrus_config::dumping::{append_sub_config_name, ser_param, SerializeConfig
 use papyrus_config::{ParamPath, ParamPrivacyInput, SerializedParam};
 use serde::{Deserialize, Serialize};
 use starknet_api::core::Nonce;
+use starknet_gateway_types::rate_limiter::RateLimiterConfig;
 use starknet_types_core::felt::Felt;
 use validator::Validate;
 
@@ -16,6 +17,9 @@ pub struct GatewayConfig {
     pub stateless_tx_validator_config: StatelessTransactionValidatorConfig,
     pub stateful_tx_validator_config: StatefulTransactionValidatorConfig,
     pub chain_info: ChainInfo,
+    // Rate limit of transactions from a single sender address.
+    #[validate]
+    pub rate_limiter_config: RateLimiterConfig,
 }
 
 impl SerializeConfig for GatewayConfig {
@@ -30,6 +34,7 @@ impl SerializeConfig for GatewayConfig {
                 "stateful_tx_validator_config",
             ),
             append_sub_config_name(self.chain_info.dump(), "chain_info"),
+            append_sub_config_name(self.rate_limiter_config.dump(), "rate_limiter_config"),
         ]
         .into_iter()
         .flatten()
@@ -3,15 +3,22 @@ use std::sync::Arc;
 
 use blockifier::context::ChainInfo;
 use papyrus_network_types::network_types::BroadcastedMessageMetadata;
+use starknet_api::core::ContractAddress;
 use starknet_api::executable_transaction::AccountTransaction;
 use starknet_api::rpc_transaction::RpcTransaction;
-use starknet_api::transaction::TransactionHash;
 use starknet_gateway_types::errors::GatewaySpecError;
+use starknet_gateway_types::gateway_types::{
+    DeclareGatewayOutput,
+    DeployAccountGatewayOutput,
+    GatewayOutput,
+    InvokeGatewayOutput,
+};
+use starknet_gateway_types::rate_limiter::RateLimiter;
 use starknet_mempool_types::communication::{AddTransactionArgsWrapper, SharedMempoolClient};
 use starknet_mempool_types::mempool_types::{AccountState, AddTransactionArgs};
 use starknet_sequencer_infra::component_definitions::ComponentStarter;
 use starknet_sierra_compile::config::SierraToCasmCompilationConfig;
-use tracing::{error, instrument, Span};
+use tracing::{debug, error, instrument, Span};
 
 use crate::compilation::GatewayCompiler;
 use crate::config::{GatewayConfig, RpcStateReaderConfig};
@@ -34,6 +41,7 @@ pub struct Gateway {
     pub gateway_compiler: Arc<GatewayCompiler>,
     pub mempool_client: SharedMempoolClient,
     pub chain_info: ChainInfo,
+    pub rate_limiter: Arc<RateLimiter<ContractAddress>>,
 }
 
 impl Gateway {
@@ -55,6 +63,7 @@ impl Gateway {
             gateway_compiler: Arc::new(gateway_compiler),
             mempool_client,
             chain_info: config.chain_info.clone(),
+            rate_limiter: Arc::new(RateLimiter::new(config.rate_limiter_config)),
         }
     }
 
@@ -63,7 +72,35 @@ impl Gateway {
         &self,
         tx: RpcTransaction,
         p2p_message_metadata: Option<BroadcastedMessageMetadata>,
-    ) -> GatewayResult<TransactionHash> {
+    ) -> GatewayResult<GatewayOutput> {
+        // Transactions relayed by peers were already limited by the gateway that accepted them.
+        if p2p_message_metadata.is_some() {
+            return self.process_and_add_tx(tx, p2p_message_metadata).await;
+        }
+
+        // Charge the claimed sender before the costly compilation and validation, and refund it if
+        // the transaction is rejected. Only transactions authenticated by the sender's signature
+        // keep their token, so that the address of another account cannot be used to throttle it.
+        let sender_address = tx
+            .calculate_sender_address()
+            .map_err(|e| GatewaySpecError::ValidationFailure { data: e.to_string() })?;
+        if !self.rate_limiter.try_acquire(sender_address) {
+            debug!("Rate limit exceeded for sender address {}.", sender_address);
+            return Err(GatewaySpecError::RateLimited);
+        }
+
+        let result = self.process_and_add_tx(tx, None).await;
+        if result.is_err() {
+            self.rate_limiter.release(sender_address);
+        }
+        result
+    }
+
+    async fn process_and_add_tx(
+        &self,
+        tx: RpcTransaction,
+        p2p_message_metadata: Option<BroadcastedMessageMetadata>,
+    ) -> GatewayResult<GatewayOutput> {
         let blocking_task = ProcessTxBlockingTask::new(self, tx);
         // Run the blocking task in the current span.
         let curr_span = Span::current();
@@ -75,15 +112,33 @@ impl Gateway {
                     GatewaySpecError::UnexpectedError { data: "Internal server error".to_owned() }
                 })??;
 
-        let tx_hash = add_tx_args.tx.tx_hash();
+        let gateway_output = gateway_output(&add_tx_args.tx);
 
         let add_tx_args = AddTransactionArgsWrapper { args: add_tx_args, p2p_message_metadata };
         self.mempool_client.add_tx(add_tx_args).await.map_err(|e| {
             error!("Failed to send tx to mempool: {}", e);
             GatewaySpecError::UnexpectedError { data: "Internal server error".to_owned() }
         })?;
-        // TODO: Also return `ContractAddress` for deploy and `ClassHash` for Declare.
-        Ok(tx_hash)
+        Ok(gateway_output)
+    }
+}
+
+fn gateway_output(tx: &AccountTransaction) -> GatewayOutput {
+    let transaction_hash = tx.tx_hash();
+    match tx {
+        AccountTransaction::Declare(declare_tx) => GatewayOutput::Declare(DeclareGatewayOutput {
+            transaction_hash,
+            class_hash: declare_tx.class_hash(),
+        }),
+        AccountTransaction::DeployAccount(deploy_account_tx) => {
+            GatewayOutput::DeployAccount(DeployAccountGatewayOutput {
+                transaction_hash,
+                contract_address: deploy_account_tx.contract_address(),
+            })
+        }
+        AccountTransaction::Invoke(_) => {
+            GatewayOutput::Invoke(InvokeGatewayOutput { transaction_hash })
+        }
     }
 }
 
@@ -111,8 +166,6 @@ impl ProcessTxBlockingTask {
     }
 
     fn process_tx(self) -> GatewayResult<AddTransactionArgs> {
-        // TODO(Arni, 1/5/2024): Perform congestion control.
-
         // Perform stateless validations.
         self.stateless_tx_validator.validate(&self.tx)?;
 
@@ -10,8 +10,11 @@ use papyrus_test_utils::{get_rng, GetTestInstance};
 use rstest::{fixture, rstest};
 use starknet_api::core::{ChainId, CompiledClassHash, ContractAddress};
 use starknet_api::executable_transaction::{AccountTransaction, InvokeTransaction};
-use starknet_api::rpc_transaction::{RpcDeclareTransaction, RpcTransaction};
+use starknet_api::rpc_transaction::{RpcDeclareTransaction, RpcInvokeTransaction, RpcTransaction};
+use starknet_api::transaction::fields::AllResourceBounds;
 use starknet_gateway_types::errors::GatewaySpecError;
+use starknet_gateway_types::gateway_types::{GatewayOutput, InvokeGatewayOutput};
+use starknet_gateway_types::rate_limiter::RateLimiterConfig;
 use starknet_mempool_types::communication::{AddTransactionArgsWrapper, MockMempoolClient};
 use starknet_mempool_types::mempool_types::{AccountState, AddTransactionArgs};
 use starknet_sierra_compile::config::SierraToCasmCompilationConfig;
@@ -31,6 +34,7 @@ fn config() -> GatewayConfig {
         stateless_tx_validator_config: StatelessTransactionValidatorConfig::default(),
         stateful_tx_validator_config: StatefulTransactionValidatorConfig::default(),
         chain_info: ChainInfo::create_for_testing(),
+        rate_limiter_config: RateLimiterConfig::default(),
     }
 }
 
@@ -74,6 +78,10 @@ impl MockDependencies {
     fn expect_add_tx(&mut self, args: AddTransactionArgsWrapper) {
         self.mock_mempool_client.expect_add_tx().once().with(eq(args)).return_once(|_| Ok(()));
     }
+
+    fn expect_add_tx_times(&mut self, args: AddTransactionArgsWrapper, times: usize) {
+        self.mock_mempool_client.expect_add_tx().times(times).with(eq(args)).returning(|_| Ok(()));
+    }
 }
 
 type SenderAddress = ContractAddress;
@@ -89,24 +97,27 @@ fn create_tx() -> (RpcTransaction, SenderAddress) {
     (tx, sender_address)
 }
 
-// TODO: add test with Some broadcasted message metadata
-#[rstest]
-#[tokio::test]
-async fn test_add_tx(mut mock_dependencies: MockDependencies) {
-    let (rpc_tx, address) = create_tx();
+fn create_add_tx_args(rpc_tx: &RpcTransaction, address: SenderAddress) -> AddTransactionArgs {
     let rpc_invoke_tx =
         assert_matches!(rpc_tx.clone(), RpcTransaction::Invoke(rpc_invoke_tx) => rpc_invoke_tx);
     let executable_tx = AccountTransaction::Invoke(
         InvokeTransaction::from_rpc_tx(rpc_invoke_tx, &ChainId::create_for_testing()).unwrap(),
     );
+    AddTransactionArgs {
+        tx: executable_tx,
+        account_state: AccountState { address, nonce: *rpc_tx.nonce() },
+    }
+}
 
-    let tx_hash = executable_tx.tx_hash();
+// TODO: add test with Some broadcasted message metadata
+#[rstest]
+#[tokio::test]
+async fn test_add_tx(mut mock_dependencies: MockDependencies) {
+    let (rpc_tx, address) = create_tx();
+    let add_tx_args = create_add_tx_args(&rpc_tx, address);
+    let tx_hash = add_tx_args.tx.tx_hash();
 
     let p2p_message_metadata = Some(BroadcastedMessageMetadata::get_test_instance(&mut get_rng()));
-    let add_tx_args = AddTransactionArgs {
-        tx: executable_tx,
-        account_state: AccountState { address, nonce: *rpc_tx.nonce() },
-    };
     mock_dependencies.expect_add_tx(AddTransactionArgsWrapper {
         args: add_tx_args,
         p2p_message_metadata: p2p_message_metadata.clone(),
@@ -114,9 +125,9 @@ async fn test_add_tx(mut mock_dependencies: MockDependencies) {
 
     let gateway = mock_dependencies.gateway();
 
-    let response_tx_hash = gateway.add_tx(rpc_tx, p2p_message_metadata).await.unwrap();
+    let response = gateway.add_tx(rpc_tx, p2p_message_metadata).await.unwrap();
 
-    assert_eq!(tx_hash, response_tx_hash);
+    assert_eq!(response, GatewayOutput::Invoke(InvokeGatewayOutput { transaction_hash: tx_hash }));
 }
 
 // Gateway spec errors tests.
@@ -138,3 +149,68 @@ async fn test_compiled_class_hash_mismatch(mock_dependencies: MockDependencies)
     let err = gateway.add_tx(tx, None).await.unwrap_err();
     assert_matches!(err, GatewaySpecError::CompiledClassHashMismatch);
 }
+
+#[rstest]
+#[tokio::test]
+async fn test_rate_limited(mut mock_dependencies: MockDependencies) {
+    mock_dependencies.config.rate_limiter_config =
+        RateLimiterConfig { enabled: true, burst_size: 1, refill_per_second: 1 };
+    let (rpc_tx, address) = create_tx();
+    // Only the first transaction is forwarded to the mempool.
+    mock_dependencies.expect_add_tx(AddTransactionArgsWrapper {
+        args: create_add_tx_args(&rpc_tx, address),
+        p2p_message_metadata: None,
+    });
+
+    let gateway = mock_dependencies.gateway();
+
+    gateway.add_tx(rpc_tx.clone(), None).await.unwrap();
+    let err = gateway.add_tx(rpc_tx, None).await.unwrap_err();
+    assert_matches!(err, GatewaySpecError::RateLimited);
+}
+
+#[rstest]
+#[tokio::test]
+async fn test_rejected_tx_not_charged_by_rate_limit(mut mock_dependencies: MockDependencies) {
+    mock_dependencies.config.rate_limiter_config =
+        RateLimiterConfig { enabled: true, burst_size: 1, refill_per_second: 1 };
+    let (rpc_tx, address) = create_tx();
+    mock_dependencies.expect_add_tx(AddTransactionArgsWrapper {
+        args: create_add_tx_args(&rpc_tx, address),
+        p2p_message_metadata: None,
+    });
+    let mut invalid_tx = assert_matches!(
+        rpc_tx.clone(),
+        RpcTransaction::Invoke(RpcInvokeTransaction::V3(tx)) => tx
+    );
+    invalid_tx.resource_bounds = AllResourceBounds::default();
+    let invalid_tx = RpcTransaction::Invoke(RpcInvokeTransaction::V3(invalid_tx));
+
+    let gateway = mock_dependencies.gateway();
+
+    // The token of the sender is returned when its transaction is rejected.
+    let err = gateway.add_tx(invalid_tx, None).await.unwrap_err();
+    assert_matches!(err, GatewaySpecError::ValidationFailure { .. });
+    gateway.add_tx(rpc_tx, None).await.unwrap();
+}
+
+#[rstest]
+#[tokio::test]
+async fn test_p2p_txs_not_rate_limited(mut mock_dependencies: MockDependencies) {
+    mock_dependencies.config.rate_limiter_config =
+        RateLimiterConfig { enabled: true, burst_size: 1, refill_per_second: 1 };
+    let (rpc_tx, address) = create_tx();
+    let p2p_message_metadata = Some(BroadcastedMessageMetadata::get_test_instance(&mut get_rng()));
+    mock_dependencies.expect_add_tx_times(
+        AddTransactionArgsWrapper {
+            args: create_add_tx_args(&rpc_tx, address),
+            p2p_message_metadata: p2p_message_metadata.clone(),
+        },
+        2,
+    );
+
+    let gateway = mock_dependencies.gateway();
+
+    gateway.add_tx(rpc_tx.clone(), p2p_message_metadata.clone()).await.unwrap();
+    gateway.add_tx(rpc_tx, p2p_message_metadata).await.unwrap();
+}
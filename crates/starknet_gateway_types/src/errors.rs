@@ -22,6 +22,10 @@ use papyrus_rpc::error::{
 use serde::{Deserialize, Serialize};
 use thiserror::Error;
 
+// Not part of the Starknet RPC spec; uses the commonly used JSON-RPC "limit exceeded" code.
+pub const RATE_LIMITED: JsonRpcError<String> =
+    JsonRpcError { code: -32005, message: "Too many requests, rate limit exceeded", data: None };
+
 /// Error returned by the gateway, adhering to the Starknet RPC error format.
 // To get JsonRpcError from GatewaySpecError, use `into_rpc` method.
 // TODO(yair): papyrus_rpc has a test that the add_tx functions return the correct error. Make sure
@@ -49,6 +53,8 @@ pub enum GatewaySpecError {
     InvalidTransactionNonce,
     #[assoc(into_rpc = NON_ACCOUNT)]
     NonAccount,
+    #[assoc(into_rpc = RATE_LIMITED)]
+    RateLimited,
     #[assoc(into_rpc = unexpected_error(_data))]
     UnexpectedError { data: String },
     #[assoc(into_rpc = UNSUPPORTED_CONTRACT_CLASS_VERSION)]
@@ -61,12 +67,16 @@ pub enum GatewaySpecError {
 
 impl IntoResponse for GatewaySpecError {
     fn into_response(self) -> Response {
+        let is_rate_limited = self == GatewaySpecError::RateLimited;
         let as_rpc = self.into_rpc();
         // TODO(Arni): Fix the status code. The status code should be a HTTP status code - not a
         // Json RPC error code. status code.
-        let status =
+        let status = if is_rate_limited {
+            StatusCode::TOO_MANY_REQUESTS
+        } else {
             StatusCode::from_u16(u16::try_from(as_rpc.code).expect("Expecting a valid u16"))
-                .expect("Expecting a valid error code");
+                .expect("Expecting a valid error code")
+        };
 
         let resp = Response::builder()
             .status(status)
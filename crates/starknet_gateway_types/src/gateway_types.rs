@@ -1,6 +1,8 @@
 use papyrus_network_types::network_types::BroadcastedMessageMetadata;
 use serde::{Deserialize, Serialize};
+use starknet_api::core::{ClassHash, ContractAddress};
 use starknet_api::rpc_transaction::RpcTransaction;
+use starknet_api::transaction::TransactionHash;
 
 use crate::errors::GatewayError;
 
@@ -10,4 +12,39 @@ pub struct GatewayInput {
     pub message_metadata: Option<BroadcastedMessageMetadata>,
 }
 
+/// The output of adding a transaction, shaped after the results of the Starknet RPC write API.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub enum GatewayOutput {
+    Declare(DeclareGatewayOutput),
+    DeployAccount(DeployAccountGatewayOutput),
+    Invoke(InvokeGatewayOutput),
+}
+
+impl GatewayOutput {
+    pub fn transaction_hash(&self) -> TransactionHash {
+        match self {
+            GatewayOutput::Declare(output) => output.transaction_hash,
+            GatewayOutput::DeployAccount(output) => output.transaction_hash,
+            GatewayOutput::Invoke(output) => output.transaction_hash,
+        }
+    }
+}
+
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub struct DeclareGatewayOutput {
+    pub transaction_hash: TransactionHash,
+    pub class_hash: ClassHash,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub struct DeployAccountGatewayOutput {
+    pub transaction_hash: TransactionHash,
+    pub contract_address: ContractAddress,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub struct InvokeGatewayOutput {
+    pub transaction_hash: TransactionHash,
+}
+
 pub type GatewayResult<T> = Result<T, GatewayError>;
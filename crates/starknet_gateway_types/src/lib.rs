@@ -1,3 +1,4 @@
 pub mod communication;
 pub mod errors;
 pub mod gateway_types;
+pub mod rate_limiter;
@@ -0,0 +1,146 @@
+use std::collections::{BTreeMap, HashMap};
+use std::hash::Hash;
+use std::sync::Mutex;
+use std::time::Instant;
+
+use papyrus_config::dumping::{ser_param, SerializeConfig};
+use papyrus_config::{ParamPath, ParamPrivacyInput, SerializedParam};
+use serde::{Deserialize, Serialize};
+use validator::Validate;
+
+#[cfg(test)]
+#[path = "rate_limiter_test.rs"]
+pub mod rate_limiter_test;
+
+// Number of tracked keys above which buckets that have refilled completely are dropped; such
+// buckets are equivalent to new ones.
+const MAX_TRACKED_KEYS: usize = 100_000;
+
+/// Token bucket rate limiting configuration; each key may send up to `burst_size` requests at
+/// once, and is then limited to `refill_per_second` requests per second.
+#[derive(Clone, Copy, Debug, Serialize, Deserialize, Validate, PartialEq)]
+pub struct RateLimiterConfig {
+    pub enabled: bool,
+    #[validate(range(min = 1))]
+    pub burst_size: u32,
+    #[validate(range(min = 1))]
+    pub refill_per_second: u32,
+}
+
+impl Default for RateLimiterConfig {
+    fn default() -> Self {
+        Self { enabled: false, burst_size: 50, refill_per_second: 10 }
+    }
+}
+
+impl SerializeConfig for RateLimiterConfig {
+    fn dump(&self) -> BTreeMap<ParamPath, SerializedParam> {
+        BTreeMap::from_iter([
+            ser_param(
+                "enabled",
+                &self.enabled,
+                "If true, requests exceeding the rate limit are rejected.",
+                ParamPrivacyInput::Public,
+            ),
+            ser_param(
+                "burst_size",
+                &self.burst_size,
+                "Maximum number of requests accepted at once from a single source.",
+                ParamPrivacyInput::Public,
+            ),
+            ser_param(
+                "refill_per_second",
+                &self.refill_per_second,
+                "Number of requests per second accepted from a single source, once its burst is \
+                 exhausted.",
+                ParamPrivacyInput::Public,
+            ),
+        ])
+    }
+}
+
+/// Limits the rate of requests per key (e.g., remote IP or sender address), using a token bucket
+/// per key.
+#[derive(Debug)]
+pub struct RateLimiter<K> {
+    config: RateLimiterConfig,
+    buckets: Mutex<HashMap<K, TokenBucket>>,
+}
+
+impl<K: Eq + Hash> RateLimiter<K> {
+    pub fn new(config: RateLimiterConfig) -> Self {
+        Self { config, buckets: Mutex::new(HashMap::new()) }
+    }
+
+    /// Consumes a token of the given key; returns false if the key exceeded its rate limit.
+    pub fn try_acquire(&self, key: K) -> bool {
+        self.try_acquire_at(key, Instant::now())
+    }
+
+    pub(crate) fn try_acquire_at(&self, key: K, now: Instant) -> bool {
+        if !self.config.enabled {
+            return true;
+        }
+
+        let mut buckets = self.buckets.lock().expect("Rate limiter lock should not be poisoned.");
+        if buckets.len() >= MAX_TRACKED_KEYS {
+            buckets.retain(|_, bucket| !bucket.refill(&self.config, now).is_full(&self.config));
+        }
+
+        buckets
+            .entry(key)
+            .or_insert_with(|| TokenBucket::full(&self.config, now))
+            .refill(&self.config, now)
+            .try_take()
+    }
+
+    /// Returns a token consumed by [`Self::try_acquire`], e.g., for a request that was rejected.
+    pub fn release(&self, key: K) {
+        if !self.config.enabled {
+            return;
+        }
+
+        let mut buckets = self.buckets.lock().expect("Rate limiter lock should not be poisoned.");
+        // A bucket that is no longer tracked was full, and there is nothing to return to it.
+        if let Some(bucket) = buckets.get_mut(&key) {
+            bucket.put_back(&self.config);
+        }
+    }
+}
+
+#[derive(Debug)]
+struct TokenBucket {
+    tokens: f64,
+    last_refill: Instant,
+}
+
+impl TokenBucket {
+    fn full(config: &RateLimiterConfig, now: Instant) -> Self {
+        Self { tokens: f64::from(config.burst_size), last_refill: now }
+    }
+
+    fn refill(&mut self, config: &RateLimiterConfig, now: Instant) -> &mut Self {
+        let elapsed = now.saturating_duration_since(self.last_refill).as_secs_f64();
+        self.tokens = (self.tokens + elapsed * f64::from(config.refill_per_second))
+            .min(f64::from(config.burst_size));
+        self.last_refill = now;
+        self
+    }
+
+    fn is_full(&self, config: &RateLimiterConfig) -> bool {
+        self.tokens >= f64::from(config.burst_size)
+    }
+
+    fn try_take(&mut self) -> bool {
+        if self.tokens < 1.0 {
+            return false;
+        }
+
+        self.tokens -= 1.0;
+        true
+    }
+
+    fn put_back(&mut self, config: &RateLimiterConfig) {
+        self.tokens = (self.tokens + 1.0).min(f64::from(config.burst_size));
+    }
+}
@@ -0,0 +1,76 @@
+use std::time::{Duration, Instant};
+
+use rstest::rstest;
+use validator::Validate;
+
+use crate::rate_limiter::{RateLimiter, RateLimiterConfig};
+
+fn rate_limiter(burst_size: u32, refill_per_second: u32) -> RateLimiter<u8> {
+    RateLimiter::new(RateLimiterConfig { enabled: true, burst_size, refill_per_second })
+}
+
+#[rstest]
+fn test_rate_limiter_rejects_requests_above_burst() {
+    let rate_limiter = rate_limiter(2, 1);
+    let now = Instant::now();
+
+    assert!(rate_limiter.try_acquire_at(0, now));
+    assert!(rate_limiter.try_acquire_at(0, now));
+    assert!(!rate_limiter.try_acquire_at(0, now));
+
+    // Other keys are limited independently.
+    assert!(rate_limiter.try_acquire_at(1, now));
+}
+
+#[rstest]
+fn test_rate_limiter_refills_over_time() {
+    let rate_limiter = rate_limiter(1, 2);
+    let now = Instant::now();
+
+    assert!(rate_limiter.try_acquire_at(0, now));
+    assert!(!rate_limiter.try_acquire_at(0, now + Duration::from_millis(250)));
+    assert!(rate_limiter.try_acquire_at(0, now + Duration::from_millis(500)));
+
+    // Tokens do not accumulate beyond the burst size.
+    let later = now + Duration::from_secs(60);
+    assert!(rate_limiter.try_acquire_at(0, later));
+    assert!(!rate_limiter.try_acquire_at(0, later));
+}
+
+#[rstest]
+fn test_rate_limiter_release_returns_token() {
+    let rate_limiter = rate_limiter(1, 1);
+    let now = Instant::now();
+
+    assert!(rate_limiter.try_acquire_at(0, now));
+    rate_limiter.release(0);
+    assert!(rate_limiter.try_acquire_at(0, now));
+    assert!(!rate_limiter.try_acquire_at(0, now));
+
+    // Releasing does not accumulate tokens beyond the burst size.
+    rate_limiter.release(1);
+    rate_limiter.release(1);
+    assert!(rate_limiter.try_acquire_at(1, now));
+    assert!(!rate_limiter.try_acquire_at(1, now));
+}
+
+#[rstest]
+fn test_disabled_rate_limiter_accepts_all_requests() {
+    let rate_limiter =
+        RateLimiter::new(RateLimiterConfig { enabled: false, burst_size: 0, refill_per_second: 0 });
+
+    assert!(rate_limiter.try_acquire(0_u8));
+}
+
+#[rstest]
+#[case::zero_burst_size(0, 1)]
+#[case::zero_refill_per_second(1, 0)]
+fn test_rate_limiter_config_rejects_zero_limits(
+    #[case] burst_size: u32,
+    #[case] refill_per_second: u32,
+) {
+    let config = RateLimiterConfig { enabled: true, burst_size, refill_per_second };
+
+    assert!(config.validate().is_err());
+    assert!(RateLimiterConfig::default().validate().is_ok());
+}
@@ -1,9 +1,10 @@
 use std::collections::BTreeMap;
 use std::net::IpAddr;
 
-use papyrus_config::dumping::{ser_param, SerializeConfig};
+use papyrus_config::dumping::{append_sub_config_name, ser_param, SerializeConfig};
 use papyrus_config::{ParamPath, ParamPrivacyInput, SerializedParam};
 use serde::{Deserialize, Serialize};
+use starknet_gateway_types::rate_limiter::RateLimiterConfig;
 use validator::Validate;
 
 /// The http server connection related configuration.
@@ -11,19 +12,31 @@ use validator::Validate;
 pub struct HttpServerConfig {
     pub ip: IpAddr,
     pub port: u16,
+    // Rate limit of requests from a single remote IP.
+    #[validate]
+    pub rate_limiter_config: RateLimiterConfig,
 }
 
 impl SerializeConfig for HttpServerConfig {
     fn dump(&self) -> BTreeMap<ParamPath, SerializedParam> {
-        BTreeMap::from_iter([
+        let mut dump = BTreeMap::from_iter([
             ser_param("ip", &self.ip.to_string(), "The http server ip.", ParamPrivacyInput::Public),
             ser_param("port", &self.port, "The http server port.", ParamPrivacyInput::Public),
-        ])
+        ]);
+        dump.append(&mut append_sub_config_name(
+            self.rate_limiter_config.dump(),
+            "rate_limiter_config",
+        ));
+        dump
     }
 }
 
 impl Default for HttpServerConfig {
     fn default() -> Self {
-        Self { ip: "0.0.0.0".parse().unwrap(), port: 8080 }
+        Self {
+            ip: "0.0.0.0".parse().unwrap(),
+            port: 8080,
+            rate_limiter_config: RateLimiterConfig::default(),
+        }
     }
 }
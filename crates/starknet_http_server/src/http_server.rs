@@ -1,8 +1,10 @@
 use std::any::type_name;
 use std::clone::Clone;
-use std::net::SocketAddr;
+use std::net::{IpAddr, SocketAddr};
+use std::sync::Arc;
 
-use axum::extract::State;
+use axum::body::Bytes;
+use axum::extract::{ConnectInfo, State};
 use axum::routing::post;
 use axum::{async_trait, Json, Router};
 use starknet_api::rpc_transaction::RpcTransaction;
@@ -10,12 +12,14 @@ use starknet_api::transaction::TransactionHash;
 use starknet_gateway_types::communication::SharedGatewayClient;
 use starknet_gateway_types::errors::GatewaySpecError;
 use starknet_gateway_types::gateway_types::GatewayInput;
+use starknet_gateway_types::rate_limiter::RateLimiter;
 use starknet_sequencer_infra::component_definitions::ComponentStarter;
 use starknet_sequencer_infra::errors::ComponentError;
-use tracing::{error, info, instrument};
+use tracing::{debug, info, instrument};
 
 use crate::config::HttpServerConfig;
 use crate::errors::HttpServerRunError;
+use crate::json_rpc::{handle_add_tx_request, parse_request, to_spec_error, JsonRpcResponse};
 
 #[cfg(test)]
 #[path = "http_server_test.rs"]
@@ -31,27 +35,34 @@ pub struct HttpServer {
 #[derive(Clone)]
 pub struct AppState {
     pub gateway_client: SharedGatewayClient,
+    pub rate_limiter: Arc<RateLimiter<IpAddr>>,
 }
 
 impl HttpServer {
     pub fn new(config: HttpServerConfig, gateway_client: SharedGatewayClient) -> Self {
-        let app_state = AppState { gateway_client };
+        let rate_limiter = Arc::new(RateLimiter::new(config.rate_limiter_config));
+        let app_state = AppState { gateway_client, rate_limiter };
         HttpServer { config, app_state }
     }
 
     pub async fn run(&mut self) -> Result<(), HttpServerRunError> {
         // Parses the bind address from HttpServerConfig, returning an error for invalid addresses.
-        let HttpServerConfig { ip, port } = self.config;
+        let HttpServerConfig { ip, port, .. } = self.config;
         let addr = SocketAddr::new(ip, port);
         let app = self.app();
         info!("HttpServer running using socket: {}", addr);
 
         // Create a server that runs forever.
-        Ok(axum::Server::bind(&addr).serve(app.into_make_service()).await?)
+        Ok(axum::Server::bind(&addr)
+            .serve(app.into_make_service_with_connect_info::<SocketAddr>())
+            .await?)
     }
 
     pub fn app(&self) -> Router {
-        Router::new().route("/add_tx", post(add_tx)).with_state(self.app_state.clone())
+        Router::new()
+            .route("/add_tx", post(add_tx))
+            .route("/rpc", post(json_rpc))
+            .with_state(self.app_state.clone())
     }
 }
 
@@ -60,16 +71,50 @@ impl HttpServer {
 #[instrument(skip(app_state))]
 async fn add_tx(
     State(app_state): State<AppState>,
+    remote_addr: Option<ConnectInfo<SocketAddr>>,
     Json(tx): Json<RpcTransaction>,
 ) -> HttpServerResult<Json<TransactionHash>> {
+    check_rate_limit(&app_state, remote_addr)?;
+
     let gateway_input: GatewayInput = GatewayInput { rpc_tx: tx.clone(), message_metadata: None };
 
-    let add_tx_result = app_state.gateway_client.add_tx(gateway_input).await.map_err(|join_err| {
-        error!("Failed to process tx: {}", join_err);
-        GatewaySpecError::UnexpectedError { data: "Internal server error".to_owned() }
-    });
+    let add_tx_result = app_state.gateway_client.add_tx(gateway_input).await.map_err(to_spec_error);
+
+    add_tx_result_as_json(add_tx_result.map(|gateway_output| gateway_output.transaction_hash()))
+}
 
-    add_tx_result_as_json(add_tx_result)
+/// Serves the Starknet RPC write API methods, i.e., `starknet_add*Transaction`, over JSON-RPC 2.0.
+#[instrument(skip(app_state, body))]
+async fn json_rpc(
+    State(app_state): State<AppState>,
+    remote_addr: Option<ConnectInfo<SocketAddr>>,
+    body: Bytes,
+) -> Json<JsonRpcResponse> {
+    let request = match parse_request(&body) {
+        Ok(request) => request,
+        Err(response) => return Json(response),
+    };
+    if let Err(error) = check_rate_limit(&app_state, remote_addr) {
+        return Json(JsonRpcResponse::error(request.id, error.into()));
+    }
+
+    Json(handle_add_tx_request(app_state.gateway_client.as_ref(), request).await)
+}
+
+fn check_rate_limit(
+    app_state: &AppState,
+    remote_addr: Option<ConnectInfo<SocketAddr>>,
+) -> HttpServerResult<()> {
+    // The remote address is unknown if the app is not served over a socket, e.g., in tests.
+    let Some(ConnectInfo(remote_addr)) = remote_addr else {
+        return Ok(());
+    };
+
+    if !app_state.rate_limiter.try_acquire(remote_addr.ip()) {
+        debug!("Rate limit exceeded for remote ip {}.", remote_addr.ip());
+        return Err(GatewaySpecError::RateLimited);
+    }
+    Ok(())
 }
 
 pub(crate) fn add_tx_result_as_json(
@@ -1,9 +1,18 @@
+use std::sync::Arc;
+
 use axum::body::{Bytes, HttpBody};
+use axum::extract::State;
 use axum::http::StatusCode;
 use axum::response::{IntoResponse, Response};
+use axum::Json;
+use starknet_api::invoke_tx_args;
+use starknet_api::test_utils::invoke::rpc_invoke_tx;
 use starknet_api::transaction::TransactionHash;
+use starknet_gateway_types::communication::{GatewayClientError, MockGatewayClient};
+use starknet_gateway_types::errors::{GatewayError, GatewaySpecError};
+use starknet_gateway_types::rate_limiter::{RateLimiter, RateLimiterConfig};
 
-use crate::http_server::add_tx_result_as_json;
+use crate::http_server::{add_tx, add_tx_result_as_json, AppState};
 
 #[tokio::test]
 async fn test_tx_hash_json_conversion() {
@@ -17,6 +26,32 @@ async fn test_tx_hash_json_conversion() {
     assert_eq!(tx_hash, serde_json::from_slice(response_bytes).unwrap());
 }
 
+#[tokio::test]
+async fn test_rate_limited_error_conversion() {
+    let response = add_tx_result_as_json(Err(GatewaySpecError::RateLimited)).into_response();
+
+    assert_eq!(response.status(), StatusCode::TOO_MANY_REQUESTS);
+}
+
+#[tokio::test]
+async fn test_gateway_spec_error_is_passed_through() {
+    let mut gateway_client = MockGatewayClient::new();
+    gateway_client.expect_add_tx().once().return_once(|_| {
+        Err(GatewayClientError::GatewayError(GatewayError::GatewaySpecError {
+            source: GatewaySpecError::DuplicateTx,
+            p2p_message_metadata: None,
+        }))
+    });
+    let app_state = AppState {
+        gateway_client: Arc::new(gateway_client),
+        rate_limiter: Arc::new(RateLimiter::new(RateLimiterConfig::default())),
+    };
+
+    let result = add_tx(State(app_state), None, Json(rpc_invoke_tx(invoke_tx_args!()))).await;
+
+    assert_eq!(result.unwrap_err(), GatewaySpecError::DuplicateTx);
+}
+
 async fn to_bytes(res: Response) -> Bytes {
     res.into_body().collect().await.unwrap().to_bytes()
 }
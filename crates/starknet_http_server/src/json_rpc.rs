@@ -0,0 +1,190 @@
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use starknet_api::rpc_transaction::RpcTransaction;
+use starknet_gateway_types::communication::{GatewayClient, GatewayClientError};
+use starknet_gateway_types::errors::{GatewayError, GatewaySpecError};
+use starknet_gateway_types::gateway_types::{GatewayInput, GatewayOutput};
+use tracing::error;
+
+#[cfg(test)]
+#[path = "json_rpc_test.rs"]
+pub mod json_rpc_test;
+
+const JSON_RPC_VERSION: &str = "2.0";
+
+// Error codes defined by the JSON-RPC 2.0 specification.
+const PARSE_ERROR: i32 = -32700;
+const INVALID_REQUEST: i32 = -32600;
+const METHOD_NOT_FOUND: i32 = -32601;
+const INVALID_PARAMS: i32 = -32602;
+
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+pub struct JsonRpcRequest {
+    pub jsonrpc: String,
+    #[serde(default)]
+    pub id: Value,
+    pub method: String,
+    #[serde(default)]
+    pub params: Value,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+pub struct JsonRpcResponse {
+    pub jsonrpc: String,
+    pub id: Value,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub result: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<JsonRpcErrorObject>,
+}
+
+impl JsonRpcResponse {
+    pub fn result(id: Value, result: Value) -> Self {
+        Self { jsonrpc: JSON_RPC_VERSION.to_owned(), id, result: Some(result), error: None }
+    }
+
+    pub fn error(id: Value, error: JsonRpcErrorObject) -> Self {
+        Self { jsonrpc: JSON_RPC_VERSION.to_owned(), id, result: None, error: Some(error) }
+    }
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+pub struct JsonRpcErrorObject {
+    pub code: i32,
+    pub message: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub data: Option<Value>,
+}
+
+impl JsonRpcErrorObject {
+    fn new(code: i32, message: &str) -> Self {
+        Self { code, message: message.to_owned(), data: None }
+    }
+}
+
+impl From<GatewaySpecError> for JsonRpcErrorObject {
+    fn from(error: GatewaySpecError) -> Self {
+        let as_rpc = error.into_rpc();
+        Self {
+            code: as_rpc.code,
+            message: as_rpc.message.to_owned(),
+            data: as_rpc.data.map(Value::String),
+        }
+    }
+}
+
+/// The Starknet RPC write API methods served by the HTTP server.
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum AddTxMethod {
+    Declare,
+    DeployAccount,
+    Invoke,
+}
+
+impl AddTxMethod {
+    fn from_name(method: &str) -> Option<Self> {
+        match method {
+            "starknet_addDeclareTransaction" => Some(AddTxMethod::Declare),
+            "starknet_addDeployAccountTransaction" => Some(AddTxMethod::DeployAccount),
+            "starknet_addInvokeTransaction" => Some(AddTxMethod::Invoke),
+            _ => None,
+        }
+    }
+
+    fn param_name(&self) -> &'static str {
+        match self {
+            AddTxMethod::Declare => "declare_transaction",
+            AddTxMethod::DeployAccount => "deploy_account_transaction",
+            AddTxMethod::Invoke => "invoke_transaction",
+        }
+    }
+
+    fn matches(&self, tx: &RpcTransaction) -> bool {
+        matches!(
+            (self, tx),
+            (AddTxMethod::Declare, RpcTransaction::Declare(_))
+                | (AddTxMethod::DeployAccount, RpcTransaction::DeployAccount(_))
+                | (AddTxMethod::Invoke, RpcTransaction::Invoke(_))
+        )
+    }
+}
+
+/// Parses a JSON-RPC request; on failure, returns the error response to send back.
+pub fn parse_request(body: &[u8]) -> Result<JsonRpcRequest, JsonRpcResponse> {
+    let value: Value = serde_json::from_slice(body).map_err(|_| {
+        JsonRpcResponse::error(Value::Null, JsonRpcErrorObject::new(PARSE_ERROR, "Parse error"))
+    })?;
+    let id = value.get("id").cloned().unwrap_or_default();
+    let invalid_request = || {
+        JsonRpcResponse::error(
+            id.clone(),
+            JsonRpcErrorObject::new(INVALID_REQUEST, "Invalid request"),
+        )
+    };
+
+    let request: JsonRpcRequest = serde_json::from_value(value).map_err(|_| invalid_request())?;
+    if request.jsonrpc != JSON_RPC_VERSION {
+        return Err(invalid_request());
+    }
+
+    Ok(request)
+}
+
+/// Handles a request of one of the `starknet_add*Transaction` methods, forwarding the transaction
+/// to the gateway.
+pub async fn handle_add_tx_request(
+    gateway_client: &dyn GatewayClient,
+    request: JsonRpcRequest,
+) -> JsonRpcResponse {
+    let JsonRpcRequest { id, method, params, .. } = request;
+    let Some(method) = AddTxMethod::from_name(&method) else {
+        return JsonRpcResponse::error(
+            id,
+            JsonRpcErrorObject::new(METHOD_NOT_FOUND, "Method not found"),
+        );
+    };
+    let Some(rpc_tx) = parse_tx_param(params, method) else {
+        return JsonRpcResponse::error(
+            id,
+            JsonRpcErrorObject::new(INVALID_PARAMS, "Invalid params"),
+        );
+    };
+
+    let gateway_input = GatewayInput { rpc_tx, message_metadata: None };
+    match gateway_client.add_tx(gateway_input).await {
+        Ok(gateway_output) => JsonRpcResponse::result(id, output_as_json(gateway_output)),
+        Err(error) => JsonRpcResponse::error(id, to_spec_error(error).into()),
+    }
+}
+
+/// Extracts the transaction from the params, given either by name or by position, and checks it
+/// is of the method's transaction type.
+fn parse_tx_param(params: Value, method: AddTxMethod) -> Option<RpcTransaction> {
+    let tx = match params {
+        Value::Object(mut params) if params.len() == 1 => params.remove(method.param_name())?,
+        Value::Array(params) if params.len() == 1 => params.into_iter().next()?,
+        _ => return None,
+    };
+
+    let tx: RpcTransaction = serde_json::from_value(tx).ok()?;
+    method.matches(&tx).then_some(tx)
+}
+
+fn output_as_json(gateway_output: GatewayOutput) -> Value {
+    let result = match gateway_output {
+        GatewayOutput::Declare(output) => serde_json::to_value(output),
+        GatewayOutput::DeployAccount(output) => serde_json::to_value(output),
+        GatewayOutput::Invoke(output) => serde_json::to_value(output),
+    };
+    result.expect("Gateway output should be serializable.")
+}
+
+pub(crate) fn to_spec_error(error: GatewayClientError) -> GatewaySpecError {
+    match error {
+        GatewayClientError::GatewayError(GatewayError::GatewaySpecError { source, .. }) => source,
+        GatewayClientError::ClientError(error) => {
+            error!("Failed to send tx to the gateway: {}", error);
+            GatewaySpecError::UnexpectedError { data: "Internal server error".to_owned() }
+        }
+    }
+}
@@ -0,0 +1,124 @@
+use rstest::rstest;
+use serde_json::{json, Value};
+use starknet_api::core::ContractAddress;
+use starknet_api::test_utils::deploy_account::rpc_deploy_account_tx;
+use starknet_api::test_utils::invoke::rpc_invoke_tx;
+use starknet_api::transaction::TransactionHash;
+use starknet_api::{deploy_account_tx_args, invoke_tx_args};
+use starknet_gateway_types::communication::{GatewayClientError, MockGatewayClient};
+use starknet_gateway_types::errors::{GatewayError, GatewaySpecError};
+use starknet_gateway_types::gateway_types::{
+    DeployAccountGatewayOutput,
+    GatewayOutput,
+    InvokeGatewayOutput,
+};
+
+use crate::json_rpc::{handle_add_tx_request, parse_request, JsonRpcResponse};
+
+async fn handle(gateway_client: &MockGatewayClient, body: Value) -> JsonRpcResponse {
+    let request = parse_request(body.to_string().as_bytes()).unwrap();
+    handle_add_tx_request(gateway_client, request).await
+}
+
+fn request(method: &str, params: Value) -> Value {
+    json!({"jsonrpc": "2.0", "id": 1, "method": method, "params": params})
+}
+
+#[rstest]
+#[case::named_params(|tx| json!({"invoke_transaction": tx}))]
+#[case::positional_params(|tx| json!([tx]))]
+#[tokio::test]
+async fn test_add_invoke_tx(#[case] to_params: fn(Value) -> Value) {
+    let tx = rpc_invoke_tx(invoke_tx_args!());
+    let tx_hash = TransactionHash(1_u8.into());
+    let mut gateway_client = MockGatewayClient::new();
+    gateway_client.expect_add_tx().once().return_once(move |_| {
+        Ok(GatewayOutput::Invoke(InvokeGatewayOutput { transaction_hash: tx_hash }))
+    });
+
+    let params = to_params(serde_json::to_value(tx).unwrap());
+    let response = handle(&gateway_client, request("starknet_addInvokeTransaction", params)).await;
+
+    assert_eq!(response, JsonRpcResponse::result(json!(1), json!({"transaction_hash": tx_hash})));
+}
+
+#[tokio::test]
+async fn test_add_deploy_account_tx_returns_contract_address() {
+    let tx = rpc_deploy_account_tx(deploy_account_tx_args!());
+    let tx_hash = TransactionHash(1_u8.into());
+    let contract_address = ContractAddress::from(2_u8);
+    let mut gateway_client = MockGatewayClient::new();
+    gateway_client.expect_add_tx().once().return_once(move |_| {
+        Ok(GatewayOutput::DeployAccount(DeployAccountGatewayOutput {
+            transaction_hash: tx_hash,
+            contract_address,
+        }))
+    });
+
+    let params = json!({"deploy_account_transaction": tx});
+    let response =
+        handle(&gateway_client, request("starknet_addDeployAccountTransaction", params)).await;
+
+    let expected_result =
+        json!({"transaction_hash": tx_hash, "contract_address": contract_address});
+    assert_eq!(response, JsonRpcResponse::result(json!(1), expected_result));
+}
+
+#[tokio::test]
+async fn test_gateway_spec_error_is_mapped_to_rpc_error() {
+    let tx = rpc_invoke_tx(invoke_tx_args!());
+    let mut gateway_client = MockGatewayClient::new();
+    gateway_client.expect_add_tx().once().return_once(|_| {
+        Err(GatewayClientError::GatewayError(GatewayError::GatewaySpecError {
+            source: GatewaySpecError::DuplicateTx,
+            p2p_message_metadata: None,
+        }))
+    });
+
+    let params = json!({"invoke_transaction": tx});
+    let response = handle(&gateway_client, request("starknet_addInvokeTransaction", params)).await;
+
+    let expected_error = GatewaySpecError::DuplicateTx.into();
+    assert_eq!(response, JsonRpcResponse::error(json!(1), expected_error));
+}
+
+#[rstest]
+#[case::unknown_method("starknet_getNonce", json!([]), -32601)]
+#[case::mismatching_tx_type(
+    "starknet_addDeclareTransaction",
+    json!({"declare_transaction": rpc_invoke_tx(invoke_tx_args!())}),
+    -32602
+)]
+#[case::missing_params("starknet_addInvokeTransaction", json!({}), -32602)]
+#[tokio::test]
+async fn test_invalid_add_tx_request(
+    #[case] method: &str,
+    #[case] params: Value,
+    #[case] expected_error_code: i32,
+) {
+    // The gateway is not expected to be called.
+    let gateway_client = MockGatewayClient::new();
+
+    let response = handle(&gateway_client, request(method, params)).await;
+
+    assert_eq!(response.error.unwrap().code, expected_error_code);
+}
+
+#[rstest]
+#[case::invalid_json(b"{".as_slice(), Value::Null, -32700)]
+#[case::missing_method(br#"{"jsonrpc": "2.0", "id": 7}"#.as_slice(), json!(7), -32600)]
+#[case::wrong_version(
+    br#"{"jsonrpc": "1.0", "id": 7, "method": "starknet_addInvokeTransaction"}"#.as_slice(),
+    json!(7),
+    -32600
+)]
+fn test_parse_invalid_request(
+    #[case] body: &[u8],
+    #[case] expected_id: Value,
+    #[case] expected_error_code: i32,
+) {
+    let response = parse_request(body).unwrap_err();
+
+    assert_eq!(response.id, expected_id);
+    assert_eq!(response.error.unwrap().code, expected_error_code);
+}
@@ -2,3 +2,4 @@ pub mod communication;
 pub mod config;
 pub mod errors;
 pub mod http_server;
+pub mod json_rpc;
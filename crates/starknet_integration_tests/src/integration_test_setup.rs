@@ -62,7 +62,7 @@ impl IntegrationTestSetup {
         let MonitoringEndpointConfig { ip, port } = config.monitoring_endpoint_config;
         let is_alive_test_client = IsAliveClient::new(SocketAddr::from((ip, port)));
 
-        let HttpServerConfig { ip, port } = config.http_server_config;
+        let HttpServerConfig { ip, port, .. } = config.http_server_config;
         let add_tx_http_client = HttpTestClient::new(SocketAddr::from((ip, port)));
 
         IntegrationTestSetup {
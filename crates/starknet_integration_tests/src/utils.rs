@@ -245,13 +245,18 @@ pub async fn create_gateway_config(chain_info: ChainInfo) -> GatewayConfig {
     };
     let stateful_tx_validator_config = StatefulTransactionValidatorConfig::default();
 
-    GatewayConfig { stateless_tx_validator_config, stateful_tx_validator_config, chain_info }
+    GatewayConfig {
+        stateless_tx_validator_config,
+        stateful_tx_validator_config,
+        chain_info,
+        ..Default::default()
+    }
 }
 
 pub async fn create_http_server_config() -> HttpServerConfig {
     // TODO(Tsabary): use ser_generated_param.
     let socket = get_available_socket().await;
-    HttpServerConfig { ip: socket.ip(), port: socket.port() }
+    HttpServerConfig { ip: socket.ip(), port: socket.port(), ..Default::default() }
 }
 
 pub fn create_batcher_config(
@@ -112,3 +112,20 @@ fn validation_start_errors() {
         L1ProviderError::UnexpectedProviderStateTransition { from: Validate, to: Propose }
     );
 }
+
+#[test]
+fn commit_block_returns_uncommitted_proposed_txs() {
+    // Setup.
+    let txs = [tx!(tx_hash: 0), tx!(tx_hash: 1), tx!(tx_hash: 2)];
+    let mut l1_provider = L1ProviderContentBuilder::new()
+        .with_txs(txs.clone())
+        .with_state(Propose)
+        .build_into_l1_provider();
+    l1_provider.get_txs(2).unwrap();
+
+    // Test.
+    l1_provider.commit_block(&[tx_hash!(1)]);
+
+    l1_provider.proposal_start().unwrap();
+    assert_eq!(l1_provider.get_txs(3).unwrap(), [txs[0].clone(), txs[2].clone()]);
+}
@@ -0,0 +1,200 @@
+use std::fmt::Display;
+use std::sync::{Arc, Mutex};
+
+use papyrus_base_layer::{BaseLayerContract, L1BlockHash, L1BlockNumber, L1Event, L1ToL2Message};
+use starknet_api::executable_transaction::L1HandlerTransaction;
+use starknet_api::hash::StarkHash;
+use starknet_api::transaction::fields::{Calldata, Fee};
+use starknet_api::transaction::{TransactionHash, TransactionHasher, TransactionVersion};
+use starknet_api::StarknetApiError;
+use thiserror::Error;
+use tracing::{debug, error, info};
+
+use crate::{Event, L1Provider, L1ProviderConfig};
+
+#[cfg(test)]
+#[path = "l1_scraper_test.rs"]
+pub mod l1_scraper_test;
+
+type L1ScraperResult<T, B> = Result<T, L1ScraperError<<B as BaseLayerContract>::Error>>;
+
+/// Scrapes L1 to L2 messaging events from the base layer, and feeds them into the L1 provider.
+pub struct L1Scraper<B: BaseLayerContract> {
+    config: L1ProviderConfig,
+    base_layer: B,
+    l1_provider: Arc<Mutex<L1Provider>>,
+    next_block_number_to_fetch: L1BlockNumber,
+    // The last scraped block and its hash, used to detect L1 reorgs.
+    last_scraped_block: Option<(L1BlockNumber, L1BlockHash)>,
+}
+
+impl<B: BaseLayerContract + Send + Sync> L1Scraper<B> {
+    /// Creates a scraper that starts from `config.startup_rewind_l1_blocks` blocks before the
+    /// latest L1 block, so that messages sent shortly before startup are not missed.
+    pub async fn new(
+        config: L1ProviderConfig,
+        base_layer: B,
+        l1_provider: Arc<Mutex<L1Provider>>,
+    ) -> L1ScraperResult<Self, B> {
+        let mut scraper = Self {
+            config,
+            base_layer,
+            l1_provider,
+            next_block_number_to_fetch: 0,
+            last_scraped_block: None,
+        };
+        scraper.rewind().await?;
+        Ok(scraper)
+    }
+
+    /// Polls the base layer every `config.polling_interval`, forever.
+    pub async fn run(&mut self)
+    where
+        B::Error: Display,
+    {
+        let mut interval = tokio::time::interval(self.config.polling_interval);
+        loop {
+            interval.tick().await;
+            if let Err(error) = self.poll().await {
+                error!("Failed to scrape L1 events: {error}");
+            }
+        }
+    }
+
+    /// Fetches the events of all finalized L1 blocks that were not yet scraped, and applies them
+    /// to the provider. If the last scraped block was reorged out of L1, handles the reorg first.
+    pub async fn poll(&mut self) -> L1ScraperResult<(), B> {
+        if self.is_last_scraped_block_reorged().await? {
+            info!("Detected an L1 reorg of the scraped blocks.");
+            self.handle_reorg().await?;
+        }
+
+        let Some(latest_block_number) = self.latest_l1_block_number().await? else {
+            return Ok(());
+        };
+        if latest_block_number < self.next_block_number_to_fetch {
+            return Ok(());
+        }
+
+        // The hash is fetched before the events, so a reorg in between is detected by the next
+        // poll.
+        let latest_block_hash = self
+            .base_layer
+            .l1_block_hash(latest_block_number)
+            .await
+            .map_err(L1ScraperError::BaseLayer)?;
+        let block_range = self.next_block_number_to_fetch..=latest_block_number;
+        debug!("Scraping L1 events in blocks {block_range:?}.");
+        let events =
+            self.base_layer.events(block_range).await.map_err(L1ScraperError::BaseLayer)?;
+        let events = events
+            .into_iter()
+            .filter_map(|event| self.event_from_l1_event(event).transpose())
+            .collect::<Result<Vec<_>, _>>()?;
+
+        self.l1_provider
+            .lock()
+            .expect("L1 provider lock should not be poisoned.")
+            .apply_events(events);
+        self.next_block_number_to_fetch = latest_block_number + 1;
+        self.last_scraped_block = latest_block_hash.map(|hash| (latest_block_number, hash));
+        Ok(())
+    }
+
+    /// Resets the provider and rewinds the scraper, so that the provider's buffers are rebuilt from
+    /// the recent L1 history. Called by [`Self::poll`] when it detects a reorg.
+    pub async fn handle_reorg(&mut self) -> L1ScraperResult<(), B> {
+        self.l1_provider
+            .lock()
+            .expect("L1 provider lock should not be poisoned.")
+            .handle_reorg()
+            .expect("Resetting the L1 provider should not fail.");
+        self.rewind().await
+    }
+
+    async fn rewind(&mut self) -> L1ScraperResult<(), B> {
+        let latest_block_number = self.latest_l1_block_number().await?.unwrap_or_default();
+        self.next_block_number_to_fetch =
+            latest_block_number.saturating_sub(self.config.startup_rewind_l1_blocks);
+        self.last_scraped_block = None;
+        info!("Scraping L1 events starting from block {}.", self.next_block_number_to_fetch);
+        Ok(())
+    }
+
+    async fn is_last_scraped_block_reorged(&self) -> L1ScraperResult<bool, B> {
+        let Some((block_number, block_hash)) = self.last_scraped_block else {
+            return Ok(false);
+        };
+        let current_block_hash =
+            self.base_layer.l1_block_hash(block_number).await.map_err(L1ScraperError::BaseLayer)?;
+        Ok(current_block_hash != Some(block_hash))
+    }
+
+    async fn latest_l1_block_number(&self) -> L1ScraperResult<Option<L1BlockNumber>, B> {
+        self.base_layer
+            .latest_l1_block_number(self.config.finality)
+            .await
+            .map_err(L1ScraperError::BaseLayer)
+    }
+
+    fn event_from_l1_event(&self, event: L1Event) -> L1ScraperResult<Option<Event>, B> {
+        let event = match event {
+            L1Event::LogMessageToL2 { message, fee } => {
+                Event::L1HandlerTransaction(self.l1_handler_tx(message, fee)?)
+            }
+            L1Event::ConsumedMessageToL2(message) => {
+                Event::TransactionConsumed(self.l1_handler_tx_hash(message)?)
+            }
+            L1Event::MessageToL2Canceled(message) => {
+                Event::TransactionCanceled(self.l1_handler_tx_hash(message)?)
+            }
+            // The message may still be consumed until its cancellation is completed.
+            L1Event::MessageToL2CancellationStarted(message) => {
+                debug!("Cancellation started for L1 to L2 message: {message:?}.");
+                return Ok(None);
+            }
+        };
+
+        Ok(Some(event))
+    }
+
+    fn l1_handler_tx(
+        &self,
+        message: L1ToL2Message,
+        paid_fee_on_l1: Fee,
+    ) -> L1ScraperResult<L1HandlerTransaction, B> {
+        let tx = l1_handler_tx_from_message(message);
+        let tx_hash = tx.calculate_transaction_hash(&self.config.chain_id, &tx.version)?;
+        Ok(L1HandlerTransaction { tx, tx_hash, paid_fee_on_l1 })
+    }
+
+    fn l1_handler_tx_hash(&self, message: L1ToL2Message) -> L1ScraperResult<TransactionHash, B> {
+        let tx = l1_handler_tx_from_message(message);
+        Ok(tx.calculate_transaction_hash(&self.config.chain_id, &tx.version)?)
+    }
+}
+
+fn l1_handler_tx_from_message(
+    message: L1ToL2Message,
+) -> starknet_api::transaction::L1HandlerTransaction {
+    let L1ToL2Message { from_address, to_address, entry_point_selector, payload, nonce } = message;
+    // The sender's address is passed to the L1 handler as the first calldata element.
+    let calldata =
+        std::iter::once(StarkHash::from(from_address)).chain(payload.0.iter().copied()).collect();
+
+    starknet_api::transaction::L1HandlerTransaction {
+        version: TransactionVersion::ZERO,
+        nonce,
+        contract_address: to_address,
+        entry_point_selector,
+        calldata: Calldata(Arc::new(calldata)),
+    }
+}
+
+#[derive(Error, Debug)]
+pub enum L1ScraperError<E> {
+    #[error("Base layer error: {0}")]
+    BaseLayer(E),
+    #[error(transparent)]
+    HashCalculationError(#[from] StarknetApiError),
+}
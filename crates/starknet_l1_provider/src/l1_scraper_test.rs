@@ -0,0 +1,173 @@
+use std::sync::{Arc, Mutex};
+
+use papyrus_base_layer::{L1Event, L1ToL2Message};
+use pretty_assertions::assert_eq;
+use starknet_api::core::{ContractAddress, EntryPointSelector, EthAddress, Nonce};
+use starknet_api::executable_transaction::L1HandlerTransaction;
+use starknet_api::transaction::fields::Fee;
+use starknet_api::transaction::{TransactionHash, TransactionHasher};
+use starknet_api::{calldata, felt};
+
+use crate::l1_scraper::{l1_handler_tx_from_message, L1Scraper};
+use crate::test_utils::FakeBaseLayer;
+use crate::{L1Provider, L1ProviderConfig, ValidationStatus};
+
+fn message(nonce: u8) -> L1ToL2Message {
+    L1ToL2Message {
+        from_address: EthAddress::try_from(felt!(7_u8)).unwrap(),
+        to_address: ContractAddress::from(1_u8),
+        entry_point_selector: EntryPointSelector(felt!(2_u8)),
+        payload: calldata![felt!(3_u8), felt!(4_u8)],
+        nonce: Nonce(felt!(nonce)),
+    }
+}
+
+fn log_message_to_l2(nonce: u8) -> L1Event {
+    L1Event::LogMessageToL2 { message: message(nonce), fee: Fee(nonce.into()) }
+}
+
+fn tx_hash(nonce: u8) -> TransactionHash {
+    let config = L1ProviderConfig::default();
+    let tx = l1_handler_tx_from_message(message(nonce));
+    tx.calculate_transaction_hash(&config.chain_id, &tx.version).unwrap()
+}
+
+async fn scraper(
+    base_layer: &FakeBaseLayer,
+    config: L1ProviderConfig,
+) -> (L1Scraper<FakeBaseLayer>, Arc<Mutex<L1Provider>>) {
+    let l1_provider = Arc::new(Mutex::new(L1Provider::new()));
+    let scraper = L1Scraper::new(config, base_layer.clone(), l1_provider.clone()).await.unwrap();
+    (scraper, l1_provider)
+}
+
+fn proposable_txs(l1_provider: &Mutex<L1Provider>) -> Vec<L1HandlerTransaction> {
+    let mut l1_provider = l1_provider.lock().unwrap();
+    l1_provider.proposal_start().unwrap();
+    let txs = l1_provider.get_txs(usize::MAX).unwrap();
+    l1_provider.commit_block(&[]);
+    txs
+}
+
+fn validation_status(l1_provider: &Mutex<L1Provider>, nonce: u8) -> ValidationStatus {
+    let mut l1_provider = l1_provider.lock().unwrap();
+    l1_provider.validation_start().unwrap();
+    let status = l1_provider.validate(tx_hash(nonce)).unwrap();
+    l1_provider.commit_block(&[]);
+    status
+}
+
+#[tokio::test]
+async fn scrape_messages_into_l1_handler_txs() {
+    // Setup.
+    let base_layer = FakeBaseLayer::default();
+    base_layer.add_block([]);
+    let (mut scraper, l1_provider) = scraper(&base_layer, L1ProviderConfig::default()).await;
+    base_layer.add_block([log_message_to_l2(0), log_message_to_l2(1)]);
+
+    // Test.
+    scraper.poll().await.unwrap();
+
+    let txs = proposable_txs(&l1_provider);
+    assert_eq!(txs.iter().map(|tx| tx.tx_hash).collect::<Vec<_>>(), [tx_hash(0), tx_hash(1)]);
+    let tx = &txs[1];
+    assert_eq!(tx.paid_fee_on_l1, Fee(1));
+    assert_eq!(tx.tx.nonce, Nonce(felt!(1_u8)));
+    // The sender is prepended to the payload.
+    assert_eq!(tx.tx.calldata, calldata![felt!(7_u8), felt!(3_u8), felt!(4_u8)]);
+
+    // Scraped blocks are not scraped again.
+    scraper.poll().await.unwrap();
+    assert_eq!(proposable_txs(&l1_provider).len(), 2);
+}
+
+#[tokio::test]
+async fn startup_rewinds_and_waits_for_finality() {
+    // Setup.
+    let base_layer = FakeBaseLayer::default();
+    base_layer.add_block([log_message_to_l2(0)]);
+    base_layer.add_block([log_message_to_l2(1)]);
+    base_layer.add_block([log_message_to_l2(2)]);
+    let config =
+        L1ProviderConfig { finality: 1, startup_rewind_l1_blocks: 0, ..Default::default() };
+    let (mut scraper, l1_provider) = scraper(&base_layer, config).await;
+
+    // Test.
+    // Blocks older than the rewind, and blocks without enough confirmations, are not scraped.
+    scraper.poll().await.unwrap();
+    assert_eq!(validation_status(&l1_provider, 0), ValidationStatus::ConsumedOnL1OrUnknown);
+    assert_eq!(validation_status(&l1_provider, 1), ValidationStatus::Validated);
+    assert_eq!(validation_status(&l1_provider, 2), ValidationStatus::ConsumedOnL1OrUnknown);
+
+    base_layer.add_block([]);
+    scraper.poll().await.unwrap();
+    assert_eq!(validation_status(&l1_provider, 2), ValidationStatus::Validated);
+}
+
+#[tokio::test]
+async fn committed_txs_are_removed_once_consumed() {
+    // Setup.
+    let base_layer = FakeBaseLayer::default();
+    base_layer.add_block([]);
+    let (mut scraper, l1_provider) = scraper(&base_layer, L1ProviderConfig::default()).await;
+    base_layer.add_block([log_message_to_l2(0), log_message_to_l2(1)]);
+    scraper.poll().await.unwrap();
+
+    // Test.
+    l1_provider.lock().unwrap().commit_block(&[tx_hash(0)]);
+    assert_eq!(validation_status(&l1_provider, 0), ValidationStatus::AlreadyIncludedOnL2);
+
+    base_layer.add_block([
+        L1Event::ConsumedMessageToL2(message(0)),
+        L1Event::MessageToL2CancellationStarted(message(1)),
+    ]);
+    scraper.poll().await.unwrap();
+    assert_eq!(validation_status(&l1_provider, 0), ValidationStatus::ConsumedOnL1OrUnknown);
+    // The message can still be consumed until its cancellation completes.
+    assert_eq!(validation_status(&l1_provider, 1), ValidationStatus::Validated);
+
+    base_layer.add_block([L1Event::MessageToL2Canceled(message(1))]);
+    scraper.poll().await.unwrap();
+    assert_eq!(validation_status(&l1_provider, 1), ValidationStatus::ConsumedOnL1OrUnknown);
+}
+
+#[tokio::test]
+async fn reorg_rebuilds_provider_from_l1() {
+    // Setup.
+    let base_layer = FakeBaseLayer::default();
+    base_layer.add_block([]);
+    let (mut scraper, l1_provider) = scraper(&base_layer, L1ProviderConfig::default()).await;
+    let reorged_block = base_layer.add_block([log_message_to_l2(0)]);
+    scraper.poll().await.unwrap();
+
+    // Test.
+    // The reorg replaces the last scraped block, and is detected by the next poll.
+    base_layer.remove_blocks_from(reorged_block);
+    base_layer.add_block([log_message_to_l2(1)]);
+    scraper.poll().await.unwrap();
+
+    assert_eq!(validation_status(&l1_provider, 0), ValidationStatus::ConsumedOnL1OrUnknown);
+    assert_eq!(validation_status(&l1_provider, 1), ValidationStatus::Validated);
+}
+
+#[tokio::test]
+async fn reorg_keeps_txs_included_on_l2() {
+    // Setup.
+    let base_layer = FakeBaseLayer::default();
+    base_layer.add_block([]);
+    let (mut scraper, l1_provider) = scraper(&base_layer, L1ProviderConfig::default()).await;
+    base_layer.add_block([log_message_to_l2(0), log_message_to_l2(1)]);
+    let reorged_block = base_layer.add_block([]);
+    scraper.poll().await.unwrap();
+    l1_provider.lock().unwrap().commit_block(&[tx_hash(0)]);
+
+    // Test.
+    base_layer.remove_blocks_from(reorged_block);
+    base_layer.add_block([]);
+    scraper.poll().await.unwrap();
+
+    // The included transaction is rescraped, but not proposed again.
+    let txs = proposable_txs(&l1_provider);
+    assert_eq!(txs.iter().map(|tx| tx.tx_hash).collect::<Vec<_>>(), [tx_hash(1)]);
+    assert_eq!(validation_status(&l1_provider, 0), ValidationStatus::AlreadyIncludedOnL2);
+}
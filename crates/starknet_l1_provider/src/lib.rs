@@ -1,11 +1,22 @@
 pub mod errors;
+pub mod l1_scraper;
 
 #[cfg(test)]
 pub mod test_utils;
 
+use std::collections::BTreeMap;
+use std::time::Duration;
+
 use indexmap::{IndexMap, IndexSet};
+use papyrus_config::converters::deserialize_seconds_to_duration;
+use papyrus_config::dumping::{ser_param, SerializeConfig};
+use papyrus_config::{ParamPath, ParamPrivacyInput, SerializedParam};
+use serde::{Deserialize, Serialize};
+use starknet_api::core::ChainId;
 use starknet_api::executable_transaction::L1HandlerTransaction;
 use starknet_api::transaction::TransactionHash;
+use tracing::debug;
+use validator::Validate;
 
 use crate::errors::L1ProviderError;
 
@@ -26,11 +37,10 @@ pub struct L1Provider {
 }
 
 impl L1Provider {
-    pub async fn new(_config: L1ProviderConfig) -> L1ProviderResult<Self> {
-        todo!(
-            "init crawler to start next crawl from ~1 hour ago, this can have l1 errors when \
-             finding the latest block on L1 to 'subtract' 1 hour from."
-        );
+    /// Creates an empty provider; it is filled by an [`l1_scraper::L1Scraper`], which starts
+    /// scraping from about an hour back on L1.
+    pub fn new() -> Self {
+        Self::default()
     }
 
     /// Retrieves up to `n_txs` transactions that have yet to be proposed or accepted on L2.
@@ -52,13 +62,31 @@ impl L1Provider {
         }
     }
 
+    /// Purges the committed transactions from the buffers, returns the uncommitted proposed
+    /// transactions to the proposable ones, and moves to `Pending` until the next proposal or
+    /// validation starts.
     // TODO: when deciding on consensus, if possible, have commit_block also tell the node if it's
     // about to [optimistically-]propose or validate the next block.
-    pub fn commit_block(&mut self, _commited_txs: &[TransactionHash]) {
-        todo!(
-            "Purges txs from internal buffers, if was proposer clear staging buffer, 
-            reset state to Pending until we get proposing/validating notice from consensus."
-        )
+    pub fn commit_block(&mut self, committed_txs: &[TransactionHash]) {
+        for tx_hash in committed_txs {
+            self.tx_manager.mark_tx_included_on_l2(tx_hash);
+        }
+        self.tx_manager.clear_proposed_txs();
+        self.state = self.state.transition_to_pending();
+    }
+
+    /// Applies the given events, collected from L1, to the internal buffers.
+    pub fn apply_events(&mut self, events: impl IntoIterator<Item = Event>) {
+        for event in events {
+            match event {
+                Event::L1HandlerTransaction(tx) => {
+                    self.tx_manager.add_unconsumed_l1_not_in_l2_block_tx(tx)
+                }
+                Event::TransactionConsumed(tx_hash) | Event::TransactionCanceled(tx_hash) => {
+                    self.tx_manager.remove_consumed_or_canceled_tx(&tx_hash)
+                }
+            }
+        }
     }
 
     // TODO: pending formal consensus API, guessing the API here to keep things moving.
@@ -73,35 +101,45 @@ impl L1Provider {
         Ok(())
     }
 
-    /// Simple recovery from L1 and L2 reorgs by reseting the service, which rewinds L1 and L2
-    /// information.
+    /// Simple recovery from L1 reorgs by reseting the service, which rewinds the L1 information.
+    /// Note: the scraper filling the provider should be rewound as well, see
+    /// [`l1_scraper::L1Scraper::handle_reorg`].
     pub fn handle_reorg(&mut self) -> L1ProviderResult<()> {
         self.reset()
     }
 
-    // TODO: this will likely change during integration with infra team.
-    pub async fn start(&self) {
-        todo!(
-            "Create a process that wakes up every config.poll_interval seconds and updates
-        internal L1 and L2 buffers according to collected L1 events and recent blocks created on
-        L2."
-        )
-    }
-
+    /// Resets the internal buffers, and moves to `Pending`; the buffers are refilled by the
+    /// scraper, rewound for about an hour on L1. The transactions included in L2 blocks are kept,
+    /// so they are not proposed again once they are rescraped.
     fn reset(&mut self) -> L1ProviderResult<()> {
-        todo!(
-            "resets internal buffers and rewinds the internal crawler _pointer_ back for ~1 \
-             hour,so that the main loop will start collecting from that time gracefully. May hit \
-             base layer errors."
-        );
+        let included_on_l2 = std::mem::take(&mut self.tx_manager.included_on_l2);
+        *self = Self::default();
+        // Their consumption on L1, if it happened, is rescraped as well.
+        self.tx_manager.on_l2_awaiting_l1_consumption = included_on_l2.clone();
+        self.tx_manager.included_on_l2 = included_on_l2;
+        Ok(())
     }
 }
 
+/// An update to the provider's buffers, collected from L1.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Event {
+    /// A message sent to L2, to be handled by the given transaction.
+    L1HandlerTransaction(L1HandlerTransaction),
+    /// The message handled by the transaction with the given hash was consumed on L1.
+    TransactionConsumed(TransactionHash),
+    /// The message handled by the transaction with the given hash was canceled on L1.
+    TransactionCanceled(TransactionHash),
+}
+
 #[derive(Debug, Default)]
 struct TransactionManager {
     txs: IndexMap<TransactionHash, L1HandlerTransaction>,
     proposed_txs: IndexSet<TransactionHash>,
     on_l2_awaiting_l1_consumption: IndexSet<TransactionHash>,
+    // All the transactions included in L2 blocks, including consumed ones.
+    // TODO: drop the transactions whose messages are older than the rewind of the scraper.
+    included_on_l2: IndexSet<TransactionHash>,
 }
 
 impl TransactionManager {
@@ -128,15 +166,36 @@ impl TransactionManager {
         }
     }
 
-    pub fn _add_unconsumed_l1_not_in_l2_block_tx(&mut self, _tx: L1HandlerTransaction) {
-        todo!(
-            "Check if tx is in L2, if it isn't on L2 add it to the txs buffer, otherwise print
-             debug and do nothing."
-        )
+    pub fn add_unconsumed_l1_not_in_l2_block_tx(&mut self, tx: L1HandlerTransaction) {
+        let tx_hash = tx.tx_hash;
+        if self.included_on_l2.contains(&tx_hash) {
+            debug!("Transaction {tx_hash} is already included on L2, ignoring it.");
+            return;
+        }
+
+        // Scraping overlapping L1 ranges, e.g., after a reset, may yield known transactions.
+        self.txs.entry(tx_hash).or_insert(tx);
+    }
+
+    pub fn mark_tx_included_on_l2(&mut self, tx_hash: &TransactionHash) {
+        self.remove_tx(tx_hash);
+        self.on_l2_awaiting_l1_consumption.insert(*tx_hash);
+        self.included_on_l2.insert(*tx_hash);
+    }
+
+    pub fn remove_consumed_or_canceled_tx(&mut self, tx_hash: &TransactionHash) {
+        self.remove_tx(tx_hash);
+        self.on_l2_awaiting_l1_consumption.shift_remove(tx_hash);
     }
 
-    pub fn _mark_tx_included_on_l2(&mut self, _tx_hash: &TransactionHash) {
-        todo!("Adds the tx hash to l2 buffer; remove tx from the txs storage if it's there.")
+    pub fn clear_proposed_txs(&mut self) {
+        self.proposed_txs.clear();
+    }
+
+    // Note: order-preserving removals keep the proposed transactions a prefix of `txs`.
+    fn remove_tx(&mut self, tx_hash: &TransactionHash) {
+        self.txs.shift_remove(tx_hash);
+        self.proposed_txs.shift_remove(tx_hash);
     }
 }
 
@@ -177,8 +236,8 @@ impl ProviderState {
         }
     }
 
-    fn _transition_to_pending(self) -> L1ProviderResult<Self> {
-        todo!()
+    fn transition_to_pending(self) -> Self {
+        ProviderState::Pending
     }
 
     pub fn as_str(&self) -> &str {
@@ -196,5 +255,55 @@ impl std::fmt::Display for ProviderState {
     }
 }
 
-#[derive(Debug)]
-pub struct L1ProviderConfig;
+#[derive(Clone, Debug, Serialize, Deserialize, Validate, PartialEq)]
+pub struct L1ProviderConfig {
+    pub chain_id: ChainId,
+    // Number of confirmations required for an L1 block to be scraped.
+    pub finality: u64,
+    #[serde(deserialize_with = "deserialize_seconds_to_duration")]
+    pub polling_interval: Duration,
+    // Number of L1 blocks the scraper rewinds on startup and on reorgs, ~1 hour of L1 blocks.
+    pub startup_rewind_l1_blocks: u64,
+}
+
+impl Default for L1ProviderConfig {
+    fn default() -> Self {
+        Self {
+            chain_id: ChainId::Mainnet,
+            finality: 0,
+            polling_interval: Duration::from_secs(1),
+            startup_rewind_l1_blocks: 300, // 12 seconds per block.
+        }
+    }
+}
+
+impl SerializeConfig for L1ProviderConfig {
+    fn dump(&self) -> BTreeMap<ParamPath, SerializedParam> {
+        BTreeMap::from_iter([
+            ser_param(
+                "chain_id",
+                &self.chain_id,
+                "The chain whose L1 handler transactions are provided.",
+                ParamPrivacyInput::Public,
+            ),
+            ser_param(
+                "finality",
+                &self.finality,
+                "Number of confirmations required for an L1 block to be scraped.",
+                ParamPrivacyInput::Public,
+            ),
+            ser_param(
+                "polling_interval",
+                &self.polling_interval.as_secs(),
+                "Interval in seconds between consecutive scrapes of L1 events.",
+                ParamPrivacyInput::Public,
+            ),
+            ser_param(
+                "startup_rewind_l1_blocks",
+                &self.startup_rewind_l1_blocks,
+                "Number of L1 blocks to rewind the scraper by on startup and on reorgs.",
+                ParamPrivacyInput::Public,
+            ),
+        ])
+    }
+}
@@ -1,4 +1,12 @@
+use std::collections::BTreeMap;
+use std::convert::Infallible;
+use std::ops::RangeInclusive;
+use std::sync::{Arc, Mutex};
+
+use async_trait::async_trait;
 use indexmap::{IndexMap, IndexSet};
+use papyrus_base_layer::{BaseLayerContract, L1BlockHash, L1BlockNumber, L1Event};
+use starknet_api::block::{BlockHash, BlockNumber};
 use starknet_api::executable_transaction::L1HandlerTransaction;
 use starknet_api::transaction::TransactionHash;
 
@@ -76,9 +84,11 @@ struct TransactionManagerContent {
 
 impl TransactionManagerContent {
     fn complete_to_tx_manager(self) -> TransactionManager {
+        let on_l2_awaiting_l1_consumption = self.on_l2_awaiting_l1_consumption.unwrap_or_default();
         TransactionManager {
             txs: self.txs.unwrap_or_default(),
-            on_l2_awaiting_l1_consumption: self.on_l2_awaiting_l1_consumption.unwrap_or_default(),
+            included_on_l2: on_l2_awaiting_l1_consumption.clone(),
+            on_l2_awaiting_l1_consumption,
             ..Default::default()
         }
     }
@@ -119,3 +129,70 @@ impl TransactionManagerContentBuilder {
         self.txs.is_none() && self.on_l2_awaiting_l1_consumption.is_none()
     }
 }
+
+// An in-process base layer, whose blocks are added by the test; clones share the same blocks.
+#[derive(Clone, Debug, Default)]
+pub struct FakeBaseLayer {
+    chain: Arc<Mutex<FakeChain>>,
+}
+
+#[derive(Debug, Default)]
+struct FakeChain {
+    blocks: BTreeMap<L1BlockNumber, (L1BlockHash, Vec<L1Event>)>,
+    // Gives every added block a unique hash, also when it replaces a reorged block.
+    n_added_blocks: u64,
+}
+
+impl FakeBaseLayer {
+    /// Adds a block with the given events on top of the latest block.
+    pub fn add_block(&self, events: impl IntoIterator<Item = L1Event>) -> L1BlockNumber {
+        let mut chain = self.chain.lock().unwrap();
+        let block_number = chain.blocks.last_key_value().map_or(0, |(number, _)| number + 1);
+        let mut block_hash = L1BlockHash::default();
+        block_hash[..8].copy_from_slice(&chain.n_added_blocks.to_be_bytes());
+        chain.n_added_blocks += 1;
+        chain.blocks.insert(block_number, (block_hash, events.into_iter().collect()));
+        block_number
+    }
+
+    /// Drops the given block and all blocks after it, simulating a reorg.
+    pub fn remove_blocks_from(&self, block_number: L1BlockNumber) {
+        self.chain.lock().unwrap().blocks.split_off(&block_number);
+    }
+}
+
+#[async_trait]
+impl BaseLayerContract for FakeBaseLayer {
+    type Error = Infallible;
+
+    async fn latest_proved_block(
+        &self,
+        _finality: u64,
+    ) -> Result<Option<(BlockNumber, BlockHash)>, Self::Error> {
+        Ok(None)
+    }
+
+    async fn latest_l1_block_number(
+        &self,
+        finality: u64,
+    ) -> Result<Option<L1BlockNumber>, Self::Error> {
+        let chain = self.chain.lock().unwrap();
+        Ok(chain.blocks.last_key_value().and_then(|(number, _)| number.checked_sub(finality)))
+    }
+
+    async fn l1_block_hash(
+        &self,
+        block_number: L1BlockNumber,
+    ) -> Result<Option<L1BlockHash>, Self::Error> {
+        let chain = self.chain.lock().unwrap();
+        Ok(chain.blocks.get(&block_number).map(|(block_hash, _)| *block_hash))
+    }
+
+    async fn events(
+        &self,
+        block_range: RangeInclusive<L1BlockNumber>,
+    ) -> Result<Vec<L1Event>, Self::Error> {
+        let chain = self.chain.lock().unwrap();
+        Ok(chain.blocks.range(block_range).flat_map(|(_, (_, events))| events.clone()).collect())
+    }
+}
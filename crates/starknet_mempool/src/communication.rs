@@ -1,11 +1,17 @@
+use std::future::Future;
+use std::sync::Arc;
+use std::time::Duration;
+
 use async_trait::async_trait;
 use papyrus_network_types::network_types::BroadcastedMessageMetadata;
+use starknet_api::core::ContractAddress;
 use starknet_api::executable_transaction::AccountTransaction;
 use starknet_api::rpc_transaction::{
     RpcDeployAccountTransaction,
     RpcInvokeTransaction,
     RpcTransaction,
 };
+use starknet_api::transaction::TransactionHash;
 use starknet_mempool_p2p_types::communication::SharedMempoolP2pPropagatorClient;
 use starknet_mempool_types::communication::{
     AddTransactionArgsWrapper,
@@ -13,26 +19,48 @@ use starknet_mempool_types::communication::{
     MempoolResponse,
 };
 use starknet_mempool_types::errors::MempoolError;
-use starknet_mempool_types::mempool_types::{CommitBlockArgs, MempoolResult};
-use starknet_sequencer_infra::component_definitions::{ComponentRequestHandler, ComponentStarter};
+use starknet_mempool_types::mempool_types::{
+    CommitBlockArgs,
+    MempoolResult,
+    MempoolSnapshot,
+    RewindBlockArgs,
+    TransactionStatus,
+};
+use starknet_sequencer_infra::component_definitions::{
+    ComponentRequestHandler,
+    ComponentStarter,
+    ResponseFuture,
+};
 use starknet_sequencer_infra::component_server::{LocalComponentServer, RemoteComponentServer};
+use tokio::sync::watch;
+
+use crate::mempool::{Mempool, MempoolConfig};
+use crate::utils::InstantClock;
 
-use crate::mempool::Mempool;
+#[cfg(test)]
+#[path = "communication_test.rs"]
+pub mod communication_test;
 
 pub type LocalMempoolServer =
     LocalComponentServer<MempoolCommunicationWrapper, MempoolRequest, MempoolResponse>;
 pub type RemoteMempoolServer = RemoteComponentServer<MempoolRequest, MempoolResponse>;
 
 pub fn create_mempool(
+    config: MempoolConfig,
     mempool_p2p_propagator_client: SharedMempoolP2pPropagatorClient,
 ) -> MempoolCommunicationWrapper {
-    MempoolCommunicationWrapper::new(Mempool::default(), mempool_p2p_propagator_client)
+    let mempool = Mempool::with_journal(config, Arc::new(InstantClock))
+        .expect("Failed to recover the mempool from its journal.");
+    MempoolCommunicationWrapper::new(mempool, mempool_p2p_propagator_client)
 }
 
 /// Wraps the mempool to enable inbound async communication from other components.
 pub struct MempoolCommunicationWrapper {
     mempool: Mempool,
     mempool_p2p_propagator_client: SharedMempoolP2pPropagatorClient,
+    // Marked as changed whenever the mempool is updated and holds transactions eligible for
+    // sequencing, to answer the requests waiting for transactions.
+    txs_available_sender: watch::Sender<()>,
 }
 
 impl MempoolCommunicationWrapper {
@@ -40,7 +68,14 @@ impl MempoolCommunicationWrapper {
         mempool: Mempool,
         mempool_p2p_propagator_client: SharedMempoolP2pPropagatorClient,
     ) -> Self {
-        MempoolCommunicationWrapper { mempool, mempool_p2p_propagator_client }
+        let (txs_available_sender, _) = watch::channel(());
+        MempoolCommunicationWrapper { mempool, mempool_p2p_propagator_client, txs_available_sender }
+    }
+
+    fn notify_if_txs_available(&self) {
+        if self.mempool.n_ready_txs() > 0 {
+            self.txs_available_sender.send_replace(());
+        }
     }
 
     async fn send_tx_to_p2p(
@@ -80,6 +115,7 @@ impl MempoolCommunicationWrapper {
 
     async fn add_tx(&mut self, args_wrapper: AddTransactionArgsWrapper) -> MempoolResult<()> {
         self.mempool.add_tx(args_wrapper.args.clone())?;
+        self.notify_if_txs_available();
         // TODO: Verify that only transactions that were added to the mempool are sent.
         // TODO: handle declare correctly and remove this match.
         match args_wrapper.args.tx {
@@ -89,12 +125,50 @@ impl MempoolCommunicationWrapper {
     }
 
     fn commit_block(&mut self, args: CommitBlockArgs) -> MempoolResult<()> {
-        self.mempool.commit_block(args)
+        self.mempool.commit_block(args)?;
+        self.notify_if_txs_available();
+        Ok(())
+    }
+
+    fn rewind_block(&mut self, args: RewindBlockArgs) -> MempoolResult<()> {
+        self.mempool.rewind_block(args)?;
+        self.notify_if_txs_available();
+        Ok(())
     }
 
     fn get_txs(&mut self, n_txs: usize) -> MempoolResult<Vec<AccountTransaction>> {
         self.mempool.get_txs(n_txs)
     }
+
+    // Returns a future that resolves once the mempool holds transactions eligible for sequencing,
+    // or once the timeout passes. The future doesn't borrow the mempool, so that the transactions
+    // it waits for can be added meanwhile.
+    fn wait_for_txs(
+        &self,
+        timeout: Duration,
+    ) -> impl Future<Output = MempoolResult<()>> + Send + 'static {
+        let txs_available = self.mempool.n_ready_txs() > 0;
+        let mut txs_available_receiver = self.txs_available_sender.subscribe();
+        async move {
+            if !txs_available {
+                // Either way, the caller fetches the transactions that are available by then.
+                let _ = tokio::time::timeout(timeout, txs_available_receiver.changed()).await;
+            }
+            Ok(())
+        }
+    }
+
+    fn get_tx_status(&self, tx_hash: TransactionHash) -> MempoolResult<TransactionStatus> {
+        self.mempool.get_tx_status(tx_hash)
+    }
+
+    fn get_account_txs(&self, address: ContractAddress) -> MempoolResult<Vec<AccountTransaction>> {
+        Ok(self.mempool.get_account_txs(address))
+    }
+
+    fn snapshot(&self) -> MempoolResult<MempoolSnapshot> {
+        Ok(self.mempool.snapshot())
+    }
 }
 
 #[async_trait]
@@ -107,11 +181,35 @@ impl ComponentRequestHandler<MempoolRequest, MempoolResponse> for MempoolCommuni
             MempoolRequest::CommitBlock(args) => {
                 MempoolResponse::CommitBlock(self.commit_block(args))
             }
+            MempoolRequest::RewindBlock(args) => {
+                MempoolResponse::RewindBlock(self.rewind_block(args))
+            }
             MempoolRequest::GetTransactions(n_txs) => {
                 MempoolResponse::GetTransactions(self.get_txs(n_txs))
             }
+            MempoolRequest::WaitForTransactions(timeout) => {
+                MempoolResponse::WaitForTransactions(self.wait_for_txs(timeout).await)
+            }
+            MempoolRequest::GetTransactionStatus(tx_hash) => {
+                MempoolResponse::GetTransactionStatus(self.get_tx_status(tx_hash))
+            }
+            MempoolRequest::GetAccountTransactions(address) => {
+                MempoolResponse::GetAccountTransactions(self.get_account_txs(address))
+            }
+            MempoolRequest::GetSnapshot => MempoolResponse::GetSnapshot(self.snapshot()),
         }
     }
+
+    fn handle_waiting_request(
+        &mut self,
+        request: &MempoolRequest,
+    ) -> Option<ResponseFuture<MempoolResponse>> {
+        let MempoolRequest::WaitForTransactions(timeout) = request else {
+            return None;
+        };
+        let txs_available = self.wait_for_txs(*timeout);
+        Some(Box::pin(async move { MempoolResponse::WaitForTransactions(txs_available.await) }))
+    }
 }
 
 impl ComponentStarter for MempoolCommunicationWrapper {}
@@ -0,0 +1,77 @@
+use std::sync::Arc;
+use std::time::Duration;
+use async_trait::async_trait;
+use papyrus_network_types::network_types::BroadcastedMessageMetadata;
+use starknet_api::rpc_transaction::RpcTransaction;
+use starknet_mempool_p2p_types::communication::{
+    MempoolP2pPropagatorClient,
+    MempoolP2pPropagatorClientResult,
+};
+use starknet_mempool_types::communication::{
+    AddTransactionArgsWrapper,
+    LocalMempoolClient,
+    MempoolClient,
+};
+use starknet_sequencer_infra::component_server::ComponentServerStarter;
+use tokio::sync::mpsc::channel;
+
+use crate::add_tx_input;
+use crate::communication::{LocalMempoolServer, MempoolCommunicationWrapper};
+use crate::mempool::{Mempool, MempoolConfig};
+use crate::utils::InstantClock;
+
+struct NoOpPropagatorClient;
+
+#[async_trait]
+impl MempoolP2pPropagatorClient for NoOpPropagatorClient {
+    async fn add_transaction(
+        &self,
+        _transaction: RpcTransaction,
+    ) -> MempoolP2pPropagatorClientResult<()> {
+        Ok(())
+    }
+
+    async fn continue_propagation(
+        &self,
+        _propagation_metadata: BroadcastedMessageMetadata,
+    ) -> MempoolP2pPropagatorClientResult<()> {
+        Ok(())
+    }
+}
+
+fn run_mempool_server() -> LocalMempoolClient {
+    let mempool = Mempool::new(MempoolConfig::default(), Arc::new(InstantClock));
+    let mempool = MempoolCommunicationWrapper::new(mempool, Arc::new(NoOpPropagatorClient));
+    let (tx, rx) = channel(32);
+    let mut server = LocalMempoolServer::new(mempool, rx);
+    tokio::spawn(async move { server.start().await });
+    LocalMempoolClient::new(tx)
+}
+
+#[tokio::test]
+async fn wait_for_txs_returns_once_txs_are_added() {
+    let client = run_mempool_server();
+    let waiting_client = client.clone();
+    let wait_for_txs =
+        tokio::spawn(async move { waiting_client.wait_for_txs(Duration::from_secs(60)).await });
+    tokio::time::sleep(Duration::from_millis(10)).await;
+    assert!(!wait_for_txs.is_finished());
+
+    // The mempool keeps handling requests while the wait is pending.
+    let args = add_tx_input!(tx_hash: 1, tx_nonce: 0);
+    client.add_tx(AddTransactionArgsWrapper { args, p2p_message_metadata: None }).await.unwrap();
+
+    tokio::time::timeout(Duration::from_secs(5), wait_for_txs)
+        .await
+        .expect("The wait should end once transactions are added.")
+        .unwrap()
+        .unwrap();
+}
+
+#[tokio::test]
+async fn wait_for_txs_times_out() {
+    let client = run_mempool_server();
+
+    client.wait_for_txs(Duration::from_millis(10)).await.unwrap();
+    assert!(client.get_txs(1).await.unwrap().is_empty());
+}
@@ -0,0 +1,129 @@
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, BufReader, BufWriter, Read, Write};
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+use starknet_mempool_types::mempool_types::{AddTransactionArgs, CommitBlockArgs, RewindBlockArgs};
+
+#[cfg(test)]
+#[path = "journal_test.rs"]
+pub mod journal_test;
+
+/// A mempool operation recorded in the journal; replaying the entries in order restores the
+/// mempool's content.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub enum JournalEntry {
+    AddTransaction(AddTransactionArgs),
+    CommitBlock(CommitBlockArgs),
+    RewindBlock(RewindBlockArgs),
+}
+
+/// An append-only file of mempool operations, used to recover accepted transactions after a
+/// restart.
+/// Each entry is JSON-serialized, and prefixed by its length as a little-endian `u32`. JSON is used
+/// since transactions hold classes with optional fields that are omitted when serialized, which
+/// non-self-describing formats cannot read back.
+#[derive(Debug)]
+pub struct MempoolJournal {
+    path: PathBuf,
+    writer: BufWriter<File>,
+    n_entries: usize,
+}
+
+impl MempoolJournal {
+    /// Opens the journal at the given path, creating it if missing, and returns it along with the
+    /// entries it already holds.
+    /// A truncated last entry, e.g., due to a crash mid-write, is discarded.
+    pub fn open(path: impl AsRef<Path>) -> io::Result<(Self, Vec<JournalEntry>)> {
+        let path = path.as_ref().to_path_buf();
+        let entries = match File::open(&path) {
+            Ok(file) => read_entries(file)?,
+            Err(error) if error.kind() == io::ErrorKind::NotFound => Vec::new(),
+            Err(error) => return Err(error),
+        };
+
+        // Rewrite the valid entries, to drop a possibly truncated last entry.
+        write_entries_atomically(&path, &entries)?;
+        let journal = Self::open_for_append(path, entries.len())?;
+
+        Ok((journal, entries))
+    }
+
+    /// Appends the entry to the journal; the entry is flushed before returning.
+    pub fn append(&mut self, entry: &JournalEntry) -> io::Result<()> {
+        write_entry(&mut self.writer, entry)?;
+        self.writer.flush()?;
+        self.n_entries += 1;
+
+        Ok(())
+    }
+
+    /// Atomically replaces the journal's content with the given entries.
+    pub fn rewrite(&mut self, entries: &[JournalEntry]) -> io::Result<()> {
+        write_entries_atomically(&self.path, entries)?;
+        *self = Self::open_for_append(self.path.clone(), entries.len())?;
+
+        Ok(())
+    }
+
+    pub fn n_entries(&self) -> usize {
+        self.n_entries
+    }
+
+    fn open_for_append(path: PathBuf, n_entries: usize) -> io::Result<Self> {
+        let file = OpenOptions::new().append(true).open(&path)?;
+        Ok(MempoolJournal { path, writer: BufWriter::new(file), n_entries })
+    }
+}
+
+fn write_entry(writer: &mut impl Write, entry: &JournalEntry) -> io::Result<()> {
+    let bytes = serde_json::to_vec(entry).map_err(to_io_error)?;
+    let len = u32::try_from(bytes.len()).map_err(to_io_error)?;
+    writer.write_all(&len.to_le_bytes())?;
+    writer.write_all(&bytes)
+}
+
+/// Writes the entries to a temporary file, which then replaces the file at the given path.
+fn write_entries_atomically(path: &Path, entries: &[JournalEntry]) -> io::Result<()> {
+    let tmp_path = path.with_extension("tmp");
+    let mut writer = BufWriter::new(File::create(&tmp_path)?);
+    for entry in entries {
+        write_entry(&mut writer, entry)?;
+    }
+    writer.flush()?;
+    writer.get_ref().sync_all()?;
+
+    fs::rename(&tmp_path, path)
+}
+
+fn read_entries(file: File) -> io::Result<Vec<JournalEntry>> {
+    let mut reader = BufReader::new(file);
+    let mut entries = Vec::new();
+    loop {
+        let mut len_bytes = [0; 4];
+        match reader.read_exact(&mut len_bytes) {
+            Ok(()) => {}
+            Err(error) if error.kind() == io::ErrorKind::UnexpectedEof => break,
+            Err(error) => return Err(error),
+        }
+
+        let mut bytes = vec![0; u32::from_le_bytes(len_bytes).try_into().map_err(to_io_error)?];
+        let entry = match reader.read_exact(&mut bytes) {
+            Ok(()) => serde_json::from_slice(&bytes).map_err(to_io_error),
+            Err(error) => Err(error),
+        };
+        match entry {
+            Ok(entry) => entries.push(entry),
+            Err(error) => {
+                tracing::warn!("Discarding journal entries from a corrupted entry: {error}.");
+                break;
+            }
+        }
+    }
+
+    Ok(entries)
+}
+
+fn to_io_error(error: impl Into<Box<dyn std::error::Error + Send + Sync>>) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, error)
+}
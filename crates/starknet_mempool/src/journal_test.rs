@@ -0,0 +1,142 @@
+use std::fs::OpenOptions;
+use std::io::Write;
+
+use cairo_lang_starknet_classes::casm_contract_class::CasmContractClass;
+use pretty_assertions::assert_eq;
+use starknet_api::block::BlockNumber;
+use starknet_api::contract_class::{ClassInfo, ContractClass};
+use starknet_api::deprecated_contract_class::ContractClass as DeprecatedContractClass;
+use starknet_api::executable_transaction::AccountTransaction;
+use starknet_api::test_utils::declare::executable_declare_tx;
+use starknet_api::transaction::TransactionHash;
+use starknet_api::{declare_tx_args, felt};
+use starknet_mempool_types::mempool_types::{AccountState, AddTransactionArgs, CommitBlockArgs};
+use tempfile::tempdir;
+
+use crate::add_tx_input;
+use crate::journal::{JournalEntry, MempoolJournal};
+
+fn entries() -> Vec<JournalEntry> {
+    vec![
+        JournalEntry::AddTransaction(add_tx_input!(tx_hash: 1, tx_nonce: 0)),
+        JournalEntry::CommitBlock(CommitBlockArgs {
+            block_number: BlockNumber(0),
+            address_to_nonce: Default::default(),
+            tx_hashes: Default::default(),
+            rejected_tx_hashes: Default::default(),
+        }),
+        JournalEntry::AddTransaction(add_tx_input!(tx_hash: 2, tx_nonce: 1)),
+    ]
+}
+
+#[test]
+fn test_journal_entries_survive_reopening() {
+    let dir = tempdir().unwrap();
+    let path = dir.path().join("mempool.journal");
+
+    let (mut journal, recovered_entries) = MempoolJournal::open(&path).unwrap();
+    assert!(recovered_entries.is_empty());
+    for entry in &entries() {
+        journal.append(entry).unwrap();
+    }
+    drop(journal);
+
+    let (journal, recovered_entries) = MempoolJournal::open(&path).unwrap();
+    assert_eq!(recovered_entries, entries());
+    assert_eq!(journal.n_entries(), entries().len());
+}
+
+fn declare_entry(tx_hash: u8, contract_class: ContractClass) -> JournalEntry {
+    let class_info = ClassInfo {
+        contract_class,
+        sierra_program_length: 1,
+        abi_length: 0,
+        sierra_contract_class: None,
+    };
+    let tx = AccountTransaction::Declare(executable_declare_tx(
+        declare_tx_args!(tx_hash: TransactionHash(felt!(tx_hash))),
+        class_info,
+    ));
+    let account_state = AccountState { address: tx.contract_address(), nonce: tx.nonce() };
+    JournalEntry::AddTransaction(AddTransactionArgs { tx, account_state })
+}
+
+#[test]
+fn test_journal_declare_entries_survive_reopening() {
+    let dir = tempdir().unwrap();
+    let path = dir.path().join("mempool.journal");
+
+    // The optional fields of the classes are left unset, so they are omitted when serialized.
+    let casm = CasmContractClass {
+        compiler_version: "0.1.0".to_string(),
+        prime: Default::default(),
+        bytecode: Default::default(),
+        bytecode_segment_lengths: None,
+        hints: Default::default(),
+        pythonic_hints: None,
+        entry_points_by_type: Default::default(),
+    };
+    let entries = vec![
+        declare_entry(1, ContractClass::V1(casm)),
+        declare_entry(2, ContractClass::V0(DeprecatedContractClass::default())),
+    ];
+
+    let (mut journal, _) = MempoolJournal::open(&path).unwrap();
+    for entry in &entries {
+        journal.append(entry).unwrap();
+    }
+    drop(journal);
+
+    let (_, recovered_entries) = MempoolJournal::open(&path).unwrap();
+    assert_eq!(recovered_entries, entries);
+}
+
+#[test]
+fn test_journal_discards_truncated_entry() {
+    let dir = tempdir().unwrap();
+    let path = dir.path().join("mempool.journal");
+
+    let (mut journal, _) = MempoolJournal::open(&path).unwrap();
+    for entry in &entries() {
+        journal.append(entry).unwrap();
+    }
+    drop(journal);
+
+    // Simulate a crash in the middle of writing an entry.
+    let mut file = OpenOptions::new().append(true).open(&path).unwrap();
+    file.write_all(&100_u32.to_le_bytes()).unwrap();
+    file.write_all(&[1, 2, 3]).unwrap();
+    drop(file);
+
+    let (mut journal, recovered_entries) = MempoolJournal::open(&path).unwrap();
+    assert_eq!(recovered_entries, entries());
+
+    // Entries appended after recovery follow the valid ones.
+    let new_entry = JournalEntry::AddTransaction(add_tx_input!(tx_hash: 3, tx_nonce: 2));
+    journal.append(&new_entry).unwrap();
+    drop(journal);
+
+    let (_, recovered_entries) = MempoolJournal::open(&path).unwrap();
+    let mut expected_entries = entries();
+    expected_entries.push(new_entry);
+    assert_eq!(recovered_entries, expected_entries);
+}
+
+#[test]
+fn test_journal_rewrite() {
+    let dir = tempdir().unwrap();
+    let path = dir.path().join("mempool.journal");
+
+    let (mut journal, _) = MempoolJournal::open(&path).unwrap();
+    for entry in &entries() {
+        journal.append(entry).unwrap();
+    }
+
+    let compacted_entries = entries().split_off(2);
+    journal.rewrite(&compacted_entries).unwrap();
+    assert_eq!(journal.n_entries(), 1);
+    drop(journal);
+
+    let (_, recovered_entries) = MempoolJournal::open(&path).unwrap();
+    assert_eq!(recovered_entries, compacted_entries);
+}
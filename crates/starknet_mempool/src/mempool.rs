@@ -1,6 +1,13 @@
-use std::collections::HashMap;
-
-use starknet_api::block::GasPrice;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::Duration;
+
+use papyrus_config::converters::deserialize_seconds_to_duration;
+use papyrus_config::dumping::{ser_optional_param, ser_param, SerializeConfig};
+use papyrus_config::{ParamPath, ParamPrivacyInput, SerializedParam};
+use serde::{Deserialize, Serialize};
+use starknet_api::block::{BlockNumber, GasPrice};
 use starknet_api::core::{ContractAddress, Nonce};
 use starknet_api::executable_transaction::AccountTransaction;
 use starknet_api::transaction::fields::Tip;
@@ -11,27 +18,141 @@ use starknet_mempool_types::mempool_types::{
     AddTransactionArgs,
     CommitBlockArgs,
     MempoolResult,
+    MempoolSnapshot,
+    RewindBlockArgs,
+    TransactionStatus,
 };
+use validator::Validate;
 
-use crate::transaction_pool::TransactionPool;
+use crate::journal::{JournalEntry, MempoolJournal};
+use crate::queue_ordering::QueueOrdering;
+use crate::transaction_pool::{tx_size_in_bytes, TransactionPool};
 use crate::transaction_queue::TransactionQueue;
-use crate::utils::try_increment_nonce;
+use crate::utils::{try_increment_nonce, Clock, InstantClock};
 
 #[cfg(test)]
 #[path = "mempool_test.rs"]
 pub mod mempool_test;
 
-#[derive(Debug)]
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize, Validate)]
 pub struct MempoolConfig {
     enable_fee_escalation: bool,
     // TODO: consider adding validations; should be bounded?
     // Percentage increase for tip and max gas price to enable transaction replacement.
     fee_escalation_percentage: u8, // E.g., 10 for a 10% increase.
+    // Maximum number of transactions held in the pool.
+    #[validate(range(min = 1))]
+    max_txs: usize,
+    // Maximum total serialized size, in bytes, of the transactions held in the pool.
+    #[validate(range(min = 1))]
+    max_total_size_in_bytes: usize,
+    // Maximum number of transactions held in the pool for a single account.
+    #[validate(range(min = 1))]
+    max_txs_per_account: usize,
+    // Time a transaction may be held in the pool before it is considered expired.
+    #[serde(deserialize_with = "deserialize_seconds_to_duration")]
+    transaction_ttl: Duration,
+    // Path of the journal used to recover the mempool's content after a restart; no journal is
+    // kept if unset.
+    journal_path: Option<PathBuf>,
+    // Number of journal entries above which the journal is compacted on block commitment.
+    #[validate(range(min = 1))]
+    journal_compaction_threshold: usize,
+    // Policy by which eligible transactions are ordered for sequencing.
+    queue_ordering: QueueOrdering,
+    // Maximum number of transactions returned for a single account in one `get_txs` call;
+    // unbounded if unset.
+    #[validate(range(min = 1))]
+    max_txs_per_sender_per_chunk: Option<usize>,
 }
 
 impl Default for MempoolConfig {
     fn default() -> Self {
-        MempoolConfig { enable_fee_escalation: true, fee_escalation_percentage: 10 }
+        MempoolConfig {
+            enable_fee_escalation: true,
+            fee_escalation_percentage: 10,
+            max_txs: 100_000,
+            max_total_size_in_bytes: 1 << 30, // 1 GiB.
+            max_txs_per_account: 1_000,
+            transaction_ttl: Duration::from_secs(60 * 60), // 1 hour.
+            journal_path: None,
+            journal_compaction_threshold: 100_000,
+            queue_ordering: QueueOrdering::default(),
+            max_txs_per_sender_per_chunk: None,
+        }
+    }
+}
+
+impl SerializeConfig for MempoolConfig {
+    fn dump(&self) -> BTreeMap<ParamPath, SerializedParam> {
+        let mut config = BTreeMap::from_iter([
+            ser_param(
+                "enable_fee_escalation",
+                &self.enable_fee_escalation,
+                "If true, transactions can be replaced with higher fee transactions.",
+                ParamPrivacyInput::Public,
+            ),
+            ser_param(
+                "fee_escalation_percentage",
+                &self.fee_escalation_percentage,
+                "Percentage increase for tip and max gas price to enable transaction replacement.",
+                ParamPrivacyInput::Public,
+            ),
+            ser_param(
+                "max_txs",
+                &self.max_txs,
+                "Maximum number of transactions held in the mempool.",
+                ParamPrivacyInput::Public,
+            ),
+            ser_param(
+                "max_total_size_in_bytes",
+                &self.max_total_size_in_bytes,
+                "Maximum total serialized size, in bytes, of the transactions held in the mempool.",
+                ParamPrivacyInput::Public,
+            ),
+            ser_param(
+                "max_txs_per_account",
+                &self.max_txs_per_account,
+                "Maximum number of transactions held in the mempool for a single account.",
+                ParamPrivacyInput::Public,
+            ),
+            ser_param(
+                "transaction_ttl",
+                &self.transaction_ttl.as_secs(),
+                "Time in seconds a transaction may be held in the mempool before it expires.",
+                ParamPrivacyInput::Public,
+            ),
+            ser_param(
+                "journal_compaction_threshold",
+                &self.journal_compaction_threshold,
+                "Number of journal entries above which the journal is compacted when a block is \
+                 committed.",
+                ParamPrivacyInput::Public,
+            ),
+            ser_param(
+                "queue_ordering",
+                &self.queue_ordering,
+                "Policy by which eligible transactions are ordered for sequencing: Tip, Fifo or \
+                 EffectiveFee.",
+                ParamPrivacyInput::Public,
+            ),
+        ]);
+        config.extend(ser_optional_param(
+            &self.journal_path,
+            PathBuf::from("mempool.journal"),
+            "journal_path",
+            "Path of the journal used to recover the mempool's content after a restart.",
+            ParamPrivacyInput::Public,
+        ));
+        config.extend(ser_optional_param(
+            &self.max_txs_per_sender_per_chunk,
+            10,
+            "max_txs_per_sender_per_chunk",
+            "Maximum number of transactions returned for a single account when transactions are \
+             requested for sequencing.",
+            ParamPrivacyInput::Public,
+        ));
+        config
     }
 }
 
@@ -60,6 +181,12 @@ impl MempoolState {
             .copied()
     }
 
+    /// Returns the account nonce known from committed blocks, or reported along with incoming
+    /// transactions, ignoring nonces staged for the block in-progress.
+    fn get_unstaged(&self, address: ContractAddress) -> Option<Nonce> {
+        self.committed.get(&address).or_else(|| self.tentative.get(&address)).copied()
+    }
+
     fn get_or_insert(&mut self, address: ContractAddress, nonce: Nonce) -> Nonce {
         if let Some(staged_or_committed_nonce) =
             self.staged.get(&address).or_else(|| self.committed.get(&address)).copied()
@@ -107,6 +234,25 @@ impl MempoolState {
         addresses_to_rewind
     }
 
+    fn is_staged(&self, address: ContractAddress) -> bool {
+        self.staged.contains_key(&address)
+    }
+
+    /// Returns whether the given transaction was proposed for sequencing in the block in-progress.
+    fn is_tx_staged(&self, tx_reference: &TransactionReference) -> bool {
+        self.staged
+            .get(&tx_reference.address)
+            .is_some_and(|&next_nonce| tx_reference.nonce < next_nonce)
+    }
+
+    /// Forgets the given account; should only be called for accounts with no transactions in the
+    /// mempool.
+    fn remove_account(&mut self, address: ContractAddress) {
+        self.committed.remove(&address);
+        self.staged.remove(&address);
+        self.tentative.remove(&address);
+    }
+
     fn validate_incoming_tx(&self, tx_reference: TransactionReference) -> MempoolResult<()> {
         let TransactionReference { address, nonce: tx_nonce, .. } = tx_reference;
         if self.get(address).is_some_and(|existing_nonce| tx_nonce < existing_nonce) {
@@ -116,23 +262,35 @@ impl MempoolState {
         Ok(())
     }
 
-    fn validate_commitment(&self, address: ContractAddress, next_nonce: Nonce) {
-        // FIXME: Remove after first POC.
-        // If commit_block wants to decrease the stored account nonce this can mean one of two
-        // things:
-        // 1. this is a reorg, which should be handled by a dedicated TBD mechanism and not inside
-        //    commit_block
-        // 2. the stored nonce originated from add_tx, so should be treated as tentative due to
-        //    possible races with the gateway; these types of nonces should be tagged somehow so
-        //    that commit_block can override them. Regardless, in the first POC this cannot happen
-        //    because the GW nonces are always 1.
+    /// Committed nonces may only decrease when the block that set them is reverted, which is
+    /// handled by `rewind`.
+    fn validate_commitment(
+        &self,
+        address: ContractAddress,
+        next_nonce: Nonce,
+    ) -> MempoolResult<()> {
         if let Some(&committed_nonce) = self.committed.get(&address) {
-            assert!(committed_nonce <= next_nonce, "NOT SUPPORTED YET {address:?} {next_nonce:?}.")
+            if next_nonce < committed_nonce {
+                return Err(MempoolError::CommittedNonceDecrease {
+                    address,
+                    committed_nonce,
+                    nonce: next_nonce,
+                });
+            }
         }
+
+        Ok(())
+    }
+
+    /// Restores the committed nonce of the given account to its value prior to a reverted block.
+    fn rewind(&mut self, address: ContractAddress, nonce: Nonce) {
+        self.committed.insert(address, nonce);
+        self.staged.remove(&address);
+        self.tentative.remove(&address);
     }
 }
 
-#[derive(Debug, Default)]
+#[derive(Debug)]
 pub struct Mempool {
     config: MempoolConfig,
     // TODO: add docstring explaining visibility and coupling of the fields.
@@ -141,15 +299,129 @@ pub struct Mempool {
     // Transactions eligible for sequencing.
     tx_queue: TransactionQueue,
     state: MempoolState,
+    // Accounts left with no transactions after the last committed block; forgotten if they remain
+    // empty after the next one.
+    accounts_to_clean: HashSet<ContractAddress>,
+    // The last block committed to the mempool.
+    last_committed_block: Option<BlockNumber>,
+    clock: Arc<dyn Clock>,
+    journal: Option<MempoolJournal>,
+}
+
+impl Default for Mempool {
+    fn default() -> Self {
+        Mempool::new(MempoolConfig::default(), Arc::new(InstantClock))
+    }
 }
 
 impl Mempool {
+    pub fn new(config: MempoolConfig, clock: Arc<dyn Clock>) -> Self {
+        Mempool {
+            tx_pool: TransactionPool::new(clock.clone()),
+            tx_queue: TransactionQueue::new(config.queue_ordering.policy()),
+            config,
+            state: MempoolState::default(),
+            accounts_to_clean: HashSet::default(),
+            last_committed_block: None,
+            clock,
+            journal: None,
+        }
+    }
+
+    /// Creates a mempool whose content is recovered from the configured journal, and which keeps
+    /// journaling its operations.
+    /// Operations are replayed in order, so transactions are re-validated against the nonces of
+    /// blocks committed since they were added.
+    pub fn with_journal(config: MempoolConfig, clock: Arc<dyn Clock>) -> MempoolResult<Self> {
+        let journal_path = config.journal_path.clone();
+        let mut mempool = Mempool::new(config, clock);
+        let Some(journal_path) = journal_path else {
+            return Ok(mempool);
+        };
+
+        let (journal, entries) = MempoolJournal::open(journal_path).map_err(journal_error)?;
+        tracing::info!("Replaying {} mempool journal entries.", entries.len());
+        for entry in entries {
+            let result = match entry {
+                JournalEntry::AddTransaction(args) => mempool.add_tx(args),
+                JournalEntry::CommitBlock(args) => mempool.commit_block(args),
+                JournalEntry::RewindBlock(args) => mempool.rewind_block(args),
+            };
+            if let Err(error) = result {
+                tracing::debug!("Skipped journal entry that is no longer valid: {error}.");
+            }
+        }
+
+        mempool.journal = Some(journal);
+        mempool.compact_journal()?;
+
+        Ok(mempool)
+    }
+
     /// Returns an iterator of the current eligible transactions for sequencing, ordered by their
     /// priority.
     pub fn iter(&self) -> impl Iterator<Item = &TransactionReference> {
         self.tx_queue.iter_over_ready_txs()
     }
 
+    /// Returns the number of transactions eligible for sequencing.
+    pub fn n_ready_txs(&self) -> usize {
+        self.tx_queue.n_ready_txs()
+    }
+
+    /// Returns the number of transactions that would be eligible for sequencing, but are suspended
+    /// since their max L2 gas price is below the gas price threshold.
+    pub fn n_suspended_txs(&self) -> usize {
+        self.tx_queue.n_suspended_txs()
+    }
+
+    /// Returns the stage of the given transaction in the mempool.
+    pub fn get_tx_status(&self, tx_hash: TransactionHash) -> MempoolResult<TransactionStatus> {
+        let tx_reference = TransactionReference::new(self.tx_pool.get_by_tx_hash(tx_hash)?);
+
+        let status = if self.tx_queue.is_suspended(&tx_reference) {
+            TransactionStatus::PendingThreshold
+        } else if self.tx_queue.contains(&tx_reference) {
+            TransactionStatus::Queued
+        } else if self.state.is_tx_staged(&tx_reference) {
+            TransactionStatus::Staged
+        } else {
+            TransactionStatus::NonceGapped
+        };
+
+        Ok(status)
+    }
+
+    /// Returns the transactions of the given account held in the mempool, sorted by nonce.
+    pub fn get_account_txs(&self, address: ContractAddress) -> Vec<AccountTransaction> {
+        self.tx_pool
+            .account_txs_sorted_by_nonce(address)
+            .map(|tx_reference| {
+                self.tx_pool
+                    .get_by_tx_hash(tx_reference.tx_hash)
+                    .expect("Transaction hash from account mapping must appear in pool.")
+            })
+            .cloned()
+            .collect()
+    }
+
+    /// Returns the sizes of the mempool's internal data structures.
+    pub fn snapshot(&self) -> MempoolSnapshot {
+        MempoolSnapshot {
+            n_txs_in_pool: self.tx_pool.n_txs(),
+            n_txs_in_priority_queue: self.tx_queue.n_ready_txs(),
+            n_txs_in_pending_queue: self.tx_queue.n_suspended_txs(),
+            n_committed_accounts: self.state.committed.len(),
+            n_staged_accounts: self.state.staged.len(),
+            n_tentative_accounts: self.state.tentative.len(),
+        }
+    }
+
+    /// Returns the last block committed to the mempool, if any.
+    pub fn last_committed_block(&self) -> Option<BlockNumber> {
+        self.last_committed_block
+    }
+
     /// Retrieves up to `n_txs` transactions with the highest priority from the mempool.
     /// Transactions are guaranteed to be unique across calls until the block in-progress is
     /// created.
@@ -159,13 +431,30 @@ impl Mempool {
         let mut eligible_tx_references: Vec<TransactionReference> = Vec::with_capacity(n_txs);
         let mut n_remaining_txs = n_txs;
 
+        // Accounts that reached the per-sender limit; their next transactions are only re-queued
+        // once this call returns, to let other accounts' transactions through.
+        let mut n_txs_per_sender: HashMap<ContractAddress, usize> = HashMap::new();
+        let mut capped_txs: Vec<TransactionReference> = Vec::new();
+
         while n_remaining_txs > 0 && self.tx_queue.has_ready_txs() {
             let chunk = self.tx_queue.pop_ready_chunk(n_remaining_txs);
-            self.enqueue_next_eligible_txs(&chunk)?;
+            let (uncapped_chunk, capped_chunk): (Vec<_>, Vec<_>) =
+                chunk.iter().copied().partition(|tx| {
+                    let n_sender_txs = n_txs_per_sender.entry(tx.address).or_default();
+                    *n_sender_txs += 1;
+                    !self
+                        .config
+                        .max_txs_per_sender_per_chunk
+                        .is_some_and(|max| *n_sender_txs >= max)
+                });
+            self.enqueue_next_eligible_txs(&uncapped_chunk)?;
+            capped_txs.extend(capped_chunk);
             n_remaining_txs -= chunk.len();
             eligible_tx_references.extend(chunk);
         }
 
+        self.enqueue_next_eligible_txs(&capped_txs)?;
+
         // Update the mempool state with the given transactions' nonces.
         for tx_reference in &eligible_tx_references {
             self.state.stage(tx_reference)?;
@@ -200,10 +489,14 @@ impl Mempool {
         err
     )]
     pub fn add_tx(&mut self, args: AddTransactionArgs) -> MempoolResult<()> {
+        let journal_entry =
+            self.journal.as_ref().map(|_| JournalEntry::AddTransaction(args.clone()));
+
         let AddTransactionArgs { tx, account_state } = args;
         let tx_reference = TransactionReference::new(&tx);
         self.validate_incoming_tx(tx_reference)?;
 
+        self.handle_capacity(&tx)?;
         self.handle_fee_escalation(&tx)?;
         self.tx_pool.insert(tx)?;
 
@@ -215,20 +508,31 @@ impl Mempool {
             self.tx_queue.insert(tx_reference);
         }
 
-        Ok(())
+        self.write_to_journal(journal_entry)
     }
 
     /// Update the mempool's internal state according to the committed block (resolves nonce gaps,
-    /// updates account balances).
+    /// updates account balances), and drops the transactions that were rejected while building it.
     #[tracing::instrument(skip(self, args), err)]
     pub fn commit_block(&mut self, args: CommitBlockArgs) -> MempoolResult<()> {
-        let CommitBlockArgs { address_to_nonce, tx_hashes } = args;
+        // Committing a block twice would forget the accounts it left empty a block early.
+        if self.last_committed_block.is_some_and(|last_block| args.block_number <= last_block) {
+            tracing::debug!("Block {} was already committed to mempool.", args.block_number);
+            return Ok(());
+        }
+
+        let journal_entry = self.journal.as_ref().map(|_| JournalEntry::CommitBlock(args.clone()));
+
+        let CommitBlockArgs { block_number, address_to_nonce, tx_hashes, rejected_tx_hashes } =
+            args;
         tracing::debug!("Committing block with {} transactions to mempool.", tx_hashes.len());
 
-        // Align mempool data to committed nonces.
         for (&address, &next_nonce) in &address_to_nonce {
-            self.validate_commitment(address, next_nonce);
+            self.validate_commitment(address, next_nonce)?;
+        }
 
+        // Align mempool data to committed nonces.
+        for (&address, &next_nonce) in &address_to_nonce {
             // Maybe remove out-of-date transactions.
             if self
                 .tx_queue
@@ -252,6 +556,7 @@ impl Mempool {
         }
 
         // Commit block and rewind nonces of addresses that were not included in block.
+        let committed_addresses: Vec<_> = address_to_nonce.keys().copied().collect();
         let addresses_to_rewind = self.state.commit(address_to_nonce);
         for address in addresses_to_rewind {
             // Account nonce is the minimal nonce of this address: it was proposed but not included.
@@ -271,21 +576,214 @@ impl Mempool {
             let Ok(_tx) = self.tx_pool.remove(tx_hash) else {
                 continue; // Transaction hash unknown to mempool, from a different node.
             };
-
-            // TODO(clean_accounts): remove address with no transactions left after a block cycle /
-            // TTL.
         }
         tracing::debug!("Removed committed transactions known to mempool.");
 
+        // Rejected transactions would fail again if proposed, so they are dropped rather than
+        // rewound; later transactions of their accounts are kept until the nonce gap is closed.
+        self.remove_rejected_txs(rejected_tx_hashes);
+
+        self.remove_expired_txs();
+        self.clean_accounts(committed_addresses);
+        self.last_committed_block = Some(block_number);
+
+        self.write_to_journal(journal_entry)?;
+
+        if self
+            .journal
+            .as_ref()
+            .is_some_and(|journal| journal.n_entries() > self.config.journal_compaction_threshold)
+        {
+            self.compact_journal()?;
+        }
+
         Ok(())
     }
 
+    /// Reverts a committed block: its transactions are returned to the mempool, and the nonces of
+    /// its accounts are restored to their values prior to the block.
+    /// Reverted transactions take precedence over transactions with the same nonce that were added
+    /// since the block was committed.
+    #[tracing::instrument(skip(self, args), err)]
+    pub fn rewind_block(&mut self, args: RewindBlockArgs) -> MempoolResult<()> {
+        // Rewinding any other block, e.g., one already reverted, would restore stale nonces.
+        if self.last_committed_block != Some(args.block_number) {
+            tracing::debug!(
+                "Block {} is not the last block committed to mempool; ignoring its rewind.",
+                args.block_number
+            );
+            return Ok(());
+        }
+
+        let journal_entry = self.journal.as_ref().map(|_| JournalEntry::RewindBlock(args.clone()));
+
+        let RewindBlockArgs { block_number, address_to_nonce, txs } = args;
+        tracing::debug!("Rewinding block with {} transactions in mempool.", txs.len());
+
+        for tx in txs {
+            let TransactionReference { address, nonce, tx_hash, .. } =
+                TransactionReference::new(&tx);
+            if let Some(existing_tx_reference) =
+                self.tx_pool.get_by_address_and_nonce(address, nonce)
+            {
+                if existing_tx_reference.tx_hash == tx_hash {
+                    continue;
+                }
+
+                self.tx_pool
+                    .remove(existing_tx_reference.tx_hash)
+                    .expect("Transaction hash from pool must exist.");
+            }
+
+            self.tx_pool.insert(tx)?;
+        }
+
+        // Realign queue to the restored nonces.
+        for (address, nonce) in address_to_nonce {
+            self.state.rewind(address, nonce);
+            self.accounts_to_clean.remove(&address);
+
+            self.tx_queue.remove(address);
+            if let Some(tx_reference) = self.tx_pool.get_by_address_and_nonce(address, nonce) {
+                self.tx_queue.insert(tx_reference);
+            }
+        }
+
+        self.last_committed_block = block_number.prev();
+
+        tracing::debug!("Rewound mempool to nonces prior to the reverted block.");
+
+        self.write_to_journal(journal_entry)
+    }
+
+    /// Removes transactions that were held in the mempool for longer than the configured TTL.
+    /// Transactions of accounts that were proposed for sequencing in the block in-progress are
+    /// kept until it is committed.
+    #[tracing::instrument(skip(self))]
+    pub fn remove_expired_txs(&mut self) {
+        let Some(expiry_time) = self.clock.now().checked_sub(self.config.transaction_ttl) else {
+            return;
+        };
+
+        let expired_txs: Vec<_> = self
+            .tx_pool
+            .get_submitted_before(expiry_time)
+            .into_iter()
+            .filter(|tx| !self.state.is_staged(tx.address))
+            .collect();
+        if expired_txs.is_empty() {
+            return;
+        }
+
+        for tx in &expired_txs {
+            if self.tx_queue.get_nonce(tx.address) == Some(tx.nonce) {
+                self.tx_queue.remove(tx.address);
+            }
+            self.tx_pool.remove(tx.tx_hash).expect("Transaction hash from pool must exist.");
+        }
+
+        // Expired accounts are forgotten immediately, unlike accounts emptied by a committed block.
+        for TransactionReference { address, .. } in &expired_txs {
+            if !self.tx_pool.contains_account(*address) {
+                self.state.remove_account(*address);
+            }
+        }
+
+        tracing::debug!("Removed {} expired transactions.", expired_txs.len());
+    }
+
+    fn remove_rejected_txs(&mut self, rejected_tx_hashes: HashSet<TransactionHash>) {
+        let mut n_removed_txs = 0;
+        for tx_hash in rejected_tx_hashes {
+            let Ok(tx) = self.tx_pool.remove(tx_hash) else {
+                continue; // Transaction hash unknown to mempool, e.g., an L1 handler transaction.
+            };
+            let TransactionReference { address, nonce, .. } = TransactionReference::new(&tx);
+            if self.tx_queue.get_nonce(address) == Some(nonce) {
+                self.tx_queue.remove(address);
+            }
+            if !self.tx_pool.contains_account(address) {
+                self.state.remove_account(address);
+            }
+            n_removed_txs += 1;
+        }
+
+        tracing::debug!("Removed {} rejected transactions.", n_removed_txs);
+    }
+
+    /// Forgets accounts that have had no transactions in the mempool for a full block cycle, and
+    /// marks the given committed accounts that were left empty for the next cycle.
+    fn clean_accounts(&mut self, committed_addresses: impl IntoIterator<Item = ContractAddress>) {
+        let accounts_to_clean = std::mem::take(&mut self.accounts_to_clean);
+        for address in accounts_to_clean {
+            if !self.tx_pool.contains_account(address) && !self.state.is_staged(address) {
+                self.state.remove_account(address);
+            }
+        }
+
+        self.accounts_to_clean = committed_addresses
+            .into_iter()
+            .filter(|&address| !self.tx_pool.contains_account(address))
+            .collect();
+    }
+
+    /// Writes the entry to the journal, if one is kept, once the operation it describes was
+    /// applied successfully; rejected operations are not replayed.
+    fn write_to_journal(&mut self, entry: Option<JournalEntry>) -> MempoolResult<()> {
+        let (Some(journal), Some(entry)) = (&mut self.journal, entry) else {
+            return Ok(());
+        };
+
+        journal.append(&entry).map_err(journal_error)
+    }
+
+    /// Replaces the journal's content with the minimal entries that recreate the current content
+    /// of the mempool: the committed nonces, followed by the transactions in the pool.
+    /// Nonces staged for the block in-progress are not recorded, as that block may not be
+    /// committed.
+    fn compact_journal(&mut self) -> MempoolResult<()> {
+        let Some(journal) = &mut self.journal else {
+            return Ok(());
+        };
+
+        // The committed nonces are recorded as of the last committed block.
+        let commit_entry = self.last_committed_block.map(|block_number| {
+            JournalEntry::CommitBlock(CommitBlockArgs {
+                block_number,
+                address_to_nonce: self.state.committed.clone(),
+                tx_hashes: HashSet::default(),
+                rejected_tx_hashes: HashSet::default(),
+            })
+        });
+        let mut tx_references: Vec<_> = self.tx_pool.iter_tx_references().collect();
+        tx_references.sort_by_key(|tx_reference| (tx_reference.address, tx_reference.nonce));
+        let add_tx_entries = tx_references.into_iter().map(|tx_reference| {
+            let TransactionReference { address, nonce: tx_nonce, tx_hash, .. } = *tx_reference;
+            let account_nonce = self.state.get_unstaged(address).unwrap_or(tx_nonce);
+            JournalEntry::AddTransaction(AddTransactionArgs {
+                tx: self
+                    .tx_pool
+                    .get_by_tx_hash(tx_hash)
+                    .expect("Transaction hash from pool must exist.")
+                    .clone(),
+                account_state: AccountState { address, nonce: account_nonce },
+            })
+        });
+        let entries: Vec<_> = commit_entry.into_iter().chain(add_tx_entries).collect();
+
+        journal.rewrite(&entries).map_err(journal_error)
+    }
+
     fn validate_incoming_tx(&self, tx_reference: TransactionReference) -> MempoolResult<()> {
         self.state.validate_incoming_tx(tx_reference)
     }
 
-    fn validate_commitment(&self, address: ContractAddress, next_nonce: Nonce) {
-        self.state.validate_commitment(address, next_nonce);
+    fn validate_commitment(
+        &self,
+        address: ContractAddress,
+        next_nonce: Nonce,
+    ) -> MempoolResult<()> {
+        self.state.validate_commitment(address, next_nonce)
     }
 
     // TODO(Mohammad): Rename this method once consensus API is added.
@@ -345,6 +843,64 @@ impl Mempool {
         Ok(())
     }
 
+    /// Makes room for the incoming transaction if the pool is full, by evicting lower-paying
+    /// transactions that are not eligible for sequencing.
+    /// Replacements are not subject to capacity limits, as they do not increase the pool size.
+    #[tracing::instrument(level = "debug", skip(self, incoming_tx), err)]
+    fn handle_capacity(&mut self, incoming_tx: &AccountTransaction) -> MempoolResult<()> {
+        let incoming_tx_reference = TransactionReference::new(incoming_tx);
+        let TransactionReference { address, nonce, tx_hash, .. } = incoming_tx_reference;
+        if self.tx_pool.get_by_address_and_nonce(address, nonce).is_some() {
+            return Ok(());
+        }
+
+        if self.tx_pool.n_account_txs(address) >= self.config.max_txs_per_account {
+            return Err(MempoolError::MempoolFull { tx_hash });
+        }
+
+        let incoming_tx_size = tx_size_in_bytes(incoming_tx);
+        while self.exceeds_capacity(incoming_tx_size) {
+            let Some(tx_to_evict) = self.lowest_priority_evictable_tx(address) else {
+                return Err(MempoolError::MempoolFull { tx_hash });
+            };
+            if eviction_priority(&tx_to_evict) >= eviction_priority(&incoming_tx_reference) {
+                return Err(MempoolError::MempoolFull { tx_hash });
+            }
+
+            tracing::debug!(
+                "{tx_to_evict} will be evicted to make room for {incoming_tx_reference}."
+            );
+            self.tx_pool
+                .remove(tx_to_evict.tx_hash)
+                .expect("Transaction hash from pool must exist.");
+        }
+
+        Ok(())
+    }
+
+    fn exceeds_capacity(&self, incoming_tx_size: usize) -> bool {
+        self.tx_pool.n_txs() >= self.config.max_txs
+            || self.tx_pool.size_in_bytes().saturating_add(incoming_tx_size)
+                > self.config.max_total_size_in_bytes
+    }
+
+    /// Returns the lowest-paying transaction that can be evicted: the highest-nonce transaction of
+    /// some account, which is neither queued nor proposed for sequencing.
+    /// Transactions of the incoming transaction's account are not evicted, to avoid creating a
+    /// nonce gap before it.
+    fn lowest_priority_evictable_tx(
+        &self,
+        incoming_address: ContractAddress,
+    ) -> Option<TransactionReference> {
+        self.tx_pool
+            .iter_highest_nonce_txs()
+            .filter(|tx| tx.address != incoming_address)
+            .filter(|tx| self.tx_queue.get_nonce(tx.address) != Some(tx.nonce))
+            .filter(|tx| !self.state.get(tx.address).is_some_and(|nonce| tx.nonce < nonce))
+            .min_by_key(|tx| eviction_priority(tx))
+            .copied()
+    }
+
     fn should_replace_tx(
         &self,
         existing_tx: &TransactionReference,
@@ -375,11 +931,20 @@ impl Mempool {
     }
 }
 
+fn journal_error(error: std::io::Error) -> MempoolError {
+    MempoolError::JournalError(error.to_string())
+}
+
 // TODO(Elin): move to a shared location with other next-gen node crates.
 fn tip(tx: &AccountTransaction) -> Tip {
     tx.tip().expect("Expected a valid tip value.")
 }
 
+/// Transactions are evicted in ascending order of tip, and then of max L2 gas price.
+fn eviction_priority(tx: &TransactionReference) -> (Tip, GasPrice) {
+    (tx.tip, tx.max_l2_gas_price)
+}
+
 fn max_l2_gas_price(tx: &AccountTransaction) -> GasPrice {
     tx.resource_bounds()
         .expect("Expected a valid resource bounds value.")
@@ -1,18 +1,37 @@
+use std::sync::Arc;
+use std::time::Duration;
+
 use pretty_assertions::assert_eq;
 use rstest::{fixture, rstest};
-use starknet_api::block::GasPrice;
+use starknet_api::block::{BlockNumber, GasPrice};
 use starknet_api::executable_transaction::AccountTransaction;
-use starknet_api::{contract_address, nonce};
+use starknet_api::transaction::TransactionHash;
+use starknet_api::{contract_address, felt, nonce};
 use starknet_mempool_types::errors::MempoolError;
-use starknet_mempool_types::mempool_types::AddTransactionArgs;
+use starknet_mempool_types::mempool_types::{
+    AddTransactionArgs,
+    CommitBlockArgs,
+    MempoolSnapshot,
+    RewindBlockArgs,
+    TransactionStatus,
+};
 
+use crate::journal::{JournalEntry, MempoolJournal};
 use crate::mempool::{Mempool, MempoolConfig, TransactionReference};
-use crate::test_utils::{add_tx, add_tx_expect_error, commit_block, get_txs_and_assert_expected};
+use crate::queue_ordering::QueueOrdering;
+use crate::test_utils::{
+    add_tx,
+    add_tx_expect_error,
+    commit_block,
+    get_txs_and_assert_expected,
+    FakeClock,
+};
 use crate::transaction_pool::TransactionPool;
 use crate::transaction_queue::transaction_queue_test_utils::{
     TransactionQueueContent,
     TransactionQueueContentBuilder,
 };
+use crate::utils::InstantClock;
 use crate::{add_tx_input, tx};
 
 // Utils.
@@ -50,6 +69,10 @@ impl From<MempoolContent> for Mempool {
                 .unwrap_or_default(),
             // TODO: Add implementation when needed.
             state: Default::default(),
+            accounts_to_clean: Default::default(),
+            last_committed_block: None,
+            clock: Arc::new(InstantClock),
+            journal: None,
         }
     }
 }
@@ -101,7 +124,36 @@ impl MempoolContentBuilder {
     }
 
     fn with_fee_escalation_percentage(mut self, fee_escalation_percentage: u8) -> Self {
-        self.config = MempoolConfig { enable_fee_escalation: true, fee_escalation_percentage };
+        self.config =
+            MempoolConfig { enable_fee_escalation: true, fee_escalation_percentage, ..self.config };
+        self
+    }
+
+    fn with_max_txs(mut self, max_txs: usize) -> Self {
+        self.config = MempoolConfig { max_txs, ..self.config };
+        self
+    }
+
+    fn with_max_txs_per_account(mut self, max_txs_per_account: usize) -> Self {
+        self.config = MempoolConfig { max_txs_per_account, ..self.config };
+        self
+    }
+
+    fn with_transaction_ttl(mut self, transaction_ttl: Duration) -> Self {
+        self.config = MempoolConfig { transaction_ttl, ..self.config };
+        self
+    }
+
+    fn with_queue_ordering(mut self, queue_ordering: QueueOrdering) -> Self {
+        self.config = MempoolConfig { queue_ordering, ..self.config };
+        self
+    }
+
+    fn with_max_txs_per_sender_per_chunk(mut self, max_txs_per_sender_per_chunk: usize) -> Self {
+        self.config = MempoolConfig {
+            max_txs_per_sender_per_chunk: Some(max_txs_per_sender_per_chunk),
+            ..self.config
+        };
         self
     }
 
@@ -314,6 +366,84 @@ fn test_get_txs_with_nonce_gap() {
     expected_mempool_content.assert_eq(&mempool);
 }
 
+#[rstest]
+fn test_get_txs_fifo_ordering() {
+    // Setup.
+    let MempoolContent { config, .. } =
+        MempoolContentBuilder::new().with_queue_ordering(QueueOrdering::Fifo).build();
+    let mut mempool = Mempool::new(config, Arc::new(InstantClock));
+
+    let input_tip_10 =
+        add_tx_input!(tx_hash: 1, address: "0x0", tx_nonce: 0, account_nonce: 0, tip: 10);
+    let input_tip_30 =
+        add_tx_input!(tx_hash: 2, address: "0x1", tx_nonce: 0, account_nonce: 0, tip: 30);
+    add_tx(&mut mempool, &input_tip_10);
+    add_tx(&mut mempool, &input_tip_30);
+
+    // Test and assert: transactions are returned in arrival order, regardless of tip.
+    get_txs_and_assert_expected(&mut mempool, 2, &[input_tip_10.tx, input_tip_30.tx]);
+}
+
+#[rstest]
+fn test_get_txs_effective_fee_ordering() {
+    // Setup.
+    let MempoolContent { config, .. } =
+        MempoolContentBuilder::new().with_queue_ordering(QueueOrdering::EffectiveFee).build();
+    let mut mempool = Mempool::new(config, Arc::new(InstantClock));
+    mempool.update_gas_price_threshold(GasPrice(10));
+
+    let input_effective_fee_70 = add_tx_input!(
+        tx_hash: 1, address: "0x0", tx_nonce: 0, account_nonce: 0, tip: 50, max_l2_gas_price: 30
+    );
+    let input_effective_fee_100 = add_tx_input!(
+        tx_hash: 2, address: "0x1", tx_nonce: 0, account_nonce: 0, tip: 10, max_l2_gas_price: 100
+    );
+    add_tx(&mut mempool, &input_effective_fee_70);
+    add_tx(&mut mempool, &input_effective_fee_100);
+
+    // Test and assert: the gas price surplus over the threshold outweighs the higher tip.
+    get_txs_and_assert_expected(
+        &mut mempool,
+        2,
+        &[input_effective_fee_100.tx, input_effective_fee_70.tx],
+    );
+}
+
+#[rstest]
+fn test_get_txs_caps_txs_per_sender() {
+    // Setup.
+    let MempoolContent { config, .. } =
+        MempoolContentBuilder::new().with_max_txs_per_sender_per_chunk(2).build();
+    let mut mempool = Mempool::new(config, Arc::new(InstantClock));
+
+    let input_address_0_nonce_0 =
+        add_tx_input!(tx_hash: 1, address: "0x0", tx_nonce: 0, account_nonce: 0, tip: 20);
+    let input_address_0_nonce_1 =
+        add_tx_input!(tx_hash: 2, address: "0x0", tx_nonce: 1, account_nonce: 0, tip: 20);
+    let input_address_0_nonce_2 =
+        add_tx_input!(tx_hash: 3, address: "0x0", tx_nonce: 2, account_nonce: 0, tip: 20);
+    let input_address_1_nonce_0 =
+        add_tx_input!(tx_hash: 4, address: "0x1", tx_nonce: 0, account_nonce: 0, tip: 10);
+    for input in [
+        &input_address_0_nonce_0,
+        &input_address_0_nonce_1,
+        &input_address_0_nonce_2,
+        &input_address_1_nonce_0,
+    ] {
+        add_tx(&mut mempool, input);
+    }
+
+    // Test and assert: account 0 gets at most two transactions in a single call.
+    get_txs_and_assert_expected(
+        &mut mempool,
+        4,
+        &[input_address_0_nonce_0.tx, input_address_1_nonce_0.tx, input_address_0_nonce_1.tx],
+    );
+
+    // Assert: the capped account's next transaction is queued for the next call.
+    get_txs_and_assert_expected(&mut mempool, 4, &[input_address_0_nonce_2.tx]);
+}
+
 // `add_tx` tests.
 
 #[rstest]
@@ -548,6 +678,333 @@ fn test_commit_block_includes_all_proposed_txs() {
     expected_mempool_content.assert_eq(&mempool);
 }
 
+#[rstest]
+fn test_commit_block_cleans_accounts_left_empty_for_a_block_cycle(mut mempool: Mempool) {
+    // Setup.
+    let input = add_tx_input!(tx_hash: 1, address: "0x0", tx_nonce: 0, account_nonce: 0);
+    add_tx(&mut mempool, &input);
+    get_txs_and_assert_expected(&mut mempool, 1, &[input.tx]);
+
+    // Test and assert: the committed nonce is kept for a single block cycle.
+    commit_block(&mut mempool, [("0x0", 1)], [1]);
+    assert_eq!(mempool.state.get(contract_address!("0x0")), Some(nonce!(1)));
+
+    commit_block(&mut mempool, [], []);
+    assert_eq!(mempool.state.get(contract_address!("0x0")), None);
+}
+
+#[rstest]
+fn test_commit_block_drops_rejected_txs(mut mempool: Mempool) {
+    // Setup.
+    let input_address_0_nonce_0 =
+        add_tx_input!(tx_hash: 1, address: "0x0", tx_nonce: 0, account_nonce: 0);
+    let input_address_0_nonce_1 =
+        add_tx_input!(tx_hash: 2, address: "0x0", tx_nonce: 1, account_nonce: 0);
+    let input_address_0_nonce_2 =
+        add_tx_input!(tx_hash: 3, address: "0x0", tx_nonce: 2, account_nonce: 0);
+    let input_address_1_nonce_0 =
+        add_tx_input!(tx_hash: 4, address: "0x1", tx_nonce: 0, account_nonce: 0);
+    for input in [
+        &input_address_0_nonce_0,
+        &input_address_0_nonce_1,
+        &input_address_0_nonce_2,
+        &input_address_1_nonce_0,
+    ] {
+        add_tx(&mut mempool, input);
+    }
+    assert_eq!(mempool.get_txs(3).unwrap().len(), 3);
+
+    // Test: the second transaction of 0x0 and the only transaction of 0x1 were rejected.
+    let args = CommitBlockArgs {
+        block_number: BlockNumber(0),
+        address_to_nonce: [(contract_address!("0x0"), nonce!(1))].into(),
+        tx_hashes: [TransactionHash(felt!(1_u8))].into(),
+        rejected_tx_hashes: [TransactionHash(felt!(2_u8)), TransactionHash(felt!(4_u8))].into(),
+    };
+    assert_eq!(mempool.commit_block(args), Ok(()));
+
+    // Assert: the rejected transactions are not proposed again; the remaining transaction of 0x0
+    // waits for its nonce gap to be closed.
+    let expected_mempool_content = MempoolContentBuilder::new()
+        .with_pool([input_address_0_nonce_2.tx])
+        .with_priority_queue([])
+        .build();
+    expected_mempool_content.assert_eq(&mempool);
+    assert_eq!(mempool.state.get(contract_address!("0x1")), None);
+}
+
+// TTL tests.
+
+#[rstest]
+fn test_remove_expired_txs() {
+    // Setup.
+    let clock = Arc::new(FakeClock::default());
+    let MempoolContent { config, .. } =
+        MempoolContentBuilder::new().with_transaction_ttl(Duration::from_secs(60)).build();
+    let mut mempool = Mempool::new(config, clock.clone());
+
+    let old_input = add_tx_input!(tx_hash: 1, address: "0x0", tx_nonce: 0, account_nonce: 0);
+    let old_gapped_input = add_tx_input!(tx_hash: 2, address: "0x1", tx_nonce: 1, account_nonce: 0);
+    add_tx(&mut mempool, &old_input);
+    add_tx(&mut mempool, &old_gapped_input);
+
+    clock.advance(Duration::from_secs(30));
+    let new_input = add_tx_input!(tx_hash: 3, address: "0x2", tx_nonce: 0, account_nonce: 0);
+    add_tx(&mut mempool, &new_input);
+
+    // Test.
+    clock.advance(Duration::from_secs(31));
+    mempool.remove_expired_txs();
+
+    // Assert: only the new transaction remains, and expired accounts are forgotten.
+    let expected_mempool_content = MempoolContentBuilder::new()
+        .with_pool([new_input.tx.clone()])
+        .with_priority_queue([TransactionReference::new(&new_input.tx)])
+        .build();
+    expected_mempool_content.assert_eq(&mempool);
+    assert_eq!(mempool.state.get(contract_address!("0x0")), None);
+    assert_eq!(mempool.state.get(contract_address!("0x1")), None);
+}
+
+#[rstest]
+fn test_remove_expired_txs_keeps_staged_accounts() {
+    // Setup.
+    let clock = Arc::new(FakeClock::default());
+    let MempoolContent { config, .. } =
+        MempoolContentBuilder::new().with_transaction_ttl(Duration::from_secs(60)).build();
+    let mut mempool = Mempool::new(config, clock.clone());
+
+    let input = add_tx_input!(tx_hash: 1, address: "0x0", tx_nonce: 0, account_nonce: 0);
+    add_tx(&mut mempool, &input);
+    get_txs_and_assert_expected(&mut mempool, 1, &[input.tx.clone()]);
+
+    // Test.
+    clock.advance(Duration::from_secs(61));
+    mempool.remove_expired_txs();
+
+    // Assert: the proposed transaction is kept until its block is committed.
+    let expected_mempool_content = MempoolContentBuilder::new().with_pool([input.tx]).build();
+    expected_mempool_content.assert_eq(&mempool);
+}
+
+#[rstest]
+fn test_commit_block_fails_on_committed_nonce_decrease(mut mempool: Mempool) {
+    // Setup.
+    commit_block(&mut mempool, [("0x0", 2)], []);
+
+    // Test and assert.
+    let args = CommitBlockArgs {
+        block_number: BlockNumber(1),
+        address_to_nonce: [(contract_address!("0x0"), nonce!(1))].into(),
+        tx_hashes: Default::default(),
+        rejected_tx_hashes: Default::default(),
+    };
+    assert_eq!(
+        mempool.commit_block(args),
+        Err(MempoolError::CommittedNonceDecrease {
+            address: contract_address!("0x0"),
+            committed_nonce: nonce!(2),
+            nonce: nonce!(1),
+        })
+    );
+}
+
+#[rstest]
+fn test_commit_block_ignores_committed_blocks(mut mempool: Mempool) {
+    // Setup: the block leaves the account with no transactions, so the account is forgotten if it
+    // remains empty after the next block.
+    commit_block(&mut mempool, [("0x0", 1)], []);
+
+    // Test: the same block is committed again.
+    let args = CommitBlockArgs {
+        block_number: BlockNumber(0),
+        address_to_nonce: [(contract_address!("0x0"), nonce!(1))].into(),
+        tx_hashes: Default::default(),
+        rejected_tx_hashes: Default::default(),
+    };
+    assert_eq!(mempool.commit_block(args), Ok(()));
+
+    // Assert: the account's committed nonce is kept.
+    assert_eq!(mempool.state.get(contract_address!("0x0")), Some(nonce!(1)));
+    assert_eq!(mempool.last_committed_block(), Some(BlockNumber(0)));
+}
+
+// `rewind_block` tests.
+
+#[rstest]
+fn test_rewind_block_returns_reverted_txs_to_mempool(mut mempool: Mempool) {
+    // Setup.
+    let input_nonce_0 = add_tx_input!(tx_hash: 1, address: "0x0", tx_nonce: 0, account_nonce: 0);
+    let input_nonce_1 = add_tx_input!(tx_hash: 2, address: "0x0", tx_nonce: 1, account_nonce: 0);
+    let input_nonce_2 = add_tx_input!(tx_hash: 3, address: "0x0", tx_nonce: 2, account_nonce: 0);
+    for input in [&input_nonce_0, &input_nonce_1, &input_nonce_2] {
+        add_tx(&mut mempool, input);
+    }
+    get_txs_and_assert_expected(
+        &mut mempool,
+        2,
+        &[input_nonce_0.tx.clone(), input_nonce_1.tx.clone()],
+    );
+    commit_block(&mut mempool, [("0x0", 2)], [1, 2]);
+
+    // Test.
+    let args = RewindBlockArgs {
+        block_number: BlockNumber(0),
+        address_to_nonce: [(contract_address!("0x0"), nonce!(0))].into(),
+        txs: vec![input_nonce_0.tx.clone(), input_nonce_1.tx.clone()],
+    };
+    assert_eq!(mempool.rewind_block(args), Ok(()));
+
+    // Assert: the account's transactions are sequenced again from the restored nonce.
+    assert_eq!(mempool.state.get(contract_address!("0x0")), Some(nonce!(0)));
+    let expected_mempool_content = MempoolContentBuilder::new()
+        .with_pool([input_nonce_0.tx.clone(), input_nonce_1.tx, input_nonce_2.tx])
+        .with_priority_queue([TransactionReference::new(&input_nonce_0.tx)])
+        .build();
+    expected_mempool_content.assert_eq(&mempool);
+    assert_eq!(mempool.last_committed_block(), None);
+}
+
+#[rstest]
+#[case::already_reverted_block(BlockNumber(1))]
+#[case::uncommitted_block(BlockNumber(2))]
+fn test_rewind_block_ignores_blocks_other_than_last_committed(
+    mut mempool: Mempool,
+    #[case] block_number: BlockNumber,
+) {
+    // Setup: blocks 0 and 1 are committed, and block 1 is reverted.
+    let input_nonce_0 = add_tx_input!(tx_hash: 1, address: "0x0", tx_nonce: 0, account_nonce: 0);
+    let input_nonce_1 = add_tx_input!(tx_hash: 2, address: "0x0", tx_nonce: 1, account_nonce: 0);
+    for input in [&input_nonce_0, &input_nonce_1] {
+        add_tx(&mut mempool, input);
+    }
+    get_txs_and_assert_expected(&mut mempool, 1, &[input_nonce_0.tx.clone()]);
+    commit_block(&mut mempool, [("0x0", 1)], [1]);
+    get_txs_and_assert_expected(&mut mempool, 1, &[input_nonce_1.tx.clone()]);
+    commit_block(&mut mempool, [("0x0", 2)], [2]);
+    let args = RewindBlockArgs {
+        block_number: BlockNumber(1),
+        address_to_nonce: [(contract_address!("0x0"), nonce!(1))].into(),
+        txs: vec![input_nonce_1.tx.clone()],
+    };
+    assert_eq!(mempool.rewind_block(args), Ok(()));
+
+    // Test: a rewind that would restore the nonce prior to block 0.
+    let args = RewindBlockArgs {
+        block_number,
+        address_to_nonce: [(contract_address!("0x0"), nonce!(0))].into(),
+        txs: vec![input_nonce_0.tx],
+    };
+    assert_eq!(mempool.rewind_block(args), Ok(()));
+
+    // Assert: the rewind is ignored.
+    assert_eq!(mempool.state.get(contract_address!("0x0")), Some(nonce!(1)));
+    let expected_mempool_content = MempoolContentBuilder::new()
+        .with_pool([input_nonce_1.tx.clone()])
+        .with_priority_queue([TransactionReference::new(&input_nonce_1.tx)])
+        .build();
+    expected_mempool_content.assert_eq(&mempool);
+    assert_eq!(mempool.last_committed_block(), Some(BlockNumber(0)));
+}
+
+// Journal tests.
+
+#[rstest]
+fn test_mempool_recovered_from_journal() {
+    // Setup.
+    let dir = tempfile::tempdir().unwrap();
+    let config = || MempoolConfig {
+        journal_path: Some(dir.path().join("mempool.journal")),
+        ..Default::default()
+    };
+    let mut mempool = Mempool::with_journal(config(), Arc::new(InstantClock)).unwrap();
+
+    let committed_input = add_tx_input!(tx_hash: 1, address: "0x0", tx_nonce: 0, account_nonce: 0);
+    let queued_input = add_tx_input!(tx_hash: 2, address: "0x0", tx_nonce: 1, account_nonce: 0);
+    let gapped_input = add_tx_input!(tx_hash: 3, address: "0x1", tx_nonce: 1, account_nonce: 0);
+    for input in [&committed_input, &queued_input, &gapped_input] {
+        add_tx(&mut mempool, input);
+    }
+    get_txs_and_assert_expected(&mut mempool, 1, &[committed_input.tx]);
+    commit_block(&mut mempool, [("0x0", 1)], [1]);
+    drop(mempool);
+
+    // Test.
+    let mempool = Mempool::with_journal(config(), Arc::new(InstantClock)).unwrap();
+
+    // Assert: only the transactions that were not committed are recovered.
+    let expected_mempool_content = MempoolContentBuilder::new()
+        .with_pool([queued_input.tx.clone(), gapped_input.tx])
+        .with_priority_queue([TransactionReference::new(&queued_input.tx)])
+        .build();
+    expected_mempool_content.assert_eq(&mempool);
+    assert_eq!(mempool.state.get(contract_address!("0x0")), Some(nonce!(1)));
+    assert_eq!(mempool.last_committed_block(), Some(BlockNumber(0)));
+}
+
+#[rstest]
+fn test_rejected_txs_are_not_journaled() {
+    // Setup.
+    let dir = tempfile::tempdir().unwrap();
+    let journal_path = dir.path().join("mempool.journal");
+    let config = MempoolConfig { journal_path: Some(journal_path.clone()), ..Default::default() };
+    let mut mempool = Mempool::with_journal(config, Arc::new(InstantClock)).unwrap();
+
+    let input = add_tx_input!(tx_hash: 1, address: "0x0", tx_nonce: 1, account_nonce: 1);
+    let duplicate_nonce_input =
+        add_tx_input!(tx_hash: 2, address: "0x0", tx_nonce: 1, account_nonce: 1);
+    let old_nonce_input = add_tx_input!(tx_hash: 3, address: "0x0", tx_nonce: 0, account_nonce: 1);
+
+    // Test.
+    add_tx(&mut mempool, &input);
+    add_tx_expect_error(
+        &mut mempool,
+        &duplicate_nonce_input,
+        MempoolError::DuplicateNonce { address: contract_address!("0x0"), nonce: nonce!(1) },
+    );
+    add_tx_expect_error(
+        &mut mempool,
+        &old_nonce_input,
+        MempoolError::NonceTooOld { address: contract_address!("0x0"), nonce: nonce!(0) },
+    );
+    drop(mempool);
+
+    // Assert: only the accepted transaction was journaled.
+    let (_, entries) = MempoolJournal::open(journal_path).unwrap();
+    assert_eq!(entries, vec![JournalEntry::AddTransaction(input)]);
+}
+
+#[rstest]
+fn test_journal_compaction_ignores_staged_nonces() {
+    // Setup.
+    let dir = tempfile::tempdir().unwrap();
+    let config = || MempoolConfig {
+        journal_path: Some(dir.path().join("mempool.journal")),
+        ..Default::default()
+    };
+    let mut mempool = Mempool::with_journal(config(), Arc::new(InstantClock)).unwrap();
+
+    let input_nonce_0 = add_tx_input!(tx_hash: 1, address: "0x0", tx_nonce: 0, account_nonce: 0);
+    let input_nonce_1 = add_tx_input!(tx_hash: 2, address: "0x0", tx_nonce: 1, account_nonce: 0);
+    for input in [&input_nonce_0, &input_nonce_1] {
+        add_tx(&mut mempool, input);
+    }
+    // Stage the first transaction for the block in-progress.
+    get_txs_and_assert_expected(&mut mempool, 1, &[input_nonce_0.tx.clone()]);
+
+    // Test.
+    mempool.compact_journal().unwrap();
+    drop(mempool);
+    let mempool = Mempool::with_journal(config(), Arc::new(InstantClock)).unwrap();
+
+    // Assert: the block in-progress was not committed, so its transaction is sequenced again.
+    let expected_mempool_content = MempoolContentBuilder::new()
+        .with_pool([input_nonce_0.tx.clone(), input_nonce_1.tx])
+        .with_priority_queue([TransactionReference::new(&input_nonce_0.tx)])
+        .build();
+    expected_mempool_content.assert_eq(&mempool);
+}
+
 // Fee escalation tests.
 
 #[rstest]
@@ -735,3 +1192,180 @@ fn test_update_gas_price_threshold_decreases_threshold() {
         .build();
     expected_mempool_content.assert_eq(&mempool);
 }
+
+// Capacity tests.
+
+#[rstest]
+fn test_add_tx_evicts_lowest_priority_non_queued_tx_when_full() {
+    // Setup.
+    let mut mempool = MempoolContentBuilder::new().with_max_txs(3).build_into_mempool();
+    let queued_input =
+        add_tx_input!(tx_hash: 1, address: "0x0", tx_nonce: 0, account_nonce: 0, tip: 1);
+    let gapped_input_tip_10 =
+        add_tx_input!(tx_hash: 2, address: "0x1", tx_nonce: 1, account_nonce: 0, tip: 10);
+    let gapped_input_tip_20 =
+        add_tx_input!(tx_hash: 3, address: "0x2", tx_nonce: 1, account_nonce: 0, tip: 20);
+    for input in [&queued_input, &gapped_input_tip_10, &gapped_input_tip_20] {
+        add_tx(&mut mempool, input);
+    }
+
+    // Test.
+    let incoming_input =
+        add_tx_input!(tx_hash: 4, address: "0x3", tx_nonce: 0, account_nonce: 0, tip: 15);
+    add_tx(&mut mempool, &incoming_input);
+
+    // Assert: the lowest-paying transaction that is not queued was evicted.
+    let expected_queue_txs = [&incoming_input.tx, &queued_input.tx].map(TransactionReference::new);
+    let expected_pool_txs = [queued_input.tx, gapped_input_tip_20.tx, incoming_input.tx];
+    let expected_mempool_content = MempoolContentBuilder::new()
+        .with_pool(expected_pool_txs)
+        .with_priority_queue(expected_queue_txs)
+        .build();
+    expected_mempool_content.assert_eq(&mempool);
+}
+
+#[rstest]
+fn test_add_tx_evicts_highest_nonce_of_account_first() {
+    // Setup.
+    let mut mempool = MempoolContentBuilder::new().with_max_txs(2).build_into_mempool();
+    let input_nonce_1 =
+        add_tx_input!(tx_hash: 1, address: "0x0", tx_nonce: 1, account_nonce: 0, tip: 10);
+    let input_nonce_2 =
+        add_tx_input!(tx_hash: 2, address: "0x0", tx_nonce: 2, account_nonce: 0, tip: 10);
+    for input in [&input_nonce_1, &input_nonce_2] {
+        add_tx(&mut mempool, input);
+    }
+
+    // Test.
+    let incoming_input =
+        add_tx_input!(tx_hash: 3, address: "0x1", tx_nonce: 0, account_nonce: 0, tip: 20);
+    add_tx(&mut mempool, &incoming_input);
+
+    // Assert: no nonce gap was created in account 0x0.
+    let expected_pool_txs = [input_nonce_1.tx, incoming_input.tx];
+    let expected_mempool_content =
+        MempoolContentBuilder::new().with_pool(expected_pool_txs).build();
+    expected_mempool_content.assert_eq(&mempool);
+}
+
+#[rstest]
+#[case::lower_tip(5)]
+#[case::equal_tip(10)]
+fn test_add_tx_rejected_when_full_and_nothing_to_evict(#[case] incoming_tip: u64) {
+    // Setup.
+    let mut mempool = MempoolContentBuilder::new().with_max_txs(2).build_into_mempool();
+    let queued_input =
+        add_tx_input!(tx_hash: 1, address: "0x0", tx_nonce: 0, account_nonce: 0, tip: 1);
+    let gapped_input =
+        add_tx_input!(tx_hash: 2, address: "0x1", tx_nonce: 1, account_nonce: 0, tip: 10);
+    for input in [&queued_input, &gapped_input] {
+        add_tx(&mut mempool, input);
+    }
+
+    // Test and assert: queued transactions are never evicted.
+    let incoming_input = add_tx_input!(
+        tx_hash: 3, address: "0x2", tx_nonce: 0, account_nonce: 0, tip: incoming_tip
+    );
+    add_tx_expect_error(
+        &mut mempool,
+        &incoming_input,
+        MempoolError::MempoolFull { tx_hash: incoming_input.tx.tx_hash() },
+    );
+    let expected_mempool_content =
+        MempoolContentBuilder::new().with_pool([queued_input.tx, gapped_input.tx]).build();
+    expected_mempool_content.assert_eq(&mempool);
+}
+
+#[rstest]
+fn test_add_tx_rejected_when_account_is_full() {
+    // Setup.
+    let mut mempool = MempoolContentBuilder::new().with_max_txs_per_account(1).build_into_mempool();
+    let input_nonce_0 = add_tx_input!(tx_hash: 1, address: "0x0", tx_nonce: 0, account_nonce: 0);
+    add_tx(&mut mempool, &input_nonce_0);
+
+    // Test and assert.
+    let input_nonce_1 = add_tx_input!(tx_hash: 2, address: "0x0", tx_nonce: 1, account_nonce: 0);
+    add_tx_expect_error(
+        &mut mempool,
+        &input_nonce_1,
+        MempoolError::MempoolFull { tx_hash: input_nonce_1.tx.tx_hash() },
+    );
+}
+
+#[rstest]
+fn test_update_gas_price_threshold_resumes_suspended_txs(mut mempool: Mempool) {
+    // Setup.
+    let input_low_gas = add_tx_input!(tx_hash: 1, address: "0x0", tip: 10, max_l2_gas_price: 100);
+    let input_high_gas = add_tx_input!(tx_hash: 2, address: "0x1", tip: 20, max_l2_gas_price: 200);
+    for input in [&input_low_gas, &input_high_gas] {
+        add_tx(&mut mempool, input);
+    }
+
+    // Test and assert: increasing the threshold suspends the low gas price transaction.
+    mempool.update_gas_price_threshold(GasPrice(150));
+    assert_eq!((mempool.n_ready_txs(), mempool.n_suspended_txs()), (1, 1));
+
+    // Test and assert: decreasing the threshold resumes it.
+    mempool.update_gas_price_threshold(GasPrice(50));
+    assert_eq!((mempool.n_ready_txs(), mempool.n_suspended_txs()), (2, 0));
+    get_txs_and_assert_expected(&mut mempool, 2, &[input_high_gas.tx, input_low_gas.tx]);
+}
+
+// Query tests.
+
+#[rstest]
+fn test_get_tx_status(mut mempool: Mempool) {
+    // Setup.
+    let staged_input = add_tx_input!(tx_hash: 1, address: "0x0", tx_nonce: 0, account_nonce: 0);
+    let queued_input = add_tx_input!(tx_hash: 2, address: "0x0", tx_nonce: 1, account_nonce: 0);
+    let gapped_input = add_tx_input!(tx_hash: 3, address: "0x0", tx_nonce: 3, account_nonce: 0);
+    let pending_input = add_tx_input!(tx_hash: 4, address: "0x1", tip: 0, max_l2_gas_price: 1);
+    for input in [&staged_input, &queued_input, &gapped_input, &pending_input] {
+        add_tx(&mut mempool, input);
+    }
+    mempool.update_gas_price_threshold(GasPrice(2));
+    get_txs_and_assert_expected(&mut mempool, 1, &[staged_input.tx.clone()]);
+
+    // Test and assert.
+    let expected_statuses = [
+        (&staged_input, TransactionStatus::Staged),
+        (&queued_input, TransactionStatus::Queued),
+        (&gapped_input, TransactionStatus::NonceGapped),
+        (&pending_input, TransactionStatus::PendingThreshold),
+    ];
+    for (input, expected_status) in expected_statuses {
+        assert_eq!(mempool.get_tx_status(input.tx.tx_hash()), Ok(expected_status));
+    }
+
+    let unknown_tx_hash = tx!(tx_hash: 5, address: "0x2", tx_nonce: 0).tx_hash();
+    assert_eq!(
+        mempool.get_tx_status(unknown_tx_hash),
+        Err(MempoolError::TransactionNotFound { tx_hash: unknown_tx_hash })
+    );
+}
+
+#[rstest]
+fn test_get_account_txs_and_snapshot(mut mempool: Mempool) {
+    // Setup.
+    let input_nonce_1 = add_tx_input!(tx_hash: 1, address: "0x0", tx_nonce: 1, account_nonce: 0);
+    let input_nonce_0 = add_tx_input!(tx_hash: 2, address: "0x0", tx_nonce: 0, account_nonce: 0);
+    let other_account_input = add_tx_input!(tx_hash: 3, address: "0x1", tx_nonce: 0);
+    for input in [&input_nonce_1, &input_nonce_0, &other_account_input] {
+        add_tx(&mut mempool, input);
+    }
+
+    // Test and assert.
+    assert_eq!(
+        mempool.get_account_txs(contract_address!("0x0")),
+        [input_nonce_0.tx, input_nonce_1.tx]
+    );
+    assert_eq!(
+        mempool.snapshot(),
+        MempoolSnapshot {
+            n_txs_in_pool: 3,
+            n_txs_in_priority_queue: 2,
+            n_tentative_accounts: 2,
+            ..Default::default()
+        }
+    );
+}
@@ -0,0 +1,75 @@
+use std::sync::Arc;
+
+use serde::{Deserialize, Serialize};
+use starknet_api::block::GasPrice;
+
+use crate::mempool::TransactionReference;
+
+/// Determines the order in which eligible transactions are returned for sequencing.
+pub trait QueueOrderingPolicy: std::fmt::Debug + Send + Sync {
+    /// Returns the priority of the given transaction; transactions with a higher priority are
+    /// sequenced first, and ties are broken by transaction hash.
+    /// `arrival_id` increases with the time the transaction became eligible for sequencing.
+    fn priority(
+        &self,
+        tx: &TransactionReference,
+        arrival_id: u64,
+        gas_price_threshold: GasPrice,
+    ) -> u128;
+}
+
+/// Orders transactions by descending tip.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct TipOrdering;
+
+impl QueueOrderingPolicy for TipOrdering {
+    fn priority(&self, tx: &TransactionReference, _arrival_id: u64, _: GasPrice) -> u128 {
+        u128::from(tx.tip.0)
+    }
+}
+
+/// Orders transactions by the time they became eligible for sequencing, earliest first.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct FifoOrdering;
+
+impl QueueOrderingPolicy for FifoOrdering {
+    fn priority(&self, _tx: &TransactionReference, arrival_id: u64, _: GasPrice) -> u128 {
+        u128::from(u64::MAX - arrival_id)
+    }
+}
+
+/// Orders transactions by descending effective fee: the tip, plus the amount by which the max L2
+/// gas price exceeds the gas price threshold.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct EffectiveFeeOrdering;
+
+impl QueueOrderingPolicy for EffectiveFeeOrdering {
+    fn priority(
+        &self,
+        tx: &TransactionReference,
+        _arrival_id: u64,
+        gas_price_threshold: GasPrice,
+    ) -> u128 {
+        let l2_gas_price_surplus = tx.max_l2_gas_price.0.saturating_sub(gas_price_threshold.0);
+        u128::from(tx.tip.0).saturating_add(l2_gas_price_surplus)
+    }
+}
+
+/// Selects a queue ordering policy from the mempool config.
+#[derive(Clone, Copy, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
+pub enum QueueOrdering {
+    #[default]
+    Tip,
+    Fifo,
+    EffectiveFee,
+}
+
+impl QueueOrdering {
+    pub fn policy(&self) -> Arc<dyn QueueOrderingPolicy> {
+        match self {
+            QueueOrdering::Tip => Arc::new(TipOrdering),
+            QueueOrdering::Fifo => Arc::new(FifoOrdering),
+            QueueOrdering::EffectiveFee => Arc::new(EffectiveFeeOrdering),
+        }
+    }
+}
@@ -1,30 +1,112 @@
-use std::collections::HashMap;
+use std::cmp::Ordering;
+use std::collections::{BTreeSet, HashMap};
 
+use starknet_api::block::GasPrice;
 use starknet_api::core::{ContractAddress, Nonce};
+use starknet_api::transaction::fields::Tip;
+use starknet_api::transaction::TransactionHash;
 
 use crate::mempool::TransactionReference;
 
-type _AddressNonceToTransaction = HashMap<(ContractAddress, Nonce), TransactionReference>;
+type AddressNonceToTransaction = HashMap<(ContractAddress, Nonce), TransactionReference>;
 
-#[derive(Debug)]
-pub struct _SuspendedTransactionPool {
-    suspended_tx_pool: _AddressNonceToTransaction,
+/// Holds eligible transactions whose max L2 gas price is below the gas price threshold; these are
+/// suspended from sequencing until the threshold is lowered.
+#[derive(Debug, Default, Eq, PartialEq)]
+pub struct SuspendedTransactionPool {
+    suspended_tx_pool: AddressNonceToTransaction,
+    // Transactions sorted by their max L2 gas price.
+    txs_by_gas_price: BTreeSet<PendingTransaction>,
 }
 
-impl _SuspendedTransactionPool {
-    pub fn _contains(&self, address: ContractAddress, nonce: Nonce) -> bool {
+impl SuspendedTransactionPool {
+    pub fn contains(&self, address: ContractAddress, nonce: Nonce) -> bool {
         self.suspended_tx_pool.contains_key(&(address, nonce))
     }
 
-    pub fn _insert(&mut self, tx: TransactionReference) {
+    pub fn insert(&mut self, tx: TransactionReference) {
         assert_eq!(
             self.suspended_tx_pool.insert((tx.address, tx.nonce), tx),
             None,
             "Keys should be unique; duplicates are checked prior."
         );
+        self.txs_by_gas_price.insert(tx.into());
     }
 
-    pub fn _remove(&mut self, tx: &TransactionReference) -> bool {
-        self.suspended_tx_pool.remove(&(tx.address, tx.nonce)).is_some()
+    pub fn remove(&mut self, tx: &TransactionReference) -> bool {
+        let Some(removed_tx) = self.suspended_tx_pool.remove(&(tx.address, tx.nonce)) else {
+            return false;
+        };
+
+        assert!(
+            self.txs_by_gas_price.remove(&removed_tx.into()),
+            "Suspended transaction pool consistency error: transaction with hash {} does not \
+             appear in the gas price mapping.",
+            removed_tx.tx_hash
+        );
+        true
+    }
+
+    /// Removes and returns all transactions whose max L2 gas price is at least the given threshold.
+    pub fn remove_at_or_above_threshold(
+        &mut self,
+        threshold: GasPrice,
+    ) -> Vec<TransactionReference> {
+        let tmp_split_tx = PendingTransaction(TransactionReference {
+            max_l2_gas_price: threshold,
+            address: ContractAddress::default(),
+            nonce: Nonce::default(),
+            tx_hash: TransactionHash::default(),
+            tip: Tip::default(),
+        });
+
+        // Split off the transactions at the given threshold, `tmp_split_tx` is not part of the set.
+        let txs_over_threshold: Vec<_> =
+            self.txs_by_gas_price.split_off(&tmp_split_tx).into_iter().map(|tx| tx.0).collect();
+        for tx in &txs_over_threshold {
+            self.suspended_tx_pool.remove(&(tx.address, tx.nonce));
+        }
+
+        txs_over_threshold
+    }
+
+    /// Returns an iterator of the suspended transactions, ordered by descending max L2 gas price.
+    pub fn iter(&self) -> impl Iterator<Item = &TransactionReference> {
+        self.txs_by_gas_price.iter().rev().map(|tx| &tx.0)
+    }
+
+    pub fn len(&self) -> usize {
+        self.suspended_tx_pool.len()
+    }
+}
+
+/// Encapsulates a transaction reference to assess its order (i.e., gas price).
+#[derive(Clone, Copy, Debug, derive_more::Deref, derive_more::From)]
+pub struct PendingTransaction(pub TransactionReference);
+
+/// Compare transactions based only on their gas price, using the Eq trait. It ensures that
+/// two gas price are either exactly equal or not.
+impl PartialEq for PendingTransaction {
+    fn eq(&self, other: &PendingTransaction) -> bool {
+        self.max_l2_gas_price == other.max_l2_gas_price && self.tx_hash == other.tx_hash
+    }
+}
+
+/// Marks this struct as capable of strict equality comparisons, signaling to the compiler it
+/// adheres to equality semantics.
+// Note: this depends on the implementation of `PartialEq`, see its docstring.
+impl Eq for PendingTransaction {}
+
+impl Ord for PendingTransaction {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.max_l2_gas_price
+            .cmp(&other.max_l2_gas_price)
+            .then_with(|| self.tx_hash.cmp(&other.tx_hash))
+    }
+}
+
+impl PartialOrd for PendingTransaction {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
     }
 }
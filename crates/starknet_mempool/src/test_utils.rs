@@ -1,6 +1,9 @@
 use std::collections::{HashMap, HashSet};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
 
 use pretty_assertions::assert_eq;
+use starknet_api::block::BlockNumber;
 use starknet_api::executable_transaction::AccountTransaction;
 use starknet_api::transaction::TransactionHash;
 use starknet_api::{contract_address, felt, nonce};
@@ -8,6 +11,7 @@ use starknet_mempool_types::errors::MempoolError;
 use starknet_mempool_types::mempool_types::{AddTransactionArgs, CommitBlockArgs};
 
 use crate::mempool::Mempool;
+use crate::utils::Clock;
 
 /// Creates an executable invoke transaction with the given field subset (the rest receive default
 /// values).
@@ -244,7 +248,15 @@ pub fn commit_block(
     );
     let tx_hashes =
         HashSet::from_iter(tx_hashes.into_iter().map(|tx_hash| TransactionHash(felt!(tx_hash))));
-    let args = CommitBlockArgs { address_to_nonce: nonces, tx_hashes };
+    let block_number = mempool
+        .last_committed_block()
+        .map_or(BlockNumber(0), |block_number| block_number.unchecked_next());
+    let args = CommitBlockArgs {
+        block_number,
+        address_to_nonce: nonces,
+        tx_hashes,
+        rejected_tx_hashes: HashSet::new(),
+    };
 
     assert_eq!(mempool.commit_block(args), Ok(()));
 }
@@ -258,3 +270,27 @@ pub fn get_txs_and_assert_expected(
     let txs = mempool.get_txs(n_txs).unwrap();
     assert_eq!(txs, expected_txs);
 }
+
+/// A clock that only advances when explicitly told to.
+#[derive(Debug)]
+pub struct FakeClock {
+    now: Mutex<Instant>,
+}
+
+impl Default for FakeClock {
+    fn default() -> Self {
+        FakeClock { now: Mutex::new(Instant::now()) }
+    }
+}
+
+impl FakeClock {
+    pub fn advance(&self, duration: Duration) {
+        *self.now.lock().unwrap() += duration;
+    }
+}
+
+impl Clock for FakeClock {
+    fn now(&self) -> Instant {
+        *self.now.lock().unwrap()
+    }
+}
@@ -1,4 +1,6 @@
-use std::collections::{hash_map, BTreeMap, HashMap};
+use std::collections::{hash_map, BTreeMap, BTreeSet, HashMap};
+use std::sync::Arc;
+use std::time::Instant;
 
 use starknet_api::core::{ContractAddress, Nonce};
 use starknet_api::executable_transaction::AccountTransaction;
@@ -7,7 +9,7 @@ use starknet_mempool_types::errors::MempoolError;
 use starknet_mempool_types::mempool_types::{AccountState, MempoolResult};
 
 use crate::mempool::TransactionReference;
-use crate::utils::try_increment_nonce;
+use crate::utils::{try_increment_nonce, Clock, InstantClock};
 
 type HashToTransaction = HashMap<TransactionHash, AccountTransaction>;
 
@@ -15,21 +17,54 @@ type HashToTransaction = HashMap<TransactionHash, AccountTransaction>;
 /// Invariant: both data structures are consistent regarding the existence of transactions:
 /// A transaction appears in one if and only if it appears in the other.
 /// No duplicate transactions appear in the pool.
-#[derive(Debug, Default, Eq, PartialEq)]
+#[derive(Debug)]
 pub struct TransactionPool {
     // Holds the complete transaction objects; it should be the sole entity that does so.
     tx_pool: HashToTransaction,
     // Transactions organized by account address, sorted by ascending nonce values.
     txs_by_account: AccountTransactionIndex,
+    // Transactions organized by the time they were added to the pool.
+    txs_by_submission_time: TimedTransactionIndex,
     // Tracks the capacity of the pool.
     capacity: PoolCapacity,
+    clock: Arc<dyn Clock>,
+}
+
+impl Default for TransactionPool {
+    fn default() -> Self {
+        Self::new(Arc::new(InstantClock))
+    }
 }
 
+/// Submission times depend on the clock, and are therefore not considered part of the pool's
+/// content.
+impl PartialEq for TransactionPool {
+    fn eq(&self, other: &Self) -> bool {
+        self.tx_pool == other.tx_pool
+            && self.txs_by_account == other.txs_by_account
+            && self.capacity == other.capacity
+    }
+}
+
+impl Eq for TransactionPool {}
+
 impl TransactionPool {
+    pub fn new(clock: Arc<dyn Clock>) -> Self {
+        TransactionPool {
+            tx_pool: HashToTransaction::default(),
+            txs_by_account: AccountTransactionIndex::default(),
+            txs_by_submission_time: TimedTransactionIndex::default(),
+            capacity: PoolCapacity::default(),
+            clock,
+        }
+    }
+
     pub fn insert(&mut self, tx: AccountTransaction) -> MempoolResult<()> {
         let tx_reference = TransactionReference::new(&tx);
         let tx_hash = tx_reference.tx_hash;
 
+        let size_in_bytes = tx_size_in_bytes(&tx);
+
         // Insert to pool.
         if let hash_map::Entry::Vacant(entry) = self.tx_pool.entry(tx_hash) {
             entry.insert(tx);
@@ -47,7 +82,8 @@ impl TransactionPool {
             )
         };
 
-        self.capacity.add();
+        self.txs_by_submission_time.insert(tx_hash, self.clock.now());
+        self.capacity.add(size_in_bytes);
 
         Ok(())
     }
@@ -65,7 +101,8 @@ impl TransactionPool {
             )
         });
 
-        self.capacity.remove();
+        self.txs_by_submission_time.remove(tx_hash);
+        self.capacity.remove(tx_size_in_bytes(&tx));
 
         Ok(tx)
     }
@@ -74,14 +111,15 @@ impl TransactionPool {
         let removed_txs = self.txs_by_account.remove_up_to_nonce(address, nonce);
 
         for TransactionReference { tx_hash, .. } in removed_txs {
-            self.tx_pool.remove(&tx_hash).unwrap_or_else(|| {
+            let tx = self.tx_pool.remove(&tx_hash).unwrap_or_else(|| {
                 panic!(
                     "Transaction pool consistency error: transaction with hash {tx_hash} appears
                     in account mapping, but does not appear in the main mapping"
                 );
             });
 
-            self.capacity.remove();
+            self.txs_by_submission_time.remove(tx_hash);
+            self.capacity.remove(tx_size_in_bytes(&tx));
         }
     }
 
@@ -113,8 +151,47 @@ impl TransactionPool {
         Ok(self.get_by_address_and_nonce(address, next_nonce))
     }
 
-    pub fn _contains_account(&self, address: ContractAddress) -> bool {
-        self.txs_by_account._contains(address)
+    pub fn contains_account(&self, address: ContractAddress) -> bool {
+        self.txs_by_account.contains(address)
+    }
+
+    pub fn n_txs(&self) -> usize {
+        self.capacity.n_txs
+    }
+
+    pub fn size_in_bytes(&self) -> usize {
+        self.capacity.size_in_bytes
+    }
+
+    pub fn n_account_txs(&self, address: ContractAddress) -> usize {
+        self.txs_by_account.n_account_txs(address)
+    }
+
+    pub fn iter_tx_references(&self) -> impl Iterator<Item = &TransactionReference> {
+        self.txs_by_account.iter()
+    }
+
+    /// Returns the transactions that were added to the pool before the given time.
+    pub fn get_submitted_before(&self, submission_time: Instant) -> Vec<TransactionReference> {
+        self.txs_by_submission_time
+            .iter_submitted_before(submission_time)
+            .map(|tx_hash| {
+                let tx = self.tx_pool.get(&tx_hash).unwrap_or_else(|| {
+                    panic!(
+                        "Transaction pool consistency error: transaction with hash {tx_hash} \
+                         appears in submission time mapping, but does not appear in the main \
+                         mapping"
+                    )
+                });
+                TransactionReference::new(tx)
+            })
+            .collect()
+    }
+
+    /// Returns the transaction with the highest nonce of each account, i.e., the transactions that
+    /// can be dropped without creating a nonce gap.
+    pub fn iter_highest_nonce_txs(&self) -> impl Iterator<Item = &TransactionReference> {
+        self.txs_by_account.iter_highest_nonce_txs()
     }
 }
 
@@ -172,24 +249,77 @@ impl AccountTransactionIndex {
         txs_with_lower_nonce.into_values().collect()
     }
 
-    fn _contains(&self, address: ContractAddress) -> bool {
+    fn contains(&self, address: ContractAddress) -> bool {
         self.0.contains_key(&address)
     }
+
+    fn iter(&self) -> impl Iterator<Item = &TransactionReference> {
+        self.0.values().flat_map(|nonce_to_tx_ref| nonce_to_tx_ref.values())
+    }
+
+    fn n_account_txs(&self, address: ContractAddress) -> usize {
+        self.0.get(&address).map_or(0, |account_txs| account_txs.len())
+    }
+
+    fn iter_highest_nonce_txs(&self) -> impl Iterator<Item = &TransactionReference> {
+        self.0.values().filter_map(|nonce_to_tx_ref| nonce_to_tx_ref.values().next_back())
+    }
+}
+
+#[derive(Debug, Default)]
+struct TimedTransactionIndex {
+    // Transactions sorted by submission time; the hash tie-breaks identical times.
+    txs_by_time: BTreeSet<(Instant, TransactionHash)>,
+    hash_to_submission_time: HashMap<TransactionHash, Instant>,
+}
+
+impl TimedTransactionIndex {
+    fn insert(&mut self, tx_hash: TransactionHash, submission_time: Instant) {
+        self.txs_by_time.insert((submission_time, tx_hash));
+        self.hash_to_submission_time.insert(tx_hash, submission_time);
+    }
+
+    fn remove(&mut self, tx_hash: TransactionHash) {
+        if let Some(submission_time) = self.hash_to_submission_time.remove(&tx_hash) {
+            self.txs_by_time.remove(&(submission_time, tx_hash));
+        }
+    }
+
+    fn iter_submitted_before(
+        &self,
+        submission_time: Instant,
+    ) -> impl Iterator<Item = TransactionHash> + '_ {
+        self.txs_by_time
+            .iter()
+            .take_while(move |(tx_submission_time, _)| *tx_submission_time < submission_time)
+            .map(|(_, tx_hash)| *tx_hash)
+    }
 }
 
 #[derive(Debug, Default, Eq, PartialEq)]
 pub struct PoolCapacity {
     n_txs: usize,
-    // TODO(Ayelet): Add size tracking.
+    // Total serialized size of the transactions in the pool.
+    size_in_bytes: usize,
 }
 
 impl PoolCapacity {
-    fn add(&mut self) {
+    fn add(&mut self, tx_size_in_bytes: usize) {
         self.n_txs += 1;
+        self.size_in_bytes += tx_size_in_bytes;
     }
 
-    fn remove(&mut self) {
+    fn remove(&mut self, tx_size_in_bytes: usize) {
         self.n_txs =
             self.n_txs.checked_sub(1).expect("Underflow: Cannot subtract from an empty pool.");
+        self.size_in_bytes = self
+            .size_in_bytes
+            .checked_sub(tx_size_in_bytes)
+            .expect("Underflow: Cannot subtract more bytes than the pool holds.");
     }
 }
+
+/// Returns the size of the serialized transaction, used for tracking the pool's capacity.
+pub fn tx_size_in_bytes(tx: &AccountTransaction) -> usize {
+    serde_json::to_vec(tx).expect("Transaction serialization should not fail.").len()
+}
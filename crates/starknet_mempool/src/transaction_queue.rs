@@ -1,58 +1,77 @@
 use std::cmp::Ordering;
 use std::collections::{BTreeSet, HashMap};
+use std::sync::Arc;
 
 use starknet_api::block::GasPrice;
 use starknet_api::core::{ContractAddress, Nonce};
-use starknet_api::transaction::fields::Tip;
-use starknet_api::transaction::TransactionHash;
 
 use crate::mempool::TransactionReference;
+use crate::queue_ordering::{QueueOrderingPolicy, TipOrdering};
+use crate::suspended_transaction_pool::SuspendedTransactionPool;
 
 #[cfg(test)]
 #[path = "transaction_queue_test_utils.rs"]
 pub mod transaction_queue_test_utils;
 
 // A queue holding the transaction that with nonces that match account nonces.
-// Note: the derived comparison functionality considers the order guaranteed by the data structures
-// used.
-#[derive(Debug, Default, Eq, PartialEq)]
+#[derive(Debug)]
 pub struct TransactionQueue {
     gas_price_threshold: GasPrice,
-    // Transactions with gas price above gas price threshold (sorted by tip).
+    // Transactions with gas price above gas price threshold (sorted by the ordering policy).
     priority_queue: BTreeSet<PriorityTransaction>,
-    // Transactions with gas price below gas price threshold (sorted by price).
-    pending_queue: BTreeSet<PendingTransaction>,
+    // Transactions with gas price below gas price threshold, suspended from sequencing.
+    suspended_tx_pool: SuspendedTransactionPool,
     // Set of account addresses for efficient existence checks.
-    address_to_tx: HashMap<ContractAddress, TransactionReference>,
+    address_to_tx: HashMap<ContractAddress, QueuedTransaction>,
+    ordering_policy: Arc<dyn QueueOrderingPolicy>,
+    next_arrival_id: u64,
+}
+
+impl Default for TransactionQueue {
+    fn default() -> Self {
+        Self::new(Arc::new(TipOrdering))
+    }
 }
 
 impl TransactionQueue {
+    pub fn new(ordering_policy: Arc<dyn QueueOrderingPolicy>) -> Self {
+        TransactionQueue {
+            gas_price_threshold: GasPrice::default(),
+            priority_queue: BTreeSet::default(),
+            suspended_tx_pool: SuspendedTransactionPool::default(),
+            address_to_tx: HashMap::default(),
+            ordering_policy,
+            next_arrival_id: 0,
+        }
+    }
+
     /// Adds a transaction to the mempool, ensuring unique keys.
     /// Panics: if given a duplicate tx.
     pub fn insert(&mut self, tx_reference: TransactionReference) {
-        assert_eq!(
-            self.address_to_tx.insert(tx_reference.address, tx_reference),
-            None,
+        let queued_tx = QueuedTransaction { tx_reference, arrival_id: self.next_arrival_id };
+        self.next_arrival_id += 1;
+        assert!(
+            self.address_to_tx.insert(tx_reference.address, queued_tx).is_none(),
             "Only a single transaction from the same contract class can be in the mempool at a \
              time."
         );
 
-        let new_tx_successfully_inserted =
-            if tx_reference.max_l2_gas_price < self.gas_price_threshold {
-                self.pending_queue.insert(tx_reference.into())
-            } else {
-                self.priority_queue.insert(tx_reference.into())
-            };
+        if tx_reference.max_l2_gas_price < self.gas_price_threshold {
+            self.suspended_tx_pool.insert(tx_reference);
+            return;
+        }
+
         assert!(
-            new_tx_successfully_inserted,
+            self.priority_queue.insert(self.to_priority_tx(queued_tx)),
             "Keys should be unique; duplicates are checked prior."
         );
     }
 
     // TODO(gilad): remove collect, if returning an iterator is possible.
     pub fn pop_ready_chunk(&mut self, n_txs: usize) -> Vec<TransactionReference> {
-        let txs: Vec<TransactionReference> =
-            (0..n_txs).filter_map(|_| self.priority_queue.pop_last().map(|tx| tx.0)).collect();
+        let txs: Vec<TransactionReference> = (0..n_txs)
+            .filter_map(|_| self.priority_queue.pop_last().map(|tx| tx.tx_reference))
+            .collect();
         for tx in &txs {
             self.address_to_tx.remove(&tx.address);
         }
@@ -60,61 +79,73 @@ impl TransactionQueue {
         txs
     }
 
-    /// Returns an iterator of the current eligible transactions for sequencing, ordered by their
-    /// priority.
     pub fn iter_over_ready_txs(&self) -> impl Iterator<Item = &TransactionReference> {
-        self.priority_queue.iter().rev().map(|tx| &tx.0)
+        self.priority_queue.iter().rev().map(|tx| &tx.tx_reference)
     }
 
     pub fn get_nonce(&self, address: ContractAddress) -> Option<Nonce> {
-        self.address_to_tx.get(&address).map(|tx| tx.nonce)
+        self.address_to_tx.get(&address).map(|tx| tx.tx_reference.nonce)
+    }
+
+    /// Returns whether the given transaction is queued, i.e., it is the eligible transaction of its
+    /// account, either ready or suspended.
+    pub fn contains(&self, tx_reference: &TransactionReference) -> bool {
+        self.address_to_tx
+            .get(&tx_reference.address)
+            .is_some_and(|tx| tx.tx_reference == *tx_reference)
+    }
+
+    pub fn is_suspended(&self, tx_reference: &TransactionReference) -> bool {
+        self.suspended_tx_pool.contains(tx_reference.address, tx_reference.nonce)
     }
 
     /// Removes the transaction of the given account address from the queue.
     /// This is well-defined, since there is at most one transaction per address in the queue.
     pub fn remove(&mut self, address: ContractAddress) -> bool {
-        let Some(tx_reference) = self.address_to_tx.remove(&address) else {
+        let Some(queued_tx) = self.address_to_tx.remove(&address) else {
             return false;
         };
 
-        self.priority_queue.remove(&tx_reference.into())
-            || self.pending_queue.remove(&tx_reference.into())
+        self.priority_queue.remove(&self.to_priority_tx(queued_tx))
+            || self.suspended_tx_pool.remove(&queued_tx.tx_reference)
     }
 
     pub fn has_ready_txs(&self) -> bool {
         !self.priority_queue.is_empty()
     }
 
+    /// Returns the number of transactions eligible for sequencing.
+    pub fn n_ready_txs(&self) -> usize {
+        self.priority_queue.len()
+    }
+
+    /// Returns the number of transactions suspended due to a gas price below the threshold.
+    pub fn n_suspended_txs(&self) -> usize {
+        self.suspended_tx_pool.len()
+    }
+
     pub fn update_gas_price_threshold(&mut self, threshold: GasPrice) {
         match threshold.cmp(&self.gas_price_threshold) {
             Ordering::Less => self.promote_txs_to_priority(threshold),
-            Ordering::Greater => self.demote_txs_to_pending(threshold),
-            Ordering::Equal => {}
+            Ordering::Greater => self.suspend_txs_below_threshold(threshold),
+            Ordering::Equal => return,
         }
 
         self.gas_price_threshold = threshold;
+        self.reprioritize();
     }
 
     fn promote_txs_to_priority(&mut self, threshold: GasPrice) {
-        let tmp_split_tx = PendingTransaction(TransactionReference {
-            max_l2_gas_price: threshold,
-            address: ContractAddress::default(),
-            nonce: Nonce::default(),
-            tx_hash: TransactionHash::default(),
-            tip: Tip::default(),
-        });
+        let txs_over_threshold = self.suspended_tx_pool.remove_at_or_above_threshold(threshold);
 
-        // Split off the pending queue at the given transaction higher than the threshold.
-        let txs_over_threshold = self.pending_queue.split_off(&tmp_split_tx).into_iter();
-
-        // Insert all transactions from the split point into the priority queue, skip
-        // `tmp_split_tx`.
-        // Note: extend will reorder transactions by `Tip` during insertion, despite them being
-        // initially ordered by fee.
-        self.priority_queue.extend(txs_over_threshold.map(|tx| PriorityTransaction::from(tx.0)));
+        // Note: priorities are assigned once the new threshold is set.
+        for tx in txs_over_threshold {
+            let queued_tx = self.address_to_tx[&tx.address];
+            self.priority_queue.insert(self.to_priority_tx(queued_tx));
+        }
     }
 
-    fn demote_txs_to_pending(&mut self, threshold: GasPrice) {
+    fn suspend_txs_below_threshold(&mut self, threshold: GasPrice) {
         let mut txs_to_remove = Vec::new();
 
         // Remove all transactions from the priority queue that are below the threshold.
@@ -124,52 +155,57 @@ impl TransactionQueue {
             }
         }
 
-        for tx in &txs_to_remove {
-            self.priority_queue.remove(tx);
+        for tx in txs_to_remove {
+            self.priority_queue.remove(&tx);
+            self.suspended_tx_pool.insert(tx.tx_reference);
         }
-        self.pending_queue.extend(txs_to_remove.iter().map(|tx| PendingTransaction::from(tx.0)));
     }
-}
 
-/// Encapsulates a transaction reference to assess its order (i.e., gas price).
-#[derive(Clone, Copy, Debug, derive_more::Deref, derive_more::From)]
-struct PendingTransaction(pub TransactionReference);
+    /// Recomputes the priorities of the ready transactions, which may depend on the gas price
+    /// threshold.
+    fn reprioritize(&mut self) {
+        let priority_queue = std::mem::take(&mut self.priority_queue);
+        self.priority_queue = priority_queue
+            .into_iter()
+            .map(|tx| self.to_priority_tx(self.address_to_tx[&tx.address]))
+            .collect();
+    }
 
-/// Compare transactions based only on their gas price, using the Eq trait. It ensures that
-/// two gas price are either exactly equal or not.
-impl PartialEq for PendingTransaction {
-    fn eq(&self, other: &PendingTransaction) -> bool {
-        self.max_l2_gas_price == other.max_l2_gas_price && self.tx_hash == other.tx_hash
+    fn to_priority_tx(&self, queued_tx: QueuedTransaction) -> PriorityTransaction {
+        let QueuedTransaction { tx_reference, arrival_id } = queued_tx;
+        let priority =
+            self.ordering_policy.priority(&tx_reference, arrival_id, self.gas_price_threshold);
+        PriorityTransaction { priority, tx_reference }
     }
 }
 
-/// Marks this struct as capable of strict equality comparisons, signaling to the compiler it
-/// adheres to equality semantics.
-// Note: this depends on the implementation of `PartialEq`, see its docstring.
-impl Eq for PendingTransaction {}
+/// A transaction in the queue, along with the order in which it was inserted.
+#[derive(Clone, Copy, Debug)]
+struct QueuedTransaction {
+    tx_reference: TransactionReference,
+    arrival_id: u64,
+}
 
-impl Ord for PendingTransaction {
-    fn cmp(&self, other: &Self) -> Ordering {
-        self.max_l2_gas_price
-            .cmp(&other.max_l2_gas_price)
-            .then_with(|| self.tx_hash.cmp(&other.tx_hash))
-    }
+/// This struct behaves similarly to `PendingTransaction`, encapsulating a transaction reference
+/// to assess its order (i.e., priority assigned by the ordering policy); see its documentation for
+/// more details.
+#[derive(Clone, Copy, Debug)]
+struct PriorityTransaction {
+    priority: u128,
+    tx_reference: TransactionReference,
 }
 
-impl PartialOrd for PendingTransaction {
-    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
-        Some(self.cmp(other))
+impl std::ops::Deref for PriorityTransaction {
+    type Target = TransactionReference;
+
+    fn deref(&self) -> &Self::Target {
+        &self.tx_reference
     }
 }
 
-/// This struct behaves similarly to `PendingTransaction`, encapsulating a transaction reference
-/// to assess its order (i.e., tip); see its documentation for more details.
-#[derive(Clone, Copy, Debug, derive_more::Deref, derive_more::From)]
-struct PriorityTransaction(pub TransactionReference);
-
 impl PartialEq for PriorityTransaction {
     fn eq(&self, other: &PriorityTransaction) -> bool {
-        self.tip == other.tip && self.tx_hash == other.tx_hash
+        self.priority == other.priority && self.tx_hash == other.tx_hash
     }
 }
 
@@ -177,7 +213,7 @@ impl Eq for PriorityTransaction {}
 
 impl Ord for PriorityTransaction {
     fn cmp(&self, other: &Self) -> Ordering {
-        self.tip.cmp(&other.tip).then_with(|| self.tx_hash.cmp(&other.tx_hash))
+        self.priority.cmp(&other.priority).then_with(|| self.tx_hash.cmp(&other.tx_hash))
     }
 }
 
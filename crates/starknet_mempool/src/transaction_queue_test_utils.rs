@@ -1,11 +1,10 @@
-use std::collections::HashMap;
-
 use starknet_api::block::GasPrice;
 
 use crate::mempool::TransactionReference;
-use crate::transaction_queue::{PendingTransaction, PriorityTransaction, TransactionQueue};
+use crate::suspended_transaction_pool::PendingTransaction;
+use crate::transaction_queue::{QueuedTransaction, TransactionQueue};
 
-type OptionalPriorityTransactions = Option<Vec<PriorityTransaction>>;
+type OptionalPriorityTransactions = Option<Vec<TransactionReference>>;
 type OptionalPendingTransactions = Option<Vec<PendingTransaction>>;
 
 /// Represents the internal content of the transaction queue.
@@ -22,15 +21,14 @@ impl TransactionQueueContent {
     #[track_caller]
     pub fn assert_eq(&self, tx_queue: &TransactionQueue) {
         if let Some(priority_queue) = &self.priority_queue {
-            let expected_priority_txs: Vec<_> = priority_queue.iter().map(|tx| &tx.0).collect();
+            let expected_priority_txs: Vec<_> = priority_queue.iter().collect();
             let actual_priority_txs: Vec<_> = tx_queue.iter_over_ready_txs().collect();
             assert_eq!(actual_priority_txs, expected_priority_txs);
         }
 
         if let Some(pending_queue) = &self.pending_queue {
             let expected_pending_txs: Vec<_> = pending_queue.iter().map(|tx| &tx.0).collect();
-            let actual_pending_txs: Vec<_> =
-                tx_queue.pending_queue.iter().rev().map(|tx| &tx.0).collect();
+            let actual_pending_txs: Vec<_> = tx_queue.suspended_tx_pool.iter().collect();
             assert_eq!(actual_pending_txs, expected_pending_txs);
         }
     }
@@ -38,27 +36,36 @@ impl TransactionQueueContent {
     pub fn complete_to_tx_queue(self) -> TransactionQueue {
         let pending_queue = self.pending_queue.unwrap_or_default();
         let priority_queue = self.priority_queue.unwrap_or_default();
-        let gas_price_threshold = self.gas_price_threshold.unwrap_or_default();
+        let mut tx_queue = TransactionQueue {
+            gas_price_threshold: self.gas_price_threshold.unwrap_or_default(),
+            ..Default::default()
+        };
 
         // Build address to nonce mapping, check queues are mutually exclusive in addresses.
         let tx_references = pending_queue
             .iter()
             .map(|pending_tx| pending_tx.0)
-            .chain(priority_queue.iter().map(|priority_tx| priority_tx.0));
-        let mut address_to_tx = HashMap::new();
-        for tx_ref in tx_references {
-            let address = tx_ref.address;
-            if address_to_tx.insert(address, tx_ref).is_some() {
+            .chain(priority_queue.iter().copied());
+        for tx_reference in tx_references {
+            let address = tx_reference.address;
+            let queued_tx =
+                QueuedTransaction { tx_reference, arrival_id: tx_queue.next_arrival_id };
+            tx_queue.next_arrival_id += 1;
+            if tx_queue.address_to_tx.insert(address, queued_tx).is_some() {
                 panic!("Duplicate address: {address}; queues must be mutually exclusive.");
             }
         }
 
-        TransactionQueue {
-            priority_queue: priority_queue.into_iter().collect(),
-            pending_queue: pending_queue.into_iter().collect(),
-            address_to_tx,
-            gas_price_threshold,
+        for pending_tx in pending_queue {
+            tx_queue.suspended_tx_pool.insert(pending_tx.0);
+        }
+        for tx_reference in priority_queue {
+            let priority_tx =
+                tx_queue.to_priority_tx(tx_queue.address_to_tx[&tx_reference.address]);
+            tx_queue.priority_queue.insert(priority_tx);
         }
+
+        tx_queue
     }
 }
 
@@ -74,8 +81,7 @@ impl TransactionQueueContentBuilder {
     where
         P: IntoIterator<Item = TransactionReference>,
     {
-        self.priority_queue =
-            Some(priority_txs.into_iter().map(PriorityTransaction::from).collect());
+        self.priority_queue = Some(priority_txs.into_iter().collect());
         self
     }
 
@@ -1,3 +1,5 @@
+use std::time::Instant;
+
 use starknet_api::core::Nonce;
 use starknet_mempool_types::communication::MempoolResult;
 use starknet_mempool_types::errors::MempoolError;
@@ -5,3 +7,18 @@ use starknet_mempool_types::errors::MempoolError;
 pub fn try_increment_nonce(nonce: Nonce) -> MempoolResult<Nonce> {
     nonce.try_increment().map_err(|_| MempoolError::NonceTooLarge(nonce))
 }
+
+/// A source of the current time, injectable to allow deterministic testing of time-dependent
+/// mempool logic.
+pub trait Clock: std::fmt::Debug + Send + Sync {
+    fn now(&self) -> Instant;
+}
+
+#[derive(Debug, Default)]
+pub struct InstantClock;
+
+impl Clock for InstantClock {
+    fn now(&self) -> Instant {
+        Instant::now()
+    }
+}
@@ -16,9 +16,8 @@ use papyrus_network_types::network_types::BroadcastedMessageMetadata;
 use papyrus_protobuf::mempool::RpcTransactionWrapper;
 use papyrus_test_utils::{get_rng, GetTestInstance};
 use starknet_api::rpc_transaction::RpcTransaction;
-use starknet_api::transaction::TransactionHash;
 use starknet_gateway_types::communication::{GatewayClient, GatewayClientResult};
-use starknet_gateway_types::gateway_types::GatewayInput;
+use starknet_gateway_types::gateway_types::{GatewayInput, GatewayOutput, InvokeGatewayOutput};
 use starknet_sequencer_infra::component_definitions::ComponentStarter;
 use tokio::time::sleep;
 
@@ -32,9 +31,9 @@ struct MockGatewayClient {
 
 #[async_trait]
 impl GatewayClient for MockGatewayClient {
-    async fn add_tx(&self, gateway_input: GatewayInput) -> GatewayClientResult<TransactionHash> {
+    async fn add_tx(&self, gateway_input: GatewayInput) -> GatewayClientResult<GatewayOutput> {
         let _ = self.clone().add_tx_sender.send(gateway_input.rpc_tx).await;
-        Ok(TransactionHash::default())
+        Ok(GatewayOutput::Invoke(InvokeGatewayOutput { transaction_hash: Default::default() }))
     }
 }
 
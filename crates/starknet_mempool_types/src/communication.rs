@@ -1,4 +1,5 @@
 use std::sync::Arc;
+use std::time::Duration;
 
 use async_trait::async_trait;
 #[cfg(any(feature = "testing", test))]
@@ -6,7 +7,9 @@ use mockall::automock;
 use papyrus_network_types::network_types::BroadcastedMessageMetadata;
 use papyrus_proc_macros::handle_response_variants;
 use serde::{Deserialize, Serialize};
+use starknet_api::core::ContractAddress;
 use starknet_api::executable_transaction::AccountTransaction;
+use starknet_api::transaction::TransactionHash;
 use starknet_sequencer_infra::component_client::{
     ClientError,
     LocalComponentClient,
@@ -19,7 +22,13 @@ use starknet_sequencer_infra::component_definitions::{
 use thiserror::Error;
 
 use crate::errors::MempoolError;
-use crate::mempool_types::{AddTransactionArgs, CommitBlockArgs};
+use crate::mempool_types::{
+    AddTransactionArgs,
+    CommitBlockArgs,
+    MempoolSnapshot,
+    RewindBlockArgs,
+    TransactionStatus,
+};
 
 pub type LocalMempoolClient = LocalComponentClient<MempoolRequest, MempoolResponse>;
 pub type RemoteMempoolClient = RemoteComponentClient<MempoolRequest, MempoolResponse>;
@@ -44,21 +53,44 @@ pub trait MempoolClient: Send + Sync {
     // TODO: Rename tx to transaction
     async fn add_tx(&self, args: AddTransactionArgsWrapper) -> MempoolClientResult<()>;
     async fn commit_block(&self, args: CommitBlockArgs) -> MempoolClientResult<()>;
+    async fn rewind_block(&self, args: RewindBlockArgs) -> MempoolClientResult<()>;
     async fn get_txs(&self, n_txs: usize) -> MempoolClientResult<Vec<AccountTransaction>>;
+    /// Returns once the mempool has transactions eligible for sequencing, or once the timeout
+    /// passes, whichever comes first.
+    async fn wait_for_txs(&self, timeout: Duration) -> MempoolClientResult<()>;
+    async fn get_tx_status(
+        &self,
+        tx_hash: TransactionHash,
+    ) -> MempoolClientResult<TransactionStatus>;
+    async fn get_account_txs(
+        &self,
+        address: ContractAddress,
+    ) -> MempoolClientResult<Vec<AccountTransaction>>;
+    async fn snapshot(&self) -> MempoolClientResult<MempoolSnapshot>;
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub enum MempoolRequest {
     AddTransaction(AddTransactionArgsWrapper),
     CommitBlock(CommitBlockArgs),
+    RewindBlock(RewindBlockArgs),
     GetTransactions(usize),
+    WaitForTransactions(Duration),
+    GetTransactionStatus(TransactionHash),
+    GetAccountTransactions(ContractAddress),
+    GetSnapshot,
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub enum MempoolResponse {
     AddTransaction(MempoolResult<()>),
     CommitBlock(MempoolResult<()>),
+    RewindBlock(MempoolResult<()>),
     GetTransactions(MempoolResult<Vec<AccountTransaction>>),
+    WaitForTransactions(MempoolResult<()>),
+    GetTransactionStatus(MempoolResult<TransactionStatus>),
+    GetAccountTransactions(MempoolResult<Vec<AccountTransaction>>),
+    GetSnapshot(MempoolResult<MempoolSnapshot>),
 }
 
 #[derive(Clone, Debug, Error)]
@@ -86,6 +118,12 @@ where
         handle_response_variants!(MempoolResponse, CommitBlock, MempoolClientError, MempoolError)
     }
 
+    async fn rewind_block(&self, args: RewindBlockArgs) -> MempoolClientResult<()> {
+        let request = MempoolRequest::RewindBlock(args);
+        let response = self.send(request).await;
+        handle_response_variants!(MempoolResponse, RewindBlock, MempoolClientError, MempoolError)
+    }
+
     async fn get_txs(&self, n_txs: usize) -> MempoolClientResult<Vec<AccountTransaction>> {
         let request = MempoolRequest::GetTransactions(n_txs);
         let response = self.send(request).await;
@@ -96,4 +134,49 @@ where
             MempoolError
         )
     }
+
+    async fn wait_for_txs(&self, timeout: Duration) -> MempoolClientResult<()> {
+        let request = MempoolRequest::WaitForTransactions(timeout);
+        let response = self.send(request).await;
+        handle_response_variants!(
+            MempoolResponse,
+            WaitForTransactions,
+            MempoolClientError,
+            MempoolError
+        )
+    }
+
+    async fn get_tx_status(
+        &self,
+        tx_hash: TransactionHash,
+    ) -> MempoolClientResult<TransactionStatus> {
+        let request = MempoolRequest::GetTransactionStatus(tx_hash);
+        let response = self.send(request).await;
+        handle_response_variants!(
+            MempoolResponse,
+            GetTransactionStatus,
+            MempoolClientError,
+            MempoolError
+        )
+    }
+
+    async fn get_account_txs(
+        &self,
+        address: ContractAddress,
+    ) -> MempoolClientResult<Vec<AccountTransaction>> {
+        let request = MempoolRequest::GetAccountTransactions(address);
+        let response = self.send(request).await;
+        handle_response_variants!(
+            MempoolResponse,
+            GetAccountTransactions,
+            MempoolClientError,
+            MempoolError
+        )
+    }
+
+    async fn snapshot(&self) -> MempoolClientResult<MempoolSnapshot> {
+        let request = MempoolRequest::GetSnapshot;
+        let response = self.send(request).await;
+        handle_response_variants!(MempoolResponse, GetSnapshot, MempoolClientError, MempoolError)
+    }
 }
@@ -5,10 +5,19 @@ use thiserror::Error;
 
 #[derive(Clone, Debug, Error, PartialEq, Eq, Serialize, Deserialize)]
 pub enum MempoolError {
+    #[error(
+        "Committed nonce of account address {address} cannot decrease from {committed_nonce} to \
+         {nonce}; reverted blocks should be rewound."
+    )]
+    CommittedNonceDecrease { address: ContractAddress, committed_nonce: Nonce, nonce: Nonce },
     #[error("Duplicate transaction, sender address: {address}, nonce: {:?}", nonce)]
     DuplicateNonce { address: ContractAddress, nonce: Nonce },
     #[error("Duplicate transaction, with hash: {tx_hash}")]
     DuplicateTransaction { tx_hash: TransactionHash },
+    #[error("Failed to access the mempool journal: {0}")]
+    JournalError(String),
+    #[error("Mempool is full; transaction with hash: {tx_hash} cannot be added.")]
+    MempoolFull { tx_hash: TransactionHash },
     #[error("{0}")]
     NonceTooLarge(Nonce),
     #[error("Nonce: {nonce} for account address {address} has already been processed.")]
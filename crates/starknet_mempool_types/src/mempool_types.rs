@@ -1,6 +1,7 @@
 use std::collections::{HashMap, HashSet};
 
 use serde::{Deserialize, Serialize};
+use starknet_api::block::BlockNumber;
 use starknet_api::core::{ContractAddress, Nonce};
 use starknet_api::executable_transaction::AccountTransaction;
 use starknet_api::transaction::TransactionHash;
@@ -29,8 +30,47 @@ pub struct AddTransactionArgs {
 
 #[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub struct CommitBlockArgs {
+    /// The committed block; blocks the mempool already committed are ignored, so a commit may be
+    /// safely retried.
+    pub block_number: BlockNumber,
     pub address_to_nonce: HashMap<ContractAddress, Nonce>,
     pub tx_hashes: HashSet<TransactionHash>,
+    /// Transactions that failed execution while building the block; dropped from the mempool.
+    pub rejected_tx_hashes: HashSet<TransactionHash>,
+}
+
+/// Describes a reverted block: its transactions, and the account nonces prior to its commitment.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct RewindBlockArgs {
+    /// The reverted block; only the last block the mempool committed can be reverted, so other
+    /// blocks are ignored and a rewind may be safely retried.
+    pub block_number: BlockNumber,
+    pub address_to_nonce: HashMap<ContractAddress, Nonce>,
+    pub txs: Vec<AccountTransaction>,
+}
+
+/// The stage of a transaction held in the mempool.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub enum TransactionStatus {
+    /// Eligible for sequencing; waiting in the priority queue.
+    Queued,
+    /// Eligible for sequencing, but its max L2 gas price is below the gas price threshold.
+    PendingThreshold,
+    /// Not eligible for sequencing, since a transaction with a lower nonce is missing.
+    NonceGapped,
+    /// Proposed for sequencing in the block in-progress.
+    Staged,
+}
+
+/// The sizes of the mempool's internal data structures.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, Serialize, Deserialize)]
+pub struct MempoolSnapshot {
+    pub n_txs_in_pool: usize,
+    pub n_txs_in_priority_queue: usize,
+    pub n_txs_in_pending_queue: usize,
+    pub n_committed_accounts: usize,
+    pub n_staged_accounts: usize,
+    pub n_tentative_accounts: usize,
 }
 
 pub type MempoolResult<T> = Result<T, MempoolError>;
@@ -1,7 +1,9 @@
 use std::any::type_name;
 use std::collections::BTreeMap;
 use std::fmt::Debug;
+use std::future::Future;
 use std::net::{IpAddr, Ipv4Addr, SocketAddr};
+use std::pin::Pin;
 
 use async_trait::async_trait;
 use papyrus_config::dumping::{ser_param, SerializeConfig};
@@ -22,9 +24,21 @@ const DEFAULT_RETRIES: usize = 3;
 const DEFAULT_IDLE_CONNECTIONS: usize = usize::MAX;
 const DEFAULT_IDLE_TIMEOUT: u64 = 90;
 
+/// A response that is computed without the component, see
+/// [`ComponentRequestHandler::handle_waiting_request`].
+pub type ResponseFuture<Response> = Pin<Box<dyn Future<Output = Response> + Send>>;
+
 #[async_trait]
 pub trait ComponentRequestHandler<Request, Response> {
     async fn handle_request(&mut self, request: Request) -> Response;
+
+    /// Handles a request that waits for the state of the component to change (e.g., a long poll),
+    /// by returning its response as a future that doesn't borrow the component. The server keeps
+    /// handling the following requests while the future is pending. Returns `None` for requests
+    /// that are handled by [`Self::handle_request`].
+    fn handle_waiting_request(&mut self, _request: &Request) -> Option<ResponseFuture<Response>> {
+        None
+    }
 }
 
 #[async_trait]
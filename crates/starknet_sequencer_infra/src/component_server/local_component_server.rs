@@ -123,7 +123,7 @@ impl<Component, Request, Response> ComponentServerStarter
 where
     Component: ComponentRequestHandler<Request, Response> + Send + Sync + ComponentStarter,
     Request: Send + Sync + Debug,
-    Response: Send + Sync + Debug,
+    Response: Send + Sync + Debug + 'static,
 {
     async fn start(&mut self) -> Result<(), ComponentServerError> {
         info!("Starting LocalComponentServer for {}.", type_name::<Component>());
@@ -144,7 +144,7 @@ impl<Component, Request, Response> ComponentServerStarter
 where
     Component: ComponentRequestHandler<Request, Response> + ComponentStarter + Clone + Send + Sync,
     Request: Send + Sync + Debug,
-    Response: Send + Sync + Debug,
+    Response: Send + Sync + Debug + 'static,
 {
     async fn start(&mut self) -> Result<(), ComponentServerError> {
         let mut component = self.component.clone();
@@ -209,7 +209,7 @@ async fn request_response_loop<Request, Response, Component>(
 ) where
     Component: ComponentRequestHandler<Request, Response> + Send + Sync,
     Request: Send + Sync + Debug,
-    Response: Send + Sync + Debug,
+    Response: Send + Sync + Debug + 'static,
 {
     info!("Starting server for component {}", type_name::<Component>());
 
@@ -218,6 +218,16 @@ async fn request_response_loop<Request, Response, Component>(
         let tx = request_and_res_tx.tx;
         debug!("Component {} received request {:?}", type_name::<Component>(), request);
 
+        if let Some(response_future) = component.handle_waiting_request(&request) {
+            let component_name = type_name::<Component>();
+            tokio::spawn(async move {
+                let response = response_future.await;
+                debug!("Component {} is sending response {:?}", component_name, response);
+                tx.send(response).await.expect("Response connection should be open.");
+            });
+            continue;
+        }
+
         let response = component.handle_request(request).await;
         debug!("Component {} is sending response {:?}", type_name::<Component>(), response);
 
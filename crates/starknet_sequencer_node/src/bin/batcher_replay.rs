@@ -0,0 +1,113 @@
+use std::process::exit;
+use std::time::Duration;
+
+use clap::{Arg, Command};
+use starknet_api::block::BlockNumber;
+use starknet_batcher::replay::create_block_replayer;
+use starknet_sequencer_infra::trace_util::configure_tracing;
+use starknet_sequencer_node::config::node_config::SequencerNodeConfig;
+use tracing::{error, info};
+
+/// Re-executes blocks from the batcher's storage and reports where the results differ from the
+/// stored blocks, e.g., to check a blockifier upgrade before deploying it. The storage should hold
+/// full blocks, e.g., that of a synced node:
+/// cargo run --bin batcher_replay -- -f <config_file> -s <start_block> -e <end_block>
+#[tokio::main]
+async fn main() -> anyhow::Result<()> {
+    configure_tracing();
+
+    let cli_params = get_cli_params();
+    let mut config_args = vec!["batcher_replay".to_owned()];
+    if let Some(config_file) = cli_params.config_file {
+        config_args.extend(["--config_file".to_owned(), config_file]);
+    }
+    let config = SequencerNodeConfig::load_and_process(config_args)?;
+    let block_replayer = create_block_replayer(&config.batcher_config);
+
+    let mut n_mismatched_blocks = 0;
+    for height in cli_params.start_block..cli_params.end_block {
+        let deadline = tokio::time::Instant::now() + cli_params.timeout;
+        let diff = match block_replayer.replay_block(BlockNumber(height), deadline).await {
+            Ok(diff) => diff,
+            Err(err) => {
+                error!("Failed to replay block {}: {}", height, err);
+                exit(1);
+            }
+        };
+        if diff.is_empty() {
+            info!("Block {} was reproduced.", height);
+        } else {
+            n_mismatched_blocks += 1;
+            error!("Block {} differs from the stored block: {:#?}", height, diff);
+        }
+    }
+
+    if n_mismatched_blocks > 0 {
+        error!("{} blocks differ from the stored blocks.", n_mismatched_blocks);
+        exit(1);
+    }
+    Ok(())
+}
+
+struct CliParams {
+    config_file: Option<String>,
+    start_block: u64,
+    end_block: u64,
+    timeout: Duration,
+}
+
+/// The start_block and end_block arguments are mandatory and define the block range to replay,
+/// start_block is inclusive and end_block is exclusive. The config_file is optional, otherwise the
+/// default sequencer config is used.
+fn get_cli_params() -> CliParams {
+    let matches = Command::new("Batcher replay")
+        .arg(
+            Arg::new("config_file")
+                .short('f')
+                .long("config_file")
+                .help("A sequencer config file, whose batcher config is used for the replay."),
+        )
+        .arg(
+            Arg::new("start_block")
+                .short('s')
+                .long("start_block")
+                .required(true)
+                .help("The block number to start replaying from."),
+        )
+        .arg(
+            Arg::new("end_block")
+                .short('e')
+                .long("end_block")
+                .required(true)
+                .help("The block number to end replaying at."),
+        )
+        .arg(
+            Arg::new("timeout_secs")
+                .short('t')
+                .long("timeout_secs")
+                .default_value("60")
+                .help("The maximal time to re-execute a block, in seconds."),
+        )
+        .get_matches();
+
+    let config_file = matches.get_one::<String>("config_file").cloned();
+    let start_block = matches
+        .get_one::<String>("start_block")
+        .expect("Failed parsing start_block")
+        .parse::<u64>()
+        .expect("Failed parsing start_block");
+    let end_block = matches
+        .get_one::<String>("end_block")
+        .expect("Failed parsing end_block")
+        .parse::<u64>()
+        .expect("Failed parsing end_block");
+    if start_block >= end_block {
+        panic!("start_block must be smaller than end_block");
+    }
+    let timeout_secs = matches
+        .get_one::<String>("timeout_secs")
+        .expect("Failed parsing timeout_secs")
+        .parse::<u64>()
+        .expect("Failed parsing timeout_secs");
+    CliParams { config_file, start_block, end_block, timeout: Duration::from_secs(timeout_secs) }
+}
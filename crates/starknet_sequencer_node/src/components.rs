@@ -31,15 +31,6 @@ pub fn create_node_components(
     config: &SequencerNodeConfig,
     clients: &SequencerNodeClients,
 ) -> SequencerNodeComponents {
-    let batcher = match config.components.batcher.execution_mode {
-        ComponentExecutionMode::LocalExecutionWithRemoteDisabled
-        | ComponentExecutionMode::LocalExecutionWithRemoteEnabled => {
-            let mempool_client =
-                clients.get_mempool_shared_client().expect("Mempool Client should be available");
-            Some(create_batcher(config.batcher_config.clone(), mempool_client))
-        }
-        ComponentExecutionMode::Disabled | ComponentExecutionMode::Remote => None,
-    };
     let consensus_manager = match config.components.consensus_manager.execution_mode {
         ComponentExecutionMode::LocalExecutionWithRemoteDisabled
         | ComponentExecutionMode::LocalExecutionWithRemoteEnabled => {
@@ -97,12 +88,23 @@ pub fn create_node_components(
             let mempool_p2p_propagator_client = clients
                 .get_mempool_p2p_propagator_shared_client()
                 .expect("Propagator Client should be available");
-            let mempool = create_mempool(mempool_p2p_propagator_client);
+            let mempool =
+                create_mempool(config.mempool_config.clone(), mempool_p2p_propagator_client);
             Some(mempool)
         }
         ComponentExecutionMode::Disabled | ComponentExecutionMode::Remote => None,
     };
 
+    let batcher = match config.components.batcher.execution_mode {
+        ComponentExecutionMode::LocalExecutionWithRemoteDisabled
+        | ComponentExecutionMode::LocalExecutionWithRemoteEnabled => {
+            let mempool_client =
+                clients.get_mempool_shared_client().expect("Mempool Client should be available");
+            Some(create_batcher(config.batcher_config.clone(), mempool_client))
+        }
+        ComponentExecutionMode::Disabled | ComponentExecutionMode::Remote => None,
+    };
+
     let monitoring_endpoint = match config.components.monitoring_endpoint.execution_mode {
         ComponentExecutionMode::LocalExecutionWithRemoteEnabled => Some(
             create_monitoring_endpoint(config.monitoring_endpoint_config.clone(), VERSION_FULL),
@@ -23,6 +23,7 @@ use starknet_batcher::VersionedConstantsOverrides;
 use starknet_consensus_manager::config::ConsensusManagerConfig;
 use starknet_gateway::config::{GatewayConfig, RpcStateReaderConfig};
 use starknet_http_server::config::HttpServerConfig;
+use starknet_mempool::mempool::MempoolConfig;
 use starknet_mempool_p2p::config::MempoolP2pConfig;
 use starknet_monitoring_endpoint::config::MonitoringEndpointConfig;
 use starknet_sierra_compile::config::SierraToCasmCompilationConfig;
@@ -119,6 +120,8 @@ pub struct SequencerNodeConfig {
     #[validate]
     pub compiler_config: SierraToCasmCompilationConfig,
     #[validate]
+    pub mempool_config: MempoolConfig,
+    #[validate]
     pub mempool_p2p_config: MempoolP2pConfig,
     #[validate]
     pub monitoring_endpoint_config: MonitoringEndpointConfig,
@@ -137,6 +140,7 @@ impl SerializeConfig for SequencerNodeConfig {
             append_sub_config_name(self.http_server_config.dump(), "http_server_config"),
             append_sub_config_name(self.rpc_state_reader_config.dump(), "rpc_state_reader_config"),
             append_sub_config_name(self.compiler_config.dump(), "compiler_config"),
+            append_sub_config_name(self.mempool_config.dump(), "mempool_config"),
             append_sub_config_name(self.mempool_p2p_config.dump(), "mempool_p2p_config"),
             append_sub_config_name(
                 self.monitoring_endpoint_config.dump(),